@@ -0,0 +1,139 @@
+use rand::Rng;
+
+use error::{Error, Result};
+
+/// Generator wrapper which enforces a maximum number of draws
+///
+/// The fallible `try_*` methods and [`take_exact`] return [`Error::BudgetExhausted`] once the
+/// budget is used up, so fixed-budget experiments fail loudly instead of quietly drawing more
+/// samples than planned.  The [`Rng`] implementation panics in the same situation, since the
+/// trait offers no way to report an error.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::Halton;
+/// # use tapas::adapter::Budget;
+/// let mut gen = Budget::new(Halton::new(1, 2).unwrap(), 10);
+///
+/// let first = gen.take_exact(8).unwrap();
+/// assert_eq!(first.len(), 8);
+/// assert_eq!(gen.remaining(), 2);
+///
+/// // Asking for more than is left fails without consuming anything
+/// assert!(gen.take_exact(3).is_err());
+/// assert_eq!(gen.remaining(), 2);
+/// ```
+///
+/// [`take_exact`]: #method.take_exact
+/// [`Error::BudgetExhausted`]: ../../enum.Error.html#variant.BudgetExhausted
+/// [`Rng`]: ../../../rand/trait.Rng.html
+#[derive(Debug, Clone)]
+pub struct Budget<R> {
+    inner: R,
+    limit: u64,
+    used: u64,
+}
+
+macro_rules! budget_next {
+    ($(#[$attr:meta])* pub $func:ident, $next:ident, $type:ident) => {
+        $(#[$attr])*
+        pub fn $func(&mut self) -> Result<$type> {
+            self.spend(1)?;
+            Ok(self.inner.$next())
+        }
+    };
+}
+
+impl<R: Rng> Budget<R> {
+    /// Allow at most `limit` draws from `inner`
+    pub fn new(inner: R, limit: u64) -> Budget<R> {
+        Budget { inner, limit, used: 0 }
+    }
+
+    /// Number of draws left in the budget
+    pub fn remaining(&self) -> u64 {
+        self.limit - self.used
+    }
+
+    /// Number of draws taken so far
+    pub fn used(&self) -> u64 {
+        self.used
+    }
+
+    /// Unwrap the budgeted generator
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn spend(&mut self, n: u64) -> Result<()> {
+        if n > self.remaining() {
+            return Err(Error::BudgetExhausted { limit: self.limit });
+        }
+        self.used += n;
+        Ok(())
+    }
+
+    budget_next!(
+        /// Draw the next u32 value if the budget allows it
+        pub try_next_u32, next_u32, u32
+    );
+
+    budget_next!(
+        /// Draw the next u64 value if the budget allows it
+        pub try_next_u64, next_u64, u64
+    );
+
+    budget_next!(
+        /// Draw the next f32 value if the budget allows it
+        pub try_next_f32, next_f32, f32
+    );
+
+    budget_next!(
+        /// Draw the next f64 value if the budget allows it
+        pub try_next_f64, next_f64, f64
+    );
+
+    /// Draw exactly `n` values, or none at all if fewer than `n` are left in the budget
+    pub fn take_exact(&mut self, n: usize) -> Result<Vec<f64>> {
+        self.spend(n as u64)?;
+        Ok((0..n).map(|_| self.inner.next_f64()).collect())
+    }
+}
+
+macro_rules! budget_rng {
+    ($func:ident, $try:ident, $type:ident) => {
+        fn $func(&mut self) -> $type {
+            self.$try().unwrap_or_else(|e| panic!("{}", e))
+        }
+    };
+}
+
+impl<R: Rng> Rng for Budget<R> {
+    budget_rng!(next_u32, try_next_u32, u32);
+    budget_rng!(next_u64, try_next_u64, u64);
+    budget_rng!(next_f32, try_next_f32, f32);
+    budget_rng!(next_f64, try_next_f64, f64);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::Halton;
+
+    #[test]
+    fn exhausts_after_limit() {
+        let mut gen = Budget::new(Halton::new(1, 3).unwrap(), 2);
+        assert!(gen.try_next_f64().is_ok());
+        assert!(gen.try_next_u32().is_ok());
+        assert!(matches!(gen.try_next_f64(), Err(Error::BudgetExhausted { limit: 2 })));
+        assert_eq!(gen.used(), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn rng_panics_when_exhausted() {
+        let mut gen = Budget::new(Halton::new(1, 3).unwrap(), 0);
+        gen.next_f64();
+    }
+}
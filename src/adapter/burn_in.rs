@@ -0,0 +1,111 @@
+use rand::Rng;
+
+use quasi::JumpAhead;
+
+/// Generator wrapper which discards the first draws of the underlying generator
+///
+/// Nothing is discarded at construction: the burn-in is applied with a single
+/// [`jump`](../../quasi/trait.JumpAhead.html#tymethod.jump) right before the first value is
+/// drawn, so wrapping a generator is free until it is used and quasi-random sequences skip
+/// their start without generating it.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate tapas;
+/// # use tapas::quasi::Halton;
+/// # use tapas::adapter::BurnIn;
+/// use rand::Rng;
+///
+/// # fn main() {
+/// let mut gen = BurnIn::new(Halton::new(1, 2).unwrap(), 3);
+///
+/// // Elements 1/2, 1/4 and 3/4 of the sequence are skipped
+/// assert_eq!(gen.next_f64(), 1. / 8.);
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct BurnIn<R> {
+    inner: R,
+    pending: u64,
+}
+
+impl<R: Rng + JumpAhead> BurnIn<R> {
+    /// Discard the first `n` draws of `inner`
+    pub fn new(inner: R, n: u64) -> BurnIn<R> {
+        BurnIn { inner, pending: n }
+    }
+
+    /// Number of draws still waiting to be discarded
+    pub fn pending(&self) -> u64 {
+        self.pending
+    }
+
+    /// Apply the burn-in and unwrap the generator
+    pub fn into_inner(mut self) -> R {
+        self.burn();
+        self.inner
+    }
+
+    #[inline]
+    fn burn(&mut self) {
+        if self.pending > 0 {
+            self.inner.jump(self.pending);
+            self.pending = 0;
+        }
+    }
+}
+
+macro_rules! burn_in_next {
+    ($func:ident, $type:ident) => {
+        fn $func(&mut self) -> $type {
+            self.burn();
+            self.inner.$func()
+        }
+    };
+}
+
+impl<R: Rng + JumpAhead> Rng for BurnIn<R> {
+    burn_in_next!(next_u32, u32);
+    burn_in_next!(next_u64, u64);
+    burn_in_next!(next_f32, f32);
+    burn_in_next!(next_f64, f64);
+}
+
+impl<R: Rng + JumpAhead> JumpAhead for BurnIn<R> {
+    fn jump(&mut self, n: u64) {
+        // Fold further jumps into the pending burn-in so they still happen in one step
+        self.pending += n;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::Halton;
+    use rand::{SeedableRng, XorShiftRng};
+
+    #[test]
+    fn lazy_until_first_draw() {
+        let gen = BurnIn::new(Halton::new(1, 3).unwrap(), 100);
+        assert_eq!(gen.pending(), 100);
+    }
+
+    #[test]
+    fn pseudo_random_discard() {
+        let mut plain = XorShiftRng::from_seed([1, 2, 3, 4]);
+        let mut burned = BurnIn::new(plain.clone(), 5);
+        for _ in 0..5 {
+            plain.next_u32();
+        }
+        assert_eq!(plain.next_u32(), burned.next_u32());
+    }
+
+    #[test]
+    fn jumps_accumulate() {
+        let mut gen = BurnIn::new(Halton::new(1, 2).unwrap(), 1);
+        gen.jump(1);
+        assert_eq!(gen.next_f64(), 3. / 4.);
+    }
+}
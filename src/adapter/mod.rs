@@ -0,0 +1,57 @@
+//! # Adapters
+//!
+//! Wrappers which observe or change how values are drawn from an underlying generator.
+//!
+//! - [`BurnIn`] lazily discards the start of a sequence
+//! - [`Budget`] enforces a maximum number of draws
+//! - [`Thin`] keeps every `k`-th point of a sequence
+//! - [`Padded`] fills the dimensions after those of a sequence from a pseudo-random generator
+//! - [`Points`] yields exactly `n` complete points, and [`IntoPoints`] every following one
+//! - [`Project`] keeps selected coordinates of every point
+//! - [`ScaledTo`] maps every point of the unit cube into an axis-aligned box
+//! - [`Recorder`] logs every value so a run can be played back with a [`Replayer`]
+//! - [`Shuffle`] randomly permutes the coordinates of each point
+//! - [`Warp`] maps every point through a transform, such as those of the [`transform`] module
+//! - [`Traced`] reports every draw to a callback (requires the `trace` feature)
+//!
+//! [`BurnIn`]: burn_in/struct.BurnIn.html
+//! [`Budget`]: budget/struct.Budget.html
+//! [`Recorder`]: replay/struct.Recorder.html
+//! [`Replayer`]: replay/struct.Replayer.html
+//! [`Shuffle`]: shuffle/struct.Shuffle.html
+//! [`Thin`]: thin/struct.Thin.html
+//! [`Padded`]: padded/struct.Padded.html
+//! [`Points`]: points/struct.Points.html
+//! [`IntoPoints`]: points/struct.IntoPoints.html
+//! [`Project`]: project/struct.Project.html
+//! [`ScaledTo`]: scaled/struct.ScaledTo.html
+//! [`Traced`]: trace/struct.Traced.html
+//! [`Warp`]: warp/struct.Warp.html
+//! [`transform`]: ../transform/index.html
+
+pub mod budget;
+pub mod burn_in;
+pub mod padded;
+pub mod points;
+pub mod project;
+pub mod replay;
+pub mod scaled;
+pub mod shuffle;
+pub mod thin;
+pub mod warp;
+#[cfg(feature = "trace")]
+pub mod trace;
+
+pub use self::budget::Budget;
+pub use self::burn_in::BurnIn;
+pub use self::padded::Padded;
+pub use self::points::{IntoPoints, Points};
+pub use self::project::Project;
+pub use self::replay::{Recorder, Replayer};
+pub use self::scaled::ScaledTo;
+pub use self::shuffle::Shuffle;
+pub use self::thin::Thin;
+pub use self::warp::Warp;
+
+#[cfg(feature = "trace")]
+pub use self::trace::{set_tracing, tracing, Draw, Traced};
@@ -0,0 +1,126 @@
+use rand::Rng;
+
+use sampler::PointSampler;
+
+/// Point generator which pads the points of a low-discrepancy sequence with pseudo-random values
+///
+/// The first `k` coordinates of every point come from the inner generator of `k` dimensions,
+/// and the remaining coordinates up to `dimensions` are drawn from an [`Rng`].  Integrands of
+/// many nominal dimensions often depend mostly on the first few, such as the first steps of a
+/// path or the first bounces of a light path, so a well stratified sequence there with a fast
+/// generator for the tail recovers most of the gain of quasi-Monte Carlo.  Usually built with
+/// [`PointSampler::padded`].
+///
+/// # Examples
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate tapas;
+/// # use tapas::quasi::Sobol;
+/// # use tapas::sampler::PointSampler;
+/// use rand::{SeedableRng, StdRng};
+///
+/// # fn main() {
+/// let rng = StdRng::from_seed(&[7][..]);
+/// let mut points = Sobol::new(2).unwrap().padded(rng, 100);
+/// assert_eq!(points.dimensions(), 100);
+///
+/// let p = points.nth(1).unwrap();
+/// assert_eq!(&p[..2], &[0.5, 0.5]);
+/// assert!(p[2..].iter().all(|&x| 0. <= x && x < 1.));
+/// # }
+/// ```
+///
+/// [`Rng`]: ../../../rand/trait.Rng.html
+/// [`PointSampler::padded`]: ../../sampler/trait.PointSampler.html#method.padded
+#[derive(Debug, Clone)]
+pub struct Padded<P, R> {
+    inner: P,
+    rng: R,
+    dimensions: usize,
+}
+
+impl<P: PointSampler, R: Rng> Padded<P, R> {
+    /// Points of `dimensions` dimensions, padded after those of `inner` with draws of `rng`
+    ///
+    /// # Panics
+    ///
+    /// If `dimensions` is less than the number of dimensions of `inner`.
+    pub fn new(inner: P, rng: R, dimensions: usize) -> Padded<P, R> {
+        assert!(dimensions >= inner.dimensions(), "{}-dimensional points padded to {}",
+            inner.dimensions(), dimensions);
+        Padded { inner, rng, dimensions }
+    }
+
+    /// Number of leading dimensions taken from the low-discrepancy sequence
+    pub fn sequence_dimensions(&self) -> usize {
+        self.inner.dimensions()
+    }
+
+    /// Unwrap the sequence and the generator of the padding
+    pub fn into_inner(self) -> (P, R) {
+        (self.inner, self.rng)
+    }
+}
+
+impl<P: PointSampler, R: Rng> PointSampler for Padded<P, R> {
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    fn next_point(&mut self, out: &mut [f64]) {
+        assert_eq!(out.len(), self.dimensions, "{}-dimensional buffer for {}-dimensional points",
+            out.len(), self.dimensions);
+        let (head, tail) = out.split_at_mut(self.inner.dimensions());
+        self.inner.next_point(head);
+        for x in tail.iter_mut() {
+            *x = self.rng.next_f64();
+        }
+    }
+}
+
+impl<P, R> From<Padded<P, R>> for Box<dyn PointSampler>
+    where P: PointSampler + 'static, R: Rng + 'static {
+
+    fn from(gen: Padded<P, R>) -> Box<dyn PointSampler> {
+        Box::new(gen)
+    }
+}
+
+impl<P: PointSampler, R: Rng> Iterator for Padded<P, R> {
+    type Item = Vec<f64>;
+
+    fn next(&mut self) -> Option<Vec<f64>> {
+        let mut point = vec![0.; self.dimensions];
+        self.next_point(&mut point);
+        Some(point)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::HaltonSeq;
+    use rand::{SeedableRng, StdRng};
+
+    #[test]
+    fn head_and_tail() {
+        let mut rng = StdRng::from_seed(&[3][..]);
+        let padded = Padded::new(HaltonSeq::new(3).unwrap(), rng, 5);
+        assert_eq!(padded.sequence_dimensions(), 3);
+        for (p, q) in padded.take(20).zip(HaltonSeq::new(3).unwrap()) {
+            assert_eq!(&p[..3], &q[..]);
+            assert_eq!(p[3..].to_vec(), vec![rng.next_f64(), rng.next_f64()]);
+        }
+
+        // Padding to the same number of dimensions leaves the sequence alone
+        let same: Vec<Vec<f64>> = Padded::new(HaltonSeq::new(2).unwrap(), rng, 2).take(5).collect();
+        assert_eq!(same, HaltonSeq::new(2).unwrap().take(5).collect::<Vec<_>>());
+    }
+
+    #[test]
+    #[should_panic]
+    fn fewer_dimensions() {
+        Padded::new(HaltonSeq::new(3).unwrap(), StdRng::from_seed(&[1][..]), 2);
+    }
+}
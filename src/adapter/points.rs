@@ -0,0 +1,144 @@
+use std::iter::FusedIterator;
+
+use sampler::PointSampler;
+
+/// Iterator over exactly `n` complete points of a point generator
+///
+/// Every item is a whole point of [`PointSampler::dimensions`] coordinates, so unlike taking
+/// values from an [`Rng`] the stream can never stop in the middle of a point.  The number of
+/// points left is always known, which lets `collect` allocate once.  Usually built with
+/// [`PointSampler::points`].
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::{Halton, Interleave};
+/// # use tapas::sampler::PointSampler;
+/// let gen = Interleave::new(&[Halton::new(1, 2).unwrap(), Halton::new(1, 3).unwrap()]).unwrap();
+/// let points: Vec<Vec<f64>> = gen.points(3).collect();
+/// assert_eq!(points, vec![
+///     vec![1. / 2., 1. / 3.],
+///     vec![1. / 4., 2. / 3.],
+///     vec![3. / 4., 1. / 9.],
+/// ]);
+/// ```
+///
+/// [`PointSampler::dimensions`]: ../../sampler/trait.PointSampler.html#tymethod.dimensions
+/// [`PointSampler::points`]: ../../sampler/trait.PointSampler.html#method.points
+/// [`Rng`]: ../../../rand/trait.Rng.html
+#[derive(Debug, Clone)]
+pub struct Points<P> {
+    inner: P,
+    remaining: usize,
+}
+
+impl<P: PointSampler> Points<P> {
+    /// Next `n` points of `inner`
+    pub fn new(inner: P, n: usize) -> Points<P> {
+        Points { inner, remaining: n }
+    }
+
+    /// Unwrap the generator, which continues after the last point returned
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+impl<P: PointSampler> Iterator for Points<P> {
+    type Item = Vec<f64>;
+
+    fn next(&mut self) -> Option<Vec<f64>> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let mut point = vec![0.; self.inner.dimensions()];
+        self.inner.next_point(&mut point);
+        Some(point)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<P: PointSampler> ExactSizeIterator for Points<P> {}
+
+impl<P: PointSampler> FusedIterator for Points<P> {}
+
+/// Endless iterator over the complete points of a point generator
+///
+/// Returned by the [`IntoIterator`] implementations of generators which only draw values one
+/// at a time as an [`Rng`], such as [`Interleave`], so they can be used in `for` loops over
+/// points.  Limit the number of points with [`Iterator::take`] or use [`Points`] instead.
+///
+/// ```
+/// # use tapas::quasi::{Halton, Interleave};
+/// let gen = Interleave::new(&[Halton::new(1, 2).unwrap(), Halton::new(1, 3).unwrap()]).unwrap();
+/// for p in gen.into_iter().take(10) {
+///     assert_eq!(p.len(), 2);
+/// }
+/// ```
+///
+/// [`IntoIterator`]: https://doc.rust-lang.org/std/iter/trait.IntoIterator.html
+/// [`Iterator::take`]: https://doc.rust-lang.org/std/iter/trait.Iterator.html#method.take
+/// [`Rng`]: ../../../rand/trait.Rng.html
+/// [`Interleave`]: ../../quasi/struct.Interleave.html
+/// [`Points`]: struct.Points.html
+#[derive(Debug, Clone)]
+pub struct IntoPoints<P> {
+    inner: P,
+}
+
+impl<P: PointSampler> IntoPoints<P> {
+    /// Every following point of `inner`
+    pub fn new(inner: P) -> IntoPoints<P> {
+        IntoPoints { inner }
+    }
+
+    /// Unwrap the generator, which continues after the last point returned
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+impl<P: PointSampler> Iterator for IntoPoints<P> {
+    type Item = Vec<f64>;
+
+    fn next(&mut self) -> Option<Vec<f64>> {
+        let mut point = vec![0.; self.inner.dimensions()];
+        self.inner.next_point(&mut point);
+        Some(point)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::{HaltonSeq, Sobol};
+
+    #[test]
+    fn exact_number_of_points() {
+        let mut points = Points::new(Sobol::new(3).unwrap(), 5);
+        assert_eq!(points.len(), 5);
+        let drawn: Vec<Vec<f64>> = points.by_ref().collect();
+        assert_eq!(drawn, Sobol::new(3).unwrap().take(5).collect::<Vec<_>>());
+        assert_eq!((points.len(), points.next()), (0, None));
+
+        // The generator continues with the next point
+        let mut rest = points.into_inner();
+        assert_eq!(rest.next(), Sobol::new(3).unwrap().nth(5));
+    }
+
+    #[test]
+    fn endless_points_match() {
+        let endless: Vec<Vec<f64>> = IntoPoints::new(HaltonSeq::new(4).unwrap()).take(50).collect();
+        let exact: Vec<Vec<f64>> = Points::new(HaltonSeq::new(4).unwrap(), 50).collect();
+        assert_eq!(endless, exact);
+        assert_eq!(Points::new(HaltonSeq::new(4).unwrap(), 0).count(), 0);
+    }
+}
@@ -0,0 +1,107 @@
+use sampler::PointSampler;
+
+/// Point generator wrapper which keeps only selected coordinates of every point
+///
+/// Every point of the inner generator is drawn in full and the coordinates in the selected
+/// dimensions are kept, in the order given.  Projections onto pairs of dimensions are the
+/// standard way to check a high-dimensional sequence for the lines and gaps which show up as
+/// poor integration of functions of those dimensions.  Usually built with
+/// [`PointSampler::project`].
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::HaltonSeq;
+/// # use tapas::sampler::PointSampler;
+/// // The bases 29 and 31 of dimensions 9 and 10 give a poor projection
+/// let mut pairs = HaltonSeq::new(12).unwrap().project(&[9, 10]);
+/// assert_eq!(pairs.next(), Some(vec![1. / 29., 1. / 31.]));
+/// assert_eq!(pairs.next(), Some(vec![2. / 29., 2. / 31.]));
+/// ```
+///
+/// [`PointSampler::project`]: ../../sampler/trait.PointSampler.html#method.project
+#[derive(Debug, Clone)]
+pub struct Project<P> {
+    inner: P,
+    dimensions: Vec<usize>,
+    /// Full point of the inner generator
+    buffer: Vec<f64>,
+}
+
+impl<P: PointSampler> Project<P> {
+    /// Keep the coordinates of `inner` in `dimensions`, in that order
+    ///
+    /// # Panics
+    ///
+    /// If `dimensions` is empty or holds a dimension which `inner` does not have.
+    pub fn new(inner: P, dimensions: &[usize]) -> Project<P> {
+        let d = inner.dimensions();
+        assert!(!dimensions.is_empty(), "projection onto no dimensions");
+        assert!(dimensions.iter().all(|&j| j < d),
+            "projection onto {:?} of {}-dimensional points", dimensions, d);
+        Project { inner, dimensions: dimensions.to_vec(), buffer: vec![0.; d] }
+    }
+
+    /// Dimensions of the inner generator which are kept
+    pub fn selected(&self) -> &[usize] {
+        &self.dimensions
+    }
+
+    /// Unwrap the projected generator
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+impl<P: PointSampler> PointSampler for Project<P> {
+    fn dimensions(&self) -> usize {
+        self.dimensions.len()
+    }
+
+    fn next_point(&mut self, out: &mut [f64]) {
+        assert_eq!(out.len(), self.dimensions.len(),
+            "{}-dimensional buffer for {}-dimensional points", out.len(), self.dimensions.len());
+        self.inner.next_point(&mut self.buffer);
+        for (x, &j) in out.iter_mut().zip(self.dimensions.iter()) {
+            *x = self.buffer[j];
+        }
+    }
+}
+
+impl<P: PointSampler + 'static> From<Project<P>> for Box<dyn PointSampler> {
+    fn from(gen: Project<P>) -> Box<dyn PointSampler> {
+        Box::new(gen)
+    }
+}
+
+impl<P: PointSampler> Iterator for Project<P> {
+    type Item = Vec<f64>;
+
+    fn next(&mut self) -> Option<Vec<f64>> {
+        let mut point = vec![0.; self.dimensions.len()];
+        self.next_point(&mut point);
+        Some(point)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::Sobol;
+
+    #[test]
+    fn keeps_selected_coordinates() {
+        let full: Vec<Vec<f64>> = Sobol::new(5).unwrap().take(50).collect();
+        let projected = Project::new(Sobol::new(5).unwrap(), &[4, 1, 4]);
+        assert_eq!(projected.selected(), &[4, 1, 4]);
+        for (p, q) in projected.zip(full) {
+            assert_eq!(p, vec![q[4], q[1], q[4]]);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn missing_dimension() {
+        Project::new(Sobol::new(2).unwrap(), &[2]);
+    }
+}
@@ -0,0 +1,291 @@
+use rand::Rng;
+use std::io::{BufRead, Write};
+
+use error::{Error, Result};
+
+/// A single recorded draw, tagged with the method which produced it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value {
+    /// Value returned by `next_u32`
+    U32(u32),
+    /// Value returned by `next_u64`
+    U64(u64),
+    /// Value returned by `next_f32`
+    F32(f32),
+    /// Value returned by `next_f64`
+    F64(f64),
+}
+
+const TWO_32: f64 = 4_294_967_296.;
+const TWO_64: f64 = 18_446_744_073_709_551_616.;
+
+impl Value {
+    /// Reinterpret the value as a `u32`, keeping its position in the output range
+    pub fn as_u32(&self) -> u32 {
+        match *self {
+            Value::U32(x) => x,
+            Value::U64(x) => (x >> 32) as u32,
+            Value::F32(x) => (x as f64 * TWO_32) as u32,
+            Value::F64(x) => (x * TWO_32) as u32,
+        }
+    }
+
+    /// Reinterpret the value as a `u64`, keeping its position in the output range
+    pub fn as_u64(&self) -> u64 {
+        match *self {
+            Value::U32(x) => (x as u64) << 32,
+            Value::U64(x) => x,
+            Value::F32(x) => (x as f64 * TWO_64) as u64,
+            Value::F64(x) => (x * TWO_64) as u64,
+        }
+    }
+
+    /// Reinterpret the value as an `f64` in `[0, 1)`
+    pub fn as_f64(&self) -> f64 {
+        match *self {
+            Value::U32(x) => x as f64 / TWO_32,
+            Value::U64(x) => (x >> 11) as f64 / (1u64 << 53) as f64,
+            Value::F32(x) => x as f64,
+            Value::F64(x) => x,
+        }
+    }
+
+    /// Reinterpret the value as an `f32` in `[0, 1)`
+    pub fn as_f32(&self) -> f32 {
+        match *self {
+            Value::U32(x) => (x >> 8) as f32 / (1u32 << 24) as f32,
+            Value::U64(x) => (x >> 40) as f32 / (1u32 << 24) as f32,
+            Value::F32(x) => x,
+            Value::F64(x) => x as f32,
+        }
+    }
+
+    fn parse(line: &str) -> Option<Value> {
+        let mut parts = line.split_whitespace();
+        let (kind, value) = (parts.next()?, parts.next()?);
+        if parts.next().is_some() {
+            return None;
+        }
+
+        let hex = value.trim_start_matches("0x");
+        match kind {
+            "u32" => value.parse().ok().map(Value::U32),
+            "u64" => value.parse().ok().map(Value::U64),
+            "f32" => u32::from_str_radix(hex, 16).ok().map(|b| Value::F32(f32::from_bits(b))),
+            "f64" => u64::from_str_radix(hex, 16).ok().map(|b| Value::F64(f64::from_bits(b))),
+            _ => None,
+        }
+    }
+}
+
+/// Generator wrapper which records every value it emits
+///
+/// The log can be turned into a [`Replayer`] directly, or written out with [`write_to`] and
+/// read back later with [`Replayer::read_from`].  Floating point values are stored as their
+/// exact bit patterns, so a replay is bit-for-bit identical.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate tapas;
+/// # use tapas::quasi::Halton;
+/// # use tapas::adapter::{Recorder, Replayer};
+/// use rand::Rng;
+///
+/// # fn main() {
+/// let mut recorder = Recorder::new(Halton::new(1, 3).unwrap());
+/// let original: Vec<f64> = (0..5).map(|_| recorder.next_f64()).collect();
+///
+/// let mut log = Vec::new();
+/// recorder.write_to(&mut log).unwrap();
+///
+/// let mut replayer = Replayer::read_from(&log[..]).unwrap();
+/// let replayed: Vec<f64> = (0..5).map(|_| replayer.next_f64()).collect();
+/// assert_eq!(original, replayed);
+/// # }
+/// ```
+///
+/// [`Replayer`]: struct.Replayer.html
+/// [`write_to`]: #method.write_to
+/// [`Replayer::read_from`]: struct.Replayer.html#method.read_from
+#[derive(Debug, Clone)]
+pub struct Recorder<R> {
+    inner: R,
+    log: Vec<Value>,
+}
+
+impl<R: Rng> Recorder<R> {
+    /// Start recording the values drawn from `inner`
+    pub fn new(inner: R) -> Recorder<R> {
+        Recorder { inner, log: Vec::new() }
+    }
+
+    /// Values recorded so far
+    pub fn values(&self) -> &[Value] {
+        &self.log
+    }
+
+    /// Write the log as text, one `<kind> <value>` pair per line
+    pub fn write_to<W: Write>(&self, mut out: W) -> Result<()> {
+        for value in self.log.iter() {
+            match *value {
+                Value::U32(x) => writeln!(out, "u32 {}", x)?,
+                Value::U64(x) => writeln!(out, "u64 {}", x)?,
+                Value::F32(x) => writeln!(out, "f32 0x{:08x}", x.to_bits())?,
+                Value::F64(x) => writeln!(out, "f64 0x{:016x}", x.to_bits())?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Stop recording, returning a replayer over the log
+    pub fn into_replayer(self) -> Replayer {
+        Replayer::new(self.log)
+    }
+
+    /// Stop recording, returning the wrapped generator
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+macro_rules! record_next {
+    ($func:ident, $type:ident, $variant:ident) => {
+        fn $func(&mut self) -> $type {
+            let value = self.inner.$func();
+            self.log.push(Value::$variant(value));
+            value
+        }
+    };
+}
+
+impl<R: Rng> Rng for Recorder<R> {
+    record_next!(next_u32, u32, U32);
+    record_next!(next_u64, u64, U64);
+    record_next!(next_f32, f32, F32);
+    record_next!(next_f64, f64, F64);
+}
+
+/// Generator which plays back a recorded log of values
+///
+/// Values are returned in the order they were recorded regardless of which method requests
+/// them; when the method differs from the recorded one the value is converted to the requested
+/// type while keeping its position in the output range.  This lets a run be reproduced even
+/// after code changes alter how values are consumed.
+///
+/// # Panics
+///
+/// Drawing from an exhausted replayer panics; check [`remaining`] to avoid it.
+///
+/// [`remaining`]: #method.remaining
+#[derive(Debug, Clone)]
+pub struct Replayer {
+    log: Vec<Value>,
+    position: usize,
+}
+
+impl Replayer {
+    /// Play back `log` from the beginning
+    pub fn new(log: Vec<Value>) -> Replayer {
+        Replayer { log, position: 0 }
+    }
+
+    /// Read a log in the format written by [`Recorder::write_to`]
+    ///
+    /// Blank lines are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if reading fails and [`Error::Parse`] for malformed lines.
+    ///
+    /// [`Recorder::write_to`]: struct.Recorder.html#method.write_to
+    /// [`Error::Io`]: ../../enum.Error.html#variant.Io
+    /// [`Error::Parse`]: ../../enum.Error.html#variant.Parse
+    pub fn read_from<B: BufRead>(input: B) -> Result<Replayer> {
+        let mut log = Vec::new();
+        for (n, line) in input.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let value = Value::parse(&line).ok_or_else(|| Error::Parse {
+                line: n + 1,
+                reason: format!("expected `<kind> <value>`, found `{}`", line.trim()),
+            })?;
+            log.push(value);
+        }
+        Ok(Replayer::new(log))
+    }
+
+    /// Number of values left to play back
+    pub fn remaining(&self) -> usize {
+        self.log.len() - self.position
+    }
+
+    fn next_value(&mut self) -> Value {
+        let value = *self.log.get(self.position)
+            .unwrap_or_else(|| panic!("replay log exhausted after {} values", self.log.len()));
+        self.position += 1;
+        value
+    }
+}
+
+impl Rng for Replayer {
+    fn next_u32(&mut self) -> u32 {
+        self.next_value().as_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.next_value().as_u64()
+    }
+
+    fn next_f32(&mut self) -> f32 {
+        self.next_value().as_f32()
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        self.next_value().as_f64()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::Halton;
+
+    #[test]
+    fn round_trip_every_kind() {
+        let mut recorder = Recorder::new(Halton::new(1, 5).unwrap());
+        let original = (recorder.next_u32(), recorder.next_u64(), recorder.next_f32(),
+            recorder.next_f64());
+
+        let mut text = Vec::new();
+        recorder.write_to(&mut text).unwrap();
+        let mut replayer = Replayer::read_from(&text[..]).unwrap();
+        assert_eq!(replayer.remaining(), 4);
+
+        let replayed = (replayer.next_u32(), replayer.next_u64(), replayer.next_f32(),
+            replayer.next_f64());
+        assert_eq!(original, replayed);
+    }
+
+    #[test]
+    fn converts_between_kinds() {
+        let mut replayer = Replayer::new(vec![Value::F64(0.5), Value::U32(1 << 31)]);
+        assert_eq!(replayer.next_u32(), 1 << 31);
+        assert_eq!(replayer.next_f64(), 0.5);
+    }
+
+    #[test]
+    fn malformed_line() {
+        let result = Replayer::read_from("u32 1\n\nf64 nope\n".as_bytes());
+        assert!(matches!(result, Err(Error::Parse { line: 3, .. })));
+    }
+
+    #[test]
+    #[should_panic]
+    fn exhausted() {
+        Replayer::new(vec![]).next_f64();
+    }
+}
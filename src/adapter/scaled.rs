@@ -0,0 +1,127 @@
+use sampler::PointSampler;
+
+/// Point generator wrapper which maps every point of the unit cube into an axis-aligned box
+///
+/// Coordinate `j` of every point is scaled from `[0, 1)` to `[lo_j, hi_j)` by
+/// `lo_j + u_j (hi_j - lo_j)`, which keeps the points uniformly spread, so integrals over the
+/// box are estimated by the mean of the integrand times the volume.  Usually built with
+/// [`PointSampler::in_box`].
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::Sobol;
+/// # use tapas::sampler::PointSampler;
+/// // Integral of x y over [1, 3) x [-1, 1), which is zero
+/// let mut points = Sobol::new(2).unwrap().in_box(&[1., -1.], &[3., 1.]);
+/// assert_eq!(points.next(), Some(vec![1., -1.]));
+/// assert_eq!(points.next(), Some(vec![2., 0.]));
+///
+/// let volume = 4.;
+/// let mean = points.take(1023).map(|p| p[0] * p[1]).sum::<f64>() / 1024.;
+/// assert!((volume * mean).abs() < 0.05);
+/// ```
+///
+/// [`PointSampler::in_box`]: ../../sampler/trait.PointSampler.html#method.in_box
+#[derive(Debug, Clone)]
+pub struct ScaledTo<P> {
+    inner: P,
+    lo: Vec<f64>,
+    /// Side length of the box along every axis
+    scale: Vec<f64>,
+}
+
+impl<P: PointSampler> ScaledTo<P> {
+    /// Map the points of `inner` into the box with corners `lo` and `hi`
+    ///
+    /// # Panics
+    ///
+    /// If the corners do not have one coordinate per dimension of `inner`, or `lo` exceeds
+    /// `hi` along any axis.
+    pub fn new(inner: P, lo: &[f64], hi: &[f64]) -> ScaledTo<P> {
+        let d = inner.dimensions();
+        assert!(lo.len() == d && hi.len() == d,
+            "corners of {} and {} dimensions for {}-dimensional points", lo.len(), hi.len(), d);
+        assert!(lo.iter().zip(hi).all(|(l, h)| l <= h), "{:?} is not below {:?}", lo, hi);
+
+        let scale = lo.iter().zip(hi).map(|(l, h)| h - l).collect();
+        ScaledTo { inner, lo: lo.to_vec(), scale }
+    }
+
+    /// Lower corner of the box
+    pub fn lo(&self) -> &[f64] {
+        &self.lo
+    }
+
+    /// Upper corner of the box
+    pub fn hi(&self) -> Vec<f64> {
+        self.lo.iter().zip(self.scale.iter()).map(|(l, s)| l + s).collect()
+    }
+
+    /// Volume of the box, which weights the mean of an integrand over the points
+    pub fn volume(&self) -> f64 {
+        self.scale.iter().product()
+    }
+
+    /// Unwrap the scaled generator
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+impl<P: PointSampler> PointSampler for ScaledTo<P> {
+    fn dimensions(&self) -> usize {
+        self.lo.len()
+    }
+
+    fn next_point(&mut self, out: &mut [f64]) {
+        self.inner.next_point(out);
+        for (x, (l, s)) in out.iter_mut().zip(self.lo.iter().zip(self.scale.iter())) {
+            *x = l + *x * s;
+        }
+    }
+}
+
+impl<P: PointSampler + 'static> From<ScaledTo<P>> for Box<dyn PointSampler> {
+    fn from(gen: ScaledTo<P>) -> Box<dyn PointSampler> {
+        Box::new(gen)
+    }
+}
+
+impl<P: PointSampler> Iterator for ScaledTo<P> {
+    type Item = Vec<f64>;
+
+    fn next(&mut self) -> Option<Vec<f64>> {
+        let mut point = vec![0.; self.lo.len()];
+        self.next_point(&mut point);
+        Some(point)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::HaltonSeq;
+
+    #[test]
+    fn maps_every_axis() {
+        let unit: Vec<Vec<f64>> = HaltonSeq::new(3).unwrap().take(100).collect();
+        let scaled = ScaledTo::new(HaltonSeq::new(3).unwrap(), &[0., -2., 5.], &[1., 2., 5.]);
+        assert_eq!((scaled.hi(), scaled.volume()), (vec![1., 2., 5.], 0.));
+        for (p, u) in scaled.zip(unit) {
+            assert_eq!(p, vec![u[0], -2. + 4. * u[1], 5.]);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn mismatched_corners() {
+        ScaledTo::new(HaltonSeq::new(2).unwrap(), &[0.], &[1.]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn inverted_corners() {
+        ScaledTo::new(HaltonSeq::new(1).unwrap(), &[1.], &[0.]);
+    }
+}
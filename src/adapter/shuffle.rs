@@ -0,0 +1,233 @@
+use rand::Rng;
+
+use quasi::{Digital, HashStream, JumpAhead};
+
+/// Generator wrapper which permutes the coordinates of each point with a seeded permutation
+///
+/// Coordinate `j` of every output point is coordinate `permutation()[j]` of the underlying
+/// point.  Randomizing which sequence dimension drives which variable of a simulation is a cheap
+/// guard against systematic projection artifacts when the sequence cannot be scrambled, and
+/// independent seeds give independent randomizations.
+///
+/// Every point is read from the wrapped generator as `dimensions` `f64` values; the integer
+/// methods convert the buffered values, keeping their position in the output range.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate tapas;
+/// # use tapas::quasi::{Halton, Interleave};
+/// # use tapas::adapter::Shuffle;
+/// use rand::Rng;
+///
+/// # fn main() {
+/// let gen = Interleave::new(&[
+///     Halton::new(1, 2).unwrap(),
+///     Halton::new(1, 3).unwrap(),
+///     Halton::new(1, 5).unwrap(),
+/// ]).unwrap();
+/// let mut shuffled = Shuffle::new(gen, 3, 7);
+///
+/// let firsts = [1. / 2., 1. / 3., 1. / 5.];
+/// let p = shuffled.permutation().to_vec();
+/// for j in 0..3 {
+///     assert_eq!(shuffled.next_f64(), firsts[p[j]]);
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Shuffle<R> {
+    inner: R,
+    permutation: Vec<usize>,
+    /// Current point in the order of the wrapped generator
+    buffer: Vec<f64>,
+    /// Next coordinate of the current point, equal to the dimension when it is used up
+    position: usize,
+}
+
+impl<R: Rng> Shuffle<R> {
+    /// Permute the coordinates of `dimensions`-dimensional points with a permutation from `seed`
+    ///
+    /// # Panics
+    ///
+    /// If `dimensions` is zero.
+    pub fn new(inner: R, dimensions: usize, seed: u64) -> Shuffle<R> {
+        assert!(dimensions > 0, "points need at least one dimension");
+
+        // Fisher-Yates shuffle driven by a hash stream
+        let mut hash = HashStream::new(seed);
+        let mut permutation: Vec<usize> = (0..dimensions).collect();
+        for i in (1..dimensions).rev() {
+            let j = (((hash.next_bits() >> 32) * (i as u64 + 1)) >> 32) as usize;
+            permutation.swap(i, j);
+        }
+
+        Shuffle::with_permutation(inner, permutation)
+    }
+
+    /// Permute coordinates with an explicit `permutation` of `0..dimensions`
+    ///
+    /// # Panics
+    ///
+    /// If `permutation` is empty or not a permutation.
+    pub fn with_permutation(inner: R, permutation: Vec<usize>) -> Shuffle<R> {
+        let d = permutation.len();
+        assert!(d > 0, "points need at least one dimension");
+        let mut seen = vec![false; d];
+        for &p in permutation.iter() {
+            assert!(p < d && !seen[p], "{:?} is not a permutation", permutation);
+            seen[p] = true;
+        }
+
+        Shuffle { inner, permutation, buffer: vec![0.; d], position: d }
+    }
+
+    /// Number of dimensions of the shuffled points
+    pub fn dimensions(&self) -> usize {
+        self.permutation.len()
+    }
+
+    /// Coordinate of the wrapped point emitted at each output coordinate
+    pub fn permutation(&self) -> &[usize] {
+        &self.permutation
+    }
+
+    /// Unwrap the shuffled generator, dropping the rest of a partly drawn point
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn refill(&mut self) {
+        for x in self.buffer.iter_mut() {
+            *x = self.inner.next_f64();
+        }
+        self.position = 0;
+    }
+
+    fn next_value(&mut self) -> f64 {
+        if self.position == self.buffer.len() {
+            self.refill();
+        }
+        let x = self.buffer[self.permutation[self.position]];
+        self.position += 1;
+        x
+    }
+}
+
+impl<R: Rng> Rng for Shuffle<R> {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_value() * 4_294_967_296.) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        (self.next_value() * 18_446_744_073_709_551_616.) as u64
+    }
+
+    fn next_f32(&mut self) -> f32 {
+        // Values just below one round up to one in single precision
+        (self.next_value() as f32).min(1. - f32::EPSILON / 2.)
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        self.next_value()
+    }
+}
+
+impl<R: Rng + JumpAhead> JumpAhead for Shuffle<R> {
+    fn jump(&mut self, n: u64) {
+        let d = self.buffer.len() as u64;
+        let target = self.position as u64 + n;
+        if target < d {
+            self.position = target as usize;
+            return;
+        }
+
+        // Whole points after the current one, then the position within the point landed on
+        let (points, position) = (target / d - 1, target % d);
+        if points > 0 {
+            self.inner.jump(points * d);
+        }
+        if position > 0 {
+            self.refill();
+            self.position = position as usize;
+        } else {
+            self.position = d as usize;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::{Halton, Interleave};
+
+    fn gen() -> Interleave<Halton> {
+        Interleave::new(&[
+            Halton::new(1, 2).unwrap(),
+            Halton::new(1, 3).unwrap(),
+            Halton::new(1, 5).unwrap(),
+            Halton::new(1, 7).unwrap(),
+        ]).unwrap()
+    }
+
+    #[test]
+    fn seeded_permutation() {
+        let a = Shuffle::new(gen(), 4, 1);
+        let mut sorted = a.permutation().to_vec();
+        sorted.sort();
+        assert_eq!(sorted, vec![0, 1, 2, 3]);
+        assert_eq!(a.permutation(), Shuffle::new(gen(), 4, 1).permutation());
+    }
+
+    #[test]
+    fn explicit_permutation() {
+        let mut s = Shuffle::with_permutation(gen(), vec![3, 2, 1, 0]);
+        let point: Vec<f64> = (0..4).map(|_| s.next_f64()).collect();
+        assert_eq!(point, vec![1. / 7., 1. / 5., 1. / 3., 1. / 2.]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn not_a_permutation() {
+        Shuffle::with_permutation(gen(), vec![0, 1, 1, 2]);
+    }
+
+    #[test]
+    fn f32_stays_below_one() {
+        struct Top;
+
+        impl Rng for Top {
+            fn next_u32(&mut self) -> u32 {
+                u32::MAX
+            }
+
+            fn next_f64(&mut self) -> f64 {
+                1. - f64::EPSILON / 2.
+            }
+        }
+
+        let mut s = Shuffle::new(Top, 2, 3);
+        for _ in 0..4 {
+            assert!(s.next_f32() < 1.);
+        }
+    }
+
+    #[test]
+    fn jump_matches_drawing() {
+        for &n in [1, 3, 4, 6, 9].iter() {
+            let mut jumped = Shuffle::new(gen(), 4, 5);
+            let mut drawn = jumped.clone();
+            jumped.next_f64();
+            drawn.next_f64();
+
+            jumped.jump(n);
+            for _ in 0..n {
+                drawn.next_f64();
+            }
+            for _ in 0..6 {
+                abs_err_eq!((jumped.next_f64()) == (drawn.next_f64()) ~ 1e-15);
+            }
+        }
+    }
+}
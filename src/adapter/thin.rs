@@ -0,0 +1,135 @@
+use rand::Rng;
+
+use quasi::JumpAhead;
+
+/// Generator wrapper which keeps only every `k`-th point of the underlying generator
+///
+/// Points consist of `dimensions` consecutive draws, which are kept together: after a complete
+/// point is drawn, the next `k - 1` points are skipped with a single
+/// [`jump`](../../quasi/trait.JumpAhead.html#tymethod.jump) instead of being generated.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate tapas;
+/// # use tapas::quasi::{Halton, Interleave};
+/// # use tapas::adapter::Thin;
+/// use rand::Rng;
+///
+/// # fn main() {
+/// let gen = Interleave::new(&[Halton::new(1, 2).unwrap(), Halton::new(1, 3).unwrap()]).unwrap();
+///
+/// // Keep points 0, 2, 4, ... of the two-dimensional sequence
+/// let mut thin = Thin::with_dimensions(gen, 2, 2);
+/// assert_eq!((thin.next_f64(), thin.next_f64()), (1. / 2., 1. / 3.));
+/// assert_eq!((thin.next_f64(), thin.next_f64()), (3. / 4., 1. / 9.));
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Thin<R> {
+    inner: R,
+    step: u64,
+    dimensions: usize,
+    /// Number of draws taken from the current point
+    position: usize,
+}
+
+impl<R: Rng + JumpAhead> Thin<R> {
+    /// Keep every `k`-th value of a scalar generator
+    pub fn new(inner: R, k: u64) -> Thin<R> {
+        Thin::with_dimensions(inner, k, 1)
+    }
+
+    /// Keep every `k`-th point of a generator producing points of `dimensions` values
+    ///
+    /// # Panics
+    ///
+    /// If `k` or `dimensions` is zero.
+    pub fn with_dimensions(inner: R, k: u64, dimensions: usize) -> Thin<R> {
+        assert!(k > 0, "thinning step must be positive");
+        assert!(dimensions > 0, "points need at least one dimension");
+
+        Thin { inner, step: k, dimensions, position: 0 }
+    }
+
+    /// Unwrap the thinned generator
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    #[inline]
+    fn drawn(&mut self) {
+        self.position += 1;
+        if self.position == self.dimensions {
+            self.position = 0;
+            let skip = (self.step - 1) * self.dimensions as u64;
+            if skip > 0 {
+                self.inner.jump(skip);
+            }
+        }
+    }
+}
+
+macro_rules! thin_next {
+    ($func:ident, $type:ident) => {
+        fn $func(&mut self) -> $type {
+            let value = self.inner.$func();
+            self.drawn();
+            value
+        }
+    };
+}
+
+impl<R: Rng + JumpAhead> Rng for Thin<R> {
+    thin_next!(next_u32, u32);
+    thin_next!(next_u64, u64);
+    thin_next!(next_f32, f32);
+    thin_next!(next_f64, f64);
+}
+
+impl<R: Rng + JumpAhead> JumpAhead for Thin<R> {
+    fn jump(&mut self, n: u64) {
+        let d = self.dimensions as u64;
+        let target = self.position as u64 + n;
+        let (points, position) = (target / d, target % d);
+
+        // Whole kept points span `step` points of the inner generator
+        let inner = points * self.step * d + position - self.position as u64;
+        if inner > 0 {
+            self.inner.jump(inner);
+        }
+        self.position = position as usize;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::Halton;
+
+    #[test]
+    fn scalar_thinning() {
+        let mut thin = Thin::new(Halton::new(1, 2).unwrap(), 3);
+        let kept: Vec<f64> = (0..3).map(|_| thin.next_f64()).collect();
+        assert_eq!(kept, vec![1. / 2., 1. / 8., 7. / 8.]);
+    }
+
+    #[test]
+    fn jump_matches_drawing() {
+        let gen = Halton::new(1, 5).unwrap();
+        let mut jumped = Thin::with_dimensions(gen.clone(), 4, 3);
+        let mut drawn = Thin::with_dimensions(gen, 4, 3);
+
+        jumped.next_f64();
+        drawn.next_f64();
+        jumped.jump(7);
+        for _ in 0..7 {
+            drawn.next_f64();
+        }
+
+        for _ in 0..5 {
+            abs_err_eq!((jumped.next_f64()) == (drawn.next_f64()) ~ 1e-15);
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use rand::Rng;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Global switch for every [`Traced`] generator, enabled by default
+///
+/// [`Traced`]: struct.Traced.html
+static TRACING: AtomicBool = AtomicBool::new(true);
+
+/// Enable or disable reporting for every [`Traced`] generator at once
+///
+/// Draws are still counted while tracing is disabled, so the indices reported after re-enabling
+/// remain accurate.
+///
+/// [`Traced`]: struct.Traced.html
+pub fn set_tracing(enabled: bool) {
+    TRACING.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether [`Traced`] generators currently report draws
+///
+/// [`Traced`]: struct.Traced.html
+pub fn tracing() -> bool {
+    TRACING.load(Ordering::Relaxed)
+}
+
+/// A single draw reported by a [`Traced`] generator
+///
+/// [`Traced`]: struct.Traced.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Draw<'a> {
+    /// Name the generator was registered with
+    pub name: &'a str,
+    /// Dimension of the point the draw belongs to
+    pub dimension: usize,
+    /// Index of the point the draw belongs to
+    pub index: u64,
+}
+
+/// Generator wrapper which reports every draw to a callback
+///
+/// Draws are attributed to points of a fixed number of dimensions: the `n`-th draw belongs to
+/// dimension `n % dimensions` of point `n / dimensions`.  When a simulation pulls the wrong
+/// number of values per point, the reported dimensions drift and show exactly which consumer
+/// took the extra draw.
+///
+/// Reporting can be toggled per generator with [`set_enabled`] or for all generators with
+/// [`set_tracing`].
+///
+/// # Examples
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate tapas;
+/// # use tapas::quasi::Halton;
+/// # use tapas::adapter::Traced;
+/// use rand::Rng;
+/// use std::sync::mpsc::channel;
+///
+/// # fn main() {
+/// let (tx, rx) = channel();
+/// let mut gen = Traced::new(Halton::new(1, 2).unwrap(), "halton-2", 2, move |draw| {
+///     tx.send((draw.dimension, draw.index)).unwrap();
+/// });
+///
+/// gen.next_f64();
+/// gen.next_f64();
+/// gen.next_f64();
+///
+/// let log: Vec<(usize, u64)> = rx.try_iter().collect();
+/// assert_eq!(log, vec![(0, 0), (1, 0), (0, 1)]);
+/// # }
+/// ```
+///
+/// [`set_enabled`]: #method.set_enabled
+/// [`set_tracing`]: fn.set_tracing.html
+pub struct Traced<R> {
+    inner: R,
+    name: String,
+    dimensions: usize,
+    draws: u64,
+    enabled: bool,
+    hook: Box<dyn FnMut(&Draw)>,
+}
+
+impl<R: Rng> Traced<R> {
+    /// Wrap `inner`, reporting its draws under `name` for points of `dimensions` dimensions
+    ///
+    /// # Panics
+    ///
+    /// If `dimensions` is zero.
+    pub fn new<F>(inner: R, name: &str, dimensions: usize, hook: F) -> Traced<R>
+        where F: FnMut(&Draw) + 'static {
+
+        assert!(dimensions > 0, "traced generator needs at least one dimension");
+
+        Traced {
+            inner,
+            name: name.to_owned(),
+            dimensions,
+            draws: 0,
+            enabled: true,
+            hook: Box::new(hook),
+        }
+    }
+
+    /// Enable or disable reporting for this generator
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Whether this generator reports draws, ignoring the global switch
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Total number of draws taken from the wrapped generator
+    pub fn draws(&self) -> u64 {
+        self.draws
+    }
+
+    /// Unwrap the traced generator
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn record(&mut self) {
+        if self.enabled && tracing() {
+            let dims = self.dimensions as u64;
+            let draw = Draw {
+                name: &self.name,
+                dimension: (self.draws % dims) as usize,
+                index: self.draws / dims,
+            };
+            (self.hook)(&draw);
+        }
+        self.draws += 1;
+    }
+}
+
+impl<R> fmt::Debug for Traced<R> where R: fmt::Debug {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Traced")
+            .field("inner", &self.inner)
+            .field("name", &self.name)
+            .field("dimensions", &self.dimensions)
+            .field("draws", &self.draws)
+            .field("enabled", &self.enabled)
+            .finish()
+    }
+}
+
+macro_rules! traced_next {
+    ($func:ident, $type:ident) => {
+        fn $func(&mut self) -> $type {
+            self.record();
+            self.inner.$func()
+        }
+    };
+}
+
+impl<R: Rng> Rng for Traced<R> {
+    traced_next!(next_u32, u32);
+    traced_next!(next_u64, u64);
+    traced_next!(next_f32, f32);
+    traced_next!(next_f64, f64);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::Halton;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn passes_values_through() {
+        let mut plain = Halton::new(1, 3).unwrap();
+        let mut traced = Traced::new(Halton::new(1, 3).unwrap(), "h", 1, |_| {});
+        for _ in 0..10 {
+            assert_eq!(plain.next_f64(), traced.next_f64());
+        }
+        assert_eq!(traced.draws(), 10);
+    }
+
+    #[test]
+    fn disabled_draws_still_count() {
+        let (tx, rx) = channel();
+        let mut gen = Traced::new(Halton::new(1, 2).unwrap(), "h", 3, move |d| {
+            tx.send((d.name.to_owned(), d.dimension, d.index)).unwrap();
+        });
+
+        gen.set_enabled(false);
+        for _ in 0..4 {
+            gen.next_u32();
+        }
+        gen.set_enabled(true);
+        gen.next_u32();
+
+        let log: Vec<_> = rx.try_iter().collect();
+        assert_eq!(log, vec![("h".to_owned(), 1, 1)]);
+    }
+}
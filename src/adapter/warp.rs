@@ -0,0 +1,106 @@
+use sampler::PointSampler;
+
+/// Point generator wrapper which maps every point through a transform of fixed dimensions
+///
+/// Points of `I` dimensions are drawn from the inner generator, passed to the transform, and
+/// returned as points of `O` dimensions, usually one of the mappings of the [`transform`]
+/// module.  Implements [`Iterator`] with arrays as items, so the points of two and three
+/// dimensional shapes can be destructured directly.  Usually built with
+/// [`PointSampler::warp`].
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::Sobol;
+/// # use tapas::sampler::PointSampler;
+/// # use tapas::transform::unit_disk;
+/// let mut disk = Sobol::new(2).unwrap().warp(unit_disk);
+/// assert_eq!(disk.nth(1), Some([0., 0.]));
+/// for [x, y] in disk.take(100) {
+///     assert!(x * x + y * y <= 1.);
+/// }
+/// ```
+///
+/// [`transform`]: ../../transform/index.html
+/// [`Iterator`]: https://doc.rust-lang.org/std/iter/trait.Iterator.html
+/// [`PointSampler::warp`]: ../../sampler/trait.PointSampler.html#method.warp
+#[derive(Debug, Clone)]
+pub struct Warp<P, const I: usize, const O: usize> {
+    inner: P,
+    transform: fn([f64; I]) -> [f64; O],
+}
+
+impl<P: PointSampler, const I: usize, const O: usize> Warp<P, I, O> {
+    /// Map the points of `inner` through `transform`
+    ///
+    /// # Panics
+    ///
+    /// If `inner` does not have `I` dimensions.
+    pub fn new(inner: P, transform: fn([f64; I]) -> [f64; O]) -> Warp<P, I, O> {
+        assert_eq!(inner.dimensions(), I, "{}-dimensional transform of {}-dimensional points",
+            I, inner.dimensions());
+        Warp { inner, transform }
+    }
+
+    /// Unwrap the transformed generator
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+impl<P: PointSampler, const I: usize, const O: usize> PointSampler for Warp<P, I, O> {
+    fn dimensions(&self) -> usize {
+        O
+    }
+
+    fn next_point(&mut self, out: &mut [f64]) {
+        assert_eq!(out.len(), O, "{}-dimensional buffer for {}-dimensional points", out.len(), O);
+        let mut u = [0.; I];
+        self.inner.next_point(&mut u);
+        out.copy_from_slice(&(self.transform)(u));
+    }
+}
+
+impl<P, const I: usize, const O: usize> From<Warp<P, I, O>> for Box<dyn PointSampler>
+    where P: PointSampler + 'static {
+
+    fn from(gen: Warp<P, I, O>) -> Box<dyn PointSampler> {
+        Box::new(gen)
+    }
+}
+
+impl<P: PointSampler, const I: usize, const O: usize> Iterator for Warp<P, I, O> {
+    type Item = [f64; O];
+
+    fn next(&mut self) -> Option<[f64; O]> {
+        let mut u = [0.; I];
+        self.inner.next_point(&mut u);
+        Some((self.transform)(u))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::HaltonSeq;
+    use transform::unit_disk;
+
+    #[test]
+    fn applies_transform() {
+        let points: Vec<Vec<f64>> = HaltonSeq::new(2).unwrap().take(30).collect();
+        let mut warped = Warp::new(HaltonSeq::new(2).unwrap(), unit_disk);
+        assert_eq!(warped.dimensions(), 2);
+
+        let mut out = [0.; 2];
+        for p in points {
+            warped.next_point(&mut out);
+            assert_eq!(out, unit_disk([p[0], p[1]]));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn mismatched_dimensions() {
+        Warp::new(HaltonSeq::new(3).unwrap(), unit_disk);
+    }
+}
@@ -0,0 +1,199 @@
+//! # Allocation
+//!
+//! Assign experimental units to treatment arms with a low-discrepancy stream per covariate bin.
+//!
+//! Bernoulli randomization flips an independent coin for every unit, so arm sizes within a
+//! covariate bin drift apart by `O(√n)`.  Driving the assignment from a van der Corput stream
+//! instead keeps every bin within `O(log n)` of its target proportions at every point in time.
+
+use rand::Rng;
+
+use error::{Error, Result};
+use quasi::Halton;
+
+/// Treatment-arm allocator stratified over covariate bins
+///
+/// Each covariate bin owns an independent base-2 Halton stream; an incoming unit draws the next
+/// value from its bin's stream and the arm is chosen by inverting the cumulative arm weights.
+/// Units in the same bin are therefore spread evenly over the arms as they arrive.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::allocation::Allocator;
+/// // Two arms with equal weight, units split over three covariate bins
+/// let mut alloc = Allocator::balanced(2, 3).unwrap();
+///
+/// let arms: Vec<usize> = (0..4).map(|_| alloc.assign(1)).collect();
+/// assert_eq!(arms.iter().filter(|&&a| a == 0).count(), 2);
+/// ```
+///
+/// A deterministic allocation is predictable, so for a live experiment shift every bin's stream
+/// by an independent random offset:
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate tapas;
+/// # use tapas::allocation::Allocator;
+/// # fn main() {
+/// let mut alloc = Allocator::new(&[0.25, 0.75], 4).unwrap().shifted(&mut rand::thread_rng());
+/// let arm = alloc.assign(2);
+/// assert!(arm < 2);
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Allocator {
+    /// Cumulative arm weights normalized so the last element is `1`
+    cdf: Vec<f64>,
+    /// One low-discrepancy stream per covariate bin
+    strata: Vec<Halton>,
+    /// Cranley-Patterson rotation applied to each bin's stream
+    shifts: Vec<f64>,
+}
+
+impl Allocator {
+    /// Create an allocator with relative arm `weights` over `bins` covariate bins
+    ///
+    /// Weights do not need to sum to one, but must be non-negative with a positive total.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidParameter`] if there are no arms or bins, or the weights are
+    /// negative, not finite, or sum to zero.
+    ///
+    /// [`Error::InvalidParameter`]: ../enum.Error.html#variant.InvalidParameter
+    pub fn new(weights: &[f64], bins: usize) -> Result<Allocator> {
+        let invalid = |name, reason: String| Err(Error::InvalidParameter { name, reason });
+        if weights.is_empty() {
+            return invalid("weights", "no treatment arms provided".to_owned());
+        }
+        if bins == 0 {
+            return invalid("bins", "no covariate bins provided".to_owned());
+        }
+        if !weights.iter().all(|&w| w >= 0. && w.is_finite()) {
+            return invalid("weights", format!("negative or infinite arm weight in {:?}", weights));
+        }
+
+        let total: f64 = weights.iter().sum();
+        if total <= 0. {
+            return invalid("weights", format!("arm weights {:?} sum to zero", weights));
+        }
+
+        let mut acc = 0.;
+        let mut cdf: Vec<f64> = weights.iter().map(|w| { acc += w / total; acc }).collect();
+        // Guard the final bucket against rounding so every draw lands on an arm
+        *cdf.last_mut().unwrap() = 1.;
+
+        Ok(Allocator {
+            cdf,
+            strata: vec![Halton::new(1, 2).unwrap(); bins],
+            shifts: vec![0.; bins],
+        })
+    }
+
+    /// Create an allocator with `arms` equally weighted arms over `bins` covariate bins
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidParameter`] if `arms` or `bins` is zero.
+    ///
+    /// [`Error::InvalidParameter`]: ../enum.Error.html#variant.InvalidParameter
+    pub fn balanced(arms: usize, bins: usize) -> Result<Allocator> {
+        Allocator::new(&vec![1.; arms], bins)
+    }
+
+    /// Randomize the allocation by shifting each bin's stream by an independent uniform offset
+    ///
+    /// Shifting modulo one preserves the balance of each stream while making the assignment of
+    /// any individual unit unpredictable.
+    pub fn shifted<R: Rng>(mut self, rng: &mut R) -> Allocator {
+        for shift in self.shifts.iter_mut() {
+            *shift = rng.next_f64();
+        }
+        self
+    }
+
+    /// Number of treatment arms
+    pub fn arms(&self) -> usize {
+        self.cdf.len()
+    }
+
+    /// Number of covariate bins
+    pub fn bins(&self) -> usize {
+        self.strata.len()
+    }
+
+    /// Assign the next unit from covariate bin `bin` to a treatment arm
+    ///
+    /// # Panics
+    ///
+    /// If `bin` is not less than the number of bins the allocator was constructed with.
+    pub fn assign(&mut self, bin: usize) -> usize {
+        let u = (self.strata[bin].next_f64() + self.shifts[bin]).fract();
+
+        // The final cdf entry is exactly one, so the search always finds an arm
+        self.cdf.iter().position(|&c| u < c).unwrap_or(self.cdf.len() - 1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{SeedableRng, XorShiftRng};
+
+    fn counts(alloc: &mut Allocator, bin: usize, units: usize) -> Vec<usize> {
+        let mut counts = vec![0; alloc.arms()];
+        for _ in 0..units {
+            counts[alloc.assign(bin)] += 1;
+        }
+        counts
+    }
+
+    #[test]
+    fn power_of_two_arms_balance_exactly() {
+        let mut alloc = Allocator::balanced(4, 2).unwrap();
+        for _ in 0..8 {
+            assert_eq!(counts(&mut alloc, 0, 4), vec![1, 1, 1, 1]);
+        }
+    }
+
+    #[test]
+    fn bins_are_independent() {
+        let mut alloc = Allocator::balanced(2, 2).unwrap();
+        let first = alloc.assign(0);
+        // A fresh bin starts from the beginning of its own stream
+        assert_eq!(alloc.assign(1), first);
+    }
+
+    #[test]
+    fn weighted_arms_track_target() {
+        for seed in 1..9 {
+            let mut rng = XorShiftRng::from_seed([seed, 2, 3, 4]);
+            let mut alloc = Allocator::new(&[1., 3.], 1).unwrap().shifted(&mut rng);
+            let c = counts(&mut alloc, 0, 1000);
+            abs_err_eq!((c[0] as f64) == 250. ~ 10.);
+        }
+    }
+
+    #[test]
+    fn uneven_arms_stay_close_to_balance() {
+        let mut alloc = Allocator::balanced(3, 1).unwrap();
+        let c = counts(&mut alloc, 0, 999);
+        for &n in c.iter() {
+            abs_err_eq!((n as f64) == 333. ~ 10.);
+        }
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        let name = |result: Result<Allocator>| match result {
+            Err(Error::InvalidParameter { name, .. }) => name,
+            other => panic!("expected an invalid parameter, found {:?}", other),
+        };
+        assert_eq!(name(Allocator::new(&[], 1)), "weights");
+        assert_eq!(name(Allocator::new(&[1.], 0)), "bins");
+        assert_eq!(name(Allocator::new(&[1., -1.], 1)), "weights");
+        assert_eq!(name(Allocator::new(&[0., 0.], 1)), "weights");
+        assert_eq!(name(Allocator::balanced(0, 3)), "weights");
+    }
+}
@@ -0,0 +1,113 @@
+//! Point sets as ndarray arrays
+//!
+//! [`ToArray2`] draws `n` points of any [`PointSampler`] into an [`Array2`] of `n` rows and
+//! one column per dimension, the usual layout of a design matrix in numerical Rust.  A
+//! [`PointSet`] can be viewed the same way without copying, and [`PointSet::dimension_views`]
+//! walks its columns for statistics of one dimension at a time.
+//!
+//! Only available with the `ndarray` feature.
+//!
+//! [`ToArray2`]: trait.ToArray2.html
+//! [`PointSampler`]: ../sampler/trait.PointSampler.html
+//! [`PointSet`]: ../point_set/struct.PointSet.html
+//! [`PointSet::dimension_views`]: ../point_set/struct.PointSet.html#method.dimension_views
+//! [`Array2`]: https://docs.rs/ndarray/*/ndarray/type.Array2.html
+
+pub use ndarray::{Array2, ArrayView1, ArrayView2, Axis};
+
+use point_set::PointSet;
+use sampler::PointSampler;
+
+/// Draw points of a generator into an array
+///
+/// Implemented for every [`PointSampler`].
+///
+/// # Examples
+///
+/// ```
+/// # extern crate tapas;
+/// # use tapas::quasi::Sobol;
+/// use tapas::array::{Axis, ToArray2};
+///
+/// # fn main() {
+/// let design = Sobol::new(3).unwrap().to_array2(64);
+/// assert_eq!(design.dim(), (64, 3));
+///
+/// // Every dimension of the first 64 points is evenly spread over the unit interval
+/// let means = design.mean_axis(Axis(0)).unwrap();
+/// assert!(means.iter().all(|&m| (m - 63. / 128.).abs() < 1e-12));
+/// # }
+/// ```
+///
+/// [`PointSampler`]: ../sampler/trait.PointSampler.html
+pub trait ToArray2: PointSampler {
+    /// Next `n` points as the rows of an array of `n` rows and one column per dimension
+    fn to_array2(&mut self, n: usize) -> Array2<f64> {
+        let mut array = Array2::zeros((n, self.dimensions()));
+        for mut row in array.rows_mut() {
+            // Rows of a new array are contiguous
+            self.next_point(row.as_slice_mut().unwrap());
+        }
+        array
+    }
+}
+
+impl<P: PointSampler + ?Sized> ToArray2 for P {}
+
+impl PointSet {
+    /// Array view of the set, with one row per point and one column per dimension
+    pub fn view(&self) -> ArrayView2<'_, f64> {
+        ArrayView2::from_shape((self.len(), self.dimensions()), self.as_slice())
+            .expect("point set holds whole points")
+    }
+
+    /// Views of the coordinates of every point in each dimension, in order
+    ///
+    /// ```
+    /// # extern crate tapas;
+    /// # use tapas::point_set::PointSet;
+    /// # use tapas::quasi::HaltonSeq;
+    /// # fn main() {
+    /// let set = PointSet::from_sampler(HaltonSeq::new(2).unwrap(), 8);
+    /// let spreads: Vec<f64> = set.dimension_views().map(|x| x.std(0.)).collect();
+    /// assert_eq!(spreads.len(), 2);
+    /// assert!(spreads.iter().all(|&s| (s - 12f64.sqrt().recip()).abs() < 0.05));
+    /// # }
+    /// ```
+    pub fn dimension_views(&self) -> impl ExactSizeIterator<Item = ArrayView1<'_, f64>> {
+        let view = self.view();
+        (0..self.dimensions()).map(move |j| view.index_axis_move(Axis(1), j))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::{HaltonSeq, Sobol};
+
+    #[test]
+    fn arrays_match_point_sets() {
+        let set = PointSet::from_sampler(Sobol::new(4).unwrap(), 50);
+        let array = Sobol::new(4).unwrap().to_array2(50);
+        assert_eq!(array.view(), set.view());
+        for (i, p) in set.iter().enumerate() {
+            assert_eq!(array.row(i).as_slice().unwrap(), p);
+        }
+    }
+
+    #[test]
+    fn views_follow_columns() {
+        let set = PointSet::from_sampler(HaltonSeq::new(3).unwrap(), 20);
+        assert_eq!(set.dimension_views().len(), 3);
+        for (j, x) in set.dimension_views().enumerate() {
+            assert_eq!(x.to_vec(), set.column(j).collect::<Vec<f64>>());
+        }
+        assert_eq!(PointSet::new(2).view().dim(), (0, 2));
+    }
+
+    #[test]
+    fn sampler_objects() {
+        let mut boxed: Box<dyn PointSampler> = Sobol::new(2).unwrap().into();
+        assert_eq!(boxed.to_array2(3).row(1).to_vec(), vec![0.5, 0.5]);
+    }
+}
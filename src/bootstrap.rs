@@ -0,0 +1,119 @@
+//! # Bootstrap
+//!
+//! Generate index sets for bootstrap resampling from any uniform generator.
+//!
+//! Each replicate draws `n` observations with replacement by scaling a uniform draw to an index.
+//! Fed from a quasi-random stream, the draws of consecutive replicates cover the observations
+//! far more evenly than independent draws do, which reduces the Monte Carlo noise of bootstrap
+//! estimates for a given number of replicates.  The [`balanced`] variant goes further and
+//! guarantees that every observation appears exactly once per replicate on aggregate.
+//!
+//! [`balanced`]: fn.balanced.html
+//!
+//! # References
+//! - Davison, A. C., Hinkley, D. V., Schechtman, E., Efficient Bootstrap Simulation
+//! - Do, K.-A., Hall, P., Quasi-Random Resampling for the Bootstrap
+
+use rand::Rng;
+
+/// Draw `replicates` resamples of `n` observation indices with replacement
+///
+/// Indices of replicate `r` are taken from draws `r * n` to `(r + 1) * n - 1` of `rng`.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::Halton;
+/// # use tapas::bootstrap::resamples;
+/// let sets = resamples(4, 2, &mut Halton::new(1, 2).unwrap());
+///
+/// // A base-2 stream visits every quarter of the unit interval once per four draws
+/// assert_eq!(sets, vec![vec![2, 1, 3, 0], vec![2, 1, 3, 0]]);
+/// ```
+pub fn resamples<R: Rng>(n: usize, replicates: usize, rng: &mut R) -> Vec<Vec<usize>> {
+    (0..replicates)
+        .map(|_| (0..n).map(|_| index(rng.next_f64(), n)).collect())
+        .collect()
+}
+
+/// Draw `replicates` resamples of `n` indices in which every index appears `replicates` times
+///
+/// This is the balanced bootstrap: `replicates` copies of the indices are concatenated, shuffled
+/// by a Fisher-Yates permutation driven by `rng`, and cut into replicates of length `n`.  The
+/// first-order bias of the bootstrap mean vanishes since every observation carries the same
+/// total weight across replicates.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate tapas;
+/// # use tapas::bootstrap::balanced;
+/// # fn main() {
+/// let sets = balanced(5, 10, &mut rand::thread_rng());
+///
+/// let mut counts = [0; 5];
+/// for i in sets.iter().flat_map(|s| s.iter()) {
+///     counts[*i] += 1;
+/// }
+/// assert_eq!(counts, [10; 5]);
+/// # }
+/// ```
+pub fn balanced<R: Rng>(n: usize, replicates: usize, rng: &mut R) -> Vec<Vec<usize>> {
+    if n == 0 {
+        return vec![Vec::new(); replicates];
+    }
+
+    let mut pool: Vec<usize> = (0..replicates).flat_map(|_| 0..n).collect();
+
+    for i in (1..pool.len()).rev() {
+        let j = index(rng.next_f64(), i + 1);
+        pool.swap(i, j);
+    }
+
+    pool.chunks(n).map(|c| c.to_vec()).collect()
+}
+
+/// Map a uniform draw in `[0, 1)` to an index below `n`
+#[inline]
+fn index(u: f64, n: usize) -> usize {
+    // Clamp guards draws which round up to exactly one
+    ((u * n as f64) as usize).min(n - 1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::Halton;
+    use rand::{SeedableRng, XorShiftRng};
+
+    #[test]
+    fn indices_in_range() {
+        let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+        let sets = resamples(7, 20, &mut rng);
+        assert_eq!(sets.len(), 20);
+        assert!(sets.iter().all(|s| s.len() == 7 && s.iter().all(|&i| i < 7)));
+    }
+
+    #[test]
+    fn balanced_replicates() {
+        let sets = balanced(6, 4, &mut Halton::new(1, 3).unwrap());
+        assert_eq!(sets.len(), 4);
+
+        let mut counts = [0; 6];
+        for set in sets.iter() {
+            assert_eq!(set.len(), 6);
+            for &i in set.iter() {
+                counts[i] += 1;
+            }
+        }
+        assert_eq!(counts, [4; 6]);
+    }
+
+    #[test]
+    fn no_observations() {
+        let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+        assert_eq!(balanced(0, 3, &mut rng), vec![Vec::<usize>::new(); 3]);
+        assert_eq!(resamples(0, 2, &mut rng), vec![Vec::<usize>::new(); 2]);
+    }
+}
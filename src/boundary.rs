@@ -0,0 +1,249 @@
+//! # Boundary Sampling
+//!
+//! Distribute points uniformly over the surface of regions rather than their volume, as needed
+//! for boundary integrals and flux estimates.
+//!
+//! A point on a `d`-dimensional boundary consumes `d - 1` values from the generator, the
+//! dimension of the surface itself.
+
+use rand::Rng;
+use rand::distributions::{IndependentSample, Sample};
+use std::f64::consts::PI;
+
+use transform::unit_sphere;
+
+/// Area-weighted sampler over the faces of an axis-aligned box
+///
+/// The first value selects one of the `2d` faces by inverting their cumulative area and is then
+/// rescaled within the face's bucket to position the point along the first free axis, as in
+/// [`MeshSampler`].  The remaining values fill the other free axes in order.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate tapas;
+/// # use tapas::quasi::{Halton, Interleave};
+/// # use tapas::boundary::BoxSurface;
+/// use rand::distributions::IndependentSample;
+///
+/// # fn main() {
+/// let cube = BoxSurface::unit(3);
+/// assert_eq!(cube.area(), 6.);
+///
+/// let mut gen = Interleave::new(&[Halton::new(1, 2).unwrap(), Halton::new(1, 3).unwrap()])
+///     .unwrap();
+/// let p = cube.ind_sample(&mut gen);
+/// assert!(p.iter().any(|&x| x == 0. || x == 1.));
+/// # }
+/// ```
+///
+/// [`MeshSampler`]: ../mesh/struct.MeshSampler.html
+#[derive(Debug, Clone)]
+pub struct BoxSurface {
+    lo: Vec<f64>,
+    hi: Vec<f64>,
+    /// Cumulative area fractions of the faces, low then high face of each axis in turn
+    cdf: Vec<f64>,
+    area: f64,
+}
+
+impl BoxSurface {
+    /// Sampler over the boundary of the box with corners `lo` and `hi`
+    ///
+    /// # Panics
+    ///
+    /// If the corners have different dimensions, there are fewer than two dimensions, or the
+    /// boundary has no area.
+    pub fn new(lo: &[f64], hi: &[f64]) -> BoxSurface {
+        assert_eq!(lo.len(), hi.len(), "corners have different dimensions");
+        assert!(lo.len() >= 2, "boundary of a {}-dimensional box", lo.len());
+
+        let sides: Vec<f64> = lo.iter().zip(hi).map(|(l, h)| h - l).collect();
+        let faces: Vec<f64> = (0..sides.len())
+            .map(|j| sides.iter().enumerate().filter(|&(i, _)| i != j).map(|(_, s)| s).product())
+            .collect();
+        let area = 2. * faces.iter().sum::<f64>();
+        assert!(area > 0., "box {:?} to {:?} has no surface area", lo, hi);
+
+        let mut acc = 0.;
+        let mut cdf = Vec::with_capacity(2 * faces.len());
+        for f in faces.iter() {
+            for _ in 0..2 {
+                acc += f / area;
+                cdf.push(acc);
+            }
+        }
+        *cdf.last_mut().unwrap() = 1.;
+
+        BoxSurface { lo: lo.to_vec(), hi: hi.to_vec(), cdf, area }
+    }
+
+    /// Sampler over the boundary of the unit cube in `dimensions` dimensions
+    pub fn unit(dimensions: usize) -> BoxSurface {
+        BoxSurface::new(&vec![0.; dimensions], &vec![1.; dimensions])
+    }
+
+    /// Total surface area of the box
+    pub fn area(&self) -> f64 {
+        self.area
+    }
+
+    /// Map `d - 1` values in `[0, 1)` onto the boundary
+    ///
+    /// # Panics
+    ///
+    /// If `u` does not have `d - 1` values.
+    pub fn point(&self, u: &[f64]) -> Vec<f64> {
+        let d = self.lo.len();
+        assert_eq!(u.len(), d - 1, "{} values for a {}-dimensional boundary", u.len(), d - 1);
+
+        let face = self.cdf.iter().position(|&c| u[0] < c).unwrap_or(self.cdf.len() - 1);
+        let start = if face == 0 { 0. } else { self.cdf[face - 1] };
+        let first = ((u[0] - start) / (self.cdf[face] - start)).min(1.);
+        let (axis, high) = (face / 2, face % 2 == 1);
+
+        let mut free = Some(first).into_iter().chain(u[1..].iter().cloned());
+        (0..d)
+            .map(|j| {
+                if j == axis {
+                    if high { self.hi[j] } else { self.lo[j] }
+                } else {
+                    self.lo[j] + free.next().unwrap() * (self.hi[j] - self.lo[j])
+                }
+            })
+            .collect()
+    }
+}
+
+impl Sample<Vec<f64>> for BoxSurface {
+    fn sample<R: Rng>(&mut self, rng: &mut R) -> Vec<f64> {
+        self.ind_sample(rng)
+    }
+}
+
+impl IndependentSample<Vec<f64>> for BoxSurface {
+    fn ind_sample<R: Rng>(&self, rng: &mut R) -> Vec<f64> {
+        let u: Vec<f64> = (1..self.lo.len()).map(|_| rng.next_f64()).collect();
+        self.point(&u)
+    }
+}
+
+/// Uniform sampler over a circle or the surface of a three-dimensional sphere
+///
+/// Circles consume one value, the angle; spheres consume two, mapped by Archimedes' equal-area
+/// projection onto the cylinder, which keeps the stratification of the input.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::boundary::SphereSurface;
+/// let sphere = SphereSurface::new(&[0., 0., 0.], 2.);
+/// let p = sphere.point(&[0.5, 0.]);
+/// assert_eq!(p, vec![2., 0., 0.]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SphereSurface {
+    center: Vec<f64>,
+    radius: f64,
+}
+
+impl SphereSurface {
+    /// Sampler over the sphere of `radius` around `center`
+    ///
+    /// # Panics
+    ///
+    /// If the center is not two or three dimensional, or the radius is negative.
+    pub fn new(center: &[f64], radius: f64) -> SphereSurface {
+        assert!(center.len() == 2 || center.len() == 3,
+            "{}-dimensional spheres are not supported", center.len());
+        assert!(radius >= 0., "negative radius {}", radius);
+
+        SphereSurface { center: center.to_vec(), radius }
+    }
+
+    /// Length of the circle or area of the sphere
+    pub fn area(&self) -> f64 {
+        match self.center.len() {
+            2 => 2. * PI * self.radius,
+            _ => 4. * PI * self.radius * self.radius,
+        }
+    }
+
+    /// Map `d - 1` values in `[0, 1)` onto the sphere
+    ///
+    /// # Panics
+    ///
+    /// If `u` does not have `d - 1` values.
+    pub fn point(&self, u: &[f64]) -> Vec<f64> {
+        let (c, r) = (&self.center, self.radius);
+        assert_eq!(u.len(), c.len() - 1, "{} values for a {}-dimensional sphere", u.len(),
+            c.len() - 1);
+
+        if c.len() == 2 {
+            let theta = 2. * PI * u[0];
+            vec![c[0] + r * theta.cos(), c[1] + r * theta.sin()]
+        } else {
+            let [x, y, z] = unit_sphere([u[0], u[1]]);
+            vec![c[0] + r * x, c[1] + r * y, c[2] + r * z]
+        }
+    }
+}
+
+impl Sample<Vec<f64>> for SphereSurface {
+    fn sample<R: Rng>(&mut self, rng: &mut R) -> Vec<f64> {
+        self.ind_sample(rng)
+    }
+}
+
+impl IndependentSample<Vec<f64>> for SphereSurface {
+    fn ind_sample<R: Rng>(&self, rng: &mut R) -> Vec<f64> {
+        let u: Vec<f64> = (1..self.center.len()).map(|_| rng.next_f64()).collect();
+        self.point(&u)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::{Halton, Interleave};
+
+    #[test]
+    fn faces_proportional_to_area() {
+        // Faces normal to x have area 1, the others area 3
+        let surface = BoxSurface::new(&[0., 0., 0.], &[3., 1., 1.]);
+        assert_eq!(surface.area(), 14.);
+
+        let mut gen = Interleave::new(&[Halton::new(1, 2).unwrap(), Halton::new(1, 3).unwrap()])
+            .unwrap();
+        let n = 1400;
+        let on_x = (0..n)
+            .map(|_| surface.ind_sample(&mut gen))
+            .filter(|p| p[0] == 0. || p[0] == 3.)
+            .count();
+        abs_err_eq!((on_x as f64 / n as f64) == (2. / 14.) ~ 0.005);
+    }
+
+    #[test]
+    fn square_boundary() {
+        let square = BoxSurface::unit(2);
+        let mut gen = Halton::new(1, 2).unwrap();
+        for _ in 0..100 {
+            let p = square.ind_sample(&mut gen);
+            let on_edge = p.iter().any(|&x| x == 0. || x == 1.);
+            assert!(on_edge && p.iter().all(|&x| (0. ..=1.).contains(&x)), "{:?}", p);
+        }
+    }
+
+    #[test]
+    fn sphere_points_have_radius() {
+        let sphere = SphereSurface::new(&[1., -1., 2.], 0.5);
+        let mut gen = Interleave::new(&[Halton::new(1, 2).unwrap(), Halton::new(1, 3).unwrap()])
+            .unwrap();
+        for _ in 0..100 {
+            let p = sphere.ind_sample(&mut gen);
+            let r = ((p[0] - 1.).powi(2) + (p[1] + 1.).powi(2) + (p[2] - 2.).powi(2)).sqrt();
+            abs_err_eq!(r == 0.5 ~ 1e-12);
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//! # Clustering
+//!
+//! Seeding routines for clustering algorithms driven by quasi-random streams.
+
+use rand::Rng;
+
+/// Select `k` initial cluster centers from `data` using k-means++ D² sampling
+///
+/// The first center is chosen uniformly; every following center is chosen with probability
+/// proportional to the squared distance from the nearest center picked so far.  Each choice
+/// consumes a single value from `rng` and inverts the cumulative D² weights, so driving the
+/// routine with a quasi-random stream spreads the selections over the weight distribution and
+/// reduces the variance between seeds.
+///
+/// `distance` can be any metric over the element type, so points need not be stored as slices.
+/// The indices of the selected centers are returned in the order they were chosen.
+///
+/// # Panics
+///
+/// If `k` is larger than the number of elements in `data`.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::Halton;
+/// # use tapas::cluster::kmeans_pp;
+/// let data = [0., 0.1, 0.2, 10., 10.1, 10.2, 20., 20.1, 20.2];
+///
+/// let mut gen = Halton::new(1, 2).unwrap();
+/// let mut seeds = kmeans_pp(&data, 3, |a: &f64, b: &f64| (a - b).abs(), &mut gen);
+/// seeds.sort();
+///
+/// // One center from each of the three well separated groups
+/// assert!(seeds[0] < 3 && seeds[1] >= 3 && seeds[1] < 6 && seeds[2] >= 6);
+/// ```
+pub fn kmeans_pp<T, D, R>(data: &[T], k: usize, distance: D, rng: &mut R) -> Vec<usize>
+    where D: Fn(&T, &T) -> f64,
+          R: Rng {
+
+    assert!(k <= data.len(), "cannot select {} centers from {} elements", k, data.len());
+
+    let mut centers = Vec::with_capacity(k);
+    if k == 0 {
+        return centers;
+    }
+
+    let n = data.len();
+    let first = ((rng.next_f64() * n as f64) as usize).min(n - 1);
+    centers.push(first);
+
+    // Squared distance from every element to its nearest chosen center
+    let mut d2: Vec<f64> = data.iter().map(|x| {
+        let d = distance(x, &data[first]);
+        d * d
+    }).collect();
+
+    while centers.len() < k {
+        let u = rng.next_f64();
+        let total: f64 = d2.iter().sum();
+
+        let next = if total > 0. {
+            // Invert the cumulative D² weights, skipping elements which already are centers
+            let target = u * total;
+            let mut acc = 0.;
+            let mut chosen = None;
+            for (i, &w) in d2.iter().enumerate() {
+                if w > 0. {
+                    acc += w;
+                    chosen = Some(i);
+                    if target < acc {
+                        break;
+                    }
+                }
+            }
+            chosen.unwrap()
+        } else {
+            // Every remaining element coincides with a center: pick uniformly among the rest
+            let rest: Vec<usize> = (0..n).filter(|i| !centers.contains(i)).collect();
+            rest[((u * rest.len() as f64) as usize).min(rest.len() - 1)]
+        };
+
+        centers.push(next);
+        for (i, x) in data.iter().enumerate() {
+            let d = distance(x, &data[next]);
+            if d * d < d2[i] {
+                d2[i] = d * d;
+            }
+        }
+        d2[next] = 0.;
+    }
+
+    centers
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::Halton;
+
+    fn dist(a: &[f64; 2], b: &[f64; 2]) -> f64 {
+        ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)).sqrt()
+    }
+
+    #[test]
+    fn centers_are_distinct() {
+        let data: Vec<[f64; 2]> = Halton::new(1, 2).unwrap().zip(Halton::new(1, 3).unwrap())
+            .take(50)
+            .map(|(x, y)| [x, y])
+            .collect();
+
+        let mut centers = kmeans_pp(&data, 20, dist, &mut Halton::new(1, 5).unwrap());
+        assert_eq!(centers.len(), 20);
+        centers.sort();
+        centers.dedup();
+        assert_eq!(centers.len(), 20);
+    }
+
+    #[test]
+    fn duplicates_still_yield_k_centers() {
+        let data = [[1., 1.]; 5];
+        let mut centers = kmeans_pp(&data, 5, dist, &mut Halton::new(1, 2).unwrap());
+        centers.sort();
+        assert_eq!(centers, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn too_many_centers() {
+        kmeans_pp(&[[0., 0.]], 2, dist, &mut Halton::new(1, 2).unwrap());
+    }
+}
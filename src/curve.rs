@@ -0,0 +1,377 @@
+//! # Space-Filling Curves
+//!
+//! Map points of the unit cube onto a one-dimensional ordering which preserves locality.
+//!
+//! Both the Hilbert curve and the simpler Z-order (Morton) curve are available, as index
+//! functions for single points, as orderings of point sets, and through [`CurveOrder`], which
+//! emits the points of a generator in batches sorted along a curve so that consecutive samples
+//! touch nearby parts of a spatial data structure.
+//!
+//! [`CurveOrder`]: struct.CurveOrder.html
+
+use rand::Rng;
+
+/// Index of a point of `[0, 1)^d` along the Hilbert curve
+///
+/// Each coordinate is quantized to `bits` bits, so the curve visits `2^(d·bits)` cells; points
+/// which share a cell share an index.  Coordinates outside of `[0, 1)` are clamped to the edge
+/// cells.  Uses Skilling's transposition algorithm, which works in any number of dimensions.
+///
+/// # Panics
+///
+/// If the index does not fit in 128 bits, that is when `d·bits > 128`, or if `bits` is zero.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::curve::hilbert_index;
+/// // The order-1 curve in two dimensions visits the quadrants in a U shape
+/// let quadrants = [[0.25, 0.25], [0.25, 0.75], [0.75, 0.75], [0.75, 0.25]];
+/// let index: Vec<u128> = quadrants.iter().map(|p| hilbert_index(p, 1)).collect();
+/// assert_eq!(index, vec![0, 1, 2, 3]);
+/// ```
+pub fn hilbert_index(point: &[f64], bits: u32) -> u128 {
+    assert!(bits > 0 && bits <= 64, "{} bits per axis is out of range", bits);
+    assert!(point.len() * bits as usize <= 128, "{}-dimensional index does not fit in 128 bits",
+        point.len());
+
+    hilbert_words(point, bits)[0]
+}
+
+/// Hilbert index of `point` in words of 128 bits, for any number of dimensions
+fn hilbert_words(point: &[f64], bits: u32) -> Vec<u128> {
+    let mut x: Vec<u64> = point.iter().map(|&p| quantize(p, bits)).collect();
+    axes_to_transpose(&mut x, bits);
+    interleave(&x, bits)
+}
+
+/// Index of a point of `[0, 1)^d` along the Z-order (Morton) curve
+///
+/// Coordinates are quantized as in [`hilbert_index`] and their bits interleaved, most
+/// significant bit first with the first coordinate leading.  The Z-order curve is cheaper to
+/// compute than the Hilbert curve, but makes long jumps between some consecutive cells.
+///
+/// # Panics
+///
+/// If `d·bits > 128` or if `bits` is zero.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::curve::morton_index;
+/// let quadrants = [[0.25, 0.25], [0.25, 0.75], [0.75, 0.25], [0.75, 0.75]];
+/// let index: Vec<u128> = quadrants.iter().map(|p| morton_index(p, 1)).collect();
+/// assert_eq!(index, vec![0, 1, 2, 3]);
+/// ```
+///
+/// [`hilbert_index`]: fn.hilbert_index.html
+pub fn morton_index(point: &[f64], bits: u32) -> u128 {
+    assert!(bits > 0 && bits <= 64, "{} bits per axis is out of range", bits);
+    assert!(point.len() * bits as usize <= 128, "{}-dimensional index does not fit in 128 bits",
+        point.len());
+
+    morton_words(point, bits)[0]
+}
+
+/// Morton index of `point` in words of 128 bits, for any number of dimensions
+fn morton_words(point: &[f64], bits: u32) -> Vec<u128> {
+    let x: Vec<u64> = point.iter().map(|&p| quantize(p, bits)).collect();
+    interleave(&x, bits)
+}
+
+/// Interleave the `bits` low bits of every value of `x`, most significant bit first
+///
+/// The bits fill words of 128 bits one after another, so indices of points of the same
+/// dimension compare as their vectors of words.  An index of at most 128 bits is the single
+/// word.
+fn interleave(x: &[u64], bits: u32) -> Vec<u128> {
+    let mut words = Vec::with_capacity((x.len() * bits as usize).div_ceil(128).max(1));
+    let (mut word, mut filled) = (0u128, 0);
+    for b in (0..bits).rev() {
+        for xi in x.iter() {
+            word = (word << 1) | ((xi >> b) & 1) as u128;
+            filled += 1;
+            if filled == 128 {
+                words.push(word);
+                word = 0;
+                filled = 0;
+            }
+        }
+    }
+    if filled > 0 || words.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Quantize a coordinate in `[0, 1)` to an integer with `bits` bits
+pub(crate) fn quantize(p: f64, bits: u32) -> u64 {
+    let cells = (1u128 << bits) as f64;
+    let max = ((1u128 << bits) - 1) as u64;
+    if p <= 0. {
+        0
+    } else {
+        ((p * cells) as u64).min(max)
+    }
+}
+
+/// Skilling's in-place conversion from axis coordinates to the transposed Hilbert index
+fn axes_to_transpose(x: &mut [u64], bits: u32) {
+    let n = x.len();
+    if n == 0 {
+        return;
+    }
+
+    // Inverse undo excess work
+    let mut q = 1u64 << (bits - 1);
+    while q > 1 {
+        let p = q - 1;
+        for i in 0..n {
+            if x[i] & q != 0 {
+                x[0] ^= p;
+            } else {
+                let t = (x[0] ^ x[i]) & p;
+                x[0] ^= t;
+                x[i] ^= t;
+            }
+        }
+        q >>= 1;
+    }
+
+    // Gray encode
+    for i in 1..n {
+        x[i] ^= x[i - 1];
+    }
+    let mut t = 0;
+    let mut q = 1u64 << (bits - 1);
+    while q > 1 {
+        if x[n - 1] & q != 0 {
+            t ^= q - 1;
+        }
+        q >>= 1;
+    }
+    for xi in x.iter_mut() {
+        *xi ^= t;
+    }
+}
+
+/// Space-filling curve used to order points
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Curve {
+    /// Hilbert curve, see [`hilbert_index`](fn.hilbert_index.html)
+    Hilbert,
+    /// Z-order curve, see [`morton_index`](fn.morton_index.html)
+    Morton,
+}
+
+impl Curve {
+    /// Index of `point` along the curve with `bits` bits per axis
+    pub fn index(&self, point: &[f64], bits: u32) -> u128 {
+        match *self {
+            Curve::Hilbert => hilbert_index(point, bits),
+            Curve::Morton => morton_index(point, bits),
+        }
+    }
+
+    /// Indices which sort `points` of the unit cube along the curve
+    ///
+    /// Uses as many bits per axis as fit in a 128 bit index, capped at 32.  Points of more
+    /// than 128 dimensions use a single bit per axis, and are sorted by their indices of more
+    /// than 128 bits.
+    pub fn order<T: AsRef<[f64]>>(&self, points: &[T]) -> Vec<usize> {
+        let d = points.first().map_or(1, |p| p.as_ref().len()).max(1);
+        let bits = (128 / d).clamp(1, 32) as u32;
+
+        let keys: Vec<Vec<u128>> = points.iter()
+            .map(|p| match *self {
+                Curve::Hilbert => hilbert_words(p.as_ref(), bits),
+                Curve::Morton => morton_words(p.as_ref(), bits),
+            })
+            .collect();
+        let mut order: Vec<usize> = (0..points.len()).collect();
+        order.sort_by(|&i, &j| keys[i].cmp(&keys[j]));
+        order
+    }
+}
+
+/// Indices which sort `points` of the unit cube along the Hilbert curve
+///
+/// Uses as many bits per axis as fit in a 128 bit index, capped at 32, or a single bit per
+/// axis in more than 128 dimensions.
+pub fn hilbert_order<T: AsRef<[f64]>>(points: &[T]) -> Vec<usize> {
+    Curve::Hilbert.order(points)
+}
+
+/// Indices which sort `points` of the unit cube along the Z-order curve
+///
+/// Uses as many bits per axis as fit in a 128 bit index, capped at 32, or a single bit per
+/// axis in more than 128 dimensions.
+pub fn morton_order<T: AsRef<[f64]>>(points: &[T]) -> Vec<usize> {
+    Curve::Morton.order(points)
+}
+
+/// Iterator emitting points of a generator in space-filling-curve order
+///
+/// Points of `dimensions` consecutive draws are taken from the generator in batches of `batch`
+/// points; each batch is sorted along the curve before it is emitted.  The batch as a whole
+/// keeps the distribution of the generator, only the order within a batch changes.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::{Halton, Interleave};
+/// # use tapas::curve::{Curve, CurveOrder};
+/// let gen = Interleave::new(&[Halton::new(1, 2).unwrap(), Halton::new(1, 3).unwrap()]).unwrap();
+/// let points: Vec<Vec<f64>> = CurveOrder::new(gen, 2, 4, Curve::Hilbert).take(4).collect();
+///
+/// // The first four Halton points, starting in the lower left quadrant and moving up
+/// assert_eq!(points[0], vec![1. / 8., 4. / 9.]);
+/// assert_eq!(points[1], vec![1. / 4., 2. / 3.]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CurveOrder<R> {
+    inner: R,
+    dimensions: usize,
+    batch: usize,
+    curve: Curve,
+    /// Sorted points of the current batch, in reverse so the next point is at the end
+    pending: Vec<Vec<f64>>,
+}
+
+impl<R: Rng> CurveOrder<R> {
+    /// Order batches of `batch` points of `dimensions` draws from `inner` along `curve`
+    ///
+    /// # Panics
+    ///
+    /// If `dimensions` or `batch` is zero.
+    pub fn new(inner: R, dimensions: usize, batch: usize, curve: Curve) -> CurveOrder<R> {
+        assert!(dimensions > 0, "points need at least one dimension");
+        assert!(batch > 0, "batches need at least one point");
+
+        CurveOrder { inner, dimensions, batch, curve, pending: Vec::with_capacity(batch) }
+    }
+
+    /// Unwrap the generator, dropping any points left in the current batch
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn refill(&mut self) {
+        let points: Vec<Vec<f64>> = (0..self.batch)
+            .map(|_| (0..self.dimensions).map(|_| self.inner.next_f64()).collect())
+            .collect();
+        let order = self.curve.order(&points);
+        self.pending = order.into_iter().rev().map(|i| points[i].clone()).collect();
+    }
+}
+
+impl<R: Rng> Iterator for CurveOrder<R> {
+    type Item = Vec<f64>;
+
+    fn next(&mut self) -> Option<Vec<f64>> {
+        if self.pending.is_empty() {
+            self.refill();
+        }
+        self.pending.pop()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Check that consecutive cells along the curve are neighbours in the grid
+    fn check_adjacent(d: usize, bits: u32) {
+        let side = 1usize << bits;
+        let cells = side.pow(d as u32);
+
+        let mut by_index = vec![None; cells];
+        for c in 0..cells {
+            let mut rest = c;
+            let coords: Vec<usize> = (0..d).map(|_| { let x = rest % side; rest /= side; x })
+                .collect();
+            let point: Vec<f64> = coords.iter().map(|&x| (x as f64 + 0.5) / side as f64).collect();
+            let h = hilbert_index(&point, bits) as usize;
+            assert!(by_index[h].is_none(), "index {} visited twice", h);
+            by_index[h] = Some(coords);
+        }
+
+        for pair in by_index.windows(2) {
+            let (a, b) = (pair[0].as_ref().unwrap(), pair[1].as_ref().unwrap());
+            let dist: usize = a.iter().zip(b.iter())
+                .map(|(&x, &y)| x.abs_diff(y))
+                .sum();
+            assert_eq!(dist, 1, "{:?} and {:?} are not adjacent", a, b);
+        }
+    }
+
+    #[test]
+    fn hilbert_2d_is_continuous() {
+        check_adjacent(2, 4);
+    }
+
+    #[test]
+    fn hilbert_3d_is_continuous() {
+        check_adjacent(3, 3);
+    }
+
+    #[test]
+    fn order_sorts_by_index() {
+        let points = [[0.75, 0.25], [0.25, 0.25], [0.75, 0.75], [0.25, 0.75]];
+        assert_eq!(hilbert_order(&points), vec![1, 3, 2, 0]);
+        assert_eq!(morton_order(&points), vec![1, 3, 0, 2]);
+    }
+
+    #[test]
+    fn morton_3d_visits_octants() {
+        let mut seen = [false; 8];
+        for o in 0..8 {
+            let p: Vec<f64> = (0..3).map(|j| if o >> (2 - j) & 1 == 1 { 0.75 } else { 0.25 })
+                .collect();
+            let i = morton_index(&p, 1) as usize;
+            assert_eq!(i, o);
+            seen[i] = true;
+        }
+        assert!(seen.iter().all(|&s| s));
+    }
+
+    #[test]
+    fn batches_keep_points() {
+        use quasi::Halton;
+
+        let mut plain = Halton::new(1, 5).unwrap();
+        let mut expected: Vec<f64> = (0..6).map(|_| plain.next_f64()).collect();
+        let mut ordered: Vec<f64> = CurveOrder::new(Halton::new(1, 5).unwrap(), 1, 3,
+            Curve::Morton).take(6).map(|p| p[0]).collect();
+
+        // Each batch of three is sorted, but no point moves to another batch
+        assert!(ordered[..3].windows(2).all(|w| w[0] <= w[1]));
+        expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        ordered.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(ordered, expected);
+    }
+
+    #[test]
+    fn orders_beyond_128_dimensions() {
+        use quasi::GeneralizedHalton;
+
+        let d = 200;
+        let mut points: Vec<Vec<f64>> = GeneralizedHalton::random(d, 3).take(64).collect();
+        for curve in [Curve::Hilbert, Curve::Morton].iter() {
+            let mut order = curve.order(&points);
+            order.sort();
+            assert_eq!(order, (0..64).collect::<Vec<_>>());
+        }
+
+        // Points in the same orthant but for the last axis are ordered by that axis
+        points.truncate(2);
+        points[1] = points[0].clone();
+        points[0][d - 1] = 0.75;
+        points[1][d - 1] = 0.25;
+        assert_eq!(morton_order(&points), vec![1, 0]);
+        assert_eq!(hilbert_order(&points).len(), 2);
+
+        let mut sampled = CurveOrder::new(GeneralizedHalton::random(d, 5), d, 8, Curve::Hilbert);
+        assert_eq!(sampled.nth(7).map(|p| p.len()), Some(d));
+    }
+}
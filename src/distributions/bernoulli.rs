@@ -0,0 +1,161 @@
+use rand::Rng;
+use rand::distributions::{IndependentSample, Sample};
+
+/// Bernoulli trial succeeding with probability `p`, consuming exactly one draw
+///
+/// A trial succeeds when its draw falls below `p`.  Driven by a low-discrepancy stream, the
+/// fraction of successes after `n` trials stays within `O(log n / n)` of `p`, so branching
+/// simulations see far fewer spurious runs of outcomes than with independent coins.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate tapas;
+/// # use tapas::quasi::Halton;
+/// # use tapas::distributions::QuasiBernoulli;
+/// use rand::distributions::IndependentSample;
+///
+/// # fn main() {
+/// let coin = QuasiBernoulli::new(0.3);
+/// let mut gen = Halton::new(1, 2).unwrap();
+///
+/// let heads = (0..1000).filter(|_| coin.ind_sample(&mut gen)).count();
+/// assert!((heads as i64 - 300).abs() <= 2);
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuasiBernoulli {
+    p: f64,
+}
+
+impl QuasiBernoulli {
+    /// Trial with success probability `p`
+    ///
+    /// # Panics
+    ///
+    /// If `p` is not in `[0, 1]`.
+    pub fn new(p: f64) -> QuasiBernoulli {
+        assert!((0. ..=1.).contains(&p), "probability {} is not in [0, 1]", p);
+
+        QuasiBernoulli { p }
+    }
+
+    /// Probability of success
+    pub fn p(&self) -> f64 {
+        self.p
+    }
+
+    /// Outcome of the trial for the uniform draw `u`
+    #[inline]
+    pub fn decide(&self, u: f64) -> bool {
+        u < self.p
+    }
+}
+
+impl Sample<bool> for QuasiBernoulli {
+    fn sample<R: Rng>(&mut self, rng: &mut R) -> bool {
+        self.ind_sample(rng)
+    }
+}
+
+impl IndependentSample<bool> for QuasiBernoulli {
+    fn ind_sample<R: Rng>(&self, rng: &mut R) -> bool {
+        self.decide(rng.next_f64())
+    }
+}
+
+/// Binomial count of `n` trials, each decided by its own draw
+///
+/// Every sample consumes exactly `n` consecutive draws, one per trial, so the decisions of a
+/// single sample are stratified against each other as well as across samples.  Use this when
+/// the trials stand for individual units, such as members of a population surviving a time
+/// step, rather than [`Binomial`] inversion from a single draw.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate tapas;
+/// # use tapas::quasi::Halton;
+/// # use tapas::distributions::StratifiedBinomial;
+/// use rand::distributions::IndependentSample;
+///
+/// # fn main() {
+/// let survivors = StratifiedBinomial::new(16, 0.75);
+///
+/// // Sixteen consecutive base-2 draws cover every sixteenth once
+/// assert_eq!(survivors.ind_sample(&mut Halton::new(1, 2).unwrap()), 12);
+/// # }
+/// ```
+///
+/// [`Binomial`]: ../discrete/struct.Binomial.html
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StratifiedBinomial {
+    n: u64,
+    trial: QuasiBernoulli,
+}
+
+impl StratifiedBinomial {
+    /// Count of `n` trials with success probability `p`
+    ///
+    /// # Panics
+    ///
+    /// If `p` is not in `[0, 1]`.
+    pub fn new(n: u64, p: f64) -> StratifiedBinomial {
+        StratifiedBinomial { n, trial: QuasiBernoulli::new(p) }
+    }
+
+    /// Number of trials per sample
+    pub fn trials(&self) -> u64 {
+        self.n
+    }
+
+    /// Outcomes of the `n` trials for the next draws of `rng`
+    pub fn outcomes<R: Rng>(&self, rng: &mut R) -> Vec<bool> {
+        (0..self.n).map(|_| self.trial.ind_sample(rng)).collect()
+    }
+}
+
+impl Sample<u64> for StratifiedBinomial {
+    fn sample<R: Rng>(&mut self, rng: &mut R) -> u64 {
+        self.ind_sample(rng)
+    }
+}
+
+impl IndependentSample<u64> for StratifiedBinomial {
+    fn ind_sample<R: Rng>(&self, rng: &mut R) -> u64 {
+        (0..self.n).filter(|_| self.trial.ind_sample(rng)).count() as u64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::Halton;
+
+    #[test]
+    fn certain_outcomes() {
+        let mut gen = Halton::new(1, 3).unwrap();
+        assert!((0..100).all(|_| QuasiBernoulli::new(1.).ind_sample(&mut gen)));
+        assert!((0..100).all(|_| !QuasiBernoulli::new(0.).ind_sample(&mut gen)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn invalid_probability() {
+        QuasiBernoulli::new(1.5);
+    }
+
+    #[test]
+    fn one_draw_per_trial() {
+        let binomial = StratifiedBinomial::new(5, 0.5);
+        let mut gen = Halton::new(1, 2).unwrap();
+        let mut plain = Halton::new(1, 2).unwrap();
+
+        let outcomes = binomial.outcomes(&mut gen);
+        let expected: Vec<bool> = (0..5).map(|_| plain.next_f64() < 0.5).collect();
+        assert_eq!(outcomes, expected);
+        assert_eq!(gen.next_f64(), plain.next_f64());
+    }
+}
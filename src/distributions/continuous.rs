@@ -0,0 +1,154 @@
+use std::f64::consts::PI;
+
+/// Exponential distribution with `rate` λ, sampled by inversion
+///
+/// Inverts `P(X > x) = e^(-λx)` as `-ln(1 - u) / λ`, through `ln_1p` so that small draws keep
+/// full relative precision.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::distributions::Exponential;
+/// let e = Exponential::new(2.);
+/// assert_eq!(e.quantile(0.), 0.);
+/// assert!((e.quantile(0.5) - 2f64.ln() / 2.).abs() < 1e-15);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Exponential {
+    rate: f64,
+}
+
+impl Exponential {
+    /// Exponential distribution with `rate`, of mean `1 / rate`
+    ///
+    /// # Panics
+    ///
+    /// If `rate` is not positive.
+    pub fn new(rate: f64) -> Exponential {
+        assert!(rate > 0., "rate {} must be positive", rate);
+
+        Exponential { rate }
+    }
+
+    /// Quantile of the uniform draw `u`
+    pub fn quantile(&self, u: f64) -> f64 {
+        -(-u).ln_1p() / self.rate
+    }
+}
+
+/// Cauchy distribution with `location` and `scale`, sampled by inversion
+///
+/// Inverts the distribution function as `location - scale / tan(π u)`, which keeps precision
+/// in the lower tail where `tan(π (u - 1/2))` would cancel.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::distributions::Cauchy;
+/// let c = Cauchy::new(1., 2.);
+/// assert!((c.quantile(0.5) - 1.).abs() < 1e-15);
+/// assert!((c.quantile(0.75) - 3.).abs() < 1e-15);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cauchy {
+    location: f64,
+    scale: f64,
+}
+
+impl Cauchy {
+    /// Cauchy distribution with median `location` and half-width `scale`
+    ///
+    /// # Panics
+    ///
+    /// If `scale` is not positive.
+    pub fn new(location: f64, scale: f64) -> Cauchy {
+        assert!(scale > 0., "scale {} must be positive", scale);
+
+        Cauchy { location, scale }
+    }
+
+    /// Quantile of the uniform draw `u`
+    pub fn quantile(&self, u: f64) -> f64 {
+        if u <= 0. {
+            return f64::NEG_INFINITY;
+        }
+        if u >= 1. {
+            return f64::INFINITY;
+        }
+        if u == 0.5 {
+            return self.location;
+        }
+
+        // Reflect the upper half so tan is only taken of small angles
+        let p = u.min(1. - u);
+        let x = self.scale / (PI * p).tan();
+        if u < 0.5 { self.location - x } else { self.location + x }
+    }
+}
+
+/// Weibull distribution with `scale` λ and `shape` k, sampled by inversion
+///
+/// Inverts `P(X > x) = e^(-(x/λ)^k)` as `λ (-ln(1 - u))^(1/k)`.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::distributions::{Exponential, Weibull};
+/// // Shape 1 gives the exponential distribution of rate 1 / scale
+/// let (w, e) = (Weibull::new(3., 1.), Exponential::new(1. / 3.));
+/// assert!((w.quantile(0.3) - e.quantile(0.3)).abs() < 1e-15);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Weibull {
+    scale: f64,
+    shape: f64,
+}
+
+impl Weibull {
+    /// Weibull distribution with `scale` and `shape`
+    ///
+    /// # Panics
+    ///
+    /// If either parameter is not positive.
+    pub fn new(scale: f64, shape: f64) -> Weibull {
+        assert!(scale > 0. && shape > 0., "invalid Weibull parameters {} and {}", scale, shape);
+
+        Weibull { scale, shape }
+    }
+
+    /// Quantile of the uniform draw `u`
+    pub fn quantile(&self, u: f64) -> f64 {
+        self.scale * (-(-u).ln_1p()).powf(1. / self.shape)
+    }
+}
+
+inversion_sample!(Exponential, Cauchy, Weibull);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn distribution_functions_invert() {
+        let (e, c, w) = (Exponential::new(0.5), Cauchy::new(-2., 0.5), Weibull::new(2., 1.5));
+        for &u in [1e-300, 1e-12, 0.1, 0.5, 0.9, 1. - 1e-12].iter() {
+            let x = e.quantile(u);
+            abs_err_eq!((-(-0.5 * x).exp_m1()) == u ~ (1e-15 * u.max(1e-300)));
+
+            let x = c.quantile(u);
+            abs_err_eq!((0.5 + ((x + 2.) / 0.5).atan() / PI) == u ~ 1e-15);
+
+            let x = w.quantile(u);
+            abs_err_eq!((-(-(x / 2.).powf(1.5)).exp_m1()) == u ~ 1e-14);
+        }
+    }
+
+    #[test]
+    fn cauchy_is_symmetric() {
+        let c = Cauchy::new(0., 3.);
+        for &u in [2f64.powi(-40), 0.125, 0.25].iter() {
+            assert_eq!(c.quantile(u), -c.quantile(1. - u));
+        }
+        assert_eq!((c.quantile(0.), c.quantile(1.)), (f64::NEG_INFINITY, f64::INFINITY));
+    }
+}
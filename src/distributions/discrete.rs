@@ -0,0 +1,436 @@
+use rand::Rng;
+use rand::distributions::{IndependentSample, Sample};
+
+use super::special::ln_gamma;
+
+/// Probabilities below this fraction of the mode's probability are treated as zero
+const TAIL: f64 = 1e-18;
+
+/// Cumulative distribution of a unimodal integer distribution around its mode
+///
+/// Only the support where the probability is non-negligible is stored, which is `O(σ)` values
+/// for the distributions in this module, and inversion is a binary search.
+#[derive(Debug, Clone, PartialEq)]
+struct Table {
+    /// Smallest value in the table
+    offset: u64,
+    cdf: Vec<f64>,
+}
+
+impl Table {
+    /// Tabulate outwards from the `mode`, within `[lo, hi]`
+    ///
+    /// `ratio(k)` gives `P(k + 1) / P(k)`; probabilities are built relative to the mode and
+    /// normalized at the end, so the mode's own probability is never needed.
+    fn around_mode<F: Fn(u64) -> f64>(mode: u64, lo: u64, hi: u64, ratio: F) -> Table {
+        let mut up = Vec::new();
+        let mut p = 1.;
+        let mut k = mode;
+        while k < hi {
+            p *= ratio(k);
+            if p < TAIL {
+                break;
+            }
+            up.push(p);
+            k += 1;
+        }
+
+        let mut down = Vec::new();
+        let mut p = 1.;
+        let mut k = mode;
+        while k > lo {
+            p /= ratio(k - 1);
+            if p < TAIL || !p.is_finite() {
+                break;
+            }
+            down.push(p);
+            k -= 1;
+        }
+
+        let offset = mode - down.len() as u64;
+        let weights: Vec<f64> = down.into_iter().rev().chain(Some(1.)).chain(up).collect();
+        let total: f64 = weights.iter().sum();
+
+        let mut acc = 0.;
+        let mut cdf: Vec<f64> = weights.iter().map(|w| { acc += w / total; acc }).collect();
+        *cdf.last_mut().unwrap() = 1.;
+
+        Table { offset, cdf }
+    }
+
+    /// Smallest value whose cumulative probability exceeds `u`
+    fn invert(&self, u: f64) -> u64 {
+        let i = self.cdf.partition_point(|&c| c <= u).min(self.cdf.len() - 1);
+        self.offset + i as u64
+    }
+}
+
+/// Poisson distribution with mean `lambda`, sampled by inversion from one draw
+///
+/// The cumulative distribution is tabulated around the mode on construction, covering every
+/// value with non-negligible probability, so each sample is a search through `O(√λ)` values.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate tapas;
+/// # use tapas::quasi::Halton;
+/// # use tapas::distributions::Poisson;
+/// use rand::distributions::IndependentSample;
+///
+/// # fn main() {
+/// let arrivals = Poisson::new(4.5);
+/// let mut gen = Halton::new(1, 2).unwrap();
+///
+/// let n = 1 << 12;
+/// let mean = (0..n).map(|_| arrivals.ind_sample(&mut gen)).sum::<u64>() as f64 / n as f64;
+/// assert!((mean - 4.5).abs() < 0.01);
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Poisson {
+    lambda: f64,
+    table: Table,
+}
+
+impl Poisson {
+    /// Poisson distribution with mean `lambda`
+    ///
+    /// # Panics
+    ///
+    /// If `lambda` is negative or not finite.
+    pub fn new(lambda: f64) -> Poisson {
+        assert!(lambda >= 0. && lambda.is_finite(), "invalid Poisson mean {}", lambda);
+
+        let table = if lambda == 0. {
+            Table { offset: 0, cdf: vec![1.] }
+        } else {
+            let mode = lambda.floor() as u64;
+            Table::around_mode(mode, 0, u64::MAX, |k| lambda / (k + 1) as f64)
+        };
+        Poisson { lambda, table }
+    }
+
+    /// Mean of the distribution
+    pub fn lambda(&self) -> f64 {
+        self.lambda
+    }
+
+    /// Quantile of the uniform draw `u`
+    pub fn quantile(&self, u: f64) -> u64 {
+        self.table.invert(u)
+    }
+}
+
+/// Number of failures before the first success of trials with success probability `p`
+///
+/// Inverts the closed form distribution function, `k = ⌊ln(1 - u) / ln(1 - p)⌋`.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::distributions::Geometric;
+/// let g = Geometric::new(0.5);
+/// assert_eq!(g.quantile(0.4), 0);
+/// assert_eq!(g.quantile(0.6), 1);
+/// assert_eq!(g.quantile(0.8), 2);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Geometric {
+    p: f64,
+    /// `ln(1 - p)`, computed accurately for small `p`
+    ln_q: f64,
+}
+
+impl Geometric {
+    /// Geometric distribution with success probability `p`
+    ///
+    /// # Panics
+    ///
+    /// If `p` is not in `(0, 1]`.
+    pub fn new(p: f64) -> Geometric {
+        assert!(p > 0. && p <= 1., "success probability {} is not in (0, 1]", p);
+
+        Geometric { p, ln_q: (-p).ln_1p() }
+    }
+
+    /// Probability of success
+    pub fn p(&self) -> f64 {
+        self.p
+    }
+
+    /// Quantile of the uniform draw `u`
+    pub fn quantile(&self, u: f64) -> u64 {
+        if self.p == 1. {
+            return 0;
+        }
+        let k = ((-u).ln_1p() / self.ln_q).floor();
+        if k >= u64::MAX as f64 { u64::MAX } else { k as u64 }
+    }
+}
+
+/// Number of successes in `n` trials with success probability `p`, from one draw
+///
+/// Tabulated around the mode like [`Poisson`].  Unlike [`StratifiedBinomial`], which spends a
+/// draw on every trial, a sample consumes a single draw regardless of `n`.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::distributions::Binomial;
+/// let b = Binomial::new(2, 0.5);
+/// assert_eq!(b.quantile(0.2), 0);
+/// assert_eq!(b.quantile(0.5), 1);
+/// assert_eq!(b.quantile(0.9), 2);
+/// ```
+///
+/// [`Poisson`]: struct.Poisson.html
+/// [`StratifiedBinomial`]: ../bernoulli/struct.StratifiedBinomial.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct Binomial {
+    n: u64,
+    p: f64,
+    table: Table,
+}
+
+impl Binomial {
+    /// Binomial distribution of `n` trials with success probability `p`
+    ///
+    /// # Panics
+    ///
+    /// If `p` is not in `[0, 1]`.
+    pub fn new(n: u64, p: f64) -> Binomial {
+        assert!((0. ..=1.).contains(&p), "probability {} is not in [0, 1]", p);
+
+        let table = if p == 0. || p == 1. || n == 0 {
+            Table { offset: if p == 1. { n } else { 0 }, cdf: vec![1.] }
+        } else {
+            let mode = (((n + 1) as f64 * p).floor() as u64).min(n);
+            let odds = p / (1. - p);
+            Table::around_mode(mode, 0, n, |k| (n - k) as f64 / (k + 1) as f64 * odds)
+        };
+        Binomial { n, p, table }
+    }
+
+    /// Number of trials
+    pub fn trials(&self) -> u64 {
+        self.n
+    }
+
+    /// Probability of success of each trial
+    pub fn p(&self) -> f64 {
+        self.p
+    }
+
+    /// Quantile of the uniform draw `u`
+    pub fn quantile(&self, u: f64) -> u64 {
+        self.table.invert(u)
+    }
+
+    /// Probability of exactly `k` successes
+    pub fn pmf(&self, k: u64) -> f64 {
+        if k > self.n {
+            return 0.;
+        }
+        let (n, k) = (self.n as f64, k as f64);
+        let ln_choose = ln_gamma(n + 1.) - ln_gamma(k + 1.) - ln_gamma(n - k + 1.);
+        (ln_choose + k * self.p.ln() + (n - k) * (-self.p).ln_1p()).exp()
+    }
+}
+
+/// Index of a category drawn with probability proportional to its weight, from one draw
+///
+/// Inverts the cumulative weights with a binary search, so consecutive ranges of draws select
+/// the categories in order and each category receives the share of a stratified stream given
+/// by its weight.  An alias table samples in constant time, but shuffles the categories
+/// across the unit interval and loses this correspondence.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::distributions::Categorical;
+/// # use tapas::quasi::VdC;
+/// let die = Categorical::new(&[1., 2., 0., 1.]);
+/// assert_eq!(die.probability(1), 0.5);
+///
+/// // Eight stratified draws give each category exactly its share
+/// let mut gen = VdC::new(2).unwrap();
+/// let mut counts = [0; 4];
+/// for _ in 0..8 {
+///     counts[die.sample(&mut gen)] += 1;
+/// }
+/// assert_eq!(counts, [2, 4, 0, 2]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Categorical {
+    table: Table,
+}
+
+impl Categorical {
+    /// Distribution over the indices of `weights`, which need not be normalized
+    ///
+    /// # Panics
+    ///
+    /// If a weight is negative or not finite, or all of them are zero.
+    pub fn new(weights: &[f64]) -> Categorical {
+        assert!(weights.iter().all(|&w| w >= 0. && w.is_finite()),
+            "weights must be finite and non-negative");
+        let total: f64 = weights.iter().sum();
+        assert!(total > 0., "weights must not all be zero");
+
+        let mut acc = 0.;
+        let mut cdf: Vec<f64> = weights.iter().map(|w| { acc += w / total; acc }).collect();
+        // Round the tail up so trailing categories without weight are never selected
+        let last = weights.iter().rposition(|&w| w > 0.).unwrap();
+        for c in cdf[last..].iter_mut() {
+            *c = 1.;
+        }
+
+        Categorical { table: Table { offset: 0, cdf } }
+    }
+
+    /// Number of categories
+    pub fn len(&self) -> usize {
+        self.table.cdf.len()
+    }
+
+    /// Whether there are no categories, which can never be the case for a constructed
+    /// distribution
+    pub fn is_empty(&self) -> bool {
+        self.table.cdf.is_empty()
+    }
+
+    /// Probability of the category `i`
+    pub fn probability(&self, i: usize) -> f64 {
+        match i {
+            0 => self.table.cdf[0],
+            i if i < self.len() => self.table.cdf[i] - self.table.cdf[i - 1],
+            _ => 0.,
+        }
+    }
+
+    /// Category of the uniform draw `u`
+    pub fn quantile(&self, u: f64) -> usize {
+        self.table.invert(u) as usize
+    }
+
+    /// Category selected by one value drawn from `rng`
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> usize {
+        self.quantile(rng.next_f64())
+    }
+}
+
+impl Sample<usize> for Categorical {
+    fn sample<R: Rng>(&mut self, rng: &mut R) -> usize {
+        Categorical::sample(self, rng)
+    }
+}
+
+impl IndependentSample<usize> for Categorical {
+    fn ind_sample<R: Rng>(&self, rng: &mut R) -> usize {
+        Categorical::sample(self, rng)
+    }
+}
+
+macro_rules! inversion_sample {
+    ($($dist:ty),*) => {
+        $(
+            impl Sample<u64> for $dist {
+                fn sample<R: Rng>(&mut self, rng: &mut R) -> u64 {
+                    self.ind_sample(rng)
+                }
+            }
+
+            impl IndependentSample<u64> for $dist {
+                fn ind_sample<R: Rng>(&self, rng: &mut R) -> u64 {
+                    self.quantile(rng.next_f64())
+                }
+            }
+        )*
+    };
+}
+
+inversion_sample!(Poisson, Geometric, Binomial);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Differences of the tabulated distribution function give the probabilities
+    fn table_pmf(t: &Table, k: u64) -> f64 {
+        if k < t.offset || k >= t.offset + t.cdf.len() as u64 {
+            return 0.;
+        }
+        let i = (k - t.offset) as usize;
+        t.cdf[i] - if i == 0 { 0. } else { t.cdf[i - 1] }
+    }
+
+    #[test]
+    fn binomial_matches_pmf() {
+        for &(n, p) in [(10, 0.3), (200, 0.01), (1000, 0.5)].iter() {
+            let b = Binomial::new(n, p);
+            for k in 0..=n.min(50) {
+                abs_err_eq!((table_pmf(&b.table, k)) == (b.pmf(k)) ~ 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn poisson_matches_pmf() {
+        for &lambda in [0.1, 3., 50., 1e6].iter() {
+            let p = Poisson::new(lambda);
+            let mode = lambda.floor();
+            let ln_pmf = |k: f64| k * lambda.ln() - lambda - ln_gamma(k + 1.);
+            for k in [mode, mode + 1., (mode - 1.).max(0.)].iter() {
+                abs_err_eq!((table_pmf(&p.table, *k as u64)) == (ln_pmf(*k).exp()) ~ 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn large_poisson_table_is_small() {
+        assert!(Poisson::new(1e8).table.cdf.len() < 200_000);
+    }
+
+    #[test]
+    fn quantiles_are_monotone() {
+        let dists = (Poisson::new(7.), Geometric::new(0.05), Binomial::new(40, 0.7));
+        let mut last = (0, 0, 0);
+        for i in 0..1000 {
+            let u = i as f64 / 1000.;
+            let q = (dists.0.quantile(u), dists.1.quantile(u), dists.2.quantile(u));
+            assert!(q.0 >= last.0 && q.1 >= last.1 && q.2 >= last.2);
+            last = q;
+        }
+    }
+
+    #[test]
+    fn degenerate_parameters() {
+        assert_eq!(Poisson::new(0.).quantile(0.9), 0);
+        assert_eq!(Geometric::new(1.).quantile(0.9), 0);
+        assert_eq!(Binomial::new(5, 1.).quantile(0.1), 5);
+        assert_eq!(Binomial::new(5, 0.).quantile(0.9), 0);
+    }
+
+    #[test]
+    fn categorical_follows_weights() {
+        let c = Categorical::new(&[0., 3., 1., 0., 4., 0.]);
+        assert_eq!(c.len(), 6);
+        let probabilities: Vec<f64> = (0..7).map(|i| c.probability(i)).collect();
+        assert_eq!(probabilities, vec![0., 0.375, 0.125, 0., 0.5, 0., 0.]);
+
+        let mut counts = [0; 6];
+        for i in 0..1000 {
+            counts[c.quantile(i as f64 / 1000.)] += 1;
+        }
+        assert_eq!(counts, [0, 375, 125, 0, 500, 0]);
+        assert_eq!(c.quantile(1. - 1e-16), 4);
+    }
+
+    #[test]
+    #[should_panic]
+    fn categorical_without_weight() {
+        Categorical::new(&[0., 0.]);
+    }
+}
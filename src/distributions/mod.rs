@@ -0,0 +1,73 @@
+//! # Distributions
+//!
+//! Samplers for common distributions which stay safe to drive with quasi-random sequences.
+//!
+//! Rejection and ratio-of-uniforms algorithms consume a varying number of draws per sample,
+//! which scrambles the dimensions of a low-discrepancy sequence.  Every sampler in this module
+//! instead consumes a fixed number of draws, usually one, and maps it by inversion so the
+//! stratification of the input carries over to the samples.
+//!
+//! - [`QuasiBernoulli`] and [`StratifiedBinomial`] make accept/reject decisions
+//! - [`Poisson`], [`Geometric`] and [`Binomial`] count events by inverting their distribution
+//!   functions, and [`Categorical`] picks among weighted categories the same way
+//! - [`InverseNormal`] maps draws to the normal distribution through its quantile function,
+//!   and [`MultivariateNormal`] correlates several of them through a Cholesky factor
+//! - [`StudentT`], [`Pareto`] and [`GeneralizedPareto`] reproduce heavy tails through accurate
+//!   quantile functions
+//! - [`Exponential`], [`Cauchy`] and [`Weibull`] invert their closed-form quantile functions
+//!
+//! Continuous distributions implement the [`Quantile`] trait, whose [`Inversion`] adapter turns
+//! any generator into a stream of samples.
+//!
+//! All samplers implement `Sample` and `IndependentSample` from `rand::distributions`.
+//!
+//! [`QuasiBernoulli`]: bernoulli/struct.QuasiBernoulli.html
+//! [`StratifiedBinomial`]: bernoulli/struct.StratifiedBinomial.html
+//! [`Poisson`]: discrete/struct.Poisson.html
+//! [`Geometric`]: discrete/struct.Geometric.html
+//! [`Binomial`]: discrete/struct.Binomial.html
+//! [`Categorical`]: discrete/struct.Categorical.html
+//! [`InverseNormal`]: normal/struct.InverseNormal.html
+//! [`MultivariateNormal`]: normal/struct.MultivariateNormal.html
+//! [`StudentT`]: heavy_tail/struct.StudentT.html
+//! [`Pareto`]: heavy_tail/struct.Pareto.html
+//! [`GeneralizedPareto`]: heavy_tail/struct.GeneralizedPareto.html
+//! [`Exponential`]: continuous/struct.Exponential.html
+//! [`Cauchy`]: continuous/struct.Cauchy.html
+//! [`Weibull`]: continuous/struct.Weibull.html
+//! [`Quantile`]: quantile/trait.Quantile.html
+//! [`Inversion`]: quantile/struct.Inversion.html
+
+/// Implement `Sample` and `IndependentSample` by inverting one draw with `quantile`
+macro_rules! inversion_sample {
+    ($($dist:ty),*) => {
+        $(
+            impl ::rand::distributions::Sample<f64> for $dist {
+                fn sample<R: ::rand::Rng>(&mut self, rng: &mut R) -> f64 {
+                    ::rand::distributions::IndependentSample::ind_sample(self, rng)
+                }
+            }
+
+            impl ::rand::distributions::IndependentSample<f64> for $dist {
+                fn ind_sample<R: ::rand::Rng>(&self, rng: &mut R) -> f64 {
+                    self.quantile(rng.next_f64())
+                }
+            }
+        )*
+    };
+}
+
+pub mod bernoulli;
+pub mod continuous;
+pub mod discrete;
+pub mod heavy_tail;
+pub mod normal;
+pub mod quantile;
+pub(crate) mod special;
+
+pub use self::bernoulli::{QuasiBernoulli, StratifiedBinomial};
+pub use self::continuous::{Cauchy, Exponential, Weibull};
+pub use self::discrete::{Binomial, Categorical, Geometric, Poisson};
+pub use self::heavy_tail::{GeneralizedPareto, Pareto, StudentT};
+pub use self::normal::{InverseNormal, MultivariateNormal};
+pub use self::quantile::{Inversion, Quantile};
@@ -0,0 +1,281 @@
+use rand::Rng;
+use rand::distributions::{IndependentSample, Sample};
+
+use super::special::inverse_normal;
+
+/// Normal distribution with `mean` and `std_dev`, sampled by inversion
+///
+/// Every sample maps one uniform draw through the normal quantile function, computed with
+/// Wichura's algorithm to about machine precision.  The quantile is increasing, so draws which
+/// are well spread over `[0, 1)` give samples which are well spread over the normal
+/// distribution, while Box–Muller pairs up draws through trigonometric functions and scatters
+/// the structure of a low-discrepancy sequence.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate tapas;
+/// # use tapas::distributions::InverseNormal;
+/// # use tapas::quasi::Halton;
+/// use rand::distributions::IndependentSample;
+///
+/// # fn main() {
+/// let normal = InverseNormal::new(10., 2.);
+/// assert!((normal.quantile(0.975) - (10. + 2. * 1.959_963_984_540_054)).abs() < 1e-12);
+///
+/// // The mean of the samples converges quickly with stratified draws
+/// let mut gen = Halton::new(1, 2).unwrap();
+/// let mean = (0..1023).map(|_| normal.ind_sample(&mut gen)).sum::<f64>() / 1023.;
+/// assert!((mean - 10.).abs() < 1e-3);
+/// # }
+/// ```
+///
+/// # References
+/// - Wichura, M. J., Algorithm AS 241: The percentage points of the normal distribution
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InverseNormal {
+    mean: f64,
+    std_dev: f64,
+}
+
+impl InverseNormal {
+    /// Normal distribution with `mean` and standard deviation `std_dev`
+    ///
+    /// # Panics
+    ///
+    /// If `std_dev` is negative.
+    pub fn new(mean: f64, std_dev: f64) -> InverseNormal {
+        assert!(std_dev >= 0., "standard deviation {} must not be negative", std_dev);
+
+        InverseNormal { mean, std_dev }
+    }
+
+    /// Standard normal distribution, of zero mean and unit variance
+    pub fn standard() -> InverseNormal {
+        InverseNormal::new(0., 1.)
+    }
+
+    /// Mean of the distribution
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Standard deviation of the distribution
+    pub fn std_dev(&self) -> f64 {
+        self.std_dev
+    }
+
+    /// Quantile of the uniform draw `u`
+    pub fn quantile(&self, u: f64) -> f64 {
+        self.mean + self.std_dev * inverse_normal(u)
+    }
+}
+
+inversion_sample!(InverseNormal);
+
+/// Multivariate normal distribution with a `mean` vector and a `covariance` matrix
+///
+/// Each sample takes a point of the unit cube with one coordinate per dimension, maps every
+/// coordinate to an independent standard normal by inversion as in [`InverseNormal`], and
+/// correlates them with the lower Cholesky factor `L` of the covariance, `x = mean + L z`.
+/// Since `L` is triangular, the first coordinate of the point drives the first coordinate of
+/// the sample alone, so the dimensions where a low-discrepancy sequence is most uniform carry
+/// the leading factors.
+///
+/// Covariances which are only semi-definite, such as those of perfectly correlated variables,
+/// are supported: dimensions which are linear combinations of earlier ones ignore their
+/// coordinate of the point.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate tapas;
+/// # use tapas::distributions::MultivariateNormal;
+/// # use tapas::quasi::Sobol;
+/// use rand::distributions::IndependentSample;
+///
+/// # fn main() {
+/// let mvn = MultivariateNormal::new(vec![1., -1.], vec![vec![4., 1.], vec![1., 1.]]);
+/// assert_eq!(mvn.cholesky()[1], vec![0.5, 0.75f64.sqrt()]);
+///
+/// // Estimate the covariance of the two coordinates
+/// let mut gen = Sobol::new(2).unwrap();
+/// gen.next();
+/// let n = 4095;
+/// let cov = (0..n)
+///     .map(|_| mvn.ind_sample(&mut gen))
+///     .map(|x| (x[0] - 1.) * (x[1] + 1.))
+///     .sum::<f64>() / n as f64;
+/// assert!((cov - 1.).abs() < 1e-2);
+/// # }
+/// ```
+///
+/// [`InverseNormal`]: struct.InverseNormal.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultivariateNormal {
+    mean: Vec<f64>,
+    /// Rows of the lower triangular Cholesky factor of the covariance
+    cholesky: Vec<Vec<f64>>,
+}
+
+impl MultivariateNormal {
+    /// Normal distribution with `mean` and `covariance`, given by its rows
+    ///
+    /// # Panics
+    ///
+    /// If the covariance is not a symmetric positive semi-definite matrix with one row and
+    /// column per dimension of the mean, or the mean is empty.
+    pub fn new(mean: Vec<f64>, covariance: Vec<Vec<f64>>) -> MultivariateNormal {
+        let d = mean.len();
+        assert!(d > 0, "normal distribution of no dimensions");
+        assert!(covariance.len() == d && covariance.iter().all(|row| row.len() == d),
+            "covariance must be a {} by {} matrix", d, d);
+        for (i, row) in covariance.iter().enumerate() {
+            for (j, &a) in row.iter().enumerate().take(i) {
+                let b = covariance[j][i];
+                assert!((a - b).abs() <= 1e-12 * a.abs().max(b.abs()),
+                    "covariance is not symmetric at ({}, {})", i, j);
+            }
+        }
+
+        MultivariateNormal { mean, cholesky: cholesky(&covariance) }
+    }
+
+    /// Number of dimensions of each sample
+    pub fn dimensions(&self) -> usize {
+        self.mean.len()
+    }
+
+    /// Mean of the distribution
+    pub fn mean(&self) -> &[f64] {
+        &self.mean
+    }
+
+    /// Rows of the lower triangular Cholesky factor `L` of the covariance, `L Lᵀ = Σ`
+    pub fn cholesky(&self) -> &[Vec<f64>] {
+        &self.cholesky
+    }
+
+    /// Sample for the point `u` of the unit cube
+    ///
+    /// # Panics
+    ///
+    /// If `u` does not have one coordinate per dimension.
+    pub fn point(&self, u: &[f64]) -> Vec<f64> {
+        assert_eq!(u.len(), self.mean.len(), "{}-dimensional point for a {}-dimensional \
+            distribution", u.len(), self.mean.len());
+        let z: Vec<f64> = u.iter().map(|&u| inverse_normal(u)).collect();
+        self.cholesky.iter().zip(self.mean.iter())
+            .map(|(row, m)| {
+                // Zero entries of the factor ignore infinite normals of draws at 0
+                m + row.iter().zip(z.iter())
+                    .filter(|&(&l, _)| l != 0.)
+                    .map(|(l, z)| l * z)
+                    .sum::<f64>()
+            })
+            .collect()
+    }
+}
+
+/// Lower Cholesky factor of a symmetric positive semi-definite matrix
+///
+/// Pivots which vanish up to rounding give zero columns, as for semi-definite matrices.
+fn cholesky(a: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let d = a.len();
+    let mut l = vec![vec![0.; d]; d];
+    for j in 0..d {
+        let pivot = a[j][j] - l[j][..j].iter().map(|x| x * x).sum::<f64>();
+        let tolerance = 1e-12 * a[j][j].abs().max(f64::MIN_POSITIVE);
+        assert!(pivot >= -tolerance, "covariance is not positive semi-definite");
+        if pivot <= tolerance {
+            continue;
+        }
+
+        let diagonal = pivot.sqrt();
+        l[j][j] = diagonal;
+        for i in j + 1..d {
+            let dot = l[i][..j].iter().zip(l[j][..j].iter()).map(|(x, y)| x * y).sum::<f64>();
+            l[i][j] = (a[i][j] - dot) / diagonal;
+        }
+    }
+    l
+}
+
+impl Sample<Vec<f64>> for MultivariateNormal {
+    fn sample<R: Rng>(&mut self, rng: &mut R) -> Vec<f64> {
+        self.ind_sample(rng)
+    }
+}
+
+impl IndependentSample<Vec<f64>> for MultivariateNormal {
+    fn ind_sample<R: Rng>(&self, rng: &mut R) -> Vec<f64> {
+        let u: Vec<f64> = (0..self.mean.len()).map(|_| rng.next_f64()).collect();
+        self.point(&u)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::Sobol;
+
+    #[test]
+    fn moments_of_stratified_samples() {
+        let normal = InverseNormal::standard();
+        let mut gen = Sobol::new(1).unwrap();
+        gen.next_f64();
+        let n = 4095;
+        let x: Vec<f64> = (0..n).map(|_| normal.ind_sample(&mut gen)).collect();
+        let mean = x.iter().sum::<f64>() / n as f64;
+        let var = x.iter().map(|x| x * x).sum::<f64>() / n as f64;
+        abs_err_eq!(mean == 0. ~ 1e-12);
+        abs_err_eq!(var == 1. ~ 5e-3);
+    }
+
+    #[test]
+    fn order_is_preserved() {
+        let normal = InverseNormal::new(-1., 3.);
+        let u: Vec<f64> = (1..1000).map(|i| i as f64 / 1000.).collect();
+        assert!(u.windows(2).all(|w| normal.quantile(w[0]) < normal.quantile(w[1])));
+        assert_eq!(normal.quantile(0.5), -1.);
+        assert_eq!(InverseNormal::new(4., 0.).quantile(0.1), 4.);
+    }
+
+    #[test]
+    fn factor_reproduces_covariance() {
+        let covariance = vec![
+            vec![4., 2., -2.],
+            vec![2., 10., 5.],
+            vec![-2., 5., 9.],
+        ];
+        let mvn = MultivariateNormal::new(vec![0.; 3], covariance.clone());
+        let l = mvn.cholesky();
+        for i in 0..3 {
+            assert!(l[i][i + 1..].iter().all(|&x| x == 0.));
+            for j in 0..3 {
+                let product: f64 = (0..3).map(|k| l[i][k] * l[j][k]).sum();
+                abs_err_eq!(product == (covariance[i][j]) ~ 1e-12);
+            }
+        }
+        assert_eq!(mvn.point(&[0.5; 3]), vec![0.; 3]);
+    }
+
+    #[test]
+    fn semi_definite_covariance() {
+        // The second variable is twice the first
+        let mvn = MultivariateNormal::new(vec![0., 1.], vec![vec![1., 2.], vec![2., 4.]]);
+        assert_eq!(mvn.cholesky()[1], vec![2., 0.]);
+        for &(u, v) in [(0.1, 0.), (0.7, 0.3), (0.99, 0.5)].iter() {
+            let x = mvn.point(&[u, v]);
+            abs_err_eq!((x[1]) == (1. + 2. * x[0]) ~ 1e-12);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn indefinite_covariance() {
+        MultivariateNormal::new(vec![0., 0.], vec![vec![1., 2.], vec![2., 1.]]);
+    }
+}
@@ -0,0 +1,141 @@
+use rand::Rng;
+
+use super::{Cauchy, Exponential, GeneralizedPareto, InverseNormal, Pareto, StudentT, Weibull};
+
+/// Continuous distribution sampled by inverting its distribution function
+///
+/// The quantile function is increasing, so it maps draws which are evenly spread over
+/// `[0, 1)` to samples which are evenly spread over the distribution: every stratum of the
+/// draws becomes a stratum of equal probability.  Any distribution with a quantile function
+/// can be sampled this way from a quasi-random sequence, one draw per sample, with
+/// [`samples`](#method.samples).
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::distributions::{Exponential, Quantile, Weibull};
+/// # use tapas::quasi::Halton;
+/// // Mean of the same draws under several lifetime models
+/// fn mean<Q: Quantile>(dist: Q) -> f64 {
+///     dist.samples(Halton::new(1, 2).unwrap()).take(1023).sum::<f64>() / 1023.
+/// }
+///
+/// assert!((mean(Exponential::new(1.)) - 1.).abs() < 1e-2);
+/// assert!((mean(Weibull::new(1., 2.)) - std::f64::consts::PI.sqrt() / 2.).abs() < 1e-2);
+/// ```
+pub trait Quantile {
+    /// Value below which a sample falls with probability `u`
+    fn quantile(&self, u: f64) -> f64;
+
+    /// Endless stream of samples, one for every value drawn from `rng`
+    ///
+    /// See [`Inversion`] for details.
+    ///
+    /// [`Inversion`]: struct.Inversion.html
+    fn samples<R: Rng>(self, rng: R) -> Inversion<Self, R>
+        where Self: Sized {
+
+        Inversion::new(self, rng)
+    }
+}
+
+macro_rules! inherent_quantile {
+    ($($dist:ty),*) => {
+        $(
+            impl Quantile for $dist {
+                fn quantile(&self, u: f64) -> f64 {
+                    <$dist>::quantile(self, u)
+                }
+            }
+        )*
+    };
+}
+
+inherent_quantile!(Cauchy, Exponential, GeneralizedPareto, InverseNormal, Pareto, StudentT,
+    Weibull);
+
+impl<Q: Quantile + ?Sized> Quantile for &Q {
+    fn quantile(&self, u: f64) -> f64 {
+        (**self).quantile(u)
+    }
+}
+
+/// Samples of a distribution, inverted one by one from the values of a generator
+///
+/// Sample `i` is the quantile of the `i`-th value drawn, so the samples keep the order and
+/// stratification of the underlying sequence.  Usually built with [`Quantile::samples`].
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::distributions::{Inversion, InverseNormal};
+/// # use tapas::quasi::Halton;
+/// let normal = Inversion::new(InverseNormal::standard(), Halton::new(1, 2).unwrap());
+/// let first: Vec<f64> = normal.take(3).collect();
+/// assert_eq!(first[0], 0.);
+/// assert_eq!(first[1], -first[2]);
+/// ```
+///
+/// [`Quantile::samples`]: trait.Quantile.html#method.samples
+#[derive(Debug, Clone)]
+pub struct Inversion<Q, R> {
+    distribution: Q,
+    rng: R,
+}
+
+impl<Q: Quantile, R: Rng> Inversion<Q, R> {
+    /// Samples of `distribution` from the values of `rng`
+    pub fn new(distribution: Q, rng: R) -> Inversion<Q, R> {
+        Inversion { distribution, rng }
+    }
+
+    /// Distribution being sampled
+    pub fn distribution(&self) -> &Q {
+        &self.distribution
+    }
+
+    /// Unwrap the distribution and the generator
+    pub fn into_inner(self) -> (Q, R) {
+        (self.distribution, self.rng)
+    }
+}
+
+impl<Q: Quantile, R: Rng> Iterator for Inversion<Q, R> {
+    type Item = f64;
+
+    fn next(&mut self) -> Option<f64> {
+        Some(self.distribution.quantile(self.rng.next_f64()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::VdC;
+
+    #[test]
+    fn samples_follow_draws() {
+        let draws: Vec<f64> = VdC::new(2).unwrap().take(64).collect();
+        let dists: Vec<Box<dyn Quantile>> = vec![
+            Box::new(Exponential::new(2.)),
+            Box::new(Cauchy::new(0., 1.)),
+            Box::new(StudentT::new(3.)),
+            Box::new(Pareto::new(1., 2.)),
+        ];
+        for dist in dists.iter() {
+            let samples = Inversion::new(&**dist, VdC::new(2).unwrap());
+            for (x, &u) in samples.zip(draws.iter()) {
+                assert_eq!(x, dist.quantile(u));
+            }
+        }
+    }
+
+    #[test]
+    fn order_is_preserved() {
+        let mut draws: Vec<f64> = VdC::new(3).unwrap().take(200).collect();
+        draws.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let w = Weibull::new(1., 0.5);
+        let samples: Vec<f64> = draws.iter().map(|&u| Quantile::quantile(&w, u)).collect();
+        assert!(samples.windows(2).all(|s| s[0] < s[1]));
+    }
+}
@@ -0,0 +1,191 @@
+//! Special functions shared by the inversion samplers
+
+use std::f64::consts::PI;
+
+/// Lanczos coefficients for `g = 7`, `n = 9`, as published
+#[allow(clippy::excessive_precision)]
+const LANCZOS: [f64; 9] = [
+    0.999_999_999_999_809_93,
+    676.520_368_121_885_1,
+    -1_259.139_216_722_402_8,
+    771.323_428_777_653_13,
+    -176.615_029_162_140_59,
+    12.507_343_278_686_905,
+    -0.138_571_095_265_720_12,
+    9.984_369_578_019_571_6e-6,
+    1.505_632_735_149_311_6e-7,
+];
+
+/// Natural logarithm of the gamma function for positive `x`
+pub fn ln_gamma(x: f64) -> f64 {
+    if x < 0.5 {
+        // Reflection formula
+        return (PI / (PI * x).sin()).ln() - ln_gamma(1. - x);
+    }
+
+    let x = x - 1.;
+    let t = x + 7.5;
+    let series = LANCZOS[1..].iter().enumerate()
+        .fold(LANCZOS[0], |acc, (i, c)| acc + c / (x + i as f64 + 1.));
+    0.5 * (2. * PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
+
+/// Natural logarithm of the regularized incomplete beta function `I_x(a, b)` at `x = e^ln_x`
+///
+/// Evaluates the continued fraction of Numerical Recipes with the modified Lentz method,
+/// switching to the symmetry `I_x(a, b) = 1 - I_{1-x}(b, a)` where that converges faster.
+/// Taking the logarithm of `x` keeps the direct branch accurate for arguments and results far
+/// below the smallest `f64`.
+pub fn ln_beta_reg(ln_x: f64, a: f64, b: f64) -> f64 {
+    let x = ln_x.exp();
+    if ln_x == f64::NEG_INFINITY {
+        return f64::NEG_INFINITY;
+    }
+    if x >= 1. {
+        return 0.;
+    }
+
+    let ln_front = a * ln_x + b * (-x).ln_1p() - ln_gamma(a) - ln_gamma(b) + ln_gamma(a + b);
+    if x < (a + 1.) / (a + b + 2.) {
+        ln_front + (beta_fraction(x, a, b) / a).ln()
+    } else {
+        (-(ln_front.exp() * beta_fraction(1. - x, b, a) / b)).ln_1p()
+    }
+}
+
+/// Continued fraction for the incomplete beta function
+fn beta_fraction(x: f64, a: f64, b: f64) -> f64 {
+    const TINY: f64 = 1e-300;
+
+    let (qab, qap, qam) = (a + b, a + 1., a - 1.);
+    let mut c = 1.;
+    let mut d = 1. - qab * x / qap;
+    if d.abs() < TINY {
+        d = TINY;
+    }
+    d = 1. / d;
+    let mut h = d;
+
+    for m in 1..1000 {
+        let m = m as f64;
+        let m2 = 2. * m;
+
+        let aa = m * (b - m) * x / ((qam + m2) * (a + m2));
+        d = 1. + aa * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = 1. + aa / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1. / d;
+        h *= d * c;
+
+        let aa = -(a + m) * (qab + m) * x / ((a + m2) * (qap + m2));
+        d = 1. + aa * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = 1. + aa / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1. / d;
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.).abs() < 1e-16 {
+            break;
+        }
+    }
+    h
+}
+
+/// Standard normal quantile by Wichura's algorithm AS 241
+///
+/// Rational approximations of degree 7 in three regions of `u` give a relative error of about
+/// `1e-16` over the whole range, down to the smallest positive `u`.  The quantile is odd
+/// around `u = 1/2` and increasing, so stratified draws stay stratified.
+pub fn inverse_normal(u: f64) -> f64 {
+    const A: [f64; 8] = [3.387_132_872_796_366_6, 1.331_416_678_917_843_8e2,
+        1.971_590_950_306_551_4e3, 1.373_169_376_550_946e4, 4.592_195_393_154_987e4,
+        6.726_577_092_700_87e4, 3.343_057_558_358_813e4, 2.509_080_928_730_122_7e3];
+    const B: [f64; 8] = [1., 4.231_333_070_160_091e1, 6.871_870_074_920_579e2,
+        5.394_196_021_424_751e3, 2.121_379_430_158_659_7e4, 3.930_789_580_009_271e4,
+        2.872_908_573_572_194_3e4, 5.226_495_278_852_854e3];
+    const C: [f64; 8] = [1.423_437_110_749_683_5, 4.630_337_846_156_545, 5.769_497_221_460_691,
+        3.647_848_324_763_204_5, 1.270_458_252_452_368_4, 2.417_807_251_774_506e-1,
+        2.272_384_498_926_918_4e-2, 7.745_450_142_783_414e-4];
+    const D: [f64; 8] = [1., 2.053_191_626_637_759, 1.676_384_830_183_803_8,
+        6.897_673_349_851e-1, 1.481_039_764_274_800_8e-1, 1.519_866_656_361_645_7e-2,
+        5.475_938_084_995_345e-4, 1.050_750_071_644_416_8e-9];
+    const E: [f64; 8] = [6.657_904_643_501_103, 5.463_784_911_164_114, 1.784_826_539_917_291_3,
+        2.965_605_718_285_048_7e-1, 2.653_218_952_657_612_4e-2, 1.242_660_947_388_078_4e-3,
+        2.711_555_568_743_487_6e-5, 2.010_334_399_292_288_1e-7];
+    const F: [f64; 8] = [1., 5.998_322_065_558_879e-1, 1.369_298_809_227_358e-1,
+        1.487_536_129_085_061_5e-2, 7.868_691_311_456_133e-4, 1.846_318_317_510_054_8e-5,
+        1.421_511_758_316_446e-7, 2.044_263_103_389_939_7e-15];
+
+    if u <= 0. {
+        return f64::NEG_INFINITY;
+    }
+    if u >= 1. {
+        return f64::INFINITY;
+    }
+
+    let ratio = |num: &[f64; 8], den: &[f64; 8], r: f64| {
+        let p = num.iter().rev().fold(0., |acc, &c| acc * r + c);
+        let q = den.iter().rev().fold(0., |acc, &c| acc * r + c);
+        p / q
+    };
+
+    let q = u - 0.5;
+    if q.abs() <= 0.425 {
+        return q * ratio(&A, &B, 0.180_625 - q * q);
+    }
+
+    let r = (-u.min(1. - u).ln()).sqrt();
+    let x = if r <= 5. {
+        ratio(&C, &D, r - 1.6)
+    } else {
+        ratio(&E, &F, r - 5.)
+    };
+    if q < 0. { -x } else { x }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn factorials() {
+        let mut factorial = 1f64;
+        for n in 1..30 {
+            factorial *= n as f64;
+            abs_err_eq!((ln_gamma(n as f64 + 1.)) == (factorial.ln()) ~ 1e-12);
+        }
+        abs_err_eq!((ln_gamma(0.5)) == (PI.sqrt().ln()) ~ 1e-14);
+    }
+
+    #[test]
+    fn normal_quantiles() {
+        abs_err_eq!((inverse_normal(0.975)) == 1.959_963_984_540_054 ~ 1e-14);
+        abs_err_eq!((inverse_normal(1e-10)) == (-6.361_340_902_404_056) ~ 1e-13);
+        abs_err_eq!((inverse_normal(0.841_344_746_068_542_9)) == 1. ~ 1e-14);
+        assert_eq!(inverse_normal(0.5), 0.);
+        assert_eq!(inverse_normal(0.25), -inverse_normal(0.75));
+    }
+
+    #[test]
+    fn incomplete_beta() {
+        // I_x(1, 1) = x and I_x(2, 1) = x²
+        for &x in [1e-12f64, 0.1, 0.5, 0.9].iter() {
+            abs_err_eq!((ln_beta_reg(x.ln(), 1., 1.)) == (x.ln()) ~ 1e-12);
+            abs_err_eq!((ln_beta_reg(x.ln(), 2., 1.)) == (2. * x.ln()) ~ 1e-12);
+        }
+        // I_x(a, b) + I_{1-x}(b, a) = 1
+        let (x, a, b) = (0.3f64, 2.5, 0.5);
+        let total = ln_beta_reg(x.ln(), a, b).exp() + ln_beta_reg((1. - x).ln(), b, a).exp();
+        abs_err_eq!(total == 1. ~ 1e-13);
+    }
+}
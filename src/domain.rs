@@ -0,0 +1,454 @@
+//! # Domains
+//!
+//! Restrict sampling to geometric regions other than the unit cube.
+//!
+//! A [`Domain`] can test whether it contains a point and, for most shapes, map points of the
+//! unit cube onto itself while preserving uniformity.  [`DomainSampler`] uses the mapping when
+//! one exists and falls back to rejection from the bounding box otherwise, reporting the
+//! acceptance rate either way.
+//!
+//! Built-in domains:
+//!
+//! - [`Cuboid`], an axis-aligned box
+//! - [`Ball`], mapped in two and three dimensions
+//! - [`Simplex`], the standard simplex `x ≥ 0, Σx ≤ 1`
+//! - [`Annulus`], a planar ring
+//! - [`BoxUnion`], a union of disjoint axis-aligned boxes
+//!
+//! [`Domain`]: trait.Domain.html
+//! [`DomainSampler`]: struct.DomainSampler.html
+//! [`Cuboid`]: struct.Cuboid.html
+//! [`Ball`]: struct.Ball.html
+//! [`Simplex`]: struct.Simplex.html
+//! [`Annulus`]: struct.Annulus.html
+//! [`BoxUnion`]: struct.BoxUnion.html
+
+use rand::Rng;
+use std::f64::consts::PI;
+
+use polytope::Acceptance;
+use transform::{unit_ball, unit_disk, unit_simplex};
+
+/// Region of `d`-dimensional space which can be sampled
+pub trait Domain {
+    /// Number of dimensions of the space the domain lives in
+    fn dimensions(&self) -> usize;
+
+    /// Whether `x` lies inside the domain
+    fn contains(&self, x: &[f64]) -> bool;
+
+    /// Lower and upper corners of a box enclosing the domain
+    fn bounds(&self) -> (Vec<f64>, Vec<f64>);
+
+    /// Map a point of the unit cube `[0, 1)^d` uniformly onto the domain
+    ///
+    /// Returns `None` when the domain has no direct mapping, in which case samplers fall back
+    /// to rejection from the [`bounds`](#tymethod.bounds).
+    fn map(&self, u: &[f64]) -> Option<Vec<f64>> {
+        let _ = u;
+        None
+    }
+}
+
+/// Axis-aligned box `[lo, hi]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cuboid {
+    lo: Vec<f64>,
+    hi: Vec<f64>,
+}
+
+impl Cuboid {
+    /// Create the box with corners `lo` and `hi`
+    ///
+    /// # Panics
+    ///
+    /// If the corners have different dimensions or `lo` exceeds `hi` along any axis.
+    pub fn new(lo: &[f64], hi: &[f64]) -> Cuboid {
+        assert_eq!(lo.len(), hi.len(), "corners have different dimensions");
+        assert!(lo.iter().zip(hi).all(|(l, h)| l <= h), "{:?} is not below {:?}", lo, hi);
+
+        Cuboid { lo: lo.to_vec(), hi: hi.to_vec() }
+    }
+
+    /// Volume of the box
+    pub fn volume(&self) -> f64 {
+        self.lo.iter().zip(self.hi.iter()).map(|(l, h)| h - l).product()
+    }
+}
+
+impl Domain for Cuboid {
+    fn dimensions(&self) -> usize {
+        self.lo.len()
+    }
+
+    fn contains(&self, x: &[f64]) -> bool {
+        x.iter().zip(self.lo.iter().zip(self.hi.iter())).all(|(x, (l, h))| l <= x && x <= h)
+    }
+
+    fn bounds(&self) -> (Vec<f64>, Vec<f64>) {
+        (self.lo.clone(), self.hi.clone())
+    }
+
+    fn map(&self, u: &[f64]) -> Option<Vec<f64>> {
+        Some(u.iter().zip(self.lo.iter().zip(self.hi.iter()))
+            .map(|(u, (l, h))| l + u * (h - l))
+            .collect())
+    }
+}
+
+/// Closed ball of `radius` around `center`
+///
+/// Only disks and three-dimensional balls have a direct mapping, through the concentric map
+/// of [`unit_disk`] and spherical coordinates; higher dimensions are sampled by rejection.
+///
+/// [`unit_disk`]: ../transform/fn.unit_disk.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ball {
+    center: Vec<f64>,
+    radius: f64,
+}
+
+impl Ball {
+    /// Create the ball of `radius` around `center`
+    ///
+    /// # Panics
+    ///
+    /// If the radius is negative.
+    pub fn new(center: &[f64], radius: f64) -> Ball {
+        assert!(radius >= 0., "negative radius {}", radius);
+
+        Ball { center: center.to_vec(), radius }
+    }
+}
+
+impl Domain for Ball {
+    fn dimensions(&self) -> usize {
+        self.center.len()
+    }
+
+    fn contains(&self, x: &[f64]) -> bool {
+        let r2: f64 = x.iter().zip(self.center.iter()).map(|(x, c)| (x - c) * (x - c)).sum();
+        r2 <= self.radius * self.radius
+    }
+
+    fn bounds(&self) -> (Vec<f64>, Vec<f64>) {
+        (self.center.iter().map(|c| c - self.radius).collect(),
+            self.center.iter().map(|c| c + self.radius).collect())
+    }
+
+    fn map(&self, u: &[f64]) -> Option<Vec<f64>> {
+        let (c, r) = (&self.center, self.radius);
+        match u.len() {
+            2 => {
+                let [x, y] = unit_disk([u[0], u[1]]);
+                Some(vec![c[0] + r * x, c[1] + r * y])
+            },
+            3 => {
+                let [x, y, z] = unit_ball([u[0], u[1], u[2]]);
+                Some(vec![c[0] + r * x, c[1] + r * y, c[2] + r * z])
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Standard simplex `{ x : x ≥ 0, Σx ≤ 1 }`
+///
+/// Points are mapped by sorting the coordinates of the unit-cube point and taking the gaps
+/// between them, as in [`unit_simplex`].
+///
+/// [`unit_simplex`]: ../transform/fn.unit_simplex.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Simplex {
+    dimensions: usize,
+}
+
+impl Simplex {
+    /// Create the standard simplex in `dimensions` dimensions
+    pub fn new(dimensions: usize) -> Simplex {
+        Simplex { dimensions }
+    }
+}
+
+impl Domain for Simplex {
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    fn contains(&self, x: &[f64]) -> bool {
+        x.iter().all(|&x| x >= 0.) && x.iter().sum::<f64>() <= 1.
+    }
+
+    fn bounds(&self) -> (Vec<f64>, Vec<f64>) {
+        (vec![0.; self.dimensions], vec![1.; self.dimensions])
+    }
+
+    fn map(&self, u: &[f64]) -> Option<Vec<f64>> {
+        // The last barycentric weight is the slack `1 - Σx`
+        let mut x = unit_simplex(u);
+        x.pop();
+        Some(x)
+    }
+}
+
+/// Planar ring between an `inner` and `outer` radius around `center`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Annulus {
+    center: [f64; 2],
+    inner: f64,
+    outer: f64,
+}
+
+impl Annulus {
+    /// Create the ring of points between `inner` and `outer` distance from `center`
+    ///
+    /// # Panics
+    ///
+    /// If `inner` is negative or exceeds `outer`.
+    pub fn new(center: [f64; 2], inner: f64, outer: f64) -> Annulus {
+        assert!(inner >= 0. && inner <= outer, "invalid radii {} and {}", inner, outer);
+
+        Annulus { center, inner, outer }
+    }
+}
+
+impl Domain for Annulus {
+    fn dimensions(&self) -> usize {
+        2
+    }
+
+    fn contains(&self, x: &[f64]) -> bool {
+        let (dx, dy) = (x[0] - self.center[0], x[1] - self.center[1]);
+        let r2 = dx * dx + dy * dy;
+        self.inner * self.inner <= r2 && r2 <= self.outer * self.outer
+    }
+
+    fn bounds(&self) -> (Vec<f64>, Vec<f64>) {
+        (self.center.iter().map(|c| c - self.outer).collect(),
+            self.center.iter().map(|c| c + self.outer).collect())
+    }
+
+    fn map(&self, u: &[f64]) -> Option<Vec<f64>> {
+        // Invert the radial CDF, which is proportional to r² between the two radii
+        let (a, b) = (self.inner * self.inner, self.outer * self.outer);
+        let rho = (a + u[0] * (b - a)).sqrt();
+        let theta = 2. * PI * u[1];
+        Some(vec![self.center[0] + rho * theta.cos(), self.center[1] + rho * theta.sin()])
+    }
+}
+
+/// Union of disjoint axis-aligned boxes
+///
+/// The first coordinate of a unit-cube point selects a box in proportion to its volume and is
+/// then rescaled to be reused within that box, so mapping needs no extra dimension.  Boxes must
+/// not overlap for the mapping to be uniform.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoxUnion {
+    boxes: Vec<Cuboid>,
+    /// Cumulative volume fractions of the boxes
+    cdf: Vec<f64>,
+}
+
+impl BoxUnion {
+    /// Create the union of `boxes`
+    ///
+    /// # Panics
+    ///
+    /// If there are no boxes, their dimensions differ, or their total volume is zero.
+    pub fn new(boxes: Vec<Cuboid>) -> BoxUnion {
+        assert!(!boxes.is_empty(), "union of no boxes");
+        let d = boxes[0].dimensions();
+        assert!(boxes.iter().all(|b| b.dimensions() == d), "boxes have different dimensions");
+
+        let total: f64 = boxes.iter().map(Cuboid::volume).sum();
+        assert!(total > 0., "boxes have no volume");
+
+        let mut acc = 0.;
+        let mut cdf: Vec<f64> = boxes.iter().map(|b| { acc += b.volume() / total; acc }).collect();
+        *cdf.last_mut().unwrap() = 1.;
+
+        BoxUnion { boxes, cdf }
+    }
+}
+
+impl Domain for BoxUnion {
+    fn dimensions(&self) -> usize {
+        self.boxes[0].dimensions()
+    }
+
+    fn contains(&self, x: &[f64]) -> bool {
+        self.boxes.iter().any(|b| b.contains(x))
+    }
+
+    fn bounds(&self) -> (Vec<f64>, Vec<f64>) {
+        let (mut lo, mut hi) = self.boxes[0].bounds();
+        for b in self.boxes[1..].iter() {
+            for j in 0..lo.len() {
+                lo[j] = lo[j].min(b.lo[j]);
+                hi[j] = hi[j].max(b.hi[j]);
+            }
+        }
+        (lo, hi)
+    }
+
+    fn map(&self, u: &[f64]) -> Option<Vec<f64>> {
+        let i = self.cdf.iter().position(|&c| u[0] < c).unwrap_or(self.cdf.len() - 1);
+        let start = if i == 0 { 0. } else { self.cdf[i - 1] };
+
+        let mut v = u.to_vec();
+        v[0] = ((u[0] - start) / (self.cdf[i] - start)).min(1.);
+        self.boxes[i].map(&v)
+    }
+}
+
+/// Sampler restricted to a [`Domain`]
+///
+/// Every point consumes one value per dimension from the generator.  Domains with a direct
+/// mapping accept every point; the others are sampled by rejection from their bounding box.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::{Halton, Interleave};
+/// # use tapas::domain::{Annulus, Ball, Domain, DomainSampler};
+/// let mut gen = Interleave::new(&[
+///     Halton::new(1, 2).unwrap(),
+///     Halton::new(1, 3).unwrap(),
+///     Halton::new(1, 5).unwrap(),
+///     Halton::new(1, 7).unwrap(),
+/// ]).unwrap();
+///
+/// // Four dimensional balls have no mapping and are sampled by rejection
+/// let ball = Ball::new(&[0.; 4], 1.);
+/// let mut sampler = DomainSampler::new(&ball);
+/// for _ in 0..1000 {
+///     assert!(ball.contains(&sampler.sample(&mut gen, 100).unwrap()));
+/// }
+/// assert!(sampler.acceptance().rate() < 0.35);
+/// ```
+///
+/// [`Domain`]: trait.Domain.html
+#[derive(Debug, Clone)]
+pub struct DomainSampler<'a, D: 'a> {
+    domain: &'a D,
+    lo: Vec<f64>,
+    hi: Vec<f64>,
+    stats: Acceptance,
+}
+
+impl<'a, D: Domain> DomainSampler<'a, D> {
+    /// Create a sampler for `domain`
+    pub fn new(domain: &'a D) -> DomainSampler<'a, D> {
+        let (lo, hi) = domain.bounds();
+        DomainSampler { domain, lo, hi, stats: Acceptance::default() }
+    }
+
+    /// Draw a single proposal, returning it only if it lies inside the domain
+    pub fn propose<R: Rng>(&mut self, rng: &mut R) -> Option<Vec<f64>> {
+        let u: Vec<f64> = (0..self.domain.dimensions()).map(|_| rng.next_f64()).collect();
+
+        self.stats.proposed += 1;
+        let x = match self.domain.map(&u) {
+            Some(x) => x,
+            None => {
+                let x: Vec<f64> = u.iter().zip(self.lo.iter().zip(self.hi.iter()))
+                    .map(|(u, (l, h))| l + u * (h - l))
+                    .collect();
+                if !self.domain.contains(&x) {
+                    return None;
+                }
+                x
+            },
+        };
+        self.stats.accepted += 1;
+        Some(x)
+    }
+
+    /// Propose points until one is accepted, giving up after `max_tries` proposals
+    pub fn sample<R: Rng>(&mut self, rng: &mut R, max_tries: usize) -> Option<Vec<f64>> {
+        (0..max_tries).filter_map(|_| self.propose(rng)).next()
+    }
+
+    /// Acceptance statistics over every proposal made so far
+    pub fn acceptance(&self) -> Acceptance {
+        self.stats
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::{Halton, Interleave};
+
+    fn gen(dims: usize) -> Interleave<Halton> {
+        let bases = [2, 3, 5, 7];
+        let halton: Vec<Halton> = bases[..dims].iter().map(|&b| Halton::new(1, b).unwrap())
+            .collect();
+        Interleave::new(&halton).unwrap()
+    }
+
+    // Every mapped point must lie inside the domain and the sampler must never reject
+    fn check_mapped<D: Domain>(domain: &D) {
+        let mut rng = gen(domain.dimensions());
+        let mut sampler = DomainSampler::new(domain);
+        for _ in 0..500 {
+            let x = sampler.propose(&mut rng).expect("mapped domain rejected a point");
+            assert!(domain.contains(&x), "{:?} outside of domain", x);
+        }
+        assert_eq!(sampler.acceptance().rate(), 1.);
+    }
+
+    #[test]
+    fn mapped_domains() {
+        check_mapped(&Cuboid::new(&[-1., 2.], &[0., 5.]));
+        check_mapped(&Ball::new(&[1., 1.], 2.));
+        check_mapped(&Ball::new(&[0., 0., 0.], 0.5));
+        check_mapped(&Simplex::new(3));
+        check_mapped(&Annulus::new([0., 0.], 1., 2.));
+        check_mapped(&BoxUnion::new(vec![
+            Cuboid::new(&[0., 0.], &[1., 1.]),
+            Cuboid::new(&[2., 0.], &[4., 1.]),
+        ]));
+    }
+
+    #[test]
+    fn disk_is_concentric() {
+        let disk = Ball::new(&[1., 2.], 2.);
+        assert_eq!(disk.map(&[0.5, 0.5]), Some(vec![1., 2.]));
+
+        // Edges of the square land on the boundary circle
+        for &u in [[0., 0.3], [1., 0.7], [0.2, 1.], [0.9, 0.]].iter() {
+            let x = disk.map(&u).unwrap();
+            let r = (x[0] - 1.).hypot(x[1] - 2.);
+            abs_err_eq!(r == 2. ~ 1e-12);
+        }
+    }
+
+    #[test]
+    fn box_union_weights_by_volume() {
+        let union = BoxUnion::new(vec![
+            Cuboid::new(&[0.], &[1.]),
+            Cuboid::new(&[2.], &[5.]),
+        ]);
+        let mut rng = Halton::new(1, 2).unwrap();
+        let right = (0..1024)
+            .filter(|_| union.map(&[rng.next_f64()]).unwrap()[0] >= 2.)
+            .count();
+        assert_eq!(right, 768);
+    }
+
+    #[test]
+    fn annulus_mean_radius() {
+        // The radial density 2r / (b² - a²) on [1, 2] has mean 14 / 9
+        let ring = Annulus::new([0., 0.], 1., 2.);
+        let mut rng = gen(2);
+        let n = 4096;
+        let mean: f64 = (0..n)
+            .map(|_| {
+                let x = ring.map(&[rng.next_f64(), rng.next_f64()]).unwrap();
+                x[0].hypot(x[1])
+            })
+            .sum::<f64>() / n as f64;
+        abs_err_eq!(mean == (14. / 9.) ~ 1e-3);
+    }
+}
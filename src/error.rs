@@ -0,0 +1,128 @@
+//! # Errors
+//!
+//! Error type shared by every fallible operation in the crate.
+//!
+//! Constructors whose arguments usually come from data or configuration, such as generators,
+//! tables, allocators, meshes and polytopes, return a [`Result`] rather than panicking on
+//! invalid input.
+//!
+//! [`Result`]: type.Result.html
+
+use std::error;
+use std::fmt;
+use std::io;
+use std::result;
+
+/// Errors produced when constructing or loading generators
+#[derive(Debug)]
+pub enum Error {
+    /// The base is not usable for a radical-inverse construction
+    InvalidBase(u32),
+    /// More dimensions were requested than the construction supports
+    DimensionOverflow {
+        /// Number of dimensions which were requested
+        requested: usize,
+        /// Largest number of dimensions available
+        supported: usize,
+    },
+    /// A combinator was given an empty list of generators
+    EmptyGenerators,
+    /// Direction numbers of a digital sequence are malformed
+    InvalidDirectionNumbers(String),
+    /// A digit modifier is not admissible for the base of its dimension
+    InvalidModifier {
+        /// Base of the dimension
+        base: u32,
+        /// Rejected modifier
+        modifier: u32,
+    },
+    /// A parameter of a constructor is outside of its valid range
+    InvalidParameter {
+        /// Name of the parameter
+        name: &'static str,
+        /// Description of what was wrong with the value
+        reason: String,
+    },
+    /// A sample budget ran out of draws
+    BudgetExhausted {
+        /// Number of draws the budget allowed
+        limit: u64,
+    },
+    /// A line of a text input could not be parsed
+    Parse {
+        /// Line number of the offending input, starting at 1
+        line: usize,
+        /// Description of what was wrong with the line
+        reason: String,
+    },
+    /// Reading an external table failed
+    Io(io::Error),
+}
+
+/// Result type with the crate's [`Error`]
+///
+/// [`Error`]: enum.Error.html
+pub type Result<T> = result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::InvalidBase(b) => write!(f, "invalid base {}, expected at least 2", b),
+            Error::DimensionOverflow { requested, supported } => write!(
+                f, "{} dimensions requested but only {} are supported", requested, supported
+            ),
+            Error::EmptyGenerators => write!(f, "no generators provided"),
+            Error::InvalidDirectionNumbers(ref msg) => {
+                write!(f, "invalid direction numbers: {}", msg)
+            },
+            Error::InvalidModifier { base, modifier } => {
+                write!(f, "modifier {} is not admissible in base {}", modifier, base)
+            },
+            Error::InvalidParameter { name, ref reason } => {
+                write!(f, "invalid {}: {}", name, reason)
+            },
+            Error::BudgetExhausted { limit } => {
+                write!(f, "sample budget of {} draws exhausted", limit)
+            },
+            Error::Parse { line, ref reason } => write!(f, "line {}: {}", line, reason),
+            Error::Io(ref err) => write!(f, "I/O error: {}", err),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error::Io(err)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::error::Error as StdError;
+
+    #[test]
+    fn display() {
+        assert_eq!(Error::InvalidBase(1).to_string(), "invalid base 1, expected at least 2");
+        let overflow = Error::DimensionOverflow { requested: 30, supported: 21 };
+        assert_eq!(overflow.to_string(), "30 dimensions requested but only 21 are supported");
+        let weights = Error::InvalidParameter { name: "weights", reason: "sum to zero".into() };
+        assert_eq!(weights.to_string(), "invalid weights: sum to zero");
+    }
+
+    #[test]
+    fn io_source() {
+        let err: Error = io::Error::new(io::ErrorKind::NotFound, "missing table").into();
+        assert!(err.source().is_some());
+        assert!(Error::EmptyGenerators.source().is_none());
+    }
+}
@@ -0,0 +1,163 @@
+//! # Integer Grids
+//!
+//! Well-spread integer coordinates in an `n`-dimensional box of cells.
+//!
+//! [`cell`] scales a point of any low-discrepancy generator to the cell containing it, which
+//! preserves the spread of the sequence but may visit a cell twice before every cell has been
+//! seen.  When the grid is small enough to be exhausted, [`GridWalk`] instead enumerates every
+//! cell exactly once, in an order whose prefixes are spread evenly over the box.
+//!
+//! [`cell`]: fn.cell.html
+//! [`GridWalk`]: struct.GridWalk.html
+
+/// Cell of a grid with the given `extents` containing the unit-cube `point`
+///
+/// # Panics
+///
+/// If `point` and `extents` have different lengths.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::grid::cell;
+/// assert_eq!(cell(&[0.5, 0.99], &[4, 3]), vec![2, 2]);
+/// ```
+pub fn cell(point: &[f64], extents: &[usize]) -> Vec<usize> {
+    assert_eq!(point.len(), extents.len(), "{}-dimensional point in a {}-dimensional grid",
+        point.len(), extents.len());
+
+    point.iter().zip(extents)
+        .map(|(&u, &n)| ((u * n as f64) as usize).min(n.saturating_sub(1)))
+        .collect()
+}
+
+/// Exhaustive walk over every cell of an integer grid
+///
+/// Each extent is padded to a power of two and the padded grid is enumerated by a counter whose
+/// bits are dealt, least significant first, to the most significant coordinate bits of each
+/// dimension in turn.  Consecutive cells therefore alternate halves of the first dimension,
+/// then of the second, and so on, like a bit-reversed Morton order: any prefix of `2^k` cells of
+/// a power of two grid forms a regular subgrid.  Cells outside the real extents are skipped, so
+/// every cell is produced exactly once.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::grid::GridWalk;
+/// let cells: Vec<Vec<usize>> = GridWalk::new(&[4, 4]).collect();
+/// assert_eq!(cells.len(), 16);
+///
+/// // The first four cells fall in different quadrants
+/// assert_eq!(cells[..4], [vec![0, 0], vec![2, 0], vec![0, 2], vec![2, 2]]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct GridWalk {
+    extents: Vec<usize>,
+    /// Coordinate dimension and bit receiving each bit of the counter
+    schedule: Vec<(usize, u32)>,
+    counter: u64,
+    remaining: usize,
+}
+
+impl GridWalk {
+    /// Walk every cell of a grid with the given `extents`
+    ///
+    /// # Panics
+    ///
+    /// If the padded grid has more than `2^63` cells.
+    pub fn new(extents: &[usize]) -> GridWalk {
+        let bits: Vec<u32> = extents.iter()
+            .map(|&n| if n > 1 { 64 - (n as u64 - 1).leading_zeros() } else { 0 })
+            .collect();
+        let total: u32 = bits.iter().sum();
+        assert!(total < 64, "grid {:?} is too large to walk", extents);
+
+        let levels = bits.iter().cloned().max().unwrap_or(0);
+        let mut schedule = Vec::with_capacity(total as usize);
+        for level in 0..levels {
+            for (d, &b) in bits.iter().enumerate() {
+                if b > level {
+                    schedule.push((d, b - 1 - level));
+                }
+            }
+        }
+
+        GridWalk {
+            extents: extents.to_vec(),
+            schedule,
+            counter: 0,
+            remaining: extents.iter().product(),
+        }
+    }
+
+    /// Dimensions of the grid
+    pub fn dimensions(&self) -> usize {
+        self.extents.len()
+    }
+
+    fn decode(&self, m: u64) -> Vec<usize> {
+        let mut coord = vec![0; self.extents.len()];
+        for (i, &(d, bit)) in self.schedule.iter().enumerate() {
+            coord[d] |= (((m >> i) & 1) as usize) << bit;
+        }
+        coord
+    }
+}
+
+impl Iterator for GridWalk {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        while self.remaining > 0 {
+            let coord = self.decode(self.counter);
+            self.counter += 1;
+            if coord.iter().zip(self.extents.iter()).all(|(c, n)| c < n) {
+                self.remaining -= 1;
+                return Some(coord);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for GridWalk {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn visits_every_cell_once() {
+        let extents = [5, 3, 7];
+        let mut seen = [false; 5 * 3 * 7];
+        for c in GridWalk::new(&extents) {
+            let i = (c[0] * 3 + c[1]) * 7 + c[2];
+            assert!(!seen[i], "cell {:?} visited twice", c);
+            seen[i] = true;
+        }
+        assert!(seen.iter().all(|&s| s));
+    }
+
+    #[test]
+    fn prefixes_are_subgrids() {
+        let cells: Vec<Vec<usize>> = GridWalk::new(&[8, 8]).take(16).collect();
+        for c in cells {
+            assert!(c[0] % 2 == 0 && c[1] % 2 == 0, "{:?} not on the coarse grid", c);
+        }
+    }
+
+    #[test]
+    fn empty_grids() {
+        assert_eq!(GridWalk::new(&[3, 0]).count(), 0);
+        assert_eq!(GridWalk::new(&[]).collect::<Vec<_>>(), vec![Vec::<usize>::new()]);
+    }
+
+    #[test]
+    fn cell_clamps_to_grid() {
+        assert_eq!(cell(&[1.0, 0.0], &[3, 3]), vec![2, 0]);
+    }
+}
@@ -2,7 +2,14 @@
 //!
 //! Extension for the `rand` crate adds generators for random number sequences.
 
+extern crate num_traits;
 extern crate rand;
+#[cfg(feature="simd")]
+extern crate wide;
+#[cfg(feature="nalgebra")]
+extern crate nalgebra;
+#[cfg(feature="ndarray")]
+extern crate ndarray;
 #[cfg(test)]
 #[macro_use]
 extern crate quickcheck;
@@ -45,7 +52,39 @@ macro_rules! abs_err_eq {
 }
 
 
+pub mod error;
 pub mod quasi;
+pub mod adapter;
+pub mod allocation;
+pub mod cluster;
+pub mod subsample;
+pub mod mesh;
+pub mod polytope;
+pub mod curve;
+pub mod sqmc;
+pub mod bootstrap;
+pub mod survey;
+pub mod combination;
+pub mod grid;
+pub mod domain;
+pub mod boundary;
+pub mod transform;
+pub mod sampler;
+pub mod point_set;
+pub mod prelude;
+pub mod spec;
+pub mod progressive;
+pub mod distributions;
+pub mod sde;
+pub mod primes;
+#[cfg(feature="simd")]
+pub mod simd;
+#[cfg(feature="nalgebra")]
+pub mod linalg;
+#[cfg(feature="ndarray")]
+pub mod array;
+
+pub use error::{Error, Result};
 
 
 mod test {
@@ -0,0 +1,138 @@
+//! Points as nalgebra vectors and matrices
+//!
+//! [`NalgebraRng`] draws [`Point2`], [`Point3`] and [`SVector`] values from the next
+//! coordinates of any generator, and [`fill_matrix`] writes one point of a [`PointSampler`]
+//! into every row of a [`DMatrix`], so designs can go straight into linear algebra code
+//! without copying through vectors of coordinates.
+//!
+//! Only available with the `nalgebra` feature.
+//!
+//! [`NalgebraRng`]: trait.NalgebraRng.html
+//! [`fill_matrix`]: fn.fill_matrix.html
+//! [`PointSampler`]: ../sampler/trait.PointSampler.html
+//! [`Point2`]: https://docs.rs/nalgebra/*/nalgebra/geometry/type.Point2.html
+//! [`Point3`]: https://docs.rs/nalgebra/*/nalgebra/geometry/type.Point3.html
+//! [`SVector`]: https://docs.rs/nalgebra/*/nalgebra/base/type.SVector.html
+//! [`DMatrix`]: https://docs.rs/nalgebra/*/nalgebra/base/type.DMatrix.html
+
+use rand::Rng;
+
+pub use nalgebra::{DMatrix, Point2, Point3, SVector};
+
+use point_set::PointSet;
+use sampler::PointSampler;
+
+/// Draw nalgebra points and vectors from consecutive values of a generator
+///
+/// Coordinate `i` holds the `i`-th value drawn, so a generator of `D` dimensions gives one
+/// whole point per call of the matching size.  Implemented for every [`Rng`].
+///
+/// # Examples
+///
+/// ```
+/// # extern crate tapas;
+/// # use tapas::quasi::HaltonSeq;
+/// use tapas::linalg::NalgebraRng;
+///
+/// # fn main() {
+/// let mut gen = HaltonSeq::new(3).unwrap();
+/// let p = gen.next_point3();
+/// assert_eq!((p.x, p.y, p.z), (0.5, 1. / 3., 0.2));
+///
+/// let v = gen.next_svector::<3>();
+/// assert_eq!(v[2], 0.4);
+/// # }
+/// ```
+///
+/// [`Rng`]: ../../rand/trait.Rng.html
+pub trait NalgebraRng: Rng {
+    /// Next two values as a point of the plane
+    fn next_point2(&mut self) -> Point2<f64> {
+        let x = self.next_f64();
+        Point2::new(x, self.next_f64())
+    }
+
+    /// Next three values as a point of space
+    fn next_point3(&mut self) -> Point3<f64> {
+        let x = self.next_f64();
+        let y = self.next_f64();
+        Point3::new(x, y, self.next_f64())
+    }
+
+    /// Next `D` values as a vector
+    fn next_svector<const D: usize>(&mut self) -> SVector<f64, D> {
+        SVector::from_fn(|_, _| self.next_f64())
+    }
+}
+
+impl<R: Rng + ?Sized> NalgebraRng for R {}
+
+/// Fill every row of `matrix` with the next point of `sampler`
+///
+/// ```
+/// # extern crate tapas;
+/// # use tapas::quasi::Sobol;
+/// use tapas::linalg::{fill_matrix, DMatrix};
+///
+/// # fn main() {
+/// let mut design = DMatrix::zeros(4, 2);
+/// fill_matrix(&mut Sobol::new(2).unwrap(), &mut design);
+/// assert_eq!(design.row(1), DMatrix::from_row_slice(1, 2, &[0.5, 0.5]));
+/// # }
+/// ```
+///
+/// # Panics
+///
+/// If `matrix` does not have one column per dimension of the sampler.
+pub fn fill_matrix<P: PointSampler + ?Sized>(sampler: &mut P, matrix: &mut DMatrix<f64>) {
+    let d = sampler.dimensions();
+    assert_eq!(matrix.ncols(), d, "{} column matrix for {}-dimensional points",
+        matrix.ncols(), d);
+    let mut point = vec![0.; d];
+    for mut row in matrix.row_iter_mut() {
+        sampler.next_point(&mut point);
+        row.copy_from_slice(&point);
+    }
+}
+
+impl<'a> From<&'a PointSet> for DMatrix<f64> {
+    /// Matrix with one row per point and one column per dimension
+    fn from(set: &'a PointSet) -> DMatrix<f64> {
+        DMatrix::from_row_slice(set.len(), set.dimensions(), set.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::{HaltonSeq, Sobol};
+
+    #[test]
+    fn values_follow_sequence() {
+        let mut scalar = HaltonSeq::new(5).unwrap();
+        let mut vector = HaltonSeq::new(5).unwrap();
+        let expected: Vec<f64> = (0..10).map(|_| scalar.next_f64()).collect();
+
+        let p = vector.next_point2();
+        let q = vector.next_point3();
+        assert_eq!(vec![p.x, p.y, q.x, q.y, q.z], expected[..5].to_vec());
+        assert_eq!(vector.next_svector::<5>().as_slice(), &expected[5..]);
+    }
+
+    #[test]
+    fn matrices_match_point_sets() {
+        let set = PointSet::from_sampler(Sobol::new(3).unwrap(), 32);
+        let mut filled = DMatrix::zeros(32, 3);
+        fill_matrix(&mut Sobol::new(3).unwrap(), &mut filled);
+        assert_eq!(filled, DMatrix::from(&set));
+        for (i, p) in set.iter().enumerate() {
+            assert_eq!(filled.row(i).iter().cloned().collect::<Vec<f64>>(), p);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn mismatched_columns() {
+        fill_matrix(&mut Sobol::new(3).unwrap(), &mut DMatrix::zeros(4, 2));
+    }
+}
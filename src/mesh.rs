@@ -0,0 +1,288 @@
+//! # Mesh Sampling
+//!
+//! Distribute points uniformly by area over the surface of triangle meshes.
+
+use rand::Rng;
+use rand::distributions::{IndependentSample, Sample};
+
+use error::{Error, Result};
+use transform::triangle;
+
+/// Area-weighted point sampler over the surface of a triangle mesh
+///
+/// Every point consumes two consecutive values from the generator.  The first selects a triangle
+/// by inverting the cumulative area distribution and is then rescaled to `[0, 1)` within the
+/// selected triangle's bucket, so both values also drive the warp inside the triangle.  This
+/// reuse keeps the stratification of a quasi-random stream intact across the whole surface.
+/// Inside a triangle the point is placed with the low-distortion mapping of
+/// [`transform::triangle`], and [`points`](#method.points) turns a generator into an endless
+/// stream of points on the surface.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate tapas;
+/// # use tapas::quasi::Interleave;
+/// # use tapas::quasi::Halton;
+/// # use tapas::mesh::MeshSampler;
+/// use rand::distributions::IndependentSample;
+///
+/// # fn main() {
+/// // Unit square in the xy-plane made from two triangles
+/// let positions = [[0., 0., 0.], [1., 0., 0.], [1., 1., 0.], [0., 1., 0.]];
+/// let mesh = MeshSampler::new(&positions, &[[0, 1, 2], [0, 2, 3]]).unwrap();
+///
+/// let mut gen = Interleave::new(&[
+///     Halton::new(1, 2).unwrap(),
+///     Halton::new(1, 3).unwrap(),
+/// ]).unwrap();
+/// let p = mesh.ind_sample(&mut gen);
+/// assert!(p[0] >= 0. && p[0] <= 1. && p[1] >= 0. && p[1] <= 1. && p[2] == 0.);
+/// # }
+/// ```
+///
+/// [`transform::triangle`]: ../transform/fn.triangle.html
+#[derive(Debug, Clone)]
+pub struct MeshSampler {
+    positions: Vec<[f64; 3]>,
+    triangles: Vec<[usize; 3]>,
+    /// Cumulative triangle areas normalized so the last element is `1`
+    cdf: Vec<f64>,
+    /// Total surface area of the mesh
+    area: f64,
+}
+
+impl MeshSampler {
+    /// Create a sampler over the triangles `indices` into the vertex list `positions`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidParameter`] if there are no triangles, an index is out of
+    /// bounds, or the mesh has no area.
+    ///
+    /// [`Error::InvalidParameter`]: ../enum.Error.html#variant.InvalidParameter
+    pub fn new(positions: &[[f64; 3]], indices: &[[usize; 3]]) -> Result<MeshSampler> {
+        let invalid = |reason: String| Err(Error::InvalidParameter { name: "mesh", reason });
+        if indices.is_empty() {
+            return invalid("mesh has no triangles".to_owned());
+        }
+        if !indices.iter().all(|t| t.iter().all(|&i| i < positions.len())) {
+            return invalid(format!("triangle index out of bounds for {} vertices",
+                positions.len()));
+        }
+
+        let mut area = 0.;
+        let mut cdf: Vec<f64> = indices.iter()
+            .map(|t| {
+                area += triangle_area(&positions[t[0]], &positions[t[1]], &positions[t[2]]);
+                area
+            })
+            .collect();
+
+        if !(area > 0. && area.is_finite()) {
+            return invalid(format!("mesh has a surface area of {}", area));
+        }
+        for c in cdf.iter_mut() {
+            *c /= area;
+        }
+        *cdf.last_mut().unwrap() = 1.;
+
+        Ok(MeshSampler {
+            positions: positions.to_vec(),
+            triangles: indices.to_vec(),
+            cdf,
+            area,
+        })
+    }
+
+    /// Create a sampler over a list of triangles, each given by its three vertices
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidParameter`] if there are no triangles or they have no area.
+    ///
+    /// [`Error::InvalidParameter`]: ../enum.Error.html#variant.InvalidParameter
+    pub fn from_triangles(triangles: &[[[f64; 3]; 3]]) -> Result<MeshSampler> {
+        let positions: Vec<[f64; 3]> = triangles.iter().flat_map(|t| t.iter().cloned()).collect();
+        let indices: Vec<[usize; 3]> = (0..triangles.len())
+            .map(|i| [3 * i, 3 * i + 1, 3 * i + 2])
+            .collect();
+        MeshSampler::new(&positions, &indices)
+    }
+
+    /// Total surface area of the mesh
+    pub fn area(&self) -> f64 {
+        self.area
+    }
+
+    /// Number of triangles in the mesh
+    pub fn len(&self) -> usize {
+        self.triangles.len()
+    }
+
+    /// Whether the mesh has no triangles, which can never be the case for a constructed sampler
+    pub fn is_empty(&self) -> bool {
+        self.triangles.is_empty()
+    }
+
+    /// Select the triangle covering `u` in the area distribution
+    ///
+    /// Returns the triangle index together with `u` rescaled to `[0, 1)` within that triangle.
+    pub fn triangle(&self, u: f64) -> (usize, f64) {
+        let t = match self.cdf.binary_search_by(|c| c.partial_cmp(&u).unwrap()) {
+            // Landing exactly on a boundary belongs to the next triangle
+            Ok(i) => (i + 1).min(self.cdf.len() - 1),
+            Err(i) => i.min(self.cdf.len() - 1),
+        };
+
+        let lo = if t == 0 { 0. } else { self.cdf[t - 1] };
+        let width = self.cdf[t] - lo;
+        let rescaled = if width > 0. { ((u - lo) / width).clamp(0., 1.) } else { 0. };
+
+        (t, rescaled)
+    }
+
+    /// Map a point of the unit square onto the surface of the mesh
+    pub fn point(&self, u: f64, v: f64) -> [f64; 3] {
+        let (t, u) = self.triangle(u);
+        let [a, b, c] = self.triangles[t];
+        triangle(&self.positions[a], &self.positions[b], &self.positions[c], [u, v])
+    }
+
+    /// Endless stream of points on the surface, two values drawn from `rng` per point
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tapas::mesh::MeshSampler;
+    /// # use tapas::quasi::HaltonSeq;
+    /// let triangle = [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]];
+    /// let mesh = MeshSampler::from_triangles(&[triangle]).unwrap();
+    /// for [x, y, _] in mesh.points(HaltonSeq::new(2).unwrap()).take(100) {
+    ///     assert!(x >= 0. && y >= 0. && x + y <= 1. + 1e-12);
+    /// }
+    /// ```
+    pub fn points<R: Rng>(&self, rng: R) -> MeshPoints<'_, R> {
+        MeshPoints { mesh: self, rng }
+    }
+}
+
+/// Endless stream of points on the surface of a mesh, built with [`MeshSampler::points`]
+///
+/// [`MeshSampler::points`]: struct.MeshSampler.html#method.points
+#[derive(Debug, Clone)]
+pub struct MeshPoints<'a, R> {
+    mesh: &'a MeshSampler,
+    rng: R,
+}
+
+impl<'a, R> MeshPoints<'a, R> {
+    /// Unwrap the generator
+    pub fn into_inner(self) -> R {
+        self.rng
+    }
+}
+
+impl<'a, R: Rng> Iterator for MeshPoints<'a, R> {
+    type Item = [f64; 3];
+
+    fn next(&mut self) -> Option<[f64; 3]> {
+        Some(self.mesh.ind_sample(&mut self.rng))
+    }
+}
+
+impl Sample<[f64; 3]> for MeshSampler {
+    fn sample<R: Rng>(&mut self, rng: &mut R) -> [f64; 3] {
+        self.ind_sample(rng)
+    }
+}
+
+impl IndependentSample<[f64; 3]> for MeshSampler {
+    fn ind_sample<R: Rng>(&self, rng: &mut R) -> [f64; 3] {
+        let u = rng.next_f64();
+        let v = rng.next_f64();
+        self.point(u, v)
+    }
+}
+
+/// Area of the triangle spanned by three points
+fn triangle_area(a: &[f64; 3], b: &[f64; 3], c: &[f64; 3]) -> f64 {
+    let e1 = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+    let e2 = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+    let cross = [
+        e1[1] * e2[2] - e1[2] * e2[1],
+        e1[2] * e2[0] - e1[0] * e2[2],
+        e1[0] * e2[1] - e1[1] * e2[0],
+    ];
+    0.5 * (cross[0] * cross[0] + cross[1] * cross[1] + cross[2] * cross[2]).sqrt()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::{Halton, HaltonSeq, Interleave};
+
+    #[test]
+    fn area_of_unit_cube() {
+        let positions = [
+            [0., 0., 0.], [1., 0., 0.], [1., 1., 0.], [0., 1., 0.],
+            [0., 0., 1.], [1., 0., 1.], [1., 1., 1.], [0., 1., 1.],
+        ];
+        let faces = [
+            [0, 1, 2], [0, 2, 3], [4, 5, 6], [4, 6, 7],
+            [0, 1, 5], [0, 5, 4], [3, 2, 6], [3, 6, 7],
+            [0, 3, 7], [0, 7, 4], [1, 2, 6], [1, 6, 5],
+        ];
+        let mesh = MeshSampler::new(&positions, &faces).unwrap();
+        abs_err_eq!((mesh.area()) == 6. ~ 1e-12);
+    }
+
+    #[test]
+    fn samples_proportional_to_area() {
+        // Small triangle with area 0.5 next to a large one with area 1.5
+        let positions = [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.], [3., 0., 0.], [0., 0., 1.]];
+        let mesh = MeshSampler::new(&positions, &[[0, 1, 2], [0, 3, 4]]).unwrap();
+
+        let mut gen = Interleave::new(&[
+            Halton::new(1, 2).unwrap(),
+            Halton::new(1, 3).unwrap(),
+        ]).unwrap();
+        let n = 4000;
+        let in_xy = (0..n).filter(|_| mesh.ind_sample(&mut gen)[2] == 0.).count();
+        abs_err_eq!((in_xy as f64 / n as f64) == 0.25 ~ 0.01);
+    }
+
+    #[test]
+    fn triangle_selection_rescales() {
+        let positions = [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.], [0., 0., 1.]];
+        let mesh = MeshSampler::new(&positions, &[[0, 1, 2], [0, 1, 3]]).unwrap();
+        let (t, u) = mesh.triangle(0.75);
+        assert_eq!(t, 1);
+        abs_err_eq!(u == 0.5 ~ 1e-12);
+    }
+
+    #[test]
+    fn stream_matches_samples() {
+        let tris = [
+            [[0., 0., 0.], [2., 0., 0.], [0., 1., 0.]],
+            [[0., 0., 1.], [0., 1., 1.], [1., 0., 1.]],
+        ];
+        let mesh = MeshSampler::from_triangles(&tris).unwrap();
+        assert_eq!((mesh.len(), mesh.area()), (2, 1.5));
+
+        let mut gen = HaltonSeq::new(2).unwrap();
+        for p in mesh.points(HaltonSeq::new(2).unwrap()).take(50) {
+            assert_eq!(p, mesh.ind_sample(&mut gen));
+        }
+    }
+
+    #[test]
+    fn invalid_meshes() {
+        let positions = [[0., 0., 0.], [1., 0., 0.], [2., 0., 0.]];
+        for indices in [&[][..], &[[0, 1, 3]][..], &[[0, 1, 2]][..]].iter() {
+            assert!(matches!(MeshSampler::new(&positions, indices),
+                Err(Error::InvalidParameter { name: "mesh", .. })));
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//! # Point Sets
+//!
+//! Materialized points of a fixed number of dimensions, for analysis after generation.
+//!
+//! Generators produce points one at a time, while discrepancy measures, plots and exports need
+//! a whole design at once, row by row or dimension by dimension.  A [`PointSet`] stores `n`
+//! points of `d` dimensions contiguously, point after point, and hands them out as slices
+//! without copying.
+//!
+//! [`PointSet`]: struct.PointSet.html
+
+use rand::Rng;
+use std::ops::{Index, Range};
+use std::slice::Chunks;
+
+use sampler::PointSampler;
+
+/// Points of the same number of dimensions stored one after another
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::point_set::PointSet;
+/// # use tapas::quasi::HaltonSeq;
+/// let set = PointSet::from_sampler(HaltonSeq::new(2).unwrap(), 4);
+/// assert_eq!((set.len(), set.dimensions()), (4, 2));
+///
+/// // Points are slices, and columns gather one coordinate of every point
+/// assert_eq!(set[0], [0.5, 1. / 3.]);
+/// assert_eq!(set.column(0).collect::<Vec<f64>>(), vec![0.5, 0.25, 0.75, 0.125]);
+///
+/// // Slicing keeps the structure of the set
+/// let tail = set.slice(2..4);
+/// assert_eq!(tail.to_vecs(), vec![vec![0.75, 1. / 9.], vec![0.125, 4. / 9.]]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PointSet {
+    dimensions: usize,
+    /// Coordinates of every point, one point after another
+    coordinates: Vec<f64>,
+}
+
+impl PointSet {
+    /// Empty set of points with `dimensions` dimensions
+    ///
+    /// # Panics
+    ///
+    /// If `dimensions` is zero.
+    pub fn new(dimensions: usize) -> PointSet {
+        assert!(dimensions > 0, "points need at least one dimension");
+        PointSet { dimensions, coordinates: Vec::new() }
+    }
+
+    /// First `n` points of `rng`, taking `dimensions` consecutive values for every point
+    ///
+    /// # Panics
+    ///
+    /// If `dimensions` is zero.
+    pub fn from_rng<R: Rng + ?Sized>(rng: &mut R, n: usize, dimensions: usize) -> PointSet {
+        let mut set = PointSet::new(dimensions);
+        set.coordinates = (0..n * dimensions).map(|_| rng.next_f64()).collect();
+        set
+    }
+
+    /// First `n` points of `sampler`
+    ///
+    /// # Panics
+    ///
+    /// If the sampler has zero dimensions.
+    pub fn from_sampler<P: PointSampler>(mut sampler: P, n: usize) -> PointSet {
+        let d = sampler.dimensions();
+        let mut set = PointSet::new(d);
+        set.coordinates = vec![0.; n * d];
+        for point in set.coordinates.chunks_mut(d) {
+            sampler.next_point(point);
+        }
+        set
+    }
+
+    /// Number of points
+    pub fn len(&self) -> usize {
+        self.coordinates.len() / self.dimensions
+    }
+
+    /// Whether the set has no points
+    pub fn is_empty(&self) -> bool {
+        self.coordinates.is_empty()
+    }
+
+    /// Number of dimensions of each point
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    /// Add a point at the end of the set
+    ///
+    /// # Panics
+    ///
+    /// If `point` does not have as many dimensions as the set.
+    pub fn push(&mut self, point: &[f64]) {
+        assert_eq!(point.len(), self.dimensions, "{}-dimensional point in a {}-dimensional set",
+            point.len(), self.dimensions);
+        self.coordinates.extend_from_slice(point);
+    }
+
+    /// Point `i` of the set
+    ///
+    /// # Panics
+    ///
+    /// If `i` is not below the number of points.
+    pub fn point(&self, i: usize) -> &[f64] {
+        assert!(i < self.len(), "point {} of a {} point set", i, self.len());
+        &self.coordinates[i * self.dimensions..(i + 1) * self.dimensions]
+    }
+
+    /// Iterator over the points of the set, in order
+    pub fn iter(&self) -> Chunks<'_, f64> {
+        self.coordinates.chunks(self.dimensions)
+    }
+
+    /// Coordinate `j` of every point, in order
+    ///
+    /// # Panics
+    ///
+    /// If `j` is not below the number of dimensions.
+    pub fn column(&self, j: usize) -> impl Iterator<Item = f64> + '_ {
+        assert!(j < self.dimensions, "dimension {} of a {}-dimensional set", j, self.dimensions);
+        self.coordinates.iter().skip(j).step_by(self.dimensions).cloned()
+    }
+
+    /// Copy of the points in `range`
+    ///
+    /// # Panics
+    ///
+    /// If `range` reaches past the last point.
+    pub fn slice(&self, range: Range<usize>) -> PointSet {
+        assert!(range.end <= self.len(), "points {:?} of a {} point set", range, self.len());
+        let d = self.dimensions;
+        PointSet {
+            dimensions: d,
+            coordinates: self.coordinates[range.start * d..range.end * d].to_vec(),
+        }
+    }
+
+    /// Points made of the coordinates in `dimensions` of every point, in that order
+    ///
+    /// ```
+    /// # use tapas::point_set::PointSet;
+    /// # use tapas::quasi::HaltonSeq;
+    /// let set = PointSet::from_sampler(HaltonSeq::new(4).unwrap(), 8);
+    /// let pair = set.project(&[3, 0]);
+    /// assert_eq!(pair[1], [2. / 7., 0.25]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `dimensions` is empty or holds a dimension which the points do not have.
+    pub fn project(&self, dimensions: &[usize]) -> PointSet {
+        assert!(dimensions.iter().all(|&j| j < self.dimensions),
+            "projection onto {:?} of a {}-dimensional set", dimensions, self.dimensions);
+        let mut set = PointSet::new(dimensions.len());
+        set.coordinates = self.iter().flat_map(|p| dimensions.iter().map(move |&j| p[j])).collect();
+        set
+    }
+
+    /// Coordinates of every point, one point after another
+    pub fn as_slice(&self) -> &[f64] {
+        &self.coordinates
+    }
+
+    /// Copy every point into a vector of its own
+    pub fn to_vecs(&self) -> Vec<Vec<f64>> {
+        self.iter().map(|p| p.to_vec()).collect()
+    }
+}
+
+impl Index<usize> for PointSet {
+    type Output = [f64];
+
+    fn index(&self, i: usize) -> &[f64] {
+        self.point(i)
+    }
+}
+
+impl<'a> IntoIterator for &'a PointSet {
+    type Item = &'a [f64];
+    type IntoIter = Chunks<'a, f64>;
+
+    fn into_iter(self) -> Chunks<'a, f64> {
+        self.iter()
+    }
+}
+
+impl Extend<Vec<f64>> for PointSet {
+    /// Add every point at the end of the set
+    ///
+    /// # Panics
+    ///
+    /// If a point does not have as many dimensions as the set.
+    fn extend<I: IntoIterator<Item = Vec<f64>>>(&mut self, points: I) {
+        for p in points {
+            self.push(&p);
+        }
+    }
+}
+
+impl From<PointSet> for Vec<Vec<f64>> {
+    fn from(set: PointSet) -> Vec<Vec<f64>> {
+        set.to_vecs()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::Sobol;
+
+    #[test]
+    fn generators_agree() {
+        let from_rng = PointSet::from_rng(&mut Sobol::new(3).unwrap(), 64, 3);
+        let from_sampler = PointSet::from_sampler(Sobol::new(3).unwrap(), 64);
+        assert_eq!(from_rng, from_sampler);
+
+        let mut pushed = PointSet::new(3);
+        pushed.extend(Sobol::new(3).unwrap().take(64));
+        assert_eq!(pushed, from_rng);
+        assert_eq!(Vec::from(pushed), Sobol::new(3).unwrap().take(64).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let mut set = PointSet::new(2);
+        assert!(set.is_empty());
+        for i in 0..5 {
+            set.push(&[i as f64, -(i as f64)]);
+        }
+        assert_eq!(set.len(), 5);
+        assert_eq!(set.column(1).sum::<f64>(), -10.);
+        assert_eq!((&set).into_iter().nth(3), Some(&[3., -3.][..]));
+        assert_eq!(set.slice(1..3).as_slice(), &[1., -1., 2., -2.]);
+        assert!(set.slice(5..5).is_empty());
+    }
+
+    #[test]
+    fn projections_match_generators() {
+        let set = PointSet::from_sampler(Sobol::new(6).unwrap(), 100);
+        let projected = PointSet::from_sampler(Sobol::new(6).unwrap().project(&[5, 2]), 100);
+        assert_eq!(set.project(&[5, 2]), projected);
+        assert_eq!(set.project(&[1]).as_slice(), &set.column(1).collect::<Vec<f64>>()[..]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn mismatched_point() {
+        PointSet::new(2).push(&[0.5]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn past_the_end() {
+        PointSet::from_rng(&mut Sobol::new(1).unwrap(), 3, 1).slice(2..4);
+    }
+}
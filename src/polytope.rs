@@ -0,0 +1,334 @@
+//! # Polytopes
+//!
+//! Sample points inside convex polytopes described by linear inequalities `A x ≤ b`.
+//!
+//! Two samplers are available:
+//!
+//! - [`Rejection`] maps unit-cube points into a bounding box and discards those outside of the
+//!   polytope, keeping track of the acceptance rate so poorly fitting boxes are easy to spot.
+//! - [`HitAndRun`] walks through the polytope along coordinate directions, which keeps working
+//!   in high dimension where the volume ratio to any bounding box collapses.
+//!
+//! [`Rejection`]: struct.Rejection.html
+//! [`HitAndRun`]: struct.HitAndRun.html
+
+use rand::Rng;
+
+use error::{Error, Result};
+
+/// Convex polytope `{ x : A x ≤ b }`
+#[derive(Debug, Clone)]
+pub struct Polytope {
+    /// Rows of the constraint matrix `A`
+    a: Vec<Vec<f64>>,
+    /// Right hand side `b` of the constraints
+    b: Vec<f64>,
+}
+
+impl Polytope {
+    /// Create a polytope from the rows of `a` and the bounds `b`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidParameter`] if there are no constraints, the rows of `a` are
+    /// empty or differ in length, or `a` and `b` have a different number of rows.
+    ///
+    /// [`Error::InvalidParameter`]: ../enum.Error.html#variant.InvalidParameter
+    pub fn new(a: Vec<Vec<f64>>, b: Vec<f64>) -> Result<Polytope> {
+        let invalid = |reason: String| Err(Error::InvalidParameter { name: "constraints", reason });
+        if a.is_empty() {
+            return invalid("polytope has no constraints".to_owned());
+        }
+        if a[0].is_empty() {
+            return invalid("constraints on no coordinates".to_owned());
+        }
+        if a.len() != b.len() {
+            return invalid(format!("{} constraint rows but {} bounds", a.len(), b.len()));
+        }
+        if !a.iter().all(|row| row.len() == a[0].len()) {
+            return invalid("ragged constraint matrix".to_owned());
+        }
+
+        Ok(Polytope { a, b })
+    }
+
+    /// Number of dimensions of the space the polytope lives in
+    pub fn dimensions(&self) -> usize {
+        self.a[0].len()
+    }
+
+    /// Whether `x` satisfies every constraint
+    pub fn contains(&self, x: &[f64]) -> bool {
+        self.a.iter().zip(self.b.iter()).all(|(row, b)| dot(row, x) <= *b)
+    }
+
+    /// Extent `(lo, hi)` of the chord through `x` along coordinate `axis`
+    ///
+    /// Moving `x[axis]` by any `t` in `[lo, hi]` keeps the point inside the polytope.
+    fn chord(&self, x: &[f64], axis: usize) -> (f64, f64) {
+        let mut lo = f64::NEG_INFINITY;
+        let mut hi = f64::INFINITY;
+        for (row, b) in self.a.iter().zip(self.b.iter()) {
+            let slack = b - dot(row, x);
+            if row[axis] > 0. {
+                hi = hi.min(slack / row[axis]);
+            } else if row[axis] < 0. {
+                lo = lo.max(slack / row[axis]);
+            }
+        }
+        (lo, hi)
+    }
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b.iter()).map(|(a, b)| a * b).sum()
+}
+
+/// Acceptance statistics of a rejection sampler
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Acceptance {
+    /// Number of proposed points
+    pub proposed: u64,
+    /// Number of proposals which were inside the target region
+    pub accepted: u64,
+}
+
+impl Acceptance {
+    /// Fraction of proposals which were accepted, `NaN` before the first proposal
+    pub fn rate(&self) -> f64 {
+        self.accepted as f64 / self.proposed as f64
+    }
+}
+
+/// Rejection sampler from an axis-aligned bounding box
+///
+/// Each proposal consumes one value per dimension from the generator.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::{Halton, Interleave};
+/// # use tapas::polytope::{Polytope, Rejection};
+/// // Triangle x ≥ 0, y ≥ 0, x + y ≤ 1 inside the unit square
+/// let triangle = Polytope::new(
+///     vec![vec![-1., 0.], vec![0., -1.], vec![1., 1.]],
+///     vec![0., 0., 1.],
+/// ).unwrap();
+/// let mut sampler = Rejection::new(&triangle, &[0., 0.], &[1., 1.]).unwrap();
+///
+/// let mut gen = Interleave::new(&[
+///     Halton::new(1, 2).unwrap(),
+///     Halton::new(1, 3).unwrap(),
+/// ]).unwrap();
+/// for _ in 0..1000 {
+///     let p = sampler.sample(&mut gen, 100).unwrap();
+///     assert!(triangle.contains(&p));
+/// }
+///
+/// assert!((sampler.acceptance().rate() - 0.5).abs() < 0.01);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Rejection<'a> {
+    polytope: &'a Polytope,
+    lo: Vec<f64>,
+    hi: Vec<f64>,
+    stats: Acceptance,
+}
+
+impl<'a> Rejection<'a> {
+    /// Create a rejection sampler proposing from the box `[lo, hi]`
+    ///
+    /// The box should enclose the polytope; any part of the polytope outside of it is never
+    /// sampled.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidParameter`] if a corner of the box does not have one coordinate
+    /// per dimension of the polytope.
+    ///
+    /// [`Error::InvalidParameter`]: ../enum.Error.html#variant.InvalidParameter
+    pub fn new(polytope: &'a Polytope, lo: &[f64], hi: &[f64]) -> Result<Rejection<'a>> {
+        let d = polytope.dimensions();
+        if lo.len() != d || hi.len() != d {
+            return Err(Error::InvalidParameter {
+                name: "box",
+                reason: format!("corners of {} and {} coordinates for a {}-dimensional polytope",
+                    lo.len(), hi.len(), d),
+            });
+        }
+
+        Ok(Rejection {
+            polytope,
+            lo: lo.to_vec(),
+            hi: hi.to_vec(),
+            stats: Acceptance::default(),
+        })
+    }
+
+    /// Propose a single point, returning it only if it lies inside the polytope
+    pub fn propose<R: Rng>(&mut self, rng: &mut R) -> Option<Vec<f64>> {
+        let x: Vec<f64> = self.lo.iter().zip(self.hi.iter())
+            .map(|(lo, hi)| lo + rng.next_f64() * (hi - lo))
+            .collect();
+
+        self.stats.proposed += 1;
+        if self.polytope.contains(&x) {
+            self.stats.accepted += 1;
+            Some(x)
+        } else {
+            None
+        }
+    }
+
+    /// Propose points until one is accepted, giving up after `max_tries` proposals
+    pub fn sample<R: Rng>(&mut self, rng: &mut R, max_tries: usize) -> Option<Vec<f64>> {
+        (0..max_tries).filter_map(|_| self.propose(rng)).next()
+    }
+
+    /// Acceptance statistics over every proposal made so far
+    pub fn acceptance(&self) -> Acceptance {
+        self.stats
+    }
+}
+
+/// Coordinate hit-and-run random walk inside a bounded polytope
+///
+/// Every step consumes two values from the generator: the first chooses a coordinate axis and
+/// the second a uniform position on the chord through the current point along that axis.
+/// Successive points are correlated, so discard a burn-in period and thin the output as needed.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::{Halton, Interleave};
+/// # use tapas::polytope::{Polytope, HitAndRun};
+/// let square = Polytope::new(
+///     vec![vec![1., 0.], vec![-1., 0.], vec![0., 1.], vec![0., -1.]],
+///     vec![1., 1., 1., 1.],
+/// ).unwrap();
+/// let mut walk = HitAndRun::new(&square, vec![0., 0.]).unwrap();
+///
+/// let mut gen = Interleave::new(&[
+///     Halton::new(1, 2).unwrap(),
+///     Halton::new(1, 3).unwrap(),
+/// ]).unwrap();
+/// for _ in 0..100 {
+///     assert!(square.contains(walk.step(&mut gen)));
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct HitAndRun<'a> {
+    polytope: &'a Polytope,
+    current: Vec<f64>,
+}
+
+impl<'a> HitAndRun<'a> {
+    /// Start a walk from the point `start`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidParameter`] if `start` has the wrong dimension or does not lie
+    /// inside the polytope.
+    ///
+    /// [`Error::InvalidParameter`]: ../enum.Error.html#variant.InvalidParameter
+    pub fn new(polytope: &'a Polytope, start: Vec<f64>) -> Result<HitAndRun<'a>> {
+        let invalid = |reason: String| Err(Error::InvalidParameter { name: "start", reason });
+        if start.len() != polytope.dimensions() {
+            return invalid(format!("{} coordinates for a {}-dimensional polytope", start.len(),
+                polytope.dimensions()));
+        }
+        if !polytope.contains(&start) {
+            return invalid(format!("{:?} is outside of the polytope", start));
+        }
+
+        Ok(HitAndRun { polytope, current: start })
+    }
+
+    /// Current position of the walk
+    pub fn current(&self) -> &[f64] {
+        &self.current
+    }
+
+    /// Move to the next point of the walk
+    ///
+    /// # Panics
+    ///
+    /// If the polytope is unbounded along the chosen axis.
+    pub fn step<R: Rng>(&mut self, rng: &mut R) -> &[f64] {
+        let d = self.current.len();
+        let axis = ((rng.next_f64() * d as f64) as usize).min(d - 1);
+        let (lo, hi) = self.polytope.chord(&self.current, axis);
+        assert!(lo.is_finite() && hi.is_finite(), "polytope is unbounded along axis {}", axis);
+
+        self.current[axis] += lo + rng.next_f64() * (hi - lo);
+        &self.current
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::{Halton, Interleave};
+
+    fn simplex() -> Polytope {
+        Polytope::new(
+            vec![vec![-1., 0., 0.], vec![0., -1., 0.], vec![0., 0., -1.], vec![1., 1., 1.]],
+            vec![0., 0., 0., 1.],
+        ).unwrap()
+    }
+
+    #[test]
+    fn rejection_rate_matches_volume() {
+        let p = simplex();
+        let mut sampler = Rejection::new(&p, &[0., 0., 0.], &[1., 1., 1.]).unwrap();
+        let mut gen = Interleave::new(&[
+            Halton::new(1, 2).unwrap(),
+            Halton::new(1, 3).unwrap(),
+            Halton::new(1, 5).unwrap(),
+        ]).unwrap();
+        for _ in 0..6000 {
+            sampler.propose(&mut gen);
+        }
+        abs_err_eq!((sampler.acceptance().rate()) == (1. / 6.) ~ 0.005);
+    }
+
+    #[test]
+    fn hit_and_run_mean() {
+        let p = simplex();
+        let mut walk = HitAndRun::new(&p, vec![0.1, 0.1, 0.1]).unwrap();
+        let mut gen = Interleave::new(&[
+            Halton::new(1, 2).unwrap(),
+            Halton::new(1, 3).unwrap(),
+        ]).unwrap();
+
+        let n = 20_000;
+        let mut mean = [0.; 3];
+        for _ in 0..n {
+            let x = walk.step(&mut gen);
+            assert!(p.contains(x));
+            for j in 0..3 {
+                mean[j] += x[j] / n as f64;
+            }
+        }
+        for m in mean.iter() {
+            abs_err_eq!(m == 0.25 ~ 0.02);
+        }
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        let p = simplex();
+        let name = |err: Error| match err {
+            Error::InvalidParameter { name, .. } => name,
+            other => panic!("expected an invalid parameter, found {:?}", other),
+        };
+        assert_eq!(name(HitAndRun::new(&p, vec![1., 1., 1.]).unwrap_err()), "start");
+        assert_eq!(name(HitAndRun::new(&p, vec![0.1, 0.1]).unwrap_err()), "start");
+        assert_eq!(name(Rejection::new(&p, &[0.; 3], &[1.; 2]).unwrap_err()), "box");
+        assert_eq!(name(Polytope::new(vec![], vec![]).unwrap_err()), "constraints");
+        assert_eq!(name(Polytope::new(vec![vec![1.]], vec![1., 2.]).unwrap_err()), "constraints");
+        let ragged = Polytope::new(vec![vec![1.], vec![1., 2.]], vec![1., 2.]);
+        assert_eq!(name(ragged.unwrap_err()), "constraints");
+        assert_eq!(name(Polytope::new(vec![vec![]], vec![1.]).unwrap_err()), "constraints");
+    }
+}
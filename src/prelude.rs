@@ -0,0 +1,43 @@
+//! # Prelude
+//!
+//! The generators, wrappers and traits needed for most sampling, in one import.
+//!
+//! Generators live in [`quasi`], wrappers of whole streams in [`adapter`] and point-based
+//! interfaces in [`sampler`] and [`point_set`]; the prelude gathers the commonly used ones,
+//! along with [`Rng`] itself, which every generator implements.  Traits of optional features
+//! are included when their feature is enabled.
+//!
+//! ```
+//! use tapas::prelude::*;
+//!
+//! let mut sobol = Sobol::new(2).unwrap().owen_scrambled(3);
+//! let x = sobol.next_f64();
+//! let set = PointSet::from_sampler(HaltonSeq::new(2).unwrap().project(&[1]), 4);
+//! assert!(x < 1. && set.len() == 4);
+//! assert!(inverse_normal(0.5).abs() < 1e-12);
+//! ```
+//!
+//! [`quasi`]: ../quasi/index.html
+//! [`adapter`]: ../adapter/index.html
+//! [`sampler`]: ../sampler/index.html
+//! [`point_set`]: ../point_set/index.html
+//! [`Rng`]: ../../rand/trait.Rng.html
+
+pub use rand::Rng;
+
+pub use adapter::{BurnIn, Padded, Points, Project, ScaledTo, Shuffle, Thin, Warp};
+pub use distributions::{InverseNormal, Quantile};
+pub use distributions::special::inverse_normal;
+pub use point_set::PointSet;
+pub use quasi::{Digital, JumpAhead, Peek};
+pub use quasi::{DigitalShift, LinearScramble, OwenTree, Scramble};
+pub use quasi::{Halton, HaltonSeq, Hammersley, Kronecker, LatinHypercube, Lattice, OwenHalton,
+    Rd, Sobol, VdC, Zip};
+pub use sampler::PointSampler;
+
+#[cfg(feature = "ndarray")]
+pub use array::ToArray2;
+#[cfg(feature = "nalgebra")]
+pub use linalg::NalgebraRng;
+#[cfg(feature = "simd")]
+pub use simd::SimdRng;
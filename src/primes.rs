@@ -0,0 +1,220 @@
+//! # Primes
+//!
+//! Prime numbers for choosing the bases of Halton, Faure and related sequences, where every
+//! dimension needs its own prime.
+//!
+//! ```
+//! # use tapas::primes::{is_prime, nth_prime, Primes};
+//! let bases: Vec<u32> = Primes::new().take(5).collect();
+//! assert_eq!(bases, vec![2, 3, 5, 7, 11]);
+//!
+//! assert_eq!(nth_prime(999), 7919);
+//! assert!(is_prime(2_147_483_647));
+//! ```
+
+/// Iterator over all primes that fit in a `u32`, in increasing order
+#[derive(Debug, Clone, Default)]
+pub struct Primes {
+    /// Primes found so far, kept up to the square root of the largest `u32`
+    found: Vec<u32>,
+    candidate: u64,
+}
+
+/// Number of primes below 2^32
+const U32_PRIMES: usize = 203_280_221;
+
+/// Largest prime needed for trial division of a `u32`
+const SQRT_U32: u32 = 1 << 16;
+
+impl Primes {
+    /// All primes, starting from 2
+    pub fn new() -> Primes {
+        Primes { found: Vec::new(), candidate: 2 }
+    }
+
+    /// Primes from `start` onward
+    ///
+    /// ```
+    /// # use tapas::primes::Primes;
+    /// assert_eq!(Primes::from(100).next(), Some(101));
+    /// ```
+    pub fn from(start: u32) -> Primes {
+        // Trial division needs the primes below the start, up to the stored range
+        let mut primes = Primes::new();
+        let stored = (start as u64).min(SQRT_U32 as u64 + 1);
+        while primes.candidate < stored {
+            let n = primes.candidate as u32;
+            if trial_division(&primes.found, n) {
+                primes.found.push(n);
+            }
+            primes.candidate += 1;
+        }
+        primes.candidate = primes.candidate.max(start as u64);
+        primes
+    }
+}
+
+impl Iterator for Primes {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        while self.candidate <= u32::MAX as u64 {
+            let n = self.candidate as u32;
+            self.candidate += 1;
+
+            let prime = if n <= SQRT_U32 {
+                trial_division(&self.found, n)
+            } else {
+                is_prime(n as u64)
+            };
+            if prime {
+                if n <= SQRT_U32 {
+                    self.found.push(n);
+                }
+                return Some(n);
+            }
+        }
+        None
+    }
+}
+
+/// Whether `n` has no factor among the increasing `primes`, which must reach `√n`
+fn trial_division(primes: &[u32], n: u32) -> bool {
+    primes.iter().take_while(|&&p| p * p <= n).all(|&p| !n.is_multiple_of(p))
+}
+
+/// The `k`-th prime counting from zero, so `nth_prime(0) == 2`
+///
+/// Runs a sieve of Eratosthenes up to an upper bound on the prime.
+///
+/// # Panics
+///
+/// If the prime does not fit in a `u32`, that is for `k` of 203 280 221 or more.
+pub fn nth_prime(k: usize) -> u32 {
+    assert!(k < U32_PRIMES, "the prime with index {} does not fit in a u32", k);
+    if k < 6 {
+        return [2, 3, 5, 7, 11, 13][k];
+    }
+
+    // Rosser's theorem: p_n < n (ln n + ln ln n) for the one-based n ≥ 6
+    let n = (k + 1) as f64;
+    let bound = ((n * (n.ln() + n.ln().ln())).ceil() as usize).min(u32::MAX as usize);
+
+    let mut composite = vec![false; bound + 1];
+    let mut count = 0;
+    for i in 2..=bound {
+        if composite[i] {
+            continue;
+        }
+        if count == k {
+            return i as u32;
+        }
+        count += 1;
+        let mut j = i * i;
+        while j <= bound {
+            composite[j] = true;
+            j += i;
+        }
+    }
+    unreachable!("Rosser's bound holds for every prime index")
+}
+
+/// Whether `n` is prime
+///
+/// Uses a Miller–Rabin test with a fixed set of witnesses which is deterministic for every
+/// `u64`.
+pub fn is_prime(n: u64) -> bool {
+    const WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+    if n < 2 {
+        return false;
+    }
+    for &p in WITNESSES.iter() {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    'witness: for &a in WITNESSES.iter() {
+        let mut x = pow_mod(a, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+/// `base^e` modulo `m`
+pub(crate) fn pow_mod(mut base: u64, mut e: u64, m: u64) -> u64 {
+    let mut result = 1;
+    base %= m;
+    while e > 0 {
+        if e & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        e >>= 1;
+    }
+    result
+}
+
+/// Greatest common divisor of `a` and `b`
+pub(crate) fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn iterator_matches_primality() {
+        let primes: Vec<u32> = Primes::new().take_while(|&p| p < 10_000).collect();
+        let tested: Vec<u32> = (0..10_000).filter(|&n| is_prime(n as u64)).collect();
+        assert_eq!(primes, tested);
+        assert_eq!(primes.len(), 1229);
+    }
+
+    #[test]
+    fn nth_matches_iterator() {
+        for (k, p) in Primes::new().take(2000).enumerate() {
+            assert_eq!(nth_prime(k), p);
+        }
+    }
+
+    #[test]
+    fn past_the_stored_range() {
+        // Primes around 2^16 switch from trial division to Miller–Rabin
+        let around: Vec<u32> = Primes::from(65_500).take(6).collect();
+        assert_eq!(around, vec![65_519, 65_521, 65_537, 65_539, 65_543, 65_551]);
+        let last: Vec<u32> = Primes::from(4_294_967_200).collect();
+        assert_eq!(last, vec![4_294_967_231, 4_294_967_279, 4_294_967_291]);
+    }
+
+    #[test]
+    fn strong_pseudoprimes() {
+        // Composites which fool Miller–Rabin for small sets of witnesses
+        for &n in [2_047u64, 3_215_031_751, 3_825_123_056_546_413_051].iter() {
+            assert!(!is_prime(n), "{} is composite", n);
+        }
+        assert!(is_prime(18_446_744_073_709_551_557));
+    }
+}
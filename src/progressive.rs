@@ -0,0 +1,193 @@
+//! # Progressive Refinement
+//!
+//! Refine quasi-Monte Carlo estimates in stages of doubling size without discarding earlier
+//! evaluations.
+//!
+//! Extensible sequences such as Halton remain well distributed at every prefix, so the points
+//! of a refined design are simply the points of the previous design followed by as many new
+//! ones.  [`Progressive`] hands out those new points stage by stage and keeps a running
+//! estimate, so an adaptive workflow can stop as soon as successive stages agree.
+//!
+//! [`Progressive`]: struct.Progressive.html
+
+use sampler::PointSampler;
+
+/// Result of one refinement stage
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stage {
+    /// Total number of points evaluated up to and including this stage
+    pub points: u64,
+    /// Mean of every evaluation so far
+    pub estimate: f64,
+    /// Difference to the estimate of the previous stage, `NaN` after the first stage
+    pub change: f64,
+}
+
+/// Driver producing batches of doubling size from an extensible sequence
+///
+/// The first stage contains `initial` points and every later stage as many points as all
+/// previous stages together, so the total doubles each time.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::{Halton, Interleave};
+/// # use tapas::progressive::Progressive;
+/// let gen = Interleave::new(&[Halton::new(1, 2).unwrap(), Halton::new(1, 3).unwrap()]).unwrap();
+/// let mut driver = Progressive::new(gen, 64);
+///
+/// // Integrate x·y over the unit square, whose exact value is 1/4
+/// let stage = driver.until(|p| p[0] * p[1], 1e-4, 1 << 20);
+/// assert!((stage.estimate - 0.25).abs() < 1e-3);
+/// assert!(stage.change.abs() < 1e-4);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Progressive<P> {
+    sampler: P,
+    initial: u64,
+    /// Points handed out, evaluated or not
+    points: u64,
+    /// Points evaluated by `refine`, whose values make up `sum`
+    evaluated: u64,
+    sum: f64,
+    estimate: Option<f64>,
+}
+
+impl<P: PointSampler> Progressive<P> {
+    /// Refine with points from `sampler`, starting with a stage of `initial` points
+    ///
+    /// # Panics
+    ///
+    /// If `initial` is zero.
+    pub fn new(sampler: P, initial: u64) -> Progressive<P> {
+        assert!(initial > 0, "first stage needs at least one point");
+
+        Progressive { sampler, initial, points: 0, evaluated: 0, sum: 0., estimate: None }
+    }
+
+    /// Total number of points handed out so far
+    pub fn points(&self) -> u64 {
+        self.points
+    }
+
+    /// Number of points evaluated by [`refine`](#method.refine) so far
+    pub fn evaluated(&self) -> u64 {
+        self.evaluated
+    }
+
+    /// Number of points in the next stage
+    pub fn next_size(&self) -> u64 {
+        self.points.max(self.initial)
+    }
+
+    /// Estimate after the latest call to [`refine`](#method.refine), if any
+    pub fn estimate(&self) -> Option<f64> {
+        self.estimate
+    }
+
+    /// New points of the next stage
+    ///
+    /// Points handed out here are not part of the running estimate; use
+    /// [`refine`](#method.refine) to evaluate a stage and update the estimate in one go.
+    pub fn next_batch(&mut self) -> Vec<Vec<f64>> {
+        let n = self.next_size();
+        let d = self.sampler.dimensions();
+        self.points += n;
+        (0..n)
+            .map(|_| {
+                let mut p = vec![0.; d];
+                self.sampler.next_point(&mut p);
+                p
+            })
+            .collect()
+    }
+
+    /// Evaluate `f` on the points of the next stage and update the running estimate
+    pub fn refine<F: FnMut(&[f64]) -> f64>(&mut self, mut f: F) -> Stage {
+        let batch = self.next_batch();
+        self.sum += batch.iter().map(|p| f(p)).sum::<f64>();
+        self.evaluated += batch.len() as u64;
+
+        let estimate = self.sum / self.evaluated as f64;
+        let change = self.estimate.map_or(f64::NAN, |previous| estimate - previous);
+        self.estimate = Some(estimate);
+
+        Stage { points: self.evaluated, estimate, change }
+    }
+
+    /// Refine until two successive estimates differ by less than `tolerance`
+    ///
+    /// Stops early, returning the latest stage, once at least `max_points` points have been
+    /// evaluated.
+    pub fn until<F>(&mut self, mut f: F, tolerance: f64, max_points: u64) -> Stage
+        where F: FnMut(&[f64]) -> f64 {
+
+        loop {
+            let stage = self.refine(&mut f);
+            if stage.change.abs() < tolerance || stage.points >= max_points {
+                return stage;
+            }
+        }
+    }
+
+    /// Unwrap the underlying sampler
+    pub fn into_inner(self) -> P {
+        self.sampler
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::Halton;
+    use rand::Rng;
+
+    #[test]
+    fn stages_double() {
+        let mut driver = Progressive::new(Halton::new(1, 2).unwrap(), 3);
+        let sizes: Vec<usize> = (0..4).map(|_| driver.next_batch().len()).collect();
+        assert_eq!(sizes, vec![3, 3, 6, 12]);
+        assert_eq!(driver.points(), 24);
+    }
+
+    #[test]
+    fn earlier_points_are_kept() {
+        let mut plain = Halton::new(1, 3).unwrap();
+        let mut driver = Progressive::new(Halton::new(1, 3).unwrap(), 2);
+
+        let mut design: Vec<f64> = Vec::new();
+        for _ in 0..3 {
+            design.extend(driver.next_batch().into_iter().map(|p| p[0]));
+        }
+        let expected: Vec<f64> = (0..8).map(|_| plain.next_f64()).collect();
+        assert_eq!(design, expected);
+    }
+
+    #[test]
+    fn first_stage_has_no_change() {
+        let mut driver = Progressive::new(Halton::new(1, 2).unwrap(), 4);
+        let first = driver.refine(|p| p[0]);
+        assert!(first.change.is_nan());
+        assert_eq!(first.estimate, 0.40625);
+
+        let second = driver.refine(|p| p[0]);
+        assert_eq!(second.points, 8);
+        assert_eq!(second.change, second.estimate - first.estimate);
+    }
+
+    #[test]
+    fn gives_up_at_max_points() {
+        let mut driver = Progressive::new(Halton::new(1, 2).unwrap(), 1);
+        let stage = driver.until(|p| p[0], 0., 100);
+        assert_eq!(stage.points, 128);
+    }
+
+    #[test]
+    fn unevaluated_batches_are_not_averaged() {
+        let mut driver = Progressive::new(Halton::new(1, 2).unwrap(), 2);
+        driver.next_batch();
+        let stage = driver.refine(|_| 1.);
+        assert_eq!((stage.points, stage.estimate), (2, 1.));
+        assert_eq!((driver.points(), driver.evaluated()), (4, 2));
+    }
+}
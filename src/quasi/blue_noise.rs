@@ -0,0 +1,291 @@
+use rand::Rng;
+
+use super::JumpAhead;
+
+/// Standard deviation in pixels of the Gaussian filter which measures clusters and voids
+const SIGMA: f64 = 1.5;
+
+/// Tileable blue noise dither mask baked by the void-and-cluster method
+///
+/// Every pixel of a `width × height` mask holds a distinct rank, and the pixels ranked below
+/// any threshold are spread evenly with no clumps or holes, also across the edges of the
+/// mask, so it tiles the plane seamlessly.  Used as per-pixel offsets for dithering or for
+/// shifting a low discrepancy sequence, the error left in an image is pushed to high
+/// frequencies which the eye barely notices.
+///
+/// Ulichney's method starts from a random sparse pattern, moves its tightest clusters into
+/// its largest voids until it settles, and then ranks pixels by removing clusters from and
+/// filling voids in that pattern, with clusters and voids measured by a Gaussian filter on the
+/// torus.  Baking costs time quadratic in the number of pixels, so masks are usually baked
+/// once at sizes such as 64 × 64.
+///
+/// The mask is a lookup table through [`rank`] and [`value`], and implements [`Rng`] by
+/// returning the values of its pixels row by row, starting over after the last one.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate tapas;
+/// # use tapas::quasi::BlueNoiseMask;
+/// use rand::{Rng, SeedableRng, StdRng};
+///
+/// # fn main() {
+/// let mut mask = BlueNoiseMask::new(16, 16, &mut StdRng::from_seed(&[1][..]));
+///
+/// // Every rank appears once, and the mask repeats outside its bounds
+/// let mut ranks = mask.ranks().to_vec();
+/// ranks.sort();
+/// assert_eq!(ranks, (0..256).collect::<Vec<u32>>());
+/// assert_eq!(mask.rank(3, 5), mask.rank(19, -11));
+///
+/// // The stream walks the pixels row by row
+/// assert_eq!(mask.next_f64(), mask.value(0, 0));
+/// assert_eq!(mask.next_f64(), mask.value(1, 0));
+/// # }
+/// ```
+///
+/// # References
+/// - Ulichney, R., The void-and-cluster method for dither array generation
+///
+/// [`rank`]: #method.rank
+/// [`value`]: #method.value
+/// [`Rng`]: ../../../rand/trait.Rng.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlueNoiseMask {
+    width: usize,
+    height: usize,
+    /// Rank of every pixel, row after row
+    ranks: Vec<u32>,
+    index: usize,
+}
+
+impl BlueNoiseMask {
+    /// Bake a `width × height` mask with a random starting pattern drawn from `rng`
+    ///
+    /// # Panics
+    ///
+    /// If the mask has no pixels or more than `2^32`.
+    pub fn new<R: Rng>(width: usize, height: usize, rng: &mut R) -> BlueNoiseMask {
+        let n = width * height;
+        assert!(n > 0, "empty {} × {} mask", width, height);
+        assert!(n as u64 <= 1 << 32, "{} × {} mask has too many pixels to rank", width, height);
+
+        // Filter weight for every offset on the torus
+        let kernel: Vec<f64> = (0..n)
+            .map(|p| {
+                let (dx, dy) = (p % width, p / width);
+                let dx = dx.min(width - dx) as f64;
+                let dy = dy.min(height - dy) as f64;
+                (-(dx * dx + dy * dy) / (2. * SIGMA * SIGMA)).exp()
+            })
+            .collect();
+        let mut field = Field { width, height, kernel, ones: vec![false; n], energy: vec![0.; n] };
+
+        // Random pattern of about a tenth of the pixels, relaxed until the tightest cluster is
+        // the largest void.  Every swap spreads the pattern out, but rounding in the densities
+        // could let two swaps undo each other forever, so relaxation stops after one swap per
+        // pixel at the latest.
+        let start = (n / 10).max(1);
+        let mut ones = 0;
+        while ones < start {
+            let p = rng.gen_range(0, n);
+            if !field.ones[p] {
+                field.toggle(p);
+                ones += 1;
+            }
+        }
+        for _ in 0..n {
+            let cluster = field.tightest_cluster();
+            field.toggle(cluster);
+            let void = field.largest_void();
+            field.toggle(void);
+            if void == cluster {
+                break;
+            }
+        }
+
+        let mut ranks = vec![0; n];
+        let prototype = field.clone();
+        for rank in (0..ones).rev() {
+            let cluster = field.tightest_cluster();
+            field.toggle(cluster);
+            ranks[cluster] = rank as u32;
+        }
+        // With the filter summing to the same total everywhere on the torus, the tightest
+        // cluster of zeros once they are the majority is the largest void among the ones, so
+        // filling voids ranks all remaining pixels
+        let mut field = prototype;
+        for rank in ones..n {
+            let void = field.largest_void();
+            field.toggle(void);
+            ranks[void] = rank as u32;
+        }
+
+        BlueNoiseMask { width, height, ranks, index: 0 }
+    }
+
+    /// Width of the mask in pixels
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Height of the mask in pixels
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Rank of every pixel, row after row
+    pub fn ranks(&self) -> &[u32] {
+        &self.ranks
+    }
+
+    /// Rank of the pixel at `(x, y)`, with the mask tiling the plane
+    pub fn rank(&self, x: i64, y: i64) -> u32 {
+        let x = x.rem_euclid(self.width as i64) as usize;
+        let y = y.rem_euclid(self.height as i64) as usize;
+        self.ranks[y * self.width + x]
+    }
+
+    /// Threshold of the pixel at `(x, y)` in `[0, 1)`, the centre of the interval of its rank
+    pub fn value(&self, x: i64, y: i64) -> f64 {
+        (self.rank(x, y) as f64 + 0.5) / self.ranks.len() as f64
+    }
+
+    /// Rank of the next pixel of the stream
+    #[inline]
+    fn next_rank(&mut self) -> u32 {
+        let rank = self.ranks[self.index];
+        self.index = if self.index + 1 == self.ranks.len() { 0 } else { self.index + 1 };
+        rank
+    }
+}
+
+/// Binary pattern on the torus with the filtered density of its ones at every pixel
+#[derive(Debug, Clone)]
+struct Field {
+    width: usize,
+    height: usize,
+    kernel: Vec<f64>,
+    ones: Vec<bool>,
+    energy: Vec<f64>,
+}
+
+impl Field {
+    fn toggle(&mut self, p: usize) {
+        let sign = if self.ones[p] { -1. } else { 1. };
+        self.ones[p] = !self.ones[p];
+        let (px, py) = (p % self.width, p / self.width);
+        for (q, e) in self.energy.iter_mut().enumerate() {
+            let dx = (q % self.width + self.width - px) % self.width;
+            let dy = (q / self.width + self.height - py) % self.height;
+            *e += sign * self.kernel[dy * self.width + dx];
+        }
+    }
+
+    /// One with the highest density, the first one on ties
+    fn tightest_cluster(&self) -> usize {
+        self.extreme(true, |a, b| a > b)
+    }
+
+    /// Zero with the lowest density, the first one on ties
+    fn largest_void(&self) -> usize {
+        self.extreme(false, |a, b| a < b)
+    }
+
+    fn extreme<F: Fn(f64, f64) -> bool>(&self, one: bool, better: F) -> usize {
+        let mut best = None;
+        for (p, (&o, &e)) in self.ones.iter().zip(self.energy.iter()).enumerate() {
+            if o == one && best.is_none_or(|(_, b)| better(e, b)) {
+                best = Some((p, e));
+            }
+        }
+        best.expect("pattern has pixels of both kinds").0
+    }
+}
+
+impl Rng for BlueNoiseMask {
+    fn next_u32(&mut self) -> u32 {
+        let n = self.ranks.len() as u64;
+        (((2 * self.next_rank() as u64 + 1) << 31) / n) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let n = self.ranks.len() as u128;
+        (((2 * self.next_rank() as u128 + 1) << 63) / n) as u64
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        (self.next_rank() as f64 + 0.5) / self.ranks.len() as f64
+    }
+}
+
+impl JumpAhead for BlueNoiseMask {
+    /// Skip `n` pixels, wrapping around the mask
+    fn jump(&mut self, n: u64) {
+        let len = self.ranks.len() as u64;
+        self.index = ((self.index as u64 + n % len) % len) as usize;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{SeedableRng, StdRng};
+
+    /// Smallest distance on the torus between pixels ranked below `threshold`
+    fn min_spacing(mask: &BlueNoiseMask, threshold: u32) -> f64 {
+        let (w, h) = (mask.width() as i64, mask.height() as i64);
+        let pixels: Vec<(i64, i64)> = (0..w * h)
+            .filter(|&p| mask.ranks()[p as usize] < threshold)
+            .map(|p| (p % w, p / w))
+            .collect();
+        let mut min = f64::INFINITY;
+        for (i, &(ax, ay)) in pixels.iter().enumerate() {
+            for &(bx, by) in pixels[..i].iter() {
+                let dx = (ax - bx).abs().min(w - (ax - bx).abs());
+                let dy = (ay - by).abs().min(h - (ay - by).abs());
+                min = min.min(((dx * dx + dy * dy) as f64).sqrt());
+            }
+        }
+        min
+    }
+
+    #[test]
+    fn ranks_are_permutation() {
+        let mask = BlueNoiseMask::new(12, 20, &mut StdRng::from_seed(&[2][..]));
+        let mut ranks = mask.ranks().to_vec();
+        ranks.sort();
+        assert_eq!(ranks, (0..240).collect::<Vec<u32>>());
+        assert_eq!((mask.width(), mask.height()), (12, 20));
+    }
+
+    #[test]
+    fn thresholds_are_spread() {
+        let mask = BlueNoiseMask::new(32, 32, &mut StdRng::from_seed(&[3][..]));
+        // 64 evenly spread pixels would be 4 apart and 128 would be 2.8 apart, while random
+        // ones would touch
+        assert!(min_spacing(&mask, 64) >= 2.5, "{}", min_spacing(&mask, 64));
+        assert!(min_spacing(&mask, 128) >= 2., "{}", min_spacing(&mask, 128));
+        // Inverting the mask gives a pattern just as spread
+        let inverted = BlueNoiseMask {
+            ranks: mask.ranks().iter().map(|&r| 1023 - r).collect(),
+            ..mask.clone()
+        };
+        assert!(min_spacing(&inverted, 64) >= 2.5, "{}", min_spacing(&inverted, 64));
+    }
+
+    #[test]
+    fn stream_tiles() {
+        let mut mask = BlueNoiseMask::new(4, 3, &mut StdRng::from_seed(&[4][..]));
+        let first: Vec<f64> = (0..12).map(|_| mask.next_f64()).collect();
+        let again: Vec<f64> = (0..12).map(|_| mask.next_f64()).collect();
+        assert_eq!(first, again);
+        abs_err_eq!((first.iter().sum::<f64>() / 12.) == 0.5 ~ 1e-12);
+
+        let mut jumped = mask.clone();
+        jumped.jump(12 * 1000 + 7);
+        assert_eq!(jumped.next_f64(), mask.value(3, 1));
+        assert_eq!(jumped.next_u32(), (mask.value(0, 2) * 4_294_967_296.) as u32);
+    }
+}
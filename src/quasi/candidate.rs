@@ -0,0 +1,241 @@
+use rand::Rng;
+
+use error::{Error, Result};
+
+/// Mitchell's best-candidate points drawn progressively from a candidate stream
+///
+/// Every new point is the best of `k` candidates: the one whose distance to the nearest point
+/// placed so far is largest.  Candidates are whole points read from any generator, such as a
+/// low discrepancy sequence or a pseudo-random one, by taking its coordinates in order.
+/// Unlike Poisson disk sampling there is no radius to pick up front, so points can be drawn
+/// one at a time for as long as needed, with every prefix spread out much like a blue noise
+/// pattern.
+///
+/// Distances are measured on the torus, wrapping around every axis, so a pattern tiles the
+/// unit cube without clumps along its faces.  Placing point `n` costs `k n` distance
+/// evaluations and every point placed is kept.
+///
+/// Implements [`Rng`] by returning the coordinates of consecutive points one after another, and
+/// [`Iterator`] by returning whole points.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::{BestCandidate, Rd};
+/// let mut points = BestCandidate::new(Rd::new(2).unwrap(), 2, 10).unwrap();
+///
+/// // The first point is the first candidate
+/// assert_eq!(points.next(), Some(vec![0.5, 0.5]));
+///
+/// // The next ones keep away from it
+/// for p in points.take(3) {
+///     assert!((p[0] - 0.5).abs().max((p[1] - 0.5).abs()) > 0.2);
+/// }
+/// ```
+///
+/// # References
+/// - Mitchell, D. P., Spectrally optimal sampling for distribution ray tracing
+///
+/// [`Rng`]: ../../../rand/trait.Rng.html
+/// [`Iterator`]: https://doc.rust-lang.org/std/iter/trait.Iterator.html
+#[derive(Debug, Clone)]
+pub struct BestCandidate<R> {
+    source: R,
+    dimensions: usize,
+    candidates: usize,
+    /// Coordinates of every point placed, one point after another
+    points: Vec<f64>,
+    /// Next coordinate of the last point placed
+    current: usize,
+}
+
+impl<R: Rng> BestCandidate<R> {
+    /// Points of `dimensions` dimensions, each the best of `candidates` points from `source`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyGenerators`] if `dimensions` is zero and
+    /// [`Error::InvalidParameter`] if `candidates` is zero.
+    ///
+    /// [`Error::EmptyGenerators`]: ../../enum.Error.html#variant.EmptyGenerators
+    /// [`Error::InvalidParameter`]: ../../enum.Error.html#variant.InvalidParameter
+    pub fn new(source: R, dimensions: usize, candidates: usize) -> Result<BestCandidate<R>> {
+        if dimensions == 0 {
+            return Err(Error::EmptyGenerators);
+        }
+        if candidates == 0 {
+            return Err(Error::InvalidParameter {
+                name: "candidates",
+                reason: "a point needs at least one candidate".to_owned(),
+            });
+        }
+        Ok(BestCandidate {
+            source,
+            dimensions,
+            candidates,
+            points: Vec::new(),
+            current: dimensions,
+        })
+    }
+
+    /// Number of dimensions of each point
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    /// Number of candidates drawn for every point
+    pub fn candidates(&self) -> usize {
+        self.candidates
+    }
+
+    /// Number of points placed so far
+    pub fn points(&self) -> usize {
+        self.points.len() / self.dimensions
+    }
+
+    /// Point `i` among those placed so far
+    ///
+    /// # Panics
+    ///
+    /// If `i` is not below the number of points placed.
+    pub fn point(&self, i: usize) -> &[f64] {
+        assert!(i < self.points(), "point {} of {} placed", i, self.points());
+        &self.points[i * self.dimensions..(i + 1) * self.dimensions]
+    }
+
+    /// Give back the candidate stream
+    pub fn into_inner(self) -> R {
+        self.source
+    }
+
+    /// Place the next point
+    fn place(&mut self) {
+        let d = self.dimensions;
+        let mut best = (-1., vec![0.; d]);
+        let mut candidate = vec![0.; d];
+        for _ in 0..self.candidates {
+            for x in candidate.iter_mut() {
+                *x = self.source.next_f64();
+            }
+            let nearest = self.points.chunks(d)
+                .map(|p| toroidal_distance(p, &candidate))
+                .fold(f64::INFINITY, f64::min);
+            if nearest > best.0 {
+                best = (nearest, candidate.clone());
+            }
+        }
+        self.points.extend(best.1);
+        self.current = 0;
+    }
+
+    #[inline]
+    fn next_coordinate(&mut self) -> f64 {
+        if self.current == self.dimensions {
+            self.place();
+        }
+        let x = self.points[self.points.len() - self.dimensions + self.current];
+        self.current += 1;
+        x
+    }
+}
+
+/// Squared Euclidean distance between `a` and `b` with every axis wrapping around
+fn toroidal_distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b)
+        .map(|(x, y)| {
+            let d = (x - y).abs();
+            let d = d.min(1. - d);
+            d * d
+        })
+        .sum()
+}
+
+impl<R: Rng> Rng for BestCandidate<R> {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_coordinate() * 4_294_967_296.) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        (self.next_coordinate() * 18_446_744_073_709_551_616.) as u64
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        self.next_coordinate()
+    }
+}
+
+impl<R: Rng> Iterator for BestCandidate<R> {
+    type Item = Vec<f64>;
+
+    /// Remaining coordinates of the current point, or the next whole point
+    fn next(&mut self) -> Option<Vec<f64>> {
+        if self.current == self.dimensions {
+            self.place();
+        }
+        let n = self.dimensions - self.current;
+        Some((0..n).map(|_| self.next_coordinate()).collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{SeedableRng, StdRng};
+
+    fn min_distance(points: &[Vec<f64>]) -> f64 {
+        (0..points.len()).flat_map(|a| (0..a).map(move |b| (a, b)))
+            .map(|(a, b)| toroidal_distance(&points[a], &points[b]))
+            .fold(f64::INFINITY, f64::min)
+            .sqrt()
+    }
+
+    #[test]
+    fn no_dimensions() {
+        let rng = StdRng::from_seed(&[1][..]);
+        assert!(matches!(BestCandidate::new(rng, 0, 4), Err(Error::EmptyGenerators)));
+    }
+
+    #[test]
+    fn no_candidates() {
+        let rng = StdRng::from_seed(&[1][..]);
+        assert!(matches!(BestCandidate::new(rng, 2, 0),
+            Err(Error::InvalidParameter { name: "candidates", .. })));
+    }
+
+    #[test]
+    fn spreads_candidates() {
+        let mut rng = StdRng::from_seed(&[2][..]);
+        let random: Vec<Vec<f64>> =
+            (0..256).map(|_| vec![rng.next_f64(), rng.next_f64()]).collect();
+        let best: Vec<Vec<f64>> = BestCandidate::new(rng, 2, 16).unwrap().take(256).collect();
+        // Random points come much closer than 1/(2√n) while the best candidates stay near it
+        assert!(min_distance(&random) < 0.01, "random {}", min_distance(&random));
+        assert!(min_distance(&best) > 0.02, "best candidate {}", min_distance(&best));
+    }
+
+    #[test]
+    fn one_candidate_is_the_source() {
+        let mut rng = StdRng::from_seed(&[3][..]);
+        let mut points = BestCandidate::new(rng, 3, 1).unwrap();
+        for _ in 0..30 {
+            assert_eq!(points.next_f64(), rng.next_f64());
+        }
+        assert_eq!(points.points(), 10);
+        assert_eq!(points.point(9), &points.points[27..]);
+    }
+
+    #[test]
+    fn coordinates_match_points() {
+        let rng = StdRng::from_seed(&[4][..]);
+        let mut points = BestCandidate::new(rng, 3, 8).unwrap();
+        let mut coordinates = points.clone();
+        assert_eq!(points.next_f64(), coordinates.next_f64());
+        assert_eq!(points.next().unwrap().len(), 2);
+        coordinates.next_f64();
+        coordinates.next_f64();
+        let p = points.next().unwrap();
+        let q: Vec<f64> = (0..3).map(|_| coordinates.next_f64()).collect();
+        assert_eq!(p, q);
+        assert_eq!(points.point(1), &p[..]);
+    }
+}
@@ -0,0 +1,451 @@
+use rand::Rng;
+use std::cmp::Ordering;
+use std::fmt;
+
+use super::JumpAhead;
+
+/// Generators whose values are available as exact binary digits
+///
+/// `next_bits` returns the next value of the sequence as a 64 bit binary fraction: the value is
+/// `bits / 2^64`, with the most significant bit holding the first binary digit.  Base-2
+/// constructions such as [`Halton`] with base `2` produce their digits exactly, while other
+/// generators expose the leading 64 bits of their output.  Working on the digits directly lets
+/// sequences be combined digit by digit with [`Xor`].
+///
+/// [`Halton`]: struct.Halton.html
+/// [`Xor`]: struct.Xor.html
+pub trait Digital {
+    /// Next value as a 64 bit binary fraction
+    fn next_bits(&mut self) -> u64;
+
+    /// Next value as an exact dyadic rational
+    fn next_dyadic(&mut self) -> Dyadic {
+        Dyadic::from_bits(self.next_bits())
+    }
+}
+
+/// Exact dyadic rational `mantissa / 2^exponent` in `[0, 1)`
+///
+/// Base-2 sequences produce dyadic rationals, which an `f64` can only hold exactly up to 53
+/// significant bits.  `Dyadic` keeps all 64 bits in lowest terms, so points can be compared,
+/// stored and checked for stratification without rounding.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::{Digital, Dyadic, Halton};
+/// let mut gen = Halton::new(1, 2).unwrap();
+/// gen.next_dyadic();
+/// let x = gen.next_dyadic();
+///
+/// assert_eq!((x.mantissa(), x.exponent()), (1, 2));
+/// assert_eq!(x.to_string(), "1/2^2");
+/// assert_eq!(x.stratum(3), 2);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Dyadic {
+    mantissa: u64,
+    exponent: u32,
+}
+
+impl Dyadic {
+    /// Reduce the 64 bit binary fraction `bits / 2^64` to lowest terms
+    pub fn from_bits(bits: u64) -> Dyadic {
+        if bits == 0 {
+            return Dyadic { mantissa: 0, exponent: 0 };
+        }
+        let zeros = bits.trailing_zeros();
+        Dyadic { mantissa: bits >> zeros, exponent: 64 - zeros }
+    }
+
+    /// Odd numerator, or zero
+    pub fn mantissa(&self) -> u64 {
+        self.mantissa
+    }
+
+    /// Power of two in the denominator
+    pub fn exponent(&self) -> u32 {
+        self.exponent
+    }
+
+    /// Value as a 64 bit binary fraction
+    pub fn to_bits(&self) -> u64 {
+        if self.mantissa == 0 { 0 } else { self.mantissa << (64 - self.exponent) }
+    }
+
+    /// Nearest `f64`, exact when the mantissa has at most 53 bits
+    pub fn to_f64(&self) -> f64 {
+        self.mantissa as f64 / 2f64.powi(self.exponent as i32)
+    }
+
+    /// Index of the interval `[k / 2^m, (k + 1) / 2^m)` containing the value
+    ///
+    /// # Panics
+    ///
+    /// If `m > 64`.
+    pub fn stratum(&self, m: u32) -> u64 {
+        assert!(m <= 64, "{} bits of stratification exceed 64", m);
+        if m == 0 { 0 } else { self.to_bits() >> (64 - m) }
+    }
+}
+
+impl PartialOrd for Dyadic {
+    fn partial_cmp(&self, other: &Dyadic) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Dyadic {
+    fn cmp(&self, other: &Dyadic) -> Ordering {
+        self.to_bits().cmp(&other.to_bits())
+    }
+}
+
+impl fmt::Display for Dyadic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/2^{}", self.mantissa, self.exponent)
+    }
+}
+
+impl<D: Digital + ?Sized> Digital for &mut D {
+    fn next_bits(&mut self) -> u64 {
+        (**self).next_bits()
+    }
+}
+
+/// Convert a 64 bit binary fraction to an `f64` in `[0, 1)`, rounding down
+#[inline]
+pub(crate) fn bits_to_f64(bits: u64) -> f64 {
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Convert a 64 bit binary fraction to an `f32` in `[0, 1)`, rounding down
+#[inline]
+pub(crate) fn bits_to_f32(bits: u64) -> f32 {
+    (bits >> 40) as f32 / (1u32 << 24) as f32
+}
+
+/// Hybrid sequence combining the binary digits of two generators with exclusive or
+///
+/// XOR-ing a base-2 sequence with a constant is a digital shift, which keeps every
+/// stratification property of the sequence; XOR-ing it with another sequence or a hash stream
+/// gives the hybrid scrambled sequences studied by Ökten, trading some uniformity for
+/// randomness.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate tapas;
+/// # use tapas::quasi::{Halton, HashStream, Xor};
+/// use rand::Rng;
+///
+/// # fn main() {
+/// // Scramble the van der Corput sequence with a hash stream
+/// let mut gen = Xor::new(Halton::new(1, 2).unwrap(), HashStream::new(42));
+/// let x = gen.next_f64();
+/// assert!(x >= 0. && x < 1.);
+/// # }
+/// ```
+///
+/// # References
+/// - Ökten, G., Generalized von Neumann-Kakutani transformation and random-start scrambled
+///   Halton sequences
+#[derive(Debug, Clone)]
+pub struct Xor<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A: Digital, B: Digital> Xor<A, B> {
+    /// Combine the digits of `a` and `b`
+    pub fn new(a: A, b: B) -> Xor<A, B> {
+        Xor { a, b }
+    }
+
+    /// Unwrap the combined generators
+    pub fn into_inner(self) -> (A, B) {
+        (self.a, self.b)
+    }
+}
+
+impl<A: Digital, B: Digital> Digital for Xor<A, B> {
+    fn next_bits(&mut self) -> u64 {
+        self.a.next_bits() ^ self.b.next_bits()
+    }
+}
+
+impl<A: Digital, B: Digital> Rng for Xor<A, B> {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_bits() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.next_bits()
+    }
+
+    fn next_f32(&mut self) -> f32 {
+        bits_to_f32(self.next_bits())
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        bits_to_f64(self.next_bits())
+    }
+}
+
+impl<A: JumpAhead, B: JumpAhead> JumpAhead for Xor<A, B> {
+    fn jump(&mut self, n: u64) {
+        self.a.jump(n);
+        self.b.jump(n);
+    }
+}
+
+/// Random digital shift of a digital sequence
+///
+/// Every coordinate of the inner generator is XOR-ed with a fixed random 64 bit pattern, one
+/// pattern per dimension, drawn once from the `Rng` given at construction.  The shift keeps
+/// every stratification property of a digital net while making each point uniformly
+/// distributed, so independently shifted replicates give unbiased estimates whose spread
+/// measures the integration error.  It is much cheaper than Owen scrambling, at the cost of a
+/// weaker randomization.
+///
+/// The inner generator should return the coordinates of its points one after another, as
+/// [`Sobol`] and [`Interleave`] do; shifts are applied to consecutive values in turn.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate tapas;
+/// # use tapas::quasi::{DigitalShift, Sobol};
+/// use rand::{Rng, SeedableRng, StdRng};
+///
+/// # fn main() {
+/// let mut rng = StdRng::from_seed(&[1, 2, 3][..]);
+///
+/// // Independent replicates of the same two dimensional Sobol points
+/// let mut first = DigitalShift::new(Sobol::new(2).unwrap(), 2, &mut rng);
+/// let mut second = DigitalShift::new(Sobol::new(2).unwrap(), 2, &mut rng);
+/// assert!(first.next_f64() != second.next_f64());
+/// # }
+/// ```
+///
+/// [`Sobol`]: ../sobol/struct.Sobol.html
+/// [`Interleave`]: ../struct.Interleave.html
+#[derive(Debug, Clone)]
+pub struct DigitalShift<R> {
+    inner: R,
+    shifts: Vec<u64>,
+    /// Dimension of the next value
+    current: usize,
+}
+
+impl<R: Digital> DigitalShift<R> {
+    /// Shift the values of `inner`, a sequence of `dimensions` dimensional points
+    ///
+    /// # Panics
+    ///
+    /// If `dimensions` is zero.
+    pub fn new<G: Rng>(inner: R, dimensions: usize, rng: &mut G) -> DigitalShift<R> {
+        assert!(dimensions > 0, "points need at least one dimension");
+        let shifts = (0..dimensions).map(|_| rng.next_u64()).collect();
+        DigitalShift { inner, shifts, current: 0 }
+    }
+
+    /// Number of dimensions of the shifted points
+    pub fn dimensions(&self) -> usize {
+        self.shifts.len()
+    }
+
+    /// Shift of every dimension as a 64 bit binary fraction
+    pub fn shifts(&self) -> &[u64] {
+        &self.shifts
+    }
+
+    /// Unwrap the shifted generator
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Digital> Digital for DigitalShift<R> {
+    fn next_bits(&mut self) -> u64 {
+        let bits = self.inner.next_bits() ^ self.shifts[self.current];
+        self.current = (self.current + 1) % self.shifts.len();
+        bits
+    }
+}
+
+impl<R: Digital> Rng for DigitalShift<R> {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_bits() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.next_bits()
+    }
+
+    fn next_f32(&mut self) -> f32 {
+        bits_to_f32(self.next_bits())
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        bits_to_f64(self.next_bits())
+    }
+}
+
+impl<R: JumpAhead> JumpAhead for DigitalShift<R> {
+    fn jump(&mut self, n: u64) {
+        self.inner.jump(n);
+        self.current = ((self.current as u64 + n) % self.shifts.len() as u64) as usize;
+    }
+}
+
+/// Stream of hashed counter values
+///
+/// Value `i` is a SplitMix64 hash of the seed and `i`, so the stream can be indexed and jumped
+/// directly.  Mostly useful as the random component of an [`Xor`] combination.
+///
+/// [`Xor`]: struct.Xor.html
+#[derive(Debug, Clone)]
+pub struct HashStream {
+    seed: u64,
+    index: u64,
+}
+
+impl HashStream {
+    /// Create a hash stream from `seed`
+    pub fn new(seed: u64) -> HashStream {
+        HashStream { seed, index: 0 }
+    }
+}
+
+/// SplitMix64 finalizer
+#[inline]
+pub(crate) fn mix64(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+impl Digital for HashStream {
+    fn next_bits(&mut self) -> u64 {
+        self.index = self.index.wrapping_add(1);
+        mix64(self.seed ^ self.index.wrapping_mul(0x9e37_79b9_7f4a_7c15))
+    }
+}
+
+impl Rng for HashStream {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_bits() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.next_bits()
+    }
+}
+
+impl JumpAhead for HashStream {
+    fn jump(&mut self, n: u64) {
+        self.index = self.index.wrapping_add(n);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::Halton;
+
+    #[test]
+    fn halton_base_two_is_bit_reversal() {
+        let mut gen = Halton::new(1, 2).unwrap();
+        for i in 1..1000u64 {
+            assert_eq!(gen.next_bits(), i.reverse_bits());
+        }
+    }
+
+    #[test]
+    fn dyadic_round_trip() {
+        for &bits in [0, 1, 1 << 63, 0xdead_beef_0000_0000, u64::MAX].iter() {
+            let x = Dyadic::from_bits(bits);
+            assert_eq!(x.to_bits(), bits);
+            assert!(x.mantissa() == 0 || x.mantissa() % 2 == 1);
+        }
+        assert_eq!(Dyadic::from_bits(u64::MAX).exponent(), 64);
+    }
+
+    #[test]
+    fn dyadic_strata_are_exact() {
+        let mut gen = Halton::new(1, 2).unwrap();
+        let points: Vec<Dyadic> = (0..16).map(|_| gen.next_dyadic()).collect();
+        let mut strata: Vec<u64> = points.iter().map(|x| x.stratum(4)).collect();
+        strata.sort();
+        assert_eq!(strata, (0..16).collect::<Vec<u64>>());
+
+        let mut sorted = points.clone();
+        sorted.sort();
+        assert!(sorted.windows(2).all(|w| w[0].to_f64() < w[1].to_f64()));
+    }
+
+    #[test]
+    fn self_xor_vanishes() {
+        let gen = Halton::new(1, 2).unwrap();
+        let mut xor = Xor::new(gen.clone(), gen);
+        assert!((0..100).all(|_| xor.next_bits() == 0));
+    }
+
+    #[test]
+    fn digital_shift_keeps_strata() {
+        // XOR with a constant is a digital shift
+        struct Shift(u64);
+        impl Digital for Shift {
+            fn next_bits(&mut self) -> u64 {
+                self.0
+            }
+        }
+
+        let mut gen = Xor::new(Halton::new(1, 2).unwrap(), Shift(HashStream::new(7).next_bits()));
+        let mut strata = [false; 64];
+        for _ in 0..64 {
+            strata[(gen.next_bits() >> 58) as usize] = true;
+        }
+        assert!(strata.iter().all(|&s| s));
+    }
+
+    #[test]
+    fn shifted_sobol_keeps_nets() {
+        use quasi::Sobol;
+        use rand::{SeedableRng, StdRng};
+
+        let mut rng = StdRng::from_seed(&[7][..]);
+        let mut gen = DigitalShift::new(Sobol::new(2).unwrap(), 2, &mut rng);
+        assert!(gen.shifts()[0] != gen.shifts()[1]);
+
+        // One point in each 4 by 4 square of the unit square
+        let mut strata = [false; 16];
+        for _ in 0..16 {
+            let x = gen.next_bits() >> 62;
+            let y = gen.next_bits() >> 62;
+            strata[(x << 2 | y) as usize] = true;
+        }
+        assert!(strata.iter().all(|&s| s));
+
+        let mut jumped = gen.clone();
+        jumped.jump(5);
+        for _ in 0..5 {
+            gen.next_bits();
+        }
+        assert_eq!(jumped.next_bits(), gen.next_bits());
+    }
+
+    #[test]
+    fn hash_stream_jump() {
+        let mut jumped = HashStream::new(3);
+        let mut drawn = jumped.clone();
+        jumped.jump(10);
+        for _ in 0..10 {
+            drawn.next_bits();
+        }
+        assert_eq!(jumped.next_bits(), drawn.next_bits());
+    }
+}
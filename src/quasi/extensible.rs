@@ -0,0 +1,203 @@
+use rand::Rng;
+
+use error::{Error, Result};
+use super::JumpAhead;
+
+/// Extensible rank-1 lattice sequence in base 2
+///
+/// Point `i` is `frac(φ_2(i) z + Δ)`, where `φ_2` is the van der Corput sequence and `z` an
+/// integer generating vector.  The first `2^m` points are exactly the rank-1 [`Lattice`] rule
+/// of `2^m` points with generating vector `z`, in a different order, so the sequence can be
+/// extended from one power of two to the next without discarding any points already used.
+/// Adaptive integration which doubles the sample count until it converges keeps every earlier
+/// evaluation.
+///
+/// All arithmetic is in 64 bit fixed point, which makes every point exact to 64 bits.
+///
+/// Implements [`Rng`] by returning the coordinates of consecutive points one after another, and
+/// [`Iterator`] by returning whole points.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::{ExtensibleLattice, Lattice};
+/// let mut seq = ExtensibleLattice::new(&[1, 5]).unwrap();
+///
+/// // The first 8 points form the 8 point lattice with the same generator
+/// let mut first: Vec<Vec<f64>> = seq.by_ref().take(8).collect();
+/// let mut rule: Vec<Vec<f64>> = Lattice::new(&[1, 5], 8).unwrap().collect();
+/// first.sort_by(|a, b| a.partial_cmp(b).unwrap());
+/// rule.sort_by(|a, b| a.partial_cmp(b).unwrap());
+/// assert_eq!(first, rule);
+/// ```
+///
+/// # References
+/// - Hickernell, F. J., Hong, H. S., L'Écuyer, P., Lemieux, C., Extensible lattice sequences
+///   for quasi-Monte Carlo quadrature
+///
+/// [`Lattice`]: ../lattice/struct.Lattice.html
+/// [`Rng`]: ../../../rand/trait.Rng.html
+/// [`Iterator`]: https://doc.rust-lang.org/std/iter/trait.Iterator.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtensibleLattice {
+    generator: Vec<u64>,
+    /// Shift as 64 bit fixed point fractions
+    shift: Vec<u64>,
+    /// Index of the point whose coordinates are being returned
+    index: u64,
+    /// Next coordinate of the current point
+    current: usize,
+}
+
+impl ExtensibleLattice {
+    /// Sequence with generating vector `generator`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyGenerators`] if `generator` is empty.
+    ///
+    /// [`Error::EmptyGenerators`]: ../../enum.Error.html#variant.EmptyGenerators
+    pub fn new(generator: &[u64]) -> Result<ExtensibleLattice> {
+        if generator.is_empty() {
+            return Err(Error::EmptyGenerators);
+        }
+        Ok(ExtensibleLattice {
+            generator: generator.to_vec(),
+            shift: vec![0; generator.len()],
+            index: 0,
+            current: 0,
+        })
+    }
+
+    /// Sequence with the Korobov generating vector `(1, a, a², ...)` in `dimensions` dimensions
+    ///
+    /// Powers of `a` wrap around modulo `2^64`, which does not change any point.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyGenerators`] if `dimensions` is zero.
+    ///
+    /// [`Error::EmptyGenerators`]: ../../enum.Error.html#variant.EmptyGenerators
+    pub fn korobov(a: u64, dimensions: usize) -> Result<ExtensibleLattice> {
+        let generator: Vec<u64> = (0..dimensions)
+            .scan(1u64, |z, _| {
+                let current = *z;
+                *z = z.wrapping_mul(a);
+                Some(current)
+            })
+            .collect();
+        ExtensibleLattice::new(&generator)
+    }
+
+    /// Shift every point by `shift` modulo one
+    ///
+    /// # Panics
+    ///
+    /// If `shift` does not have one value per dimension.
+    pub fn with_shift(mut self, shift: &[f64]) -> ExtensibleLattice {
+        assert_eq!(shift.len(), self.generator.len(), "{} shift values for {} dimensions",
+            shift.len(), self.generator.len());
+        self.shift = shift.iter()
+            .map(|&x| ((x - x.floor()) * 18_446_744_073_709_551_616.) as u128 as u64)
+            .collect();
+        self
+    }
+
+    /// Shift every point by a uniform random vector drawn from `rng`
+    pub fn random_shift<R: Rng + ?Sized>(mut self, rng: &mut R) -> ExtensibleLattice {
+        self.shift = (0..self.generator.len()).map(|_| rng.next_u64()).collect();
+        self
+    }
+
+    /// Number of dimensions of each point
+    pub fn dimensions(&self) -> usize {
+        self.generator.len()
+    }
+
+    /// Next coordinate as a 64 bit fixed point fraction
+    #[inline]
+    fn next_fixed(&mut self) -> u64 {
+        let j = self.current;
+        let x = self.index.reverse_bits().wrapping_mul(self.generator[j])
+            .wrapping_add(self.shift[j]);
+        self.current += 1;
+        if self.current == self.generator.len() {
+            self.current = 0;
+            self.index = self.index.wrapping_add(1);
+        }
+        x
+    }
+}
+
+impl Rng for ExtensibleLattice {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_fixed() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.next_fixed()
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        (self.next_fixed() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+impl JumpAhead for ExtensibleLattice {
+    /// Skip `n` coordinates, which need not be whole points
+    fn jump(&mut self, n: u64) {
+        let d = self.generator.len() as u64;
+        let target = self.current as u64 + n;
+        self.index = self.index.wrapping_add(target / d);
+        self.current = (target % d) as usize;
+    }
+}
+
+impl Iterator for ExtensibleLattice {
+    type Item = Vec<f64>;
+
+    /// Remaining coordinates of the current point, or the next whole point
+    fn next(&mut self) -> Option<Vec<f64>> {
+        let n = self.generator.len() - self.current;
+        Some((0..n).map(|_| self.next_f64()).collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::Lattice;
+
+    #[test]
+    fn empty_generator() {
+        assert!(matches!(ExtensibleLattice::new(&[]), Err(Error::EmptyGenerators)));
+        assert!(matches!(ExtensibleLattice::korobov(3, 0), Err(Error::EmptyGenerators)));
+    }
+
+    #[test]
+    fn every_power_of_two_is_a_lattice() {
+        let seq = ExtensibleLattice::korobov(17_797, 3).unwrap();
+        let points: Vec<Vec<f64>> = seq.take(1024).collect();
+        for m in 0..=10 {
+            let n = 1 << m;
+            let mut prefix = points[..n].to_vec();
+            prefix.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            let mut rule: Vec<Vec<f64>> = Lattice::new(&[1, 17_797, 17_797 * 17_797], n as u64)
+                .unwrap()
+                .collect();
+            rule.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            assert_eq!(prefix, rule, "first {} points", n);
+        }
+    }
+
+    #[test]
+    fn jump_matches_drawing() {
+        let mut jumped = ExtensibleLattice::korobov(5, 4).unwrap().with_shift(&[0.3; 4]);
+        let mut drawn = jumped.clone();
+        jumped.jump(123);
+        for _ in 0..123 {
+            drawn.next_u64();
+        }
+        assert_eq!(jumped, drawn);
+    }
+}
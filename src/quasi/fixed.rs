@@ -0,0 +1,265 @@
+//! Point generators with the number of dimensions fixed at compile time
+//!
+//! The generators of this module yield points as arrays `[f64; D]` rather than vectors, so
+//! drawing a point never allocates and loops over the coordinates can be unrolled.  They
+//! suit the two and three dimensional points of rendering and physics, where the dimension
+//! is known up front and points are drawn in hot loops.
+//!
+//! ```
+//! # use tapas::quasi::fixed::{HaltonPoints, RdPoints, SobolPoints};
+//! let [x, y, z] = HaltonPoints::<3>::new().next().unwrap();
+//! assert_eq!((x, y, z), (0.5, 1. / 3., 0.2));
+//!
+//! let corners: Vec<[f64; 2]> = SobolPoints::<2>::new().take(2).collect();
+//! assert_eq!(corners, vec![[0., 0.], [0.5, 0.5]]);
+//!
+//! assert_eq!(RdPoints::<2>::new().next(), Some([0.5, 0.5]));
+//! ```
+
+use rand::Rng;
+
+use primes::nth_prime;
+use super::{Rd, Sobol};
+use super::radical::radical_inverse;
+
+/// Halton points of `D` dimensions over the first `D` primes
+///
+/// Coordinate `j` of point `i` is the radical inverse of `i` in the `j`-th prime.  Like
+/// [`HaltonSeq`], the sequence starts at index `1`, skipping the origin, and every point is
+/// computed directly from its index without any per-point allocation.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::fixed::HaltonPoints;
+/// let halton = HaltonPoints::<2>::new();
+/// assert_eq!(halton.bases(), [2, 3]);
+/// assert_eq!(halton.point(5), [0.625, 7. / 9.]);
+/// ```
+///
+/// [`HaltonSeq`]: ../halton/struct.HaltonSeq.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HaltonPoints<const D: usize> {
+    bases: [u32; D],
+    index: u64,
+}
+
+impl<const D: usize> HaltonPoints<D> {
+    /// Sequence starting at index `1`, skipping the origin
+    ///
+    /// # Panics
+    ///
+    /// If `D` is zero.
+    pub fn new() -> HaltonPoints<D> {
+        assert!(D > 0, "points need at least one dimension");
+        HaltonPoints { bases: std::array::from_fn(nth_prime), index: 1 }
+    }
+
+    /// Base of every dimension
+    pub fn bases(&self) -> [u32; D] {
+        self.bases
+    }
+
+    /// Index of the next point
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+
+    /// Point `i` of the sequence, which need not be the next one
+    pub fn point(&self, i: u64) -> [f64; D] {
+        let mut p = [0.; D];
+        for (x, &b) in p.iter_mut().zip(self.bases.iter()) {
+            *x = radical_inverse(b, i);
+        }
+        p
+    }
+}
+
+impl<const D: usize> Default for HaltonPoints<D> {
+    fn default() -> HaltonPoints<D> {
+        HaltonPoints::new()
+    }
+}
+
+impl<const D: usize> Iterator for HaltonPoints<D> {
+    type Item = [f64; D];
+
+    fn next(&mut self) -> Option<[f64; D]> {
+        let p = self.point(self.index);
+        self.index = self.index.wrapping_add(1);
+        Some(p)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<[f64; D]> {
+        self.index = self.index.wrapping_add(n as u64);
+        self.next()
+    }
+}
+
+/// Draw the next `D` coordinates of `rng` as a point
+#[inline]
+fn draw<R: Rng, const D: usize>(rng: &mut R) -> [f64; D] {
+    let mut p = [0.; D];
+    for x in p.iter_mut() {
+        *x = rng.next_f64();
+    }
+    p
+}
+
+/// Sobol points of `D` dimensions, plain or scrambled
+///
+/// Yields the points of a [`Sobol`] sequence of `D` dimensions, updated in place by its
+/// Gray-code step with no allocation per point.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::Sobol;
+/// # use tapas::quasi::fixed::SobolPoints;
+/// let scrambled = SobolPoints::<4>::from_sobol(Sobol::new(4).unwrap().owen_scrambled(7));
+/// for [x, y, z, w] in scrambled.take(16) {
+///     assert!(x < 1. && y < 1. && z < 1. && w < 1.);
+/// }
+/// ```
+///
+/// [`Sobol`]: ../sobol/struct.Sobol.html
+#[derive(Debug, Clone)]
+pub struct SobolPoints<const D: usize> {
+    inner: Sobol,
+}
+
+impl<const D: usize> SobolPoints<D> {
+    /// Sequence with the Joe–Kuo direction numbers, starting at the origin
+    ///
+    /// # Panics
+    ///
+    /// If `D` is zero or above [`MAX_DIMENSIONS`].
+    ///
+    /// [`MAX_DIMENSIONS`]: ../sobol/constant.MAX_DIMENSIONS.html
+    pub fn new() -> SobolPoints<D> {
+        SobolPoints { inner: Sobol::new(D).expect("Sobol points of unsupported dimension") }
+    }
+
+    /// Points of `sobol`, continuing from its next coordinate
+    ///
+    /// # Panics
+    ///
+    /// If `sobol` does not have `D` dimensions.
+    pub fn from_sobol(sobol: Sobol) -> SobolPoints<D> {
+        assert_eq!(sobol.dimensions(), D, "{}-dimensional Sobol points from a {}-dimensional \
+            sequence", D, sobol.dimensions());
+        SobolPoints { inner: sobol }
+    }
+
+    /// Unwrap the underlying sequence
+    pub fn into_inner(self) -> Sobol {
+        self.inner
+    }
+}
+
+impl<const D: usize> Default for SobolPoints<D> {
+    fn default() -> SobolPoints<D> {
+        SobolPoints::new()
+    }
+}
+
+impl<const D: usize> Iterator for SobolPoints<D> {
+    type Item = [f64; D];
+
+    fn next(&mut self) -> Option<[f64; D]> {
+        Some(draw(&mut self.inner))
+    }
+}
+
+/// Roberts' `R_D` points of `D` dimensions
+///
+/// Yields the points of [`Rd`]`::new(D)`, starting from the centre of the cube, with no
+/// allocation per point.
+///
+/// [`Rd`]: ../roberts/struct.Rd.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RdPoints<const D: usize> {
+    inner: Rd,
+}
+
+impl<const D: usize> RdPoints<D> {
+    /// Sequence starting from the centre of the cube
+    ///
+    /// # Panics
+    ///
+    /// If `D` is zero.
+    pub fn new() -> RdPoints<D> {
+        RdPoints { inner: Rd::new(D).expect("points need at least one dimension") }
+    }
+
+    /// Unwrap the underlying sequence
+    pub fn into_inner(self) -> Rd {
+        self.inner
+    }
+}
+
+impl<const D: usize> Default for RdPoints<D> {
+    fn default() -> RdPoints<D> {
+        RdPoints::new()
+    }
+}
+
+impl<const D: usize> Iterator for RdPoints<D> {
+    type Item = [f64; D];
+
+    fn next(&mut self) -> Option<[f64; D]> {
+        Some(draw(&mut self.inner))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::{GeneralizedHalton, HaltonSeq};
+
+    #[test]
+    fn halton_matches_generalized() {
+        let identity = (0..5).map(|j| (0..nth_prime(j)).collect()).collect();
+        let vectors = GeneralizedHalton::new(identity).unwrap();
+        for (p, q) in HaltonPoints::<5>::new().zip(vectors).take(500) {
+            assert_eq!(p.to_vec(), q);
+        }
+
+        let mut halton = HaltonPoints::<2>::default();
+        assert_eq!(halton.nth(40), Some(halton.point(41)));
+        assert_eq!(halton.index(), 42);
+    }
+
+    #[test]
+    fn halton_matches_sequence() {
+        // The sequence updates its coordinates incrementally, so they may differ in the last bit
+        for (p, q) in HaltonPoints::<4>::new().zip(HaltonSeq::new(4).unwrap()).take(300) {
+            for (&x, &y) in p.iter().zip(q.iter()) {
+                abs_err_eq!(x == y ~ 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn wrappers_match_vectors() {
+        let sobol: Vec<Vec<f64>> = Sobol::new(3).unwrap().take(300).collect();
+        let arrays: Vec<Vec<f64>> = SobolPoints::<3>::new().take(300).map(|p| p.to_vec()).collect();
+        assert_eq!(arrays, sobol);
+
+        let rd: Vec<Vec<f64>> = Rd::new(4).unwrap().take(300).collect();
+        let arrays: Vec<Vec<f64>> = RdPoints::<4>::new().take(300).map(|p| p.to_vec()).collect();
+        assert_eq!(arrays, rd);
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_dimensions() {
+        HaltonPoints::<0>::new();
+    }
+
+    #[test]
+    #[should_panic]
+    fn mismatched_sobol() {
+        SobolPoints::<2>::from_sobol(Sobol::new(3).unwrap());
+    }
+}
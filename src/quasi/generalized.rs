@@ -0,0 +1,225 @@
+use rand::Rng;
+
+use error::{Error, Result};
+use primes::Primes;
+use super::{Digital, HashStream, JumpAhead};
+use super::radical::permuted;
+
+/// Halton sequence with a permutation applied to the digits of every dimension
+///
+/// Coordinate `j` of point `i` is the scrambled radical inverse of `i` in base `b_j`, in which
+/// every base-`b_j` digit `d` is replaced by `σ_j(d)`.  The plain Halton sequence in large
+/// prime bases has long runs of strongly correlated coordinates, since the leading digits of
+/// consecutive indices grow in lockstep; permuting the digits breaks those runs up, which is
+/// the standard fix for Halton in more than a handful of dimensions.
+///
+/// The base of every dimension is the length of its permutation.  Like [`Halton`], the
+/// sequence starts at index `1`.
+///
+/// Implements [`Rng`] by returning the coordinates of consecutive points one after another,
+/// and [`Iterator`] by returning whole points.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::GeneralizedHalton;
+/// // Swap digits 1 and 2 in base 3
+/// let mut gen = GeneralizedHalton::new(vec![vec![0, 1], vec![0, 2, 1]]).unwrap();
+/// assert_eq!(gen.next(), Some(vec![0.5, 2. / 3.]));
+/// assert_eq!(gen.next(), Some(vec![0.25, 1. / 3.]));
+///
+/// // Or pseudo-random permutations over the first primes
+/// let gen = GeneralizedHalton::random(16, 7);
+/// assert_eq!(gen.bases()[15], 53);
+/// ```
+///
+/// # References
+/// - Braaten, E., Weller, G., An improved low-discrepancy sequence for multidimensional
+///   quasi-Monte Carlo integration
+/// - Faure, H., Lemieux, C., Generalized Halton sequences in 2008: a comparative study
+///
+/// [`Halton`]: ../halton/struct.Halton.html
+/// [`Rng`]: ../../../rand/trait.Rng.html
+/// [`Iterator`]: https://doc.rust-lang.org/std/iter/trait.Iterator.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneralizedHalton {
+    bases: Vec<u32>,
+    permutations: Vec<Vec<u32>>,
+    /// Index of the point whose coordinates are being returned
+    index: u64,
+    /// Next coordinate of the current point
+    current: usize,
+}
+
+impl GeneralizedHalton {
+    /// Sequence with one dimension per digit permutation
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyGenerators`] if there are no permutations,
+    /// [`Error::InvalidBase`] if one has fewer than two digits, and
+    /// [`Error::InvalidParameter`] if one is not a permutation of `0..b` for its length `b`.
+    ///
+    /// [`Error::EmptyGenerators`]: ../../enum.Error.html#variant.EmptyGenerators
+    /// [`Error::InvalidBase`]: ../../enum.Error.html#variant.InvalidBase
+    /// [`Error::InvalidParameter`]: ../../enum.Error.html#variant.InvalidParameter
+    pub fn new(permutations: Vec<Vec<u32>>) -> Result<GeneralizedHalton> {
+        if permutations.is_empty() {
+            return Err(Error::EmptyGenerators);
+        }
+        let mut bases = Vec::with_capacity(permutations.len());
+        for perm in permutations.iter() {
+            let b = perm.len();
+            if b < 2 {
+                return Err(Error::InvalidBase(b as u32));
+            }
+            let mut seen = vec![false; b];
+            for &d in perm.iter() {
+                if d as usize >= b || seen[d as usize] {
+                    return Err(Error::InvalidParameter {
+                        name: "permutation",
+                        reason: format!("{:?} is not a permutation", perm),
+                    });
+                }
+                seen[d as usize] = true;
+            }
+            bases.push(b as u32);
+        }
+        Ok(GeneralizedHalton { bases, permutations, index: 1, current: 0 })
+    }
+
+    /// Sequence over the first `dimensions` primes with pseudo-random digit permutations
+    ///
+    /// Permutations are uniformly random given the `seed`, and the same seed always gives the
+    /// same sequence.
+    ///
+    /// # Panics
+    ///
+    /// If `dimensions` is zero.
+    pub fn random(dimensions: usize, seed: u64) -> GeneralizedHalton {
+        assert!(dimensions > 0, "points need at least one dimension");
+        let mut hash = HashStream::new(seed);
+        let permutations = Primes::new().take(dimensions)
+            .map(|b| {
+                // Fisher-Yates shuffle driven by a hash stream
+                let mut perm: Vec<u32> = (0..b).collect();
+                for i in (1..b as usize).rev() {
+                    let j = (((hash.next_bits() >> 32) * (i as u64 + 1)) >> 32) as usize;
+                    perm.swap(i, j);
+                }
+                perm
+            })
+            .collect();
+        GeneralizedHalton::new(permutations).unwrap()
+    }
+
+    /// Number of dimensions of each point
+    pub fn dimensions(&self) -> usize {
+        self.bases.len()
+    }
+
+    /// Base of every dimension
+    pub fn bases(&self) -> &[u32] {
+        &self.bases
+    }
+
+    /// Digit permutation of every dimension
+    pub fn permutations(&self) -> &[Vec<u32>] {
+        &self.permutations
+    }
+}
+
+impl Rng for GeneralizedHalton {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_f64() * u32::MAX as f64).floor() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        (self.next_f64() * u64::MAX as f64).floor() as u64
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        let j = self.current;
+        let x = permuted(self.bases[j], self.index, &self.permutations[j]);
+        self.current += 1;
+        if self.current == self.bases.len() {
+            self.current = 0;
+            self.index = self.index.wrapping_add(1);
+        }
+        x
+    }
+}
+
+impl JumpAhead for GeneralizedHalton {
+    /// Skip `n` coordinates, which need not be whole points
+    fn jump(&mut self, n: u64) {
+        let d = self.bases.len() as u64;
+        let target = self.current as u64 + n;
+        self.index = self.index.wrapping_add(target / d);
+        self.current = (target % d) as usize;
+    }
+}
+
+impl Iterator for GeneralizedHalton {
+    type Item = Vec<f64>;
+
+    /// Remaining coordinates of the current point, or the next whole point
+    fn next(&mut self) -> Option<Vec<f64>> {
+        let n = self.bases.len() - self.current;
+        Some((0..n).map(|_| self.next_f64()).collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::Halton;
+
+    #[test]
+    fn identity_is_halton() {
+        let perms = vec![(0..2).collect(), (0..3).collect(), (0..5).collect()];
+        let gen = GeneralizedHalton::new(perms).unwrap();
+        let halton: Vec<Halton> = [2, 3, 5].iter().map(|&b| Halton::new(1, b).unwrap()).collect();
+        for (i, p) in gen.take(200).enumerate() {
+            for j in 0..3 {
+                let h = halton[j].clone().nth(i).unwrap();
+                abs_err_eq!((p[j]) == h ~ 1e-15);
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_permutations() {
+        assert!(matches!(GeneralizedHalton::new(vec![]), Err(Error::EmptyGenerators)));
+        assert!(matches!(GeneralizedHalton::new(vec![vec![0]]), Err(Error::InvalidBase(1))));
+        for perm in [vec![0, 2, 2], vec![0, 1, 3]] {
+            assert!(matches!(GeneralizedHalton::new(vec![perm]),
+                Err(Error::InvalidParameter { name: "permutation", .. })));
+        }
+    }
+
+    #[test]
+    fn random_permutations_stratify() {
+        // A permutation of the digits keeps every prefix of b^k points stratified
+        let gen = GeneralizedHalton::random(30, 99);
+        assert_ne!(gen, GeneralizedHalton::random(30, 100));
+        let b = gen.bases()[29] as usize;
+        let values: Vec<f64> = gen.take(b * b).map(|p| p[29]).collect();
+        let mut strata = vec![false; b * b];
+        for x in values {
+            strata[(x * (b * b) as f64) as usize] = true;
+        }
+        assert!(strata.iter().all(|&s| s));
+    }
+
+    #[test]
+    fn jump_matches_drawing() {
+        let mut jumped = GeneralizedHalton::random(4, 1);
+        let mut drawn = jumped.clone();
+        jumped.jump(41);
+        for _ in 0..41 {
+            drawn.next_f64();
+        }
+        assert_eq!(jumped, drawn);
+    }
+}
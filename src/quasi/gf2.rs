@@ -0,0 +1,294 @@
+//! Linear algebra over GF(2), the field of the digits of binary digital nets
+//!
+//! A binary digital net maps the bits of an index to the bits of a coordinate through a
+//! generating matrix over GF(2), where addition is XOR and multiplication is AND.  The
+//! [`BitMatrix`] type stores such matrices compactly and provides the operations needed to
+//! construct, scramble and analyze them.
+//!
+//! [`BitMatrix`]: struct.BitMatrix.html
+
+use std::fmt;
+use std::ops::Mul;
+
+/// Matrix over GF(2) with at most 64 rows
+///
+/// Every column is stored as the bits of a `u64`, with row `r` in bit `r`.  Multiplying by a
+/// vector, whose bits are likewise packed in a `u64`, is then an XOR of the columns selected
+/// by the set bits of the vector, the natural form of a digital net's generating matrix.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::gf2::BitMatrix;
+/// // Upper triangular matrix of ones, the generating matrix of the second Sobol dimension
+/// let mut m = BitMatrix::zeros(4, 4);
+/// for c in 0..4 {
+///     for r in 0..=c {
+///         m.set(r, c, true);
+///     }
+/// }
+///
+/// assert_eq!(m.rank(), 4);
+/// let inverse = m.inverse().unwrap();
+/// assert_eq!(&m * &inverse, BitMatrix::identity(4));
+/// assert_eq!(m.mul_vec(0b0010), 0b0011);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitMatrix {
+    rows: usize,
+    columns: Vec<u64>,
+}
+
+impl BitMatrix {
+    /// Matrix of zeros with `rows` rows and `columns` columns
+    ///
+    /// # Panics
+    ///
+    /// If `rows > 64`.
+    pub fn zeros(rows: usize, columns: usize) -> BitMatrix {
+        assert!(rows <= 64, "bit matrices have at most 64 rows, not {}", rows);
+        BitMatrix { rows, columns: vec![0; columns] }
+    }
+
+    /// Identity matrix of size `n`
+    ///
+    /// # Panics
+    ///
+    /// If `n > 64`.
+    pub fn identity(n: usize) -> BitMatrix {
+        let mut m = BitMatrix::zeros(n, n);
+        for (c, col) in m.columns.iter_mut().enumerate() {
+            *col = 1 << c;
+        }
+        m
+    }
+
+    /// Matrix with the given packed `columns`, each holding row `r` in bit `r`
+    ///
+    /// # Panics
+    ///
+    /// If `rows > 64` or a column has bits set at or above `rows`.
+    pub fn from_columns(rows: usize, columns: Vec<u64>) -> BitMatrix {
+        assert!(rows <= 64, "bit matrices have at most 64 rows, not {}", rows);
+        assert!(columns.iter().all(|&c| c & !mask(rows) == 0),
+            "columns have bits outside of {} rows", rows);
+        BitMatrix { rows, columns }
+    }
+
+    /// Number of rows
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Number of columns
+    pub fn cols(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// Column `c` packed into the low `rows` bits
+    pub fn column(&self, c: usize) -> u64 {
+        self.columns[c]
+    }
+
+    /// Entry at row `r` and column `c`
+    pub fn get(&self, r: usize, c: usize) -> bool {
+        assert!(r < self.rows, "row {} out of {}", r, self.rows);
+        self.columns[c] >> r & 1 == 1
+    }
+
+    /// Set the entry at row `r` and column `c`
+    pub fn set(&mut self, r: usize, c: usize, value: bool) {
+        assert!(r < self.rows, "row {} out of {}", r, self.rows);
+        if value {
+            self.columns[c] |= 1 << r;
+        } else {
+            self.columns[c] &= !(1 << r);
+        }
+    }
+
+    /// Product with the column vector whose entry `c` is bit `c` of `v`
+    ///
+    /// # Panics
+    ///
+    /// If `v` has bits set at or above the number of columns.
+    pub fn mul_vec(&self, v: u64) -> u64 {
+        assert!(v & !mask(self.cols()) == 0, "vector longer than {} columns", self.cols());
+        let mut out = 0;
+        let mut bits = v;
+        while bits != 0 {
+            out ^= self.columns[bits.trailing_zeros() as usize];
+            bits &= bits - 1;
+        }
+        out
+    }
+
+    /// Transposed matrix
+    ///
+    /// # Panics
+    ///
+    /// If the matrix has more than 64 columns.
+    pub fn transpose(&self) -> BitMatrix {
+        let mut t = BitMatrix::zeros(self.cols(), self.rows);
+        for (c, &col) in self.columns.iter().enumerate() {
+            for r in 0..self.rows {
+                if col >> r & 1 == 1 {
+                    t.columns[r] |= 1 << c;
+                }
+            }
+        }
+        t
+    }
+
+    /// Rank over GF(2), by Gaussian elimination of the columns
+    pub fn rank(&self) -> usize {
+        let mut basis: Vec<u64> = Vec::with_capacity(self.rows);
+        for &col in self.columns.iter() {
+            // Reduce by every basis vector with a leading bit in common
+            let reduced = basis.iter().fold(col, |v, &b| v.min(v ^ b));
+            if reduced != 0 {
+                basis.push(reduced);
+                basis.sort_unstable_by(|a, b| b.cmp(a));
+            }
+        }
+        basis.len()
+    }
+
+    /// Inverse of a square matrix, or `None` if it is singular
+    ///
+    /// # Panics
+    ///
+    /// If the matrix is not square.
+    pub fn inverse(&self) -> Option<BitMatrix> {
+        let n = self.rows;
+        assert_eq!(n, self.cols(), "only square matrices have an inverse");
+
+        // Column operations on [self; I] reduce self to the identity and I to the inverse
+        let mut a = self.columns.clone();
+        let mut inv = BitMatrix::identity(n).columns;
+        for r in 0..n {
+            let pivot = (r..n).find(|&c| a[c] >> r & 1 == 1)?;
+            a.swap(r, pivot);
+            inv.swap(r, pivot);
+            for c in 0..n {
+                if c != r && a[c] >> r & 1 == 1 {
+                    a[c] ^= a[r];
+                    inv[c] ^= inv[r];
+                }
+            }
+        }
+        Some(BitMatrix { rows: n, columns: inv })
+    }
+}
+
+impl Mul for &BitMatrix {
+    type Output = BitMatrix;
+
+    /// Matrix product
+    ///
+    /// # Panics
+    ///
+    /// If the columns of `self` do not match the rows of `rhs`.
+    fn mul(self, rhs: &BitMatrix) -> BitMatrix {
+        assert_eq!(self.cols(), rhs.rows, "cannot multiply {}×{} by {}×{}",
+            self.rows, self.cols(), rhs.rows, rhs.cols());
+        BitMatrix {
+            rows: self.rows,
+            columns: rhs.columns.iter().map(|&c| self.mul_vec(c)).collect(),
+        }
+    }
+}
+
+impl fmt::Display for BitMatrix {
+    /// One line of `0` and `1` per row
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for r in 0..self.rows {
+            if r > 0 {
+                writeln!(f)?;
+            }
+            for &col in self.columns.iter() {
+                write!(f, "{}", col >> r & 1)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Degree of the polynomial over GF(2) with coefficient bits `poly`, zero for constants
+pub(crate) fn degree(poly: u64) -> u32 {
+    63u32.saturating_sub(poly.leading_zeros())
+}
+
+/// Mask of the low `n` bits
+fn mask(n: usize) -> u64 {
+    if n >= 64 { !0 } else { (1 << n) - 1 }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{Rng, SeedableRng, XorShiftRng};
+
+    fn random(rows: usize, cols: usize, rng: &mut XorShiftRng) -> BitMatrix {
+        BitMatrix::from_columns(rows, (0..cols).map(|_| rng.next_u64() & mask(rows)).collect())
+    }
+
+    #[test]
+    fn rank_of_known_matrices() {
+        assert_eq!(BitMatrix::identity(64).rank(), 64);
+        assert_eq!(BitMatrix::zeros(5, 7).rank(), 0);
+        // Third column is the sum of the first two
+        assert_eq!(BitMatrix::from_columns(3, vec![0b011, 0b110, 0b101]).rank(), 2);
+        assert_eq!(BitMatrix::from_columns(3, vec![0b001, 0b011, 0b111, 0b100]).rank(), 3);
+    }
+
+    #[test]
+    fn inverse_of_random_matrices() {
+        let mut rng = XorShiftRng::from_seed([3, 1, 4, 1]);
+        let mut found = 0;
+        for _ in 0..100 {
+            let m = random(32, 32, &mut rng);
+            match m.inverse() {
+                Some(inv) => {
+                    found += 1;
+                    assert_eq!(&m * &inv, BitMatrix::identity(32));
+                    assert_eq!(&inv * &m, BitMatrix::identity(32));
+                },
+                None => assert!(m.rank() < 32),
+            }
+        }
+        // About 29% of random binary matrices are invertible
+        assert!(found > 10);
+    }
+
+    #[test]
+    fn product_matches_vectors() {
+        let mut rng = XorShiftRng::from_seed([2, 7, 1, 8]);
+        let a = random(10, 20, &mut rng);
+        let b = random(20, 5, &mut rng);
+        let ab = &a * &b;
+        for v in 0..32 {
+            assert_eq!(ab.mul_vec(v), a.mul_vec(b.mul_vec(v)));
+        }
+    }
+
+    #[test]
+    fn transpose_swaps_entries() {
+        let mut rng = XorShiftRng::from_seed([5, 5, 5, 5]);
+        let m = random(7, 3, &mut rng);
+        let t = m.transpose();
+        assert_eq!((t.rows(), t.cols()), (3, 7));
+        for r in 0..7 {
+            for c in 0..3 {
+                assert_eq!(m.get(r, c), t.get(c, r));
+            }
+        }
+        assert_eq!(t.transpose(), m);
+        assert_eq!(m.rank(), t.rank());
+    }
+
+    #[test]
+    fn display_rows() {
+        let m = BitMatrix::from_columns(2, vec![0b01, 0b11, 0b10]);
+        assert_eq!(m.to_string(), "110\n011");
+    }
+}
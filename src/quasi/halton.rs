@@ -1,6 +1,13 @@
+use num_traits::Float;
 use rand::Rng;
+use std::fmt::Debug;
 use std::iter::Iterator;
 
+use error::{Error, Result};
+use primes::Primes;
+use super::{Digital, JumpAhead, Peek, Scramble};
+use super::radical::radical_inverse;
+
 /// Incrementally calculated Halton sequence
 ///
 /// Calculates the halton sequence incrementally from a given base with minimal machine error.
@@ -45,7 +52,7 @@ use std::iter::Iterator;
 /// }
 ///
 /// // Create two halton sequence generators with prime bases 17 and 19
-/// let h_est = monte_carlo_pi(10_000, Halton::new(1,17), Halton::new(1,19));
+/// let h_est = monte_carlo_pi(10_000, Halton::new(1,17).unwrap(), Halton::new(1,19).unwrap());
 ///
 /// // Estimate using standard thread_rng
 /// let r_est = monte_carlo_pi(1_000_000, rand::thread_rng(), rand::thread_rng());
@@ -61,22 +68,38 @@ use std::iter::Iterator;
 ///
 /// ```
 /// # use tapas::quasi::Halton;
-/// let seq: Vec<f64> = Halton::new(1,17).take(10).collect();
+/// let seq: Vec<f64> = Halton::new(1,17).unwrap().take(10).collect();
+/// ```
+///
+/// Internally the remainders are kept in the floating point type `F`, which defaults to `f64`.
+/// Use [`with_float`] to generate the sequence in another precision, such as `f32` for
+/// mixed-precision pipelines or a higher precision type to validate results:
+///
+/// ```
+/// # use tapas::quasi::Halton;
+/// let seq: Vec<f32> = Halton::<f32>::with_float(1, 3).unwrap().take(3).collect();
+/// assert_eq!(seq, vec![1. / 3., 2. / 3., 1. / 9.]);
 /// ```
 ///
 /// # References
 /// - Kolar, M., O'Shea, S. F., Fast, Portable, and Reliable Algorithm for the Clalculation of
 ///   Halton Numbers
+///
+/// [`with_float`]: #method.with_float
 #[derive(Debug, Clone)]
-pub struct Halton {
+pub struct Halton<F = f64> {
     /// Vector of remainders used to quickly calculate next halton number
-    rem: Vec<f64>,
+    rem: Vec<F>,
     /// Digits in base-b notation for the halton sequence for index i
     dig: Vec<u32>,
     /// Base-b used to determine which base-b notation to use
     base: u32,
     /// Latest value generated from the halton sequence
-    state: f64,
+    state: F,
+    /// Permutation of the digits of a scrambled sequence, which always keeps `0` fixed
+    permutation: Option<Vec<u32>>,
+    /// Difference between the indices of consecutive values
+    leap: u64,
 }
 
 impl Halton {
@@ -92,48 +115,167 @@ impl Halton {
     /// use rand::Rng;
     /// use std::f64::EPSILON;
     ///
-    /// let mut sampler = Halton::new(1,23);
+    /// let mut sampler = Halton::new(1,23).unwrap();
     /// let first: f64 = sampler.gen();
     /// assert!((1./23. - first).abs() < EPSILON); // Equal within machine precision
     /// # }
     /// ```
-    pub fn new(i: u32, b: u32) -> Halton {
-        // Pre-set size of the halton sequence to ensure we can get to at least the millionth
-        // index before the vectors have to resize
-        let size = 1_000_000f64.log(b as f64).ceil() as usize;
-        let mut remainders: Vec<f64> = Vec::with_capacity(size);
-        let mut digits: Vec<u32> = Vec::with_capacity(size);
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidBase`] if `b` is less than `2`.
+    ///
+    /// [`Error::InvalidBase`]: ../../enum.Error.html#variant.InvalidBase
+    pub fn new(i: u32, b: u32) -> Result<Halton> {
+        Halton::with_float(i, b)
+    }
+
+    /// Generate a Halton sequence whose digits are permuted by the `scramble` scheme
+    ///
+    /// Plain Halton sequences in larger bases are strongly correlated with each other over
+    /// long runs of points, which shows up as lines in their two-dimensional projections.
+    /// Scrambling the digits breaks up the lines while keeping the sequence stratified.
+    ///
+    /// ```
+    /// # use tapas::quasi::{Halton, Scramble};
+    /// let seq: Vec<f64> = Halton::scrambled(1, 5, Scramble::BraatenWeller).unwrap()
+    ///     .take(4)
+    ///     .collect();
+    /// assert_eq!(seq, vec![3. / 5., 1. / 5., 4. / 5., 2. / 5.]);
+    ///
+    /// // Faure permutations exist for every base
+    /// let mut faure = Halton::scrambled(1, 6, Scramble::Faure).unwrap();
+    /// assert_eq!(faure.next(), Some(2. / 6.));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidBase`] if `b` is less than `2` or the scheme has no permutation
+    /// for `b`.
+    ///
+    /// [`Error::InvalidBase`]: ../../enum.Error.html#variant.InvalidBase
+    pub fn scrambled(i: u32, b: u32, scramble: Scramble) -> Result<Halton> {
+        if b < 2 {
+            return Err(Error::InvalidBase(b));
+        }
+        let mut halton = Halton::new(i, b)?;
+        halton.permutation = Some(scramble.permutation(b)?);
+        halton.set_index(i.saturating_sub(1) as u64);
+        Ok(halton)
+    }
+
+    /// Build a sequence from options, validated together when it is built
+    ///
+    /// Defaults to the plain sequence in base `2` starting at index `1`.
+    ///
+    /// ```
+    /// # use tapas::quasi::{Halton, Scramble};
+    /// // Every fourth index of the Faure scrambled sequence in base 5, from index 1
+    /// let mut leaped = Halton::builder().base(5).start(1).leap(4).scramble(Scramble::Faure)
+    ///     .build()
+    ///     .unwrap();
+    /// let mut every = Halton::scrambled(1, 5, Scramble::Faure).unwrap();
+    /// assert_eq!(leaped.next(), every.next());
+    /// assert_eq!(leaped.next(), every.nth(3));
+    /// ```
+    pub fn builder() -> HaltonBuilder {
+        HaltonBuilder { base: 2, start: 1, leap: 1, scramble: None, skip: 0 }
+    }
+}
+
+impl<F: Float + Debug> Halton<F> {
+    /// Generate a new Halton sequence in the floating point type `F`
+    ///
+    /// Behaves exactly like [`Halton::new`], except that values are calculated and returned
+    /// in `F` rather than `f64`.
+    ///
+    /// [`Halton::new`]: #method.new
+    pub fn with_float(i: u32, b: u32) -> Result<Halton<F>> {
+        if b < 2 {
+            return Err(Error::InvalidBase(b));
+        }
+
+        // Digit storage grows with the index; see `with_capacity_for_index` to reserve it upfront
+        let mut halton = Halton {
+            base: b,
+            rem: Vec::new(),
+            dig: Vec::new(),
+            state: F::zero(),
+            permutation: None,
+            leap: 1,
+        };
+
+        // Digits hold the index of the latest value, one before the first value generated
+        halton.set_index(i.saturating_sub(1) as u64);
+
+        Ok(halton)
+    }
+
+    /// Reserve digit storage for generating up to index `n` without reallocating
+    ///
+    /// Storage otherwise grows by one digit whenever the index gains a digit in the base, which
+    /// is rare but reallocates in the middle of generation.  Reserving upfront keeps long runs
+    /// free of allocation, while short sequences only pay for the digits they use.
+    ///
+    /// ```
+    /// # use tapas::quasi::Halton;
+    /// let halton = Halton::new(1, 2).unwrap().with_capacity_for_index(1 << 40);
+    /// let first: Vec<f64> = halton.take(3).collect();
+    /// assert_eq!(first, vec![0.5, 0.25, 0.75]);
+    /// ```
+    pub fn with_capacity_for_index(mut self, n: u64) -> Halton<F> {
+        let b = self.base as u64;
+        let mut digits = 1usize;
+        let mut rest = n / b;
+        while rest > 0 {
+            digits += 1;
+            rest /= b;
+        }
+        // Reserve one more digit for the carry past the last index
+        let digits = digits + 1;
+        self.dig.reserve(digits.saturating_sub(self.dig.len()));
+        self.rem.reserve(digits.saturating_sub(self.rem.len()));
+        self
+    }
 
-        // Ensure we have mutable access for a safe value
-        let mut i = if i < 1 {0} else {i-1};
+    /// Value of digit `d`, after the permutation of a scrambled sequence
+    #[inline]
+    fn digit(&self, d: u32) -> F {
+        match self.permutation {
+            Some(ref perm) => float(perm[d as usize]),
+            None => float(d),
+        }
+    }
 
-        // Ensure a sensible base
-        let b = if b < 2 {2} else {b};
+    /// Index in the sequence one before the next value generated
+    fn index(&self) -> u64 {
+        let b = self.base as u64;
+        self.dig.iter().rev().fold(0, |acc, &d| acc * b + d as u64)
+    }
+
+    /// Reset digits and remainders so that the latest value generated has index `i`
+    fn set_index(&mut self, mut i: u64) {
+        self.dig.clear();
+        self.rem.clear();
 
         // Convert number to digits in the given base
+        let b = self.base as u64;
         while i >= b {
-            digits.push(i.wrapping_rem(b));
-            i = i / b;
+            self.dig.push((i % b) as u32);
+            i /= b;
         }
-        digits.push(i);
+        self.dig.push(i as u32);
 
         // Calculate remainders in reverse order for each digit
-        let base = b as f64;
-        remainders.push(0.);
-        for d in (&digits).iter().rev() {
-            if remainders.len() < digits.len() {
-                let last = remainders.last().cloned().unwrap();
-                remainders.push((*d as f64 + last) / base)
+        let base = float::<F>(self.base);
+        self.rem.push(F::zero());
+        for k in (0..self.dig.len()).rev() {
+            if self.rem.len() < self.dig.len() {
+                let last = self.rem.last().cloned().unwrap();
+                let d = self.digit(self.dig[k]);
+                self.rem.push((d + last) / base)
             }
         }
-
-        // Produce new Halton generator with the first set of digits and remainders
-        Halton {
-            base: b,
-            rem: remainders,
-            dig: digits,
-            state: 0.,
-        }
     }
 
     /// Advance the state of the halton number generator to the next in the sequence
@@ -157,13 +299,14 @@ impl Halton {
                 self.dig[i] += 1;
             } else {
                 self.dig.push(1);
-                self.rem.push(0.); // keep number of digits and remainders the same
+                self.rem.push(F::zero()); // keep number of digits and remainders the same
             }
 
             // Update remainders
             let len = self.rem.len();
-            let b = self.base as f64;
-            self.rem[len-i] = (self.dig[i] as f64 + self.rem[len-i-1]) / b;
+            let b = float::<F>(self.base);
+            let d = self.digit(self.dig[i]);
+            self.rem[len-i] = (d + self.rem[len-i-1]) / b;
             if i >= 2 {
                 for i in len-i..len-1 {
                     self.rem[i+1] = self.rem[i] / b;
@@ -171,46 +314,55 @@ impl Halton {
             }
 
             // Calculate new state
-            self.state = self.rem.last().unwrap() / b;
+            self.state = *self.rem.last().unwrap() / b;
         } else {
             // Calculate new state
             self.dig[0] += 1;
-            self.state = (self.dig[0] as f64 + self.rem.last().unwrap()) / self.base as f64;
+            self.state = (self.digit(self.dig[0]) + *self.rem.last().unwrap()) / float(self.base);
+        }
+
+        // Leaped sequences move the digits on to one before the next index, keeping the state
+        if self.leap > 1 {
+            let i = self.index();
+            self.set_index(i + self.leap - 1);
         }
     }
 
     /// Skip a desired number of elements from the halton sequence
     ///
-    /// In some applications, it's preferred to sample only the 100th element or so.
+    /// In some applications, it's preferred to sample only the 100th element or so.  Skipping
+    /// recomputes the digits of the new index directly, so the cost does not depend on `size`.
     pub fn skip(&mut self, size: usize) {
-        for _ in 0..size {
-            self.advance()
-        }
+        self.jump(size as u64)
     }
 
     /// Get the next value in the halton sequence as an f64 value between `0` and `1`
     #[inline]
     fn sample_f64(&mut self) -> f64 {
         self.advance();
-        self.state
+        self.state.to_f64().unwrap()
     }
 
     /// Get the next value in the halton sequence as a u64 between `0` and `u64::MAX`
     #[inline]
     fn sample_u64(&mut self) -> u64 {
-        self.advance();
-        (self.state * u64::max_value() as f64).floor() as u64
+        (self.sample_f64() * u64::MAX as f64).floor() as u64
     }
 
     /// Get the next value in the halton sequence as a u64 between `0` and `u32::MAX`
     #[inline]
     fn sample_u32(&mut self) -> u32 {
-        self.advance();
-        (self.state * u32::max_value() as f64).floor() as u32
+        (self.sample_f64() * u32::MAX as f64).floor() as u32
     }
 }
 
-impl Rng for Halton {
+/// Convert a digit or base to the floating point type of the sequence
+#[inline]
+fn float<F: Float>(x: u32) -> F {
+    F::from(x).unwrap()
+}
+
+impl<F: Float + Debug> Rng for Halton<F> {
     #[inline]
     fn next_u32(&mut self) -> u32 {
         self.sample_u32()
@@ -223,7 +375,8 @@ impl Rng for Halton {
 
     #[inline]
     fn next_f32(&mut self) -> f32 {
-        self.sample_f64() as f32
+        self.advance();
+        self.state.to_f32().unwrap()
     }
 
     #[inline]
@@ -232,8 +385,44 @@ impl Rng for Halton {
     }
 }
 
-impl Iterator for Halton {
-    type Item = f64;
+impl<F: Float + Debug> Peek for Halton<F> {
+    fn peek(&self) -> f64 {
+        // Radical inverse of the next index, computed without touching the digit state
+        let b = self.base as u64;
+        let mut i = self.index() + 1;
+        let mut scale = F::one() / float(self.base);
+        let mut value = F::zero();
+        while i > 0 {
+            value = value + self.digit((i % b) as u32) * scale;
+            scale = scale / float(self.base);
+            i /= b;
+        }
+        value.to_f64().unwrap()
+    }
+}
+
+impl<F: Float + Debug> JumpAhead for Halton<F> {
+    fn jump(&mut self, n: u64) {
+        let i = self.index();
+        self.set_index(i + n * self.leap);
+    }
+}
+
+impl<F: Float + Debug> Digital for Halton<F> {
+    /// Exact digits for base `2`; other bases give the leading bits of the value
+    fn next_bits(&mut self) -> u64 {
+        let i = self.index() + 1;
+        self.advance();
+        if self.base == 2 {
+            i.reverse_bits()
+        } else {
+            (self.state.to_f64().unwrap() * 18_446_744_073_709_551_616.) as u64
+        }
+    }
+}
+
+impl<F: Float + Debug> Iterator for Halton<F> {
+    type Item = F;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.advance();
@@ -241,11 +430,288 @@ impl Iterator for Halton {
     }
 }
 
+/// Options for a [`Halton`] sequence, from [`Halton::builder`]
+///
+/// The sequence returns the values at indices `start + (skip + k) leap` for `k = 0, 1, ...`,
+/// with digits permuted by the `scramble` scheme if one is set.  Leaping through a sequence
+/// in one base with a leap coprime to the base gives another low-discrepancy sequence, which
+/// is how parallel streams are split off one sequence.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::Halton;
+/// // Indices 4, 7 and 10 in base 2
+/// let seq: Vec<f64> = Halton::builder().leap(3).skip(1).build().unwrap()
+///     .take(3)
+///     .collect();
+/// assert_eq!(seq, vec![1. / 8., 7. / 8., 5. / 16.]);
+///
+/// assert!(Halton::builder().base(1).build().is_err());
+/// ```
+///
+/// [`Halton`]: struct.Halton.html
+/// [`Halton::builder`]: struct.Halton.html#method.builder
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HaltonBuilder {
+    base: u32,
+    start: u32,
+    leap: u64,
+    scramble: Option<Scramble>,
+    skip: u64,
+}
+
+impl HaltonBuilder {
+    /// Base of the radical inverse
+    pub fn base(mut self, b: u32) -> HaltonBuilder {
+        self.base = b;
+        self
+    }
+
+    /// Index of the first value
+    pub fn start(mut self, i: u32) -> HaltonBuilder {
+        self.start = i;
+        self
+    }
+
+    /// Difference between the indices of consecutive values, which must be positive
+    pub fn leap(mut self, leap: u64) -> HaltonBuilder {
+        self.leap = leap;
+        self
+    }
+
+    /// Permute the digits by the `scramble` scheme
+    pub fn scramble(mut self, scramble: Scramble) -> HaltonBuilder {
+        self.scramble = Some(scramble);
+        self
+    }
+
+    /// Number of values to discard after the start, each a leap apart
+    pub fn skip(mut self, n: u64) -> HaltonBuilder {
+        self.skip = n;
+        self
+    }
+
+    /// Sequence with the chosen options
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidBase`] if the base is less than `2` or the scramble scheme has
+    /// no permutation for it, and [`Error::InvalidParameter`] if the leap is zero.
+    ///
+    /// [`Error::InvalidBase`]: ../../enum.Error.html#variant.InvalidBase
+    /// [`Error::InvalidParameter`]: ../../enum.Error.html#variant.InvalidParameter
+    pub fn build(&self) -> Result<Halton> {
+        if self.leap == 0 {
+            return Err(Error::InvalidParameter {
+                name: "leap",
+                reason: "consecutive values must have different indices".to_owned(),
+            });
+        }
+        let mut halton = match self.scramble {
+            Some(scramble) => Halton::scrambled(self.start, self.base, scramble)?,
+            None => Halton::new(self.start, self.base)?,
+        };
+        halton.leap = self.leap;
+        halton.jump(self.skip);
+        Ok(halton)
+    }
+}
+
+/// Multi-dimensional Halton sequence over the first primes
+///
+/// Dimension `j` is a [`Halton`] sequence in the `j`-th prime, so a sequence of `d` dimensions
+/// uses the bases `2, 3, 5, ...` up to the `d`-th prime.  Every dimension is updated with the
+/// same incremental algorithm as [`Halton`], and all dimensions advance together so draws
+/// always line up into whole points.
+///
+/// Implements [`Rng`] by returning the coordinates of consecutive points one after another,
+/// and [`Iterator`] by returning whole points.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::HaltonSeq;
+/// let mut halton = HaltonSeq::new(3).unwrap();
+/// assert_eq!(halton.bases(), vec![2, 3, 5]);
+/// assert_eq!(halton.next(), Some(vec![1. / 2., 1. / 3., 1. / 5.]));
+/// assert_eq!(halton.next(), Some(vec![1. / 4., 2. / 3., 2. / 5.]));
+/// ```
+///
+/// [`Halton`]: struct.Halton.html
+/// [`Rng`]: ../../../rand/trait.Rng.html
+/// [`Iterator`]: https://doc.rust-lang.org/std/iter/trait.Iterator.html
+#[derive(Debug, Clone)]
+pub struct HaltonSeq {
+    dimensions: Vec<Halton>,
+    /// Next coordinate of the current point
+    current: usize,
+}
+
+impl HaltonSeq {
+    /// Sequence in `dimensions` dimensions starting at index `1`, skipping the origin
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyGenerators`] if `dimensions` is zero.
+    ///
+    /// [`Error::EmptyGenerators`]: ../../enum.Error.html#variant.EmptyGenerators
+    pub fn new(dimensions: usize) -> Result<HaltonSeq> {
+        HaltonSeq::starting_at(dimensions, 1)
+    }
+
+    /// Sequence in `dimensions` dimensions whose first point has index `i`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyGenerators`] if `dimensions` is zero.
+    ///
+    /// [`Error::EmptyGenerators`]: ../../enum.Error.html#variant.EmptyGenerators
+    pub fn starting_at(dimensions: usize, i: u32) -> Result<HaltonSeq> {
+        if dimensions == 0 {
+            return Err(Error::EmptyGenerators);
+        }
+        let dimensions = Primes::new().take(dimensions)
+            .map(|b| Halton::new(i, b))
+            .collect::<Result<Vec<Halton>>>()?;
+        Ok(HaltonSeq { dimensions, current: 0 })
+    }
+
+    /// Number of dimensions of each point
+    pub fn dimensions(&self) -> usize {
+        self.dimensions.len()
+    }
+
+    /// Base of every dimension
+    pub fn bases(&self) -> Vec<u32> {
+        self.dimensions.iter().map(|h| h.base).collect()
+    }
+
+    /// Add a dimension in the next prime to every point, including the current one
+    ///
+    /// The sequence continues as if it had been built with one more dimension, and [`point`]
+    /// reproduces the new coordinate of earlier points, so adaptive algorithms can widen
+    /// their points once they find out they need to.
+    ///
+    /// ```
+    /// # use tapas::quasi::HaltonSeq;
+    /// let mut halton = HaltonSeq::new(2).unwrap();
+    /// let first = halton.next().unwrap();
+    ///
+    /// halton.push_dimension();
+    /// assert_eq!(halton.point(1), vec![first[0], first[1], 1. / 5.]);
+    /// assert_eq!(halton.next(), Some(vec![1. / 4., 2. / 3., 2. / 5.]));
+    /// ```
+    ///
+    /// [`point`]: #method.point
+    pub fn push_dimension(&mut self) {
+        let last = &self.dimensions[self.dimensions.len() - 1];
+        let base = Primes::from(last.base + 1).next().expect("the next prime fits in a u32");
+        // The last dimension has not been drawn yet for the current point
+        let mut next = Halton::new(0, base).unwrap();
+        next.set_index(last.index());
+        self.dimensions.push(next);
+    }
+
+    /// Point with index `i`, which need not be the next one
+    ///
+    /// Sequences from [`new`] start at index `1`.
+    ///
+    /// [`new`]: #method.new
+    pub fn point(&self, i: u64) -> Vec<f64> {
+        self.dimensions.iter().map(|h| radical_inverse(h.base, i)).collect()
+    }
+}
+
+impl Rng for HaltonSeq {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_f64() * u32::MAX as f64).floor() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        (self.next_f64() * u64::MAX as f64).floor() as u64
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        let x = self.dimensions[self.current].next_f64();
+        self.current = (self.current + 1) % self.dimensions.len();
+        x
+    }
+}
+
+impl JumpAhead for HaltonSeq {
+    /// Skip `n` coordinates, which need not be whole points
+    fn jump(&mut self, n: u64) {
+        let d = self.dimensions.len();
+        let (full, extra) = (n / d as u64, (n % d as u64) as usize);
+        for (j, h) in self.dimensions.iter_mut().enumerate() {
+            // Dimensions from the current one on are drawn once more in a partial point
+            let draws = if (j + d - self.current) % d < extra { full + 1 } else { full };
+            if draws > 0 {
+                h.jump(draws);
+            }
+        }
+        self.current = (self.current + extra) % d;
+    }
+}
+
+impl Iterator for HaltonSeq {
+    type Item = Vec<f64>;
+
+    /// Remaining coordinates of the current point, or the next whole point
+    fn next(&mut self) -> Option<Vec<f64>> {
+        let n = self.dimensions.len() - self.current;
+        Some((0..n).map(|_| self.next_f64()).collect())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use quickcheck::TestResult;
 
+    #[test]
+    fn sequence_uses_first_primes() {
+        assert!(matches!(HaltonSeq::new(0), Err(Error::EmptyGenerators)));
+        let points: Vec<Vec<f64>> = HaltonSeq::starting_at(6, 0).unwrap().take(100).collect();
+        for (j, &b) in [2, 3, 5, 7, 11, 13].iter().enumerate() {
+            let mut halton = Halton::new(0, b).unwrap();
+            for p in points.iter() {
+                assert_eq!(p[j], halton.next_f64());
+            }
+        }
+    }
+
+    #[test]
+    fn pushed_dimensions_match_wider_sequences() {
+        let mut narrow = HaltonSeq::new(2).unwrap();
+        let mut wide = HaltonSeq::new(4).unwrap();
+        narrow.jump(2 * 30 + 1);
+        wide.jump(4 * 30 + 1);
+        narrow.push_dimension();
+        narrow.push_dimension();
+        assert_eq!(narrow.bases(), wide.bases());
+        for _ in 0..200 {
+            abs_err_eq!((narrow.next_f64()) == (wide.next_f64()) ~ 1e-15);
+        }
+        assert_eq!(narrow.point(17), wide.point(17));
+        assert_eq!(narrow.point(3), vec![0.75, 1. / 9., 3. / 5., 3. / 7.]);
+    }
+
+    #[test]
+    fn sequence_jump_matches_drawing() {
+        let mut jumped = HaltonSeq::new(3).unwrap();
+        let mut drawn = jumped.clone();
+        jumped.next_f64();
+        drawn.next_f64();
+        jumped.jump(13);
+        for _ in 0..13 {
+            drawn.next_f64();
+        }
+        assert_eq!(jumped.next(), drawn.next());
+        assert_eq!(jumped.next(), drawn.next());
+    }
+
     // Brute force calculate element `i` in Halton base `b`
     fn brute_force(index: u32, base: u32) -> f64 {
         let mut i = if index == 0 {1} else {index};
@@ -254,7 +720,7 @@ mod test {
         let mut r = 0f64;
         while i > 0 {
             f /= base as f64;
-            r = r + f * (i.wrapping_rem(base)) as f64;
+            r += f * (i.wrapping_rem(base)) as f64;
             i /= base;
         }
 
@@ -264,38 +730,34 @@ mod test {
     quickcheck! {
         // Ensure implementation is equal to brute force within 2 times machine precision
         fn compare_to_brute_force(index: u32, base: u32) -> TestResult {
-            use std::f64::EPSILON;
-
             if index == 0 ||  base < 2 {
                 return TestResult::discard();
             };
 
-            let mut sampler = Halton::new(index, base);
+            let mut sampler = Halton::new(index, base).unwrap();
 
             sampler.gen::<f64>();
             let bf = brute_force(index, base);
             // Check brute force against implementation
-            TestResult::from_bool((sampler.state - bf).abs() < EPSILON * 2.)
+            TestResult::from_bool((sampler.state - bf).abs() < f64::EPSILON * 2.)
         }
 
         // Ensure implementation is equal to brute force for a range of values to ensure that the
         // advance method actually calculates the expected values
         fn compare_to_range(base: u32, start: u32, end: u32) -> TestResult {
-            use std::f64::EPSILON;
-
             // Ensure random arguments won't break brute force method and can create a valid range
             if start > end || start == 0 || base < 2 {
                 return TestResult::discard();
             };
 
             // Initialize the sampler with the given start index and base
-            let mut sampler = Halton::new(start,base);
+            let mut sampler = Halton::new(start,base).unwrap();
 
             let mut all = true;
             for i in start..end {
                 sampler.gen::<f64>();
                 let bf = brute_force(i,base);
-                if (sampler.state - bf).abs() > EPSILON * 2. {
+                if (sampler.state - bf).abs() > f64::EPSILON * 2. {
                     all = false;
                     println!("{:?} != {}",sampler, bf)
                 }
@@ -305,12 +767,120 @@ mod test {
         }
     }
 
+    #[test]
+    fn invalid_base() {
+        assert!(matches!(Halton::new(1, 0), Err(Error::InvalidBase(0))));
+        assert!(matches!(Halton::new(1, 1), Err(Error::InvalidBase(1))));
+    }
+
+    #[test]
+    fn single_precision_matches_double() {
+        let single = Halton::<f32>::with_float(1, 7).unwrap();
+        let double = Halton::new(1, 7).unwrap();
+        for (s, d) in single.zip(double).take(500) {
+            abs_err_eq!((s as f64) == d ~ (f32::EPSILON as f64 * 4.));
+        }
+    }
+
+    #[test]
+    fn scrambled_matches_radical_inverse() {
+        use quasi::radical::scrambled_radical_inverse;
+        for &b in [3, 7, 31].iter() {
+            let perm = Scramble::BraatenWeller.permutation(b).unwrap();
+            let mut halton = Halton::scrambled(1, b, Scramble::BraatenWeller).unwrap();
+            let expected: Vec<f64> = (1..2000)
+                .map(|i| scrambled_radical_inverse(b, i, &perm))
+                .collect();
+            assert_eq!(halton.peek(), expected[0]);
+            for (i, h) in halton.by_ref().take(1999).enumerate() {
+                abs_err_eq!(h == (expected[i]) ~ 1e-15);
+            }
+            halton.jump(500);
+            abs_err_eq!((halton.next_f64()) == (scrambled_radical_inverse(b, 2500, &perm)) ~ 1e-15);
+        }
+        assert!(matches!(Halton::scrambled(1, 6, Scramble::BraatenWeller),
+            Err(Error::InvalidBase(6))));
+    }
+
+    #[test]
+    fn reserved_capacity_is_kept() {
+        let small = Halton::new(1, 10).unwrap();
+        assert!(small.dig.capacity() < 6);
+
+        let mut reserved = Halton::new(1, 10).unwrap().with_capacity_for_index(999_999);
+        let (dig, rem) = (reserved.dig.capacity(), reserved.rem.capacity());
+        assert!(dig >= 7 && rem >= 7);
+        reserved.jump(999_998);
+        reserved.next_f64();
+        assert_eq!((reserved.dig.capacity(), reserved.rem.capacity()), (dig, rem));
+        abs_err_eq!((reserved.state) == (brute_force(999_999, 10)) ~ 1e-15);
+    }
+
+    #[test]
+    fn builder_composes_options() {
+        use quasi::radical::scrambled_radical_inverse;
+        let perm = Scramble::Faure.permutation(7).unwrap();
+        let builder = Halton::builder().base(7).start(3).leap(5).skip(2).scramble(Scramble::Faure);
+        let mut halton = builder.build().unwrap();
+        for k in 0..300 {
+            let i = 3 + (2 + k) * 5;
+            abs_err_eq!((halton.peek()) == (scrambled_radical_inverse(7, i, &perm)) ~ 1e-15);
+            abs_err_eq!((halton.next_f64()) == (scrambled_radical_inverse(7, i, &perm)) ~ 1e-15);
+        }
+
+        // Jumps skip whole leaps
+        let mut jumped = builder.build().unwrap();
+        jumped.jump(300);
+        abs_err_eq!((jumped.next_f64()) == (halton.next_f64()) ~ 1e-15);
+
+        let plain: Vec<f64> = Halton::builder().build().unwrap().take(10).collect();
+        assert_eq!(plain, Halton::new(1, 2).unwrap().take(10).collect::<Vec<f64>>());
+        assert!(matches!(Halton::builder().base(6).scramble(Scramble::BraatenWeller).build(),
+            Err(Error::InvalidBase(6))));
+        assert!(matches!(Halton::builder().leap(0).build(),
+            Err(Error::InvalidParameter { name: "leap", .. })));
+    }
+
+    quickcheck! {
+        // Peeking must predict the next value without changing the state
+        fn peek_predicts_next(start: u32, base: u32) -> TestResult {
+            if base < 2 {
+                return TestResult::discard();
+            }
+
+            let mut sampler = Halton::new(start, base).unwrap();
+            let first = sampler.peek();
+            let second = sampler.peek();
+
+            TestResult::from_bool(
+                first == second && (first - sampler.next_f64()).abs() < f64::EPSILON * 2.
+            )
+        }
+
+        // Jumping ahead must land on the same element as advancing one step at a time
+        fn jump_matches_advance(start: u32, base: u32, n: u16) -> TestResult {
+            if base < 2 {
+                return TestResult::discard();
+            }
+
+            let mut jumped = Halton::new(start, base).unwrap();
+            let mut stepped = jumped.clone();
+            jumped.jump(n as u64);
+            for _ in 0..n {
+                stepped.advance();
+            }
+
+            let diff = (jumped.next_f64() - stepped.next_f64()).abs();
+            TestResult::from_bool(diff < f64::EPSILON * 2.)
+        }
+    }
+
     test_known! {
-        fn compare_to_known_base_2(Halton::new(1,2)) {
+        fn compare_to_known_base_2(Halton::new(1,2).unwrap()) {
             [1./2., 1./4., 3./4., 1./8., 5./8., 3./8., 7./8.,1./16., 9./16.]
         }
 
-        fn compare_to_known_base_3(Halton::new(1,3)) {
+        fn compare_to_known_base_3(Halton::new(1,3).unwrap()) {
             [1./3., 2./3., 1./9., 4./9., 7./9., 2./9., 5./9., 8./9., 1./27.]
         }
     }
@@ -0,0 +1,131 @@
+use error::{Error, Result};
+use primes::Primes;
+use super::radical::radical_inverse;
+
+/// Hammersley point set of a fixed number of points
+///
+/// Point `i` of `n` is `(i / n, φ_2(i), φ_3(i), ...)`, where `φ_b` is the radical inverse in
+/// base `b` over the first primes.  Knowing `n` upfront buys a better first coordinate than
+/// the [`Halton`] sequence can offer, at the cost of the point set not being extensible: the
+/// points only cover the unit cube evenly once all `n` have been drawn.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::Hammersley;
+/// let points: Vec<Vec<f64>> = Hammersley::new(4, 2).unwrap().collect();
+/// assert_eq!(points, vec![
+///     vec![0., 0.],
+///     vec![0.25, 0.5],
+///     vec![0.5, 0.25],
+///     vec![0.75, 0.75],
+/// ]);
+/// ```
+///
+/// [`Halton`]: ../halton/struct.Halton.html
+#[derive(Debug, Clone)]
+pub struct Hammersley {
+    points: u64,
+    bases: Vec<u32>,
+    index: u64,
+}
+
+impl Hammersley {
+    /// Point set of `n` points in `dimensions` dimensions
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyGenerators`] if `dimensions` is zero.
+    ///
+    /// [`Error::EmptyGenerators`]: ../../enum.Error.html#variant.EmptyGenerators
+    pub fn new(n: u64, dimensions: usize) -> Result<Hammersley> {
+        if dimensions == 0 {
+            return Err(Error::EmptyGenerators);
+        }
+        let bases = Primes::new().take(dimensions - 1).collect();
+        Ok(Hammersley { points: n, bases, index: 0 })
+    }
+
+    /// Number of dimensions of each point
+    pub fn dimensions(&self) -> usize {
+        self.bases.len() + 1
+    }
+
+    /// Total number of points in the set
+    pub fn points(&self) -> u64 {
+        self.points
+    }
+
+    /// Point `i` of the set, which need not be the next one
+    ///
+    /// # Panics
+    ///
+    /// If `i` is not below the number of points.
+    pub fn point(&self, i: u64) -> Vec<f64> {
+        assert!(i < self.points, "point {} of a {} point set", i, self.points);
+        let mut point = Vec::with_capacity(self.dimensions());
+        point.push(i as f64 / self.points as f64);
+        point.extend(self.bases.iter().map(|&b| radical_inverse(b, i)));
+        point
+    }
+}
+
+impl Iterator for Hammersley {
+    type Item = Vec<f64>;
+
+    fn next(&mut self) -> Option<Vec<f64>> {
+        if self.index == self.points {
+            return None;
+        }
+        let point = self.point(self.index);
+        self.index += 1;
+        Some(point)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = (self.points - self.index) as usize;
+        (left, Some(left))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Vec<f64>> {
+        self.index = self.index.saturating_add(n as u64).min(self.points);
+        self.next()
+    }
+}
+
+impl ExactSizeIterator for Hammersley {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn no_dimensions() {
+        assert!(matches!(Hammersley::new(10, 0), Err(Error::EmptyGenerators)));
+    }
+
+    #[test]
+    fn finite_and_exact() {
+        let mut set = Hammersley::new(100, 3).unwrap();
+        assert_eq!(set.len(), 100);
+        assert_eq!(set.nth(98), Some(set.point(98)));
+        assert_eq!(set.len(), 1);
+        assert_eq!(set.by_ref().count(), 1);
+        assert_eq!(set.next(), None);
+    }
+
+    #[test]
+    fn stratifies_first_two_axes() {
+        // For n = 2^m the first two coordinates form a (0, m, 2)-net in base 2
+        let points: Vec<Vec<f64>> = Hammersley::new(64, 3).unwrap().collect();
+        for k in 0..=6 {
+            let (a, b) = (1 << k, 1 << (6 - k));
+            let mut boxes = [false; 64];
+            for p in points.iter() {
+                boxes[(p[0] * a as f64) as usize * b + (p[1] * b as f64) as usize] = true;
+            }
+            assert!(boxes.iter().all(|&x| x), "boxes of {}×{} are not filled", a, b);
+        }
+        abs_err_eq!((points[5][2]) == (2. / 3. + 1. / 9.) ~ 1e-15);
+    }
+}
@@ -0,0 +1,222 @@
+//! Interlacing of digital sequences into higher-order digital nets
+//!
+//! Interlacing `α` dimensions of a digital net digit by digit gives a higher order digital net
+//! in `α` times fewer dimensions.  For integrands with square integrable mixed
+//! derivatives of order `α`, the integration error of an order `α` net of `N` points decays
+//! like `N^-α` up to logarithmic factors, instead of the `N^-1` of the components on their own.
+
+use rand::Rng;
+
+use super::{Digital, JumpAhead, Niederreiter2, Sobol};
+use super::digital::{bits_to_f32, bits_to_f64};
+
+/// Higher order digital sequence from interlacing the dimensions of a digital sequence
+///
+/// Output dimension `j` interlaces the components `α j` to `α j + α - 1` of the inner
+/// sequence: its binary digits are the first digit of every component, then the second digit
+/// of every component, and so on.  Every component contributes `⌊64 / α⌋` digits.  Build one
+/// with [`Sobol::interlace`] or [`Niederreiter2::interlace`].
+///
+/// Implements [`Rng`] by returning the coordinates of consecutive points one after another, and
+/// [`Iterator`] by returning whole points.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::Sobol;
+/// // A two dimensional order 2 net from the first four Sobol dimensions
+/// let mut net = Sobol::new(4).unwrap().interlace(2);
+/// assert_eq!(net.dimensions(), 2);
+///
+/// net.next();
+/// // 0.1 and 0.1 in binary interlace to 0.11
+/// assert_eq!(net.next(), Some(vec![0.75, 0.75]));
+/// ```
+///
+/// # References
+/// - Dick, J., Walsh spaces containing smooth functions and quasi-Monte Carlo rules of
+///   arbitrary high order
+/// - Goda, T., Dick, J., Construction of interlaced scrambled polynomial lattice rules of
+///   arbitrary high order
+///
+/// [`Sobol::interlace`]: ../sobol/struct.Sobol.html#method.interlace
+/// [`Niederreiter2::interlace`]: ../niederreiter/struct.Niederreiter2.html#method.interlace
+/// [`Rng`]: ../../../rand/trait.Rng.html
+/// [`Iterator`]: https://doc.rust-lang.org/std/iter/trait.Iterator.html
+#[derive(Debug, Clone)]
+pub struct Interlaced<D> {
+    inner: D,
+    alpha: usize,
+    dimensions: usize,
+    /// Next coordinate of the current point
+    current: usize,
+}
+
+impl<D: Digital> Interlaced<D> {
+    /// Interlace `alpha` consecutive dimensions of `inner`, which has `dimensions` dimensions
+    ///
+    /// # Panics
+    ///
+    /// If `alpha` is zero, above 64 or does not divide `dimensions`.
+    pub fn new(inner: D, alpha: usize, dimensions: usize) -> Interlaced<D> {
+        assert!(alpha > 0 && alpha <= 64, "interlacing order {} out of range", alpha);
+        assert!(dimensions > 0 && dimensions.is_multiple_of(alpha),
+            "cannot interlace {} dimensions in groups of {}", dimensions, alpha);
+        Interlaced { inner, alpha, dimensions: dimensions / alpha, current: 0 }
+    }
+
+    /// Interlacing order `α`
+    pub fn alpha(&self) -> usize {
+        self.alpha
+    }
+
+    /// Number of dimensions of each interlaced point
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    /// Unwrap the interlaced generator
+    pub fn into_inner(self) -> D {
+        self.inner
+    }
+}
+
+/// Interlace the leading digits of `components` into one 64 bit binary fraction
+fn interlace_bits(components: &[u64]) -> u64 {
+    let alpha = components.len();
+    let mut out = 0;
+    let mut bit = 63u32;
+    for k in 0..64 / alpha {
+        for c in components.iter() {
+            out |= (c >> (63 - k) & 1) << bit;
+            bit = bit.saturating_sub(1);
+        }
+    }
+    out
+}
+
+impl<D: Digital> Digital for Interlaced<D> {
+    fn next_bits(&mut self) -> u64 {
+        let mut components = [0; 64];
+        for c in components[..self.alpha].iter_mut() {
+            *c = self.inner.next_bits();
+        }
+        self.current = (self.current + 1) % self.dimensions;
+        interlace_bits(&components[..self.alpha])
+    }
+}
+
+impl<D: Digital> Rng for Interlaced<D> {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_bits() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.next_bits()
+    }
+
+    fn next_f32(&mut self) -> f32 {
+        bits_to_f32(self.next_bits())
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        bits_to_f64(self.next_bits())
+    }
+}
+
+impl<D: JumpAhead> JumpAhead for Interlaced<D> {
+    /// Skip `n` interlaced coordinates, which need not be whole points
+    fn jump(&mut self, n: u64) {
+        self.inner.jump(n * self.alpha as u64);
+        self.current = ((self.current as u64 + n) % self.dimensions as u64) as usize;
+    }
+}
+
+impl<D: Digital> Iterator for Interlaced<D> {
+    type Item = Vec<f64>;
+
+    /// Remaining coordinates of the current point, or the next whole point
+    fn next(&mut self) -> Option<Vec<f64>> {
+        let n = self.dimensions - self.current;
+        Some((0..n).map(|_| self.next_f64()).collect())
+    }
+}
+
+impl Sobol {
+    /// Interlace every `alpha` consecutive dimensions into a higher order digital sequence
+    ///
+    /// See [`Interlaced`] for details.
+    ///
+    /// # Panics
+    ///
+    /// If `alpha` is zero or does not divide the number of dimensions.
+    ///
+    /// [`Interlaced`]: ../interlace/struct.Interlaced.html
+    pub fn interlace(self, alpha: usize) -> Interlaced<Sobol> {
+        let dimensions = self.dimensions();
+        Interlaced::new(self, alpha, dimensions)
+    }
+}
+
+impl Niederreiter2 {
+    /// Interlace every `alpha` consecutive dimensions into a higher order digital sequence
+    ///
+    /// See [`Interlaced`] for details.
+    ///
+    /// # Panics
+    ///
+    /// If `alpha` is zero or does not divide the number of dimensions.
+    ///
+    /// [`Interlaced`]: ../interlace/struct.Interlaced.html
+    pub fn interlace(self, alpha: usize) -> Interlaced<Niederreiter2> {
+        let dimensions = self.dimensions();
+        Interlaced::new(self, alpha, dimensions)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn interlaced_digits() {
+        assert_eq!(interlace_bits(&[1 << 63, 0]), 1 << 63);
+        assert_eq!(interlace_bits(&[0, 1 << 63]), 1 << 62);
+        assert_eq!(interlace_bits(&[0, 1 << 62, 0]), 1 << 59);
+        assert_eq!(interlace_bits(&[u64::MAX; 3]), u64::MAX << 1);
+        assert_eq!(interlace_bits(&[0xdead_beef]), 0xdead_beef);
+    }
+
+    #[test]
+    fn order_two_nets_stratify() {
+        // Interlacing a (0,2m,2)-net digit by digit spreads its points over 4^m intervals
+        for net in [
+            Box::new(Sobol::new(2).unwrap().interlace(2)) as Box<dyn Iterator<Item = Vec<f64>>>,
+            Box::new(Niederreiter2::new(2).unwrap().interlace(2)),
+        ] {
+            let mut strata = [false; 256];
+            for p in net.take(256) {
+                strata[(p[0] * 256.) as usize] = true;
+            }
+            assert!(strata.iter().all(|&s| s));
+        }
+    }
+
+    #[test]
+    fn jump_matches_drawing() {
+        let mut jumped = Sobol::new(6).unwrap().interlace(3);
+        let mut drawn = jumped.clone();
+        jumped.jump(9);
+        for _ in 0..9 {
+            drawn.next_u64();
+        }
+        assert_eq!(jumped.next_u64(), drawn.next_u64());
+        assert_eq!(jumped.next(), drawn.next());
+    }
+
+    #[test]
+    #[should_panic]
+    fn alpha_must_divide_dimensions() {
+        Sobol::new(5).unwrap().interlace(2);
+    }
+}
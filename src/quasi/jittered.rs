@@ -0,0 +1,164 @@
+use rand::Rng;
+
+use error::{Error, Result};
+
+/// Stratified sample with one jittered point in every cell of a grid
+///
+/// The unit cube is cut into a grid with `extents[j]` cells along axis `j`, and every cell
+/// receives one point placed uniformly at random inside it.  Each point is uniform over its
+/// cell, so the sample mean of any integrand stays unbiased while its variance can only
+/// shrink compared to independent points; for smooth integrands it shrinks much faster.
+///
+/// Points are ordered cell by cell with the last axis varying fastest.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate tapas;
+/// # use tapas::quasi::JitteredGrid;
+/// # fn main() {
+/// let grid = JitteredGrid::new(&[4, 2], &mut rand::thread_rng()).unwrap();
+/// assert_eq!(grid.len(), 8);
+///
+/// // The second point is in the cell [0, 1/4) × [1/2, 1)
+/// let p = grid.point(1);
+/// assert!(p[0] < 0.25 && p[1] >= 0.5);
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct JitteredGrid {
+    extents: Vec<usize>,
+    /// Coordinates of every point, one point after another
+    design: Vec<f64>,
+    index: usize,
+}
+
+impl JitteredGrid {
+    /// Jittered grid with `extents[j]` cells along axis `j`, drawing from `rng`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyGenerators`] if `extents` is empty.
+    ///
+    /// [`Error::EmptyGenerators`]: ../../enum.Error.html#variant.EmptyGenerators
+    pub fn new<R: Rng>(extents: &[usize], rng: &mut R) -> Result<JitteredGrid> {
+        if extents.is_empty() {
+            return Err(Error::EmptyGenerators);
+        }
+
+        let cells: usize = extents.iter().product();
+        let mut design = Vec::with_capacity(cells * extents.len());
+        let mut cell = vec![0; extents.len()];
+        for _ in 0..cells {
+            for (&c, &n) in cell.iter().zip(extents) {
+                design.push((c as f64 + rng.next_f64()) / n as f64);
+            }
+            // Odometer step to the next cell
+            for j in (0..cell.len()).rev() {
+                cell[j] += 1;
+                if cell[j] < extents[j] {
+                    break;
+                }
+                cell[j] = 0;
+            }
+        }
+        Ok(JitteredGrid { extents: extents.to_vec(), design, index: 0 })
+    }
+
+    /// Number of cells along every axis
+    pub fn extents(&self) -> &[usize] {
+        &self.extents
+    }
+
+    /// Number of dimensions of each point
+    pub fn dimensions(&self) -> usize {
+        self.extents.len()
+    }
+
+    /// Total number of points, one per cell
+    pub fn points(&self) -> usize {
+        self.design.len() / self.extents.len()
+    }
+
+    /// Point `i` of the sample, which need not be the next one
+    ///
+    /// # Panics
+    ///
+    /// If `i` is not below the number of points.
+    pub fn point(&self, i: usize) -> &[f64] {
+        assert!(i < self.points(), "point {} of a {} point grid", i, self.points());
+        let d = self.extents.len();
+        &self.design[i * d..(i + 1) * d]
+    }
+}
+
+impl Iterator for JitteredGrid {
+    type Item = Vec<f64>;
+
+    fn next(&mut self) -> Option<Vec<f64>> {
+        if self.index == self.points() {
+            return None;
+        }
+        let point = self.point(self.index).to_vec();
+        self.index += 1;
+        Some(point)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.points() - self.index;
+        (left, Some(left))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Vec<f64>> {
+        self.index = self.index.saturating_add(n).min(self.points());
+        self.next()
+    }
+}
+
+impl ExactSizeIterator for JitteredGrid {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{SeedableRng, StdRng};
+
+    #[test]
+    fn one_point_per_cell() {
+        let grid = JitteredGrid::new(&[3, 4, 5], &mut StdRng::from_seed(&[8][..])).unwrap();
+        assert_eq!((grid.points(), grid.dimensions()), (60, 3));
+        let mut cells: Vec<Vec<usize>> = grid
+            .map(|p| p.iter().zip(&[3, 4, 5]).map(|(x, &n)| (x * n as f64) as usize).collect())
+            .collect();
+        let ordered = cells.clone();
+        cells.sort();
+        cells.dedup();
+        assert_eq!(cells.len(), 60);
+        assert_eq!(cells, ordered);
+    }
+
+    #[test]
+    fn degenerate_grids() {
+        let mut rng = StdRng::from_seed(&[9][..]);
+        assert!(matches!(JitteredGrid::new(&[], &mut rng), Err(Error::EmptyGenerators)));
+        assert_eq!(JitteredGrid::new(&[3, 0], &mut rng).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn stratification_reduces_variance() {
+        // Mean of x² over 4×4 jittered grids against independent points
+        let mut rng = StdRng::from_seed(&[10][..]);
+        let reps = 400;
+        let estimates: Vec<f64> = (0..reps)
+            .map(|_| {
+                let grid = JitteredGrid::new(&[4, 4], &mut rng).unwrap();
+                grid.map(|p| p[0] * p[0]).sum::<f64>() / 16.
+            })
+            .collect();
+        let mean = estimates.iter().sum::<f64>() / reps as f64;
+        let var = estimates.iter().map(|e| (e - mean).powi(2)).sum::<f64>() / reps as f64;
+        abs_err_eq!(mean == (1. / 3.) ~ 0.005);
+        // Independent points have variance (1/5 - 1/9) / 16 ≈ 0.0056
+        assert!(var < 0.001, "variance {}", var);
+    }
+}
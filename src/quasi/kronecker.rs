@@ -0,0 +1,253 @@
+use rand::Rng;
+
+use error::{Error, Result};
+use primes::Primes;
+use super::JumpAhead;
+
+/// Scale of 64 bit fixed point fractions
+const ONE: f64 = 18_446_744_073_709_551_616.;
+
+/// Kronecker, or Weyl, additive recurrence sequence
+///
+/// Point `n` is `frac(x_0 + n α)` for a vector `α` of irrational steps whose components are
+/// linearly independent over the rationals together with `1`.  Every point costs a single
+/// addition per coordinate, much cheaper than digit-based sequences, which suits animation and
+/// jitter where samples are drawn every frame.
+///
+/// Coordinates are held as 64 bit fixed point fractions, so the recurrence wraps around
+/// exactly and does not accumulate rounding error over long runs.
+///
+/// Implements [`Rng`] by returning the coordinates of consecutive points one after another, and
+/// [`Iterator`] by returning whole points.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::Kronecker;
+/// let mut gen = Kronecker::new(&[0.5f64.sqrt()]).unwrap();
+/// assert_eq!(gen.next(), Some(vec![0.]));
+///
+/// // Steps of √½ modulo one
+/// let x = gen.next().unwrap()[0];
+/// assert!((x - 0.5f64.sqrt()).abs() < 1e-15);
+/// let x = gen.next().unwrap()[0];
+/// assert!((x - (2. * 0.5f64.sqrt() - 1.)).abs() < 1e-15);
+/// ```
+///
+/// [`Rng`]: ../../../rand/trait.Rng.html
+/// [`Iterator`]: https://doc.rust-lang.org/std/iter/trait.Iterator.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Kronecker {
+    /// Steps as fixed point fractions
+    alpha: Vec<u64>,
+    /// Current point as fixed point fractions
+    point: Vec<u64>,
+    /// Next coordinate of the current point
+    current: usize,
+}
+
+impl Kronecker {
+    /// Sequence with steps `alpha`, starting at the origin
+    ///
+    /// Only the fractional part of every step matters.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyGenerators`] if `alpha` is empty.
+    ///
+    /// [`Error::EmptyGenerators`]: ../../enum.Error.html#variant.EmptyGenerators
+    pub fn new(alpha: &[f64]) -> Result<Kronecker> {
+        if alpha.is_empty() {
+            return Err(Error::EmptyGenerators);
+        }
+        Ok(Kronecker {
+            alpha: alpha.iter().map(|&a| fixed(a)).collect(),
+            point: vec![0; alpha.len()],
+            current: 0,
+        })
+    }
+
+    /// Richtmyer sequence, with the square roots of the first `dimensions` primes as steps
+    ///
+    /// Square roots of distinct primes are linearly independent over the rationals, so every
+    /// dimension is equidistributed and no two dimensions line up.  Their fractional parts are
+    /// computed with integer square roots rather than in floating point, which keeps at least
+    /// 48 exact bits of every step even for the primes of millions of dimensions, where the
+    /// integer part of `√p` would otherwise take up much of an `f64`.
+    ///
+    /// ```
+    /// # use tapas::quasi::Kronecker;
+    /// let mut richtmyer = Kronecker::sqrt_primes(3).unwrap();
+    /// richtmyer.next();
+    /// let p = richtmyer.next().unwrap();
+    /// assert!((p[2] - (5f64.sqrt() - 2.)).abs() < 1e-15);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyGenerators`] if `dimensions` is zero.
+    ///
+    /// # References
+    /// - Richtmyer, R. D., The evaluation of definite integrals, and a quasi-Monte-Carlo method
+    ///   based on the properties of algebraic numbers
+    ///
+    /// [`Error::EmptyGenerators`]: ../../enum.Error.html#variant.EmptyGenerators
+    pub fn sqrt_primes(dimensions: usize) -> Result<Kronecker> {
+        if dimensions == 0 {
+            return Err(Error::EmptyGenerators);
+        }
+        Ok(Kronecker {
+            alpha: Primes::new().take(dimensions).map(sqrt_fraction).collect(),
+            point: vec![0; dimensions],
+            current: 0,
+        })
+    }
+
+    /// Start the sequence at `start` instead of the origin
+    ///
+    /// # Panics
+    ///
+    /// If `start` does not have one value per dimension.
+    pub fn with_start(mut self, start: &[f64]) -> Kronecker {
+        assert_eq!(start.len(), self.alpha.len(), "{} start values for {} dimensions",
+            start.len(), self.alpha.len());
+        self.point = start.iter().map(|&x| fixed(x)).collect();
+        self
+    }
+
+    /// Number of dimensions of each point
+    pub fn dimensions(&self) -> usize {
+        self.alpha.len()
+    }
+
+    /// Next coordinate as a 64 bit fixed point fraction
+    #[inline]
+    fn next_fixed(&mut self) -> u64 {
+        let j = self.current;
+        let x = self.point[j];
+        self.point[j] = x.wrapping_add(self.alpha[j]);
+        self.current = if j + 1 == self.alpha.len() { 0 } else { j + 1 };
+        x
+    }
+}
+
+/// Fractional part of `x` as a 64 bit fixed point fraction
+fn fixed(x: f64) -> u64 {
+    let frac = x - x.floor();
+    // Fractions just below one round up to exactly one, which wraps to zero
+    (frac * ONE) as u128 as u64
+}
+
+/// Fractional part of `√p` as a 64 bit fixed point fraction, exact in its leading bits
+fn sqrt_fraction(p: u32) -> u64 {
+    // ⌊√p 2^k⌋ with as many fractional bits k as fit in 128 bits
+    let k = (128 - (32 - p.leading_zeros())).min(128) / 2;
+    let root = ((p as u128) << (2 * k)).isqrt();
+    (root << (64 - k)) as u64
+}
+
+impl Rng for Kronecker {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_fixed() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.next_fixed()
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        (self.next_fixed() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+impl JumpAhead for Kronecker {
+    /// Skip `n` coordinates, which need not be whole points
+    fn jump(&mut self, n: u64) {
+        let d = self.alpha.len() as u64;
+        let (points, rest) = (n / d, n % d);
+        for (x, &a) in self.point.iter_mut().zip(self.alpha.iter()) {
+            *x = x.wrapping_add(a.wrapping_mul(points));
+        }
+        for _ in 0..rest {
+            self.next_fixed();
+        }
+    }
+}
+
+impl Iterator for Kronecker {
+    type Item = Vec<f64>;
+
+    /// Remaining coordinates of the current point, or the next whole point
+    fn next(&mut self) -> Option<Vec<f64>> {
+        let n = self.alpha.len() - self.current;
+        Some((0..n).map(|_| self.next_f64()).collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn no_dimensions() {
+        assert!(matches!(Kronecker::new(&[]), Err(Error::EmptyGenerators)));
+        assert!(matches!(Kronecker::sqrt_primes(0), Err(Error::EmptyGenerators)));
+    }
+
+    #[test]
+    fn matches_direct_formula() {
+        let alpha = [2f64.sqrt(), 3f64.sqrt()];
+        let gen = Kronecker::sqrt_primes(2).unwrap().with_start(&[0.25, 0.5]);
+        for (n, p) in gen.take(1000).enumerate() {
+            for j in 0..2 {
+                let x = ([0.25, 0.5][j] + n as f64 * alpha[j]).fract();
+                abs_err_eq!((p[j]) == x ~ 1e-11);
+            }
+        }
+    }
+
+    #[test]
+    fn exact_square_roots() {
+        // Fractional parts of √2, √3, ..., √19, the initial hash words of SHA-512
+        let sha512 = [
+            0x6a09_e667_f3bc_c908, 0xbb67_ae85_84ca_a73b, 0x3c6e_f372_fe94_f82b,
+            0xa54f_f53a_5f1d_36f1, 0x510e_527f_ade6_82d1, 0x9b05_688c_2b3e_6c1f,
+            0x1f83_d9ab_fb41_bd6b, 0x5be0_cd19_137e_2179u64,
+        ];
+        let gen = Kronecker::sqrt_primes(8).unwrap();
+        for (&a, &h) in gen.alpha.iter().zip(sha512.iter()) {
+            // Up to three trailing bits are beyond the 128 bit integer square root
+            assert_eq!(a >> 3, h >> 3, "{:#x} against {:#x}", a, h);
+        }
+
+        // A prime near 2^31 still gets 48 exact bits, where an f64 keeps 37
+        let p = 2_147_483_647;
+        let exact = sqrt_fraction(p);
+        assert!(exact.trailing_zeros() >= 16);
+        let float = fixed((p as f64).sqrt());
+        assert!(float != exact && (float >> 28) == (exact >> 28));
+    }
+
+    #[test]
+    fn equidistributed() {
+        let gen = Kronecker::new(&[(5f64.sqrt() - 1.) / 2.]).unwrap();
+        let mut bins = [0; 10];
+        for p in gen.take(10_000) {
+            bins[(p[0] * 10.) as usize] += 1;
+        }
+        assert!(bins.iter().all(|&b| (b - 1000i32).abs() <= 2), "{:?}", bins);
+    }
+
+    #[test]
+    fn jump_matches_drawing() {
+        let mut jumped = Kronecker::sqrt_primes(3).unwrap();
+        let mut drawn = jumped.clone();
+        jumped.next_u64();
+        drawn.next_u64();
+        jumped.jump(1000);
+        for _ in 0..1000 {
+            drawn.next_u64();
+        }
+        assert_eq!(jumped, drawn);
+    }
+}
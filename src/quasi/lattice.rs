@@ -0,0 +1,448 @@
+use rand::Rng;
+use std::f64::consts::PI;
+
+use error::{Error, Result};
+use primes::gcd;
+
+/// Rank-1 lattice rule of a fixed number of points
+///
+/// Point `i` of `n` is `frac(i z / n + Δ)` for the integer generating vector `z` and a shift
+/// `Δ`, which is zero unless set.  Good generating vectors make lattice rules very accurate
+/// for smooth periodic integrands.
+///
+/// Independent uniform shifts turn the rule into an unbiased randomized quasi-Monte Carlo
+/// estimator: averaging the integral over a handful of [`random_shift`]s estimates it, and the
+/// spread between them estimates the error.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::Lattice;
+/// // Fibonacci lattice with 8 points
+/// let points: Vec<Vec<f64>> = Lattice::new(&[1, 5], 8).unwrap().collect();
+/// assert_eq!(points[0], vec![0., 0.]);
+/// assert_eq!(points[1], vec![0.125, 0.625]);
+/// assert_eq!(points[2], vec![0.25, 0.25]);
+/// ```
+///
+/// Instead of importing generating vectors from elsewhere, [`cbc`] and [`korobov`] search for
+/// good ones.
+///
+/// [`random_shift`]: #method.random_shift
+/// [`cbc`]: #method.cbc
+/// [`korobov`]: #method.korobov
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lattice {
+    generator: Vec<u64>,
+    shift: Vec<f64>,
+    points: u64,
+    index: u64,
+}
+
+impl Lattice {
+    /// Rule of `n` points with generating vector `generator`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyGenerators`] if `generator` is empty.
+    ///
+    /// [`Error::EmptyGenerators`]: ../../enum.Error.html#variant.EmptyGenerators
+    pub fn new(generator: &[u64], n: u64) -> Result<Lattice> {
+        if generator.is_empty() {
+            return Err(Error::EmptyGenerators);
+        }
+        Ok(Lattice {
+            generator: generator.iter().map(|&z| if n > 0 { z % n } else { z }).collect(),
+            shift: vec![0.; generator.len()],
+            points: n,
+            index: 0,
+        })
+    }
+
+    /// Rule of `n` points with a generating vector built component by component
+    ///
+    /// Uses the product weights `γ_j = 1 / j²`, which say that later dimensions matter less.
+    /// See [`cbc_weighted`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyGenerators`] if `dimensions` is zero, and
+    /// [`Error::InvalidParameter`] if `n` is zero.
+    ///
+    /// [`cbc_weighted`]: #method.cbc_weighted
+    /// [`Error::EmptyGenerators`]: ../../enum.Error.html#variant.EmptyGenerators
+    /// [`Error::InvalidParameter`]: ../../enum.Error.html#variant.InvalidParameter
+    pub fn cbc(dimensions: usize, n: u64) -> Result<Lattice> {
+        Lattice::cbc_weighted(&default_weights(dimensions), n)
+    }
+
+    /// Rule of `n` points with a generating vector built component by component for `weights`
+    ///
+    /// The component-by-component construction of Sloan and Reztsov fixes one component of
+    /// the generating vector at a time, choosing among the integers coprime to `n` the one
+    /// minimizing the worst-case error in the weighted Korobov space of smoothness 2 given the
+    /// components already fixed.  The weight `γ_j` of dimension `j` bounds how much that
+    /// dimension may matter to the integrand; the rule has one dimension per weight.  The
+    /// resulting rules achieve the optimal rate of convergence, close to `1 / n`.  The first
+    /// component is always `1`.
+    ///
+    /// The search takes time proportional to `d n²`, so it suits rules of up to some ten
+    /// thousand points.
+    ///
+    /// ```
+    /// # use tapas::quasi::Lattice;
+    /// let rule = Lattice::cbc_weighted(&[1., 0.5, 0.25], 127).unwrap();
+    /// assert_eq!(rule.generator()[0], 1);
+    /// assert_eq!(rule.len(), 127);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyGenerators`] if `weights` is empty, and
+    /// [`Error::InvalidParameter`] if `n` is zero.
+    ///
+    /// # Panics
+    ///
+    /// If a weight is negative.
+    ///
+    /// # References
+    /// - Sloan, I. H., Reztsov, A. V., Component-by-component construction of good lattice rules
+    /// - Kuo, F. Y., Component-by-component constructions achieve the optimal rate of
+    ///   convergence for multivariate integration in weighted Korobov and Sobolev spaces
+    ///
+    /// [`Error::EmptyGenerators`]: ../../enum.Error.html#variant.EmptyGenerators
+    /// [`Error::InvalidParameter`]: ../../enum.Error.html#variant.InvalidParameter
+    pub fn cbc_weighted(weights: &[f64], n: u64) -> Result<Lattice> {
+        if weights.is_empty() {
+            return Err(Error::EmptyGenerators);
+        }
+        if n == 0 {
+            return Err(no_points());
+        }
+        assert!(weights.iter().all(|&g| g >= 0.), "negative weight in {:?}", weights);
+
+        let omega = kernel(n);
+        let candidates = units(n);
+        let n = n as usize;
+        // Product over the fixed components for every point
+        let mut product = vec![1.; n];
+        let mut generator = Vec::with_capacity(weights.len());
+        for (j, &gamma) in weights.iter().enumerate() {
+            let cost = |z: u64| -> f64 {
+                product.iter().enumerate()
+                    .map(|(k, p)| p * (1. + gamma * omega[k * z as usize % n]))
+                    .sum()
+            };
+            let mut best = (f64::INFINITY, 1);
+            // Every unit is equally good for the first component
+            for &z in candidates.iter().take(if j == 0 { 1 } else { candidates.len() }) {
+                let c = cost(z);
+                if c < best.0 {
+                    best = (c, z);
+                }
+            }
+            let z = best.1 as usize;
+            for (k, p) in product.iter_mut().enumerate() {
+                *p *= 1. + gamma * omega[k * z % n];
+            }
+            generator.push(best.1);
+        }
+        Lattice::new(&generator, n as u64)
+    }
+
+    /// Korobov rule of `n` points in `dimensions` dimensions
+    ///
+    /// Uses the product weights `γ_j = 1 / j²`.  See [`korobov_weighted`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyGenerators`] if `dimensions` is zero, and
+    /// [`Error::InvalidParameter`] if `n` is zero.
+    ///
+    /// [`korobov_weighted`]: #method.korobov_weighted
+    /// [`Error::EmptyGenerators`]: ../../enum.Error.html#variant.EmptyGenerators
+    /// [`Error::InvalidParameter`]: ../../enum.Error.html#variant.InvalidParameter
+    pub fn korobov(dimensions: usize, n: u64) -> Result<Lattice> {
+        Lattice::korobov_weighted(&default_weights(dimensions), n)
+    }
+
+    /// Korobov rule of `n` points with the generating vector `(1, a, a², …) mod n` best for
+    /// `weights`
+    ///
+    /// Every integer `a` coprime to `n` is tried, keeping the one whose rule has the smallest
+    /// worst-case error in the weighted Korobov space of smoothness 2, with one dimension per
+    /// weight.  Only one parameter is searched, which takes time proportional to `d n²`, and
+    /// the resulting rule is described by `a` alone.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyGenerators`] if `weights` is empty, and
+    /// [`Error::InvalidParameter`] if `n` is zero.
+    ///
+    /// # Panics
+    ///
+    /// If a weight is negative.
+    ///
+    /// # References
+    /// - Korobov, N. M., The approximate computation of multiple integrals
+    ///
+    /// [`Error::EmptyGenerators`]: ../../enum.Error.html#variant.EmptyGenerators
+    /// [`Error::InvalidParameter`]: ../../enum.Error.html#variant.InvalidParameter
+    pub fn korobov_weighted(weights: &[f64], n: u64) -> Result<Lattice> {
+        if weights.is_empty() {
+            return Err(Error::EmptyGenerators);
+        }
+        if n == 0 {
+            return Err(no_points());
+        }
+        assert!(weights.iter().all(|&g| g >= 0.), "negative weight in {:?}", weights);
+
+        let omega = kernel(n);
+        let powers = |a: u64| -> Vec<u64> {
+            let mut z = 1 % n;
+            weights.iter()
+                .map(|_| {
+                    let current = z;
+                    z = (z as u128 * a as u128 % n as u128) as u64;
+                    current
+                })
+                .collect()
+        };
+        let mut best = (f64::INFINITY, 1);
+        for a in units(n) {
+            let e = squared_error(&powers(a), weights, &omega);
+            if e < best.0 {
+                best = (e, a);
+            }
+        }
+        Lattice::new(&powers(best.1), n)
+    }
+
+    /// Shift every point by `shift` modulo one
+    ///
+    /// # Panics
+    ///
+    /// If `shift` does not have one value per dimension.
+    pub fn with_shift(mut self, shift: &[f64]) -> Lattice {
+        assert_eq!(shift.len(), self.generator.len(), "{} shift values for {} dimensions",
+            shift.len(), self.generator.len());
+        self.shift = shift.iter().map(|&x| x - x.floor()).collect();
+        self
+    }
+
+    /// Shift every point by a uniform random vector drawn from `rng`
+    pub fn random_shift<R: Rng + ?Sized>(self, rng: &mut R) -> Lattice {
+        let shift: Vec<f64> = (0..self.generator.len()).map(|_| rng.next_f64()).collect();
+        self.with_shift(&shift)
+    }
+
+    /// Number of dimensions of each point
+    pub fn dimensions(&self) -> usize {
+        self.generator.len()
+    }
+
+    /// Total number of points in the rule
+    pub fn points(&self) -> u64 {
+        self.points
+    }
+
+    /// Generating vector, reduced modulo the number of points
+    pub fn generator(&self) -> &[u64] {
+        &self.generator
+    }
+
+    /// Point `i` of the rule, which need not be the next one
+    ///
+    /// # Panics
+    ///
+    /// If `i` is not below the number of points.
+    pub fn point(&self, i: u64) -> Vec<f64> {
+        assert!(i < self.points, "point {} of a {} point rule", i, self.points);
+        let n = self.points as u128;
+        self.generator.iter().zip(self.shift.iter())
+            .map(|(&z, &delta)| {
+                let x = (i as u128 * z as u128 % n) as f64 / self.points as f64 + delta;
+                if x >= 1. { x - 1. } else { x }
+            })
+            .collect()
+    }
+}
+
+/// Product weights `γ_j = 1 / j²`
+fn default_weights(dimensions: usize) -> Vec<f64> {
+    (1..=dimensions).map(|j| 1. / (j * j) as f64).collect()
+}
+
+/// Error for a rule asked to have no points
+fn no_points() -> Error {
+    Error::InvalidParameter { name: "points", reason: "a rule needs at least one point".to_owned() }
+}
+
+/// Integers in `[1, n)` coprime to `n`, or just `1` if there are none
+fn units(n: u64) -> Vec<u64> {
+    let units: Vec<u64> = (1..n).filter(|&z| gcd(z, n) == 1).collect();
+    if units.is_empty() { vec![1] } else { units }
+}
+
+/// `ω(k / n) = 2π² B₂(k / n)` for `k` in `[0, n)`, the reproducing kernel of the Korobov space
+/// of smoothness 2 less one
+fn kernel(n: u64) -> Vec<f64> {
+    (0..n)
+        .map(|k| {
+            let x = k as f64 / n as f64;
+            2. * PI * PI * (x * x - x + 1. / 6.)
+        })
+        .collect()
+}
+
+/// Squared worst-case error of the rule with `generator` in the weighted Korobov space
+fn squared_error(generator: &[u64], weights: &[f64], omega: &[f64]) -> f64 {
+    let n = omega.len();
+    let sum: f64 = (0..n)
+        .map(|k| {
+            generator.iter().zip(weights)
+                .map(|(&z, &gamma)| {
+                    1. + gamma * omega[(k as u128 * z as u128 % n as u128) as usize]
+                })
+                .product::<f64>()
+        })
+        .sum();
+    sum / n as f64 - 1.
+}
+
+impl Iterator for Lattice {
+    type Item = Vec<f64>;
+
+    fn next(&mut self) -> Option<Vec<f64>> {
+        if self.index == self.points {
+            return None;
+        }
+        let point = self.point(self.index);
+        self.index += 1;
+        Some(point)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = (self.points - self.index) as usize;
+        (left, Some(left))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Vec<f64>> {
+        self.index = self.index.saturating_add(n as u64).min(self.points);
+        self.next()
+    }
+}
+
+impl ExactSizeIterator for Lattice {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{SeedableRng, XorShiftRng};
+
+    #[test]
+    fn empty_generator() {
+        assert!(matches!(Lattice::new(&[], 8), Err(Error::EmptyGenerators)));
+    }
+
+    #[test]
+    fn integrates_trigonometric_polynomials_exactly() {
+        // Fibonacci lattice: the dual lattice has no short vectors, so low frequency
+        // exponentials integrate to zero
+        let lattice = Lattice::new(&[1, 89], 144).unwrap();
+        let tau = 2. * ::std::f64::consts::PI;
+        for &(h1, h2) in [(1., 0.), (0., 3.), (2., 5.), (-3., 4.)].iter() {
+            let sum: f64 = lattice.clone()
+                .map(|p| (tau * (h1 * p[0] + h2 * p[1])).cos())
+                .sum();
+            abs_err_eq!((sum / 144.) == 0. ~ 1e-12, "frequency ({}, {})", h1, h2);
+        }
+    }
+
+    #[test]
+    fn cbc_beats_the_average_vector() {
+        // Every component is at least as good as the average over all candidates
+        let weights = [1., 0.7, 0.5];
+        let omega = kernel(31);
+        let candidates = units(31);
+        let mut total = 0.;
+        for &b in candidates.iter() {
+            for &c in candidates.iter() {
+                total += squared_error(&[1, b, c], &weights, &omega);
+            }
+        }
+        let mean = total / (candidates.len() * candidates.len()) as f64;
+
+        let rule = Lattice::cbc_weighted(&weights, 31).unwrap();
+        assert_eq!(rule.generator()[0], 1);
+        let e = squared_error(rule.generator(), &weights, &omega);
+        assert!(e > 0. && e < mean, "{} against {}", e, mean);
+
+        assert!(matches!(Lattice::cbc(0, 31), Err(Error::EmptyGenerators)));
+        assert!(matches!(Lattice::korobov(0, 31), Err(Error::EmptyGenerators)));
+        let no_points = |r| matches!(r, Err(Error::InvalidParameter { name: "points", .. }));
+        assert!(no_points(Lattice::cbc(3, 0)));
+        assert!(no_points(Lattice::cbc_weighted(&weights, 0)));
+        assert!(no_points(Lattice::korobov(3, 0)));
+        assert!(no_points(Lattice::korobov_weighted(&weights, 0)));
+    }
+
+    #[test]
+    fn korobov_is_best_power_vector() {
+        let rule = Lattice::korobov(4, 101).unwrap();
+        let z = rule.generator();
+        let a = z[1];
+        assert_eq!(z, &[1, a, a * a % 101, a * a * a % 101]);
+
+        let omega = kernel(101);
+        let weights = default_weights(4);
+        let e = squared_error(z, &weights, &omega);
+        for b in 1..101 {
+            let other = [1, b, b * b % 101, b * b * b % 101];
+            assert!(e <= squared_error(&other, &weights, &omega));
+        }
+    }
+
+    #[test]
+    fn good_rules_integrate_smooth_functions() {
+        // Smooth periodic integrand with integral 1, dividing out the Bessel function I₀(1)
+        let f = |p: Vec<f64>| -> f64 {
+            p.iter().map(|&x| (2. * PI * x).cos().exp() / 1.266_065_877_752_008_4).product()
+        };
+        let n = 1009;
+        let error = |rule: Lattice| (rule.map(f).sum::<f64>() / n as f64 - 1.).abs();
+        let naive = error(Lattice::new(&[1, 2, 3, 4], n).unwrap());
+        let cbc = error(Lattice::cbc(4, n).unwrap());
+        let korobov = error(Lattice::korobov(4, n).unwrap());
+        assert!(cbc < 1e-3 && cbc < naive / 10., "{} against {}", cbc, naive);
+        assert!(korobov < 1e-3 && korobov < naive / 10., "{} against {}", korobov, naive);
+    }
+
+    #[test]
+    fn shifts_stay_in_cube() {
+        let mut rng = XorShiftRng::from_seed([9, 8, 7, 6]);
+        let lattice = Lattice::new(&[1, 433, 229], 1021).unwrap().random_shift(&mut rng);
+        assert_eq!(lattice.len(), 1021);
+        for p in lattice {
+            assert!(p.iter().all(|&x| (0. ..1.).contains(&x)), "{:?}", p);
+        }
+    }
+
+    #[test]
+    fn randomized_estimates_are_unbiased() {
+        // Mean of shifted rules estimates the integral of x y z = 1/8 within its standard error
+        let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+        let rule = Lattice::new(&[1, 182, 372], 1021).unwrap();
+        let estimates: Vec<f64> = (0..16)
+            .map(|_| {
+                let shifted = rule.clone().random_shift(&mut rng);
+                shifted.map(|p| p[0] * p[1] * p[2]).sum::<f64>() / 1021.
+            })
+            .collect();
+        let mean = estimates.iter().sum::<f64>() / 16.;
+        let var = estimates.iter().map(|e| (e - mean).powi(2)).sum::<f64>() / 15.;
+        let stderr = (var / 16.).sqrt();
+        assert!(stderr < 2e-3, "standard error {}", stderr);
+        abs_err_eq!(mean == 0.125 ~ (4. * stderr));
+    }
+}
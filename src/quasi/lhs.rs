@@ -0,0 +1,276 @@
+use rand::Rng;
+
+use error::{Error, Result};
+
+/// Latin hypercube design of a fixed number of points
+///
+/// Every axis of the unit cube is cut into `n` bins of equal width and each bin holds exactly
+/// one of the `n` points, so every one dimensional projection of the design is stratified.
+/// The bins of each axis are matched to the points by an independent random permutation and
+/// every point is placed uniformly at random inside its bins.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate tapas;
+/// # use tapas::quasi::LatinHypercube;
+/// # fn main() {
+/// let design = LatinHypercube::new(10, 3, &mut rand::thread_rng()).unwrap();
+///
+/// // Every tenth of every axis holds one point
+/// for j in 0..3 {
+///     let mut bins: Vec<usize> = design.clone().map(|p| (p[j] * 10.) as usize).collect();
+///     bins.sort();
+///     assert_eq!(bins, (0..10).collect::<Vec<usize>>());
+/// }
+/// # }
+/// ```
+///
+/// # References
+/// - McKay, M. D., Beckman, R. J., Conover, W. J., A comparison of three methods for selecting
+///   values of input variables in the analysis of output from a computer code
+#[derive(Debug, Clone)]
+pub struct LatinHypercube {
+    /// Coordinates of every point, one point after another
+    design: Vec<f64>,
+    dimensions: usize,
+    index: usize,
+}
+
+impl LatinHypercube {
+    /// Random design of `n` points in `dimensions` dimensions, drawing from `rng`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyGenerators`] if `dimensions` is zero.
+    ///
+    /// [`Error::EmptyGenerators`]: ../../enum.Error.html#variant.EmptyGenerators
+    pub fn new<R: Rng>(n: usize, dimensions: usize, rng: &mut R) -> Result<LatinHypercube> {
+        if dimensions == 0 {
+            return Err(Error::EmptyGenerators);
+        }
+
+        let mut design = vec![0.; n * dimensions];
+        for j in 0..dimensions {
+            let mut bins: Vec<usize> = (0..n).collect();
+            shuffle(&mut bins, rng);
+            for (i, &b) in bins.iter().enumerate() {
+                design[i * dimensions + j] = (b as f64 + rng.next_f64()) / n as f64;
+            }
+        }
+        Ok(LatinHypercube { design, dimensions, index: 0 })
+    }
+
+    /// Number of dimensions of each point
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    /// Total number of points in the design
+    pub fn points(&self) -> usize {
+        self.design.len() / self.dimensions
+    }
+
+    /// Improve the spread of the design by simulated annealing on the maximin criterion
+    ///
+    /// A random design can place points close together while still filling every bin.  The
+    /// optimizer repeatedly swaps one coordinate between two points, which keeps the design a
+    /// Latin hypercube, and accepts or rejects the swap by simulated annealing on the
+    /// Morris–Mitchell criterion `Σ d_ik^-p` over all pairwise distances, a smooth stand-in for
+    /// the smallest distance.  The best design seen in `iterations` swaps is kept.
+    ///
+    /// Every swap costs time linear in the number of points, on top of a quadratic setup.
+    ///
+    /// ```
+    /// # extern crate rand;
+    /// # extern crate tapas;
+    /// # use tapas::quasi::LatinHypercube;
+    /// use rand::{SeedableRng, StdRng};
+    ///
+    /// # fn main() {
+    /// let mut rng = StdRng::from_seed(&[4][..]);
+    /// let design = LatinHypercube::new(20, 2, &mut rng).unwrap();
+    /// let before = design.min_distance();
+    ///
+    /// let optimized = design.maximin(2000, &mut rng);
+    /// assert!(optimized.min_distance() > before);
+    /// # }
+    /// ```
+    ///
+    /// # References
+    /// - Morris, M. D., Mitchell, T. J., Exploratory designs for computational experiments
+    pub fn maximin<R: Rng>(mut self, iterations: usize, rng: &mut R) -> LatinHypercube {
+        let n = self.points();
+        let d = self.dimensions;
+        if n < 3 {
+            return self;
+        }
+
+        let mut dist = vec![0.; n * n];
+        for a in 0..n {
+            for b in 0..a {
+                let d2 = squared_distance(self.point(a), self.point(b));
+                dist[a * n + b] = d2;
+                dist[b * n + a] = d2;
+            }
+        }
+        // Sum of d^-p over all pairs, with p = 2 * P on squared distances
+        let weight = |d2: f64| d2.powi(-P);
+        let mut phi: f64 = (0..n).flat_map(|a| (0..a).map(move |b| (a, b)))
+            .map(|(a, b)| weight(dist[a * n + b]))
+            .sum();
+
+        let mut best = (phi, self.design.clone());
+        let mut temperature = 0.1 * phi;
+        let cooling = 1e-3f64.powf(1. / iterations.max(1) as f64);
+
+        for _ in 0..iterations {
+            let j = rng.gen_range(0, d);
+            let a = rng.gen_range(0, n);
+            let b = (a + rng.gen_range(1, n)) % n;
+            let (xa, xb) = (self.design[a * d + j], self.design[b * d + j]);
+
+            // Only the distances from a and b to the other points change
+            let mut delta = 0.;
+            for c in (0..n).filter(|&c| c != a && c != b) {
+                let xc = self.design[c * d + j];
+                let (ac, bc) = (dist[a * n + c], dist[b * n + c]);
+                let shift = (xb - xc) * (xb - xc) - (xa - xc) * (xa - xc);
+                delta += weight(ac + shift) + weight(bc - shift) - weight(ac) - weight(bc);
+            }
+
+            if delta < 0. || rng.next_f64() < (-delta / temperature).exp() {
+                for c in (0..n).filter(|&c| c != a && c != b) {
+                    let xc = self.design[c * d + j];
+                    let shift = (xb - xc) * (xb - xc) - (xa - xc) * (xa - xc);
+                    dist[a * n + c] += shift;
+                    dist[c * n + a] += shift;
+                    dist[b * n + c] -= shift;
+                    dist[c * n + b] -= shift;
+                }
+                self.design.swap(a * d + j, b * d + j);
+                phi += delta;
+                if phi < best.0 {
+                    best = (phi, self.design.clone());
+                }
+            }
+            temperature *= cooling;
+        }
+
+        self.design = best.1;
+        self
+    }
+
+    /// Smallest Euclidean distance between two points of the design, infinite for fewer than
+    /// two points
+    pub fn min_distance(&self) -> f64 {
+        let n = self.points();
+        (0..n).flat_map(|a| (0..a).map(move |b| (a, b)))
+            .map(|(a, b)| squared_distance(self.point(a), self.point(b)))
+            .fold(f64::INFINITY, f64::min)
+            .sqrt()
+    }
+
+    /// Point `i` of the design, which need not be the next one
+    ///
+    /// # Panics
+    ///
+    /// If `i` is not below the number of points.
+    pub fn point(&self, i: usize) -> &[f64] {
+        assert!(i < self.points(), "point {} of a {} point design", i, self.points());
+        &self.design[i * self.dimensions..(i + 1) * self.dimensions]
+    }
+}
+
+/// Half the exponent `p` of the Morris–Mitchell criterion, which approaches maximin as `p` grows
+const P: i32 = 10;
+
+fn squared_distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum()
+}
+
+/// Uniformly random permutation of `values` by a Fisher-Yates shuffle
+pub(crate) fn shuffle<T, R: Rng>(values: &mut [T], rng: &mut R) {
+    for i in (1..values.len()).rev() {
+        let j = rng.gen_range(0, i + 1);
+        values.swap(i, j);
+    }
+}
+
+impl Iterator for LatinHypercube {
+    type Item = Vec<f64>;
+
+    fn next(&mut self) -> Option<Vec<f64>> {
+        if self.index == self.points() {
+            return None;
+        }
+        let point = self.point(self.index).to_vec();
+        self.index += 1;
+        Some(point)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.points() - self.index;
+        (left, Some(left))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Vec<f64>> {
+        self.index = self.index.saturating_add(n).min(self.points());
+        self.next()
+    }
+}
+
+impl ExactSizeIterator for LatinHypercube {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{SeedableRng, StdRng};
+
+    #[test]
+    fn no_dimensions() {
+        let mut rng = StdRng::from_seed(&[1][..]);
+        assert!(matches!(LatinHypercube::new(10, 0, &mut rng), Err(Error::EmptyGenerators)));
+        assert_eq!(LatinHypercube::new(0, 2, &mut rng).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn every_bin_once() {
+        let mut rng = StdRng::from_seed(&[2][..]);
+        let design = LatinHypercube::new(50, 4, &mut rng).unwrap();
+        for j in 0..4 {
+            let mut seen = [false; 50];
+            for i in 0..50 {
+                seen[(design.point(i)[j] * 50.) as usize] = true;
+            }
+            assert!(seen.iter().all(|&s| s), "dimension {} misses a bin", j);
+        }
+    }
+
+    #[test]
+    fn maximin_spreads_points() {
+        let mut rng = StdRng::from_seed(&[5][..]);
+        let design = LatinHypercube::new(30, 3, &mut rng).unwrap();
+        let before = design.min_distance();
+        let optimized = design.maximin(5000, &mut rng);
+        assert!(optimized.min_distance() > 1.5 * before);
+
+        // Swaps keep one point per bin
+        for j in 0..3 {
+            let mut bins: Vec<usize> =
+                (0..30).map(|i| (optimized.point(i)[j] * 30.) as usize).collect();
+            bins.sort();
+            assert_eq!(bins, (0..30).collect::<Vec<usize>>());
+        }
+    }
+
+    #[test]
+    fn finite_and_exact() {
+        let mut design = LatinHypercube::new(20, 2, &mut StdRng::from_seed(&[3][..])).unwrap();
+        assert_eq!((design.len(), design.dimensions()), (20, 2));
+        assert_eq!(design.nth(18), Some(design.point(18).to_vec()));
+        assert_eq!(design.by_ref().count(), 1);
+        assert_eq!(design.next(), None);
+    }
+}
@@ -0,0 +1,242 @@
+use rand::Rng;
+
+use super::{Digital, JumpAhead, Niederreiter2, Sobol};
+use super::digital::{bits_to_f32, bits_to_f64};
+use super::gf2::BitMatrix;
+
+/// Matoušek's random linear matrix scrambling of a digital sequence
+///
+/// The binary digits of every coordinate are multiplied by a random nonsingular lower
+/// triangular matrix over GF(2), one matrix per dimension drawn once from the `Rng` given at
+/// construction: digit `k` of the output is digit `k` of the input plus a random combination
+/// of the digits before it.  This replaces the generating matrices `C_j` of a digital net by
+/// `L_j C_j`, which keeps every stratification property of the net.
+///
+/// Linear scrambling costs one matrix-vector product per coordinate regardless of the number
+/// of points, much cheaper than Owen scrambling, and shares its variance for many integrands.
+/// It maps zero to zero; follow it with a [`DigitalShift`] for the full affine scrambling in
+/// which every point is uniformly distributed.  Build one with [`Sobol::linear_scrambled`] or
+/// [`Niederreiter2::linear_scrambled`], seeding the `Rng` to reproduce a scrambling.
+///
+/// Implements [`Rng`] by returning the coordinates of consecutive points one after another, and
+/// [`Iterator`] by returning whole points.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate tapas;
+/// # use tapas::quasi::{DigitalShift, Sobol};
+/// use rand::{Rng, SeedableRng, StdRng};
+///
+/// # fn main() {
+/// let mut rng = StdRng::from_seed(&[42][..]);
+/// let mut points = Sobol::new(2).unwrap().linear_scrambled(&mut rng);
+///
+/// // The origin stays put and the next point is still in the upper right quadrant
+/// assert_eq!(points.next(), Some(vec![0., 0.]));
+/// let p = points.next().unwrap();
+/// assert!(p[0] >= 0.5 && p[1] >= 0.5);
+///
+/// // Adding a digital shift randomizes every point
+/// let scrambled = Sobol::new(2).unwrap().linear_scrambled(&mut rng);
+/// let mut affine = DigitalShift::new(scrambled, 2, &mut rng);
+/// assert!(affine.next_f64() != 0.);
+/// # }
+/// ```
+///
+/// # References
+/// - Matoušek, J., On the L2-discrepancy for anchored boxes
+/// - Owen, A. B., Variance with alternative scramblings of digital nets
+///
+/// [`DigitalShift`]: ../digital/struct.DigitalShift.html
+/// [`Sobol::linear_scrambled`]: ../sobol/struct.Sobol.html#method.linear_scrambled
+/// [`Niederreiter2::linear_scrambled`]:
+///     ../niederreiter/struct.Niederreiter2.html#method.linear_scrambled
+/// [`Rng`]: ../../../rand/trait.Rng.html
+/// [`Iterator`]: https://doc.rust-lang.org/std/iter/trait.Iterator.html
+#[derive(Debug, Clone)]
+pub struct LinearScramble<D> {
+    inner: D,
+    matrices: Vec<BitMatrix>,
+    /// Dimension of the next value
+    current: usize,
+}
+
+impl<D: Digital> LinearScramble<D> {
+    /// Scramble the values of `inner`, a sequence of `dimensions` dimensional points
+    ///
+    /// # Panics
+    ///
+    /// If `dimensions` is zero.
+    pub fn new<G: Rng>(inner: D, dimensions: usize, rng: &mut G) -> LinearScramble<D> {
+        assert!(dimensions > 0, "points need at least one dimension");
+        let matrices = (0..dimensions)
+            .map(|_| {
+                // Column c is bit c of the input, which feeds itself and the less significant
+                // digits below it
+                let columns = (0..64).map(|c| 1 << c | rng.next_u64() & ((1 << c) - 1)).collect();
+                BitMatrix::from_columns(64, columns)
+            })
+            .collect();
+        LinearScramble { inner, matrices, current: 0 }
+    }
+
+    /// Number of dimensions of each point
+    pub fn dimensions(&self) -> usize {
+        self.matrices.len()
+    }
+
+    /// Scrambling matrix of every dimension, acting on bit `63 - k` for digit `k + 1`
+    pub fn matrices(&self) -> &[BitMatrix] {
+        &self.matrices
+    }
+
+    /// Unwrap the scrambled generator
+    pub fn into_inner(self) -> D {
+        self.inner
+    }
+}
+
+impl<D: Digital> Digital for LinearScramble<D> {
+    fn next_bits(&mut self) -> u64 {
+        let bits = self.matrices[self.current].mul_vec(self.inner.next_bits());
+        self.current = (self.current + 1) % self.matrices.len();
+        bits
+    }
+}
+
+impl<D: Digital> Rng for LinearScramble<D> {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_bits() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.next_bits()
+    }
+
+    fn next_f32(&mut self) -> f32 {
+        bits_to_f32(self.next_bits())
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        bits_to_f64(self.next_bits())
+    }
+}
+
+impl<D: JumpAhead> JumpAhead for LinearScramble<D> {
+    fn jump(&mut self, n: u64) {
+        self.inner.jump(n);
+        self.current = ((self.current as u64 + n) % self.matrices.len() as u64) as usize;
+    }
+}
+
+impl<D: Digital> Iterator for LinearScramble<D> {
+    type Item = Vec<f64>;
+
+    /// Remaining coordinates of the current point, or the next whole point
+    fn next(&mut self) -> Option<Vec<f64>> {
+        let n = self.matrices.len() - self.current;
+        Some((0..n).map(|_| self.next_f64()).collect())
+    }
+}
+
+impl Sobol {
+    /// Randomize by linear matrix scrambling with matrices drawn from `rng`
+    ///
+    /// See [`LinearScramble`] for details.
+    ///
+    /// [`LinearScramble`]: ../linear/struct.LinearScramble.html
+    pub fn linear_scrambled<G: Rng>(self, rng: &mut G) -> LinearScramble<Sobol> {
+        let dimensions = self.dimensions();
+        LinearScramble::new(self, dimensions, rng)
+    }
+}
+
+impl Niederreiter2 {
+    /// Randomize by linear matrix scrambling with matrices drawn from `rng`
+    ///
+    /// See [`LinearScramble`] for details.
+    ///
+    /// [`LinearScramble`]: ../linear/struct.LinearScramble.html
+    pub fn linear_scrambled<G: Rng>(self, rng: &mut G) -> LinearScramble<Niederreiter2> {
+        let dimensions = self.dimensions();
+        LinearScramble::new(self, dimensions, rng)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::DigitalShift;
+    use rand::{SeedableRng, StdRng};
+
+    /// Whether the points have one point in each elementary interval of every shape
+    fn is_net(points: &[(u64, u64)]) -> bool {
+        let m = points.len().trailing_zeros();
+        let top = |bits: u64, k: u32| bits.checked_shr(64 - k).unwrap_or(0);
+        (0..=m).all(|k| {
+            let mut boxes = vec![false; points.len()];
+            for &(x, y) in points.iter() {
+                boxes[(top(x, k) << (m - k) | top(y, m - k)) as usize] = true;
+            }
+            boxes.iter().all(|&b| b)
+        })
+    }
+
+    #[test]
+    fn matrices_are_unit_lower_triangular() {
+        let gen = Sobol::new(3).unwrap().linear_scrambled(&mut StdRng::from_seed(&[1][..]));
+        for m in gen.matrices() {
+            assert_eq!(m.rank(), 64);
+            for c in 0..64 {
+                assert!(m.get(c, c));
+                assert_eq!(m.column(c) >> c, 1);
+            }
+        }
+        assert!(gen.matrices()[0] != gen.matrices()[1]);
+    }
+
+    #[test]
+    fn scrambling_keeps_nets() {
+        let mut rng = StdRng::from_seed(&[2][..]);
+        for _ in 0..4 {
+            let mut sobol = Sobol::new(2).unwrap().linear_scrambled(&mut rng);
+            let points: Vec<(u64, u64)> =
+                (0..256).map(|_| (sobol.next_bits(), sobol.next_bits())).collect();
+            assert!(is_net(&points));
+
+            let mut niederreiter = Niederreiter2::new(2).unwrap().linear_scrambled(&mut rng);
+            let points: Vec<(u64, u64)> =
+                (0..256).map(|_| (niederreiter.next_bits(), niederreiter.next_bits())).collect();
+            assert!(is_net(&points));
+        }
+    }
+
+    #[test]
+    fn affine_scrambling_is_unbiased() {
+        // Mean over replicates of the average of x y over 16 points
+        let mut rng = StdRng::from_seed(&[3][..]);
+        let reps = 2000;
+        let mean = (0..reps)
+            .map(|_| {
+                let scrambled = Sobol::new(2).unwrap().linear_scrambled(&mut rng);
+                let mut gen = DigitalShift::new(scrambled, 2, &mut rng);
+                (0..16).map(|_| gen.next_f64() * gen.next_f64()).sum::<f64>() / 16.
+            })
+            .sum::<f64>() / reps as f64;
+        abs_err_eq!(mean == 0.25 ~ 0.002);
+    }
+
+    #[test]
+    fn jump_matches_drawing() {
+        let mut jumped = Sobol::new(3).unwrap().linear_scrambled(&mut StdRng::from_seed(&[4][..]));
+        let mut drawn = jumped.clone();
+        jumped.jump(10);
+        for _ in 0..10 {
+            drawn.next_u64();
+        }
+        assert_eq!(jumped.next_u64(), drawn.next_u64());
+        assert_eq!(jumped.next(), drawn.next());
+    }
+}
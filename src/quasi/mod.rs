@@ -3,9 +3,87 @@
 //! Quasi-random number generators for several different commonly used quasi-random number
 //! sequences:
 //!
-//! - [`Halton`]
+//! - [`BestCandidate`], Mitchell's progressive best-candidate points from any candidate stream
+//! - [`BlueNoiseMask`], tileable void-and-cluster dither masks for per-pixel offsets
+//! - [`ExtensibleLattice`], lattice sequences which can grow from one power of two to the next
+//! - [`GeneralizedHalton`], Halton with permuted digits; [`Halton::scrambled`] offers the
+//!   standard [`Scramble`] permutations built in
+//! - [`Halton`], in one base, or [`HaltonSeq`] over the first primes for whole points
+//! - [`Hammersley`], the finite point set for a known number of points
+//! - [`JitteredGrid`], one random point in every cell of a grid
+//! - [`Kronecker`], the additive recurrence with irrational steps, such as the Richtmyer
+//!   sequence of square roots of primes for very many dimensions
+//! - [`Lattice`], rank-1 lattice rules with optional random shifts, and searches for good
+//!   generating vectors component by component or of Korobov form
+//! - [`LatinHypercube`], random designs stratified along every axis
+//! - [`ModifiedHalton`], Atanassov's Halton with digits multiplied by admissible modifiers
+//! - [`Niederreiter2`], the Niederreiter sequence in base 2
+//! - [`NRooks`], two dimensional samples with one per row and column
+//! - [`OrthogonalArray`], strength 2 designs for discrete factors and their point sets
+//! - [`OwenHalton`], Halton with Owen's nested scrambling for randomized quasi-Monte Carlo
+//! - [`PolynomialLattice`], polynomial lattice rules over GF(2)
+//! - [`Pmj02`], progressive multi-jittered (0,2) sample tables for rendering
+//! - [`Rd`], Roberts' generalized golden ratio sequences such as `R_2`
+//! - [`Sobol`], with the Joe–Kuo direction numbers or custom primitive polynomials, optionally
+//!   Owen scrambled, and looked up one coordinate at a time without state by [`sobol_owen`]
+//! - [`SphericalFibonacci`], near-uniform directions on the unit sphere
+//! - [`VdC`], the one-dimensional van der Corput sequence evaluated directly from its index,
+//!   plain or folded
+//! - [`Zero2Sequence`], scrambled two dimensional Sobol points for sampling pixels
 //!
+//! One-dimensional generators combine into points with [`Zip`], which draws a whole point at a
+//! time, or [`Interleave`], which alternates between them in a single stream of values.
+//!
+//! Sequences with exact binary digits implement [`Digital`] and can be combined with [`Xor`] or
+//! randomized with a [`DigitalShift`] or a [`LinearScramble`].  [`Interlaced`] turns the
+//! dimensions of [`Sobol`] or [`Niederreiter2`] into higher order digital nets.
+//! Digits of any base can be Owen scrambled with an [`OwenTree`], and the [`radical`] module
+//! exposes the plain and permuted radical inverse for building custom constructions.
+//! Generating matrices of binary digital nets are [`BitMatrix`] values of the [`gf2`] module,
+//! and any such matrices define a [`DigitalNet`].  With the `io` feature, nets and Sobol
+//! direction numbers can be read from the tables of other tools.  The [`fixed`] module yields
+//! the points of Halton, Sobol and `R_d` sequences of a dimension known at compile time as
+//! arrays, without allocating.
+//!
+//! [`BestCandidate`]: candidate/struct.BestCandidate.html
+//! [`BlueNoiseMask`]: blue_noise/struct.BlueNoiseMask.html
+//! [`ExtensibleLattice`]: extensible/struct.ExtensibleLattice.html
+//! [`GeneralizedHalton`]: generalized/struct.GeneralizedHalton.html
 //! [`Halton`]: halton/struct.Halton.html
+//! [`HaltonSeq`]: halton/struct.HaltonSeq.html
+//! [`Halton::scrambled`]: halton/struct.Halton.html#method.scrambled
+//! [`Scramble`]: scramble/enum.Scramble.html
+//! [`Hammersley`]: hammersley/struct.Hammersley.html
+//! [`JitteredGrid`]: jittered/struct.JitteredGrid.html
+//! [`Kronecker`]: kronecker/struct.Kronecker.html
+//! [`Lattice`]: lattice/struct.Lattice.html
+//! [`LatinHypercube`]: lhs/struct.LatinHypercube.html
+//! [`ModifiedHalton`]: modified/struct.ModifiedHalton.html
+//! [`Niederreiter2`]: niederreiter/struct.Niederreiter2.html
+//! [`NRooks`]: rooks/struct.NRooks.html
+//! [`OrthogonalArray`]: orthogonal/struct.OrthogonalArray.html
+//! [`OwenHalton`]: owen_halton/struct.OwenHalton.html
+//! [`PolynomialLattice`]: plr/struct.PolynomialLattice.html
+//! [`Pmj02`]: pmj/struct.Pmj02.html
+//! [`Rd`]: roberts/struct.Rd.html
+//! [`Sobol`]: sobol/struct.Sobol.html
+//! [`sobol_owen`]: sobol/fn.sobol_owen.html
+//! [`SphericalFibonacci`]: spherical/struct.SphericalFibonacci.html
+//! [`VdC`]: van_der_corput/struct.VdC.html
+//! [`Zero2Sequence`]: zero_two/struct.Zero2Sequence.html
+//! [`Zip`]: struct.Zip.html
+//! [`Interleave`]: struct.Interleave.html
+//! [`Digital`]: digital/trait.Digital.html
+//! [`Xor`]: digital/struct.Xor.html
+//! [`DigitalShift`]: digital/struct.DigitalShift.html
+//! [`LinearScramble`]: linear/struct.LinearScramble.html
+//! [`Interlaced`]: interlace/struct.Interlaced.html
+//! [`OwenTree`]: owen/struct.OwenTree.html
+//! [`radical`]: radical/index.html
+//! [`BitMatrix`]: gf2/struct.BitMatrix.html
+//! [`gf2`]: gf2/index.html
+//! [`DigitalNet`]: net/struct.DigitalNet.html
+//! [`fixed`]: fixed/index.html
 
 // Ensure implementation is equal to known sequence within machine precision
 #[cfg(test)]
@@ -26,14 +104,15 @@ macro_rules! test_known {
                 $(#[$m])*
                 fn $fn_name() {
                     use rand::Rng;
-                    use std::f64::EPSILON as EPS;
                     let mut sampler = $($init)*;
 
                     let seq = $($seq)*;
 
                     for s in seq.iter() {
                         let sampled: f64 = sampler.gen();
-                        abs_err_eq!(s == sampled ~ EPS, "sampled value {} != {}",sampled,s);
+                        abs_err_eq!(
+                            s == sampled ~ (f64::EPSILON), "sampled value {} != {}",sampled,s
+                        );
                     }
                 }
             )*
@@ -41,12 +120,71 @@ macro_rules! test_known {
     )
 }
 
+pub mod blue_noise;
+pub mod candidate;
+pub mod digital;
+pub mod extensible;
+pub mod fixed;
+pub mod generalized;
+pub mod gf2;
 pub mod halton;
+pub mod hammersley;
+pub mod interlace;
+pub mod jittered;
+pub mod kronecker;
+pub mod lattice;
+pub mod lhs;
+pub mod linear;
+pub mod modified;
+pub mod net;
+pub mod niederreiter;
+pub mod orthogonal;
+pub mod owen;
+pub mod owen_halton;
+pub mod plr;
+pub mod pmj;
+pub mod radical;
+pub mod roberts;
+pub mod rooks;
+pub mod scramble;
+pub mod sobol;
+pub mod spherical;
+pub mod van_der_corput;
+pub mod zero_two;
 
-pub use self::halton::Halton;
+pub use self::blue_noise::BlueNoiseMask;
+pub use self::candidate::BestCandidate;
+pub use self::digital::{Digital, DigitalShift, Dyadic, HashStream, Xor};
+pub use self::extensible::ExtensibleLattice;
+pub use self::generalized::GeneralizedHalton;
+pub use self::halton::{Halton, HaltonBuilder, HaltonSeq};
+pub use self::hammersley::Hammersley;
+pub use self::interlace::Interlaced;
+pub use self::jittered::JitteredGrid;
+pub use self::kronecker::Kronecker;
+pub use self::lattice::Lattice;
+pub use self::lhs::LatinHypercube;
+pub use self::linear::LinearScramble;
+pub use self::modified::ModifiedHalton;
+pub use self::net::DigitalNet;
+pub use self::niederreiter::Niederreiter2;
+pub use self::orthogonal::OrthogonalArray;
+pub use self::owen::OwenTree;
+pub use self::owen_halton::OwenHalton;
+pub use self::plr::PolynomialLattice;
+pub use self::pmj::Pmj02;
+pub use self::roberts::Rd;
+pub use self::rooks::NRooks;
+pub use self::scramble::Scramble;
+pub use self::sobol::{sobol_owen, sobol_owen_table, Sobol};
+pub use self::spherical::SphericalFibonacci;
+pub use self::van_der_corput::{van_der_corput, VdC};
+pub use self::zero_two::Zero2Sequence;
 
 use rand::Rng;
 
+use error::{Error, Result};
+
 /// Interleave different [`Rng`]s
 ///
 /// Enables, for example, mixing of halton generators of different bases.
@@ -54,13 +192,16 @@ use rand::Rng;
 /// ```
 /// # use tapas::quasi::Halton;
 /// # use tapas::quasi::Interleave;
-/// let mut gen = Interleave::new(&[Halton::new(1, 13), Halton::new(1, 17)]);
+/// let mut gen = Interleave::new(&[
+///     Halton::new(1, 13).unwrap(),
+///     Halton::new(1, 17).unwrap(),
+/// ]).unwrap();
 ///
 /// assert!(gen.next_f64() == 1./13. && gen.next_f64() == 1./17.);
 /// ```
 ///
 /// [`Rng`]: /rand.html
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub struct Interleave<R: Rng> {
     generators: Vec<R>,
     current: usize,
@@ -76,7 +217,8 @@ macro_rules! interleave_next {
             next
         }
     };
-    (pub $func:ident, $type:ident) => {
+    ($(#[$attr:meta])* pub $func:ident, $type:ident) => {
+        $(#[$attr])*
         pub fn $func(&mut self) -> $type {
             let next = self.generators[self.current].$func();
 
@@ -89,28 +231,70 @@ macro_rules! interleave_next {
 
 impl<R: Rng> Interleave<R> {
     /// Create a new interleaved generator from a slice of generators
-    pub fn new(generators: &[R]) -> Interleave<R>
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyGenerators`] if `generators` is empty.
+    ///
+    /// [`Error::EmptyGenerators`]: ../enum.Error.html#variant.EmptyGenerators
+    pub fn new(generators: &[R]) -> Result<Interleave<R>>
         where R: Clone {
 
-        debug_assert!(generators.len() > 0, "{} generators provided", generators.len());
+        if generators.is_empty() {
+            return Err(Error::EmptyGenerators);
+        }
 
-        Interleave {
+        Ok(Interleave {
             generators: generators.to_vec(),
             current: 0,
-        }
+        })
+    }
+
+    /// Number of interleaved generators
+    pub fn len(&self) -> usize {
+        self.generators.len()
     }
 
-    /// Grab next u32 value from the current underlying generator
-    interleave_next!(pub next_u32, u32);
+    /// Always `false`, since an interleaved generator holds at least one generator
+    pub fn is_empty(&self) -> bool {
+        self.generators.is_empty()
+    }
 
-    /// Grab next u64 value from the current underlying generator
-    interleave_next!(pub next_u64, u64);
+    interleave_next!(
+        /// Grab next u32 value from the current underlying generator
+        pub next_u32, u32
+    );
 
-    /// Grab next f32 value from the current underlying generator
-    interleave_next!(pub next_f32, f32);
+    interleave_next!(
+        /// Grab next u64 value from the current underlying generator
+        pub next_u64, u64
+    );
 
-    /// Grab next f64 value from the current underlying generator
-    interleave_next!(pub next_f64, f64);
+    interleave_next!(
+        /// Grab next f32 value from the current underlying generator
+        pub next_f32, f32
+    );
+
+    interleave_next!(
+        /// Grab next f64 value from the current underlying generator
+        pub next_f64, f64
+    );
+}
+
+impl<R: Rng + Peek> Interleave<R> {
+    /// Next value of the interleaved stream, without advancing any generator
+    pub fn peek(&self) -> f64 {
+        self.generators[self.current].peek()
+    }
+
+    /// Next value of every underlying generator, in the order they will be drawn
+    ///
+    /// The returned point holds exactly the next `n` values of the interleaved stream, where
+    /// `n` is the number of generators.
+    pub fn peek_point(&self) -> Vec<f64> {
+        let n = self.generators.len();
+        (0..n).map(|k| self.generators[(self.current + k) % n].peek()).collect()
+    }
 }
 
 impl<R: Rng> Rng for Interleave<R> {
@@ -120,14 +304,322 @@ impl<R: Rng> Rng for Interleave<R> {
     interleave_next!(next_f64, f64);
 }
 
+impl<R: Rng + JumpAhead> JumpAhead for Interleave<R> {
+    fn jump(&mut self, n: u64) {
+        let len = self.generators.len() as u64;
+        let (full, extra) = (n / len, (n % len) as usize);
+
+        // The next `extra` generators in turn receive one draw more than the rest
+        for k in 0..self.generators.len() {
+            let offset = (k + self.generators.len() - self.current) % self.generators.len();
+            let draws = if offset < extra { full + 1 } else { full };
+            if draws > 0 {
+                self.generators[k].jump(draws);
+            }
+        }
+
+        self.current = (self.current + extra) % self.generators.len();
+    }
+}
+
+/// Combine different [`Rng`]s into points with one coordinate from each
+///
+/// Every point draws exactly one value from each generator, in order, so unlike an
+/// [`Interleave`] the dimensions of the points can never drift out of step with the
+/// generators.  Points are returned as vectors by the [`Iterator`] implementation, or as
+/// arrays by [`next_array`].
+///
+/// ```
+/// # use tapas::quasi::{Halton, Zip};
+/// let mut gen = Zip::new(&[
+///     Halton::new(1, 2).unwrap(),
+///     Halton::new(1, 3).unwrap(),
+/// ]).unwrap();
+///
+/// assert_eq!(gen.next(), Some(vec![1. / 2., 1. / 3.]));
+/// assert_eq!(gen.next_array(), [1. / 4., 2. / 3.]);
+/// ```
+///
+/// [`Rng`]: /rand.html
+/// [`Interleave`]: struct.Interleave.html
+/// [`Iterator`]: https://doc.rust-lang.org/std/iter/trait.Iterator.html
+/// [`next_array`]: #method.next_array
+#[derive(Debug, Clone)]
+pub struct Zip<R: Rng> {
+    generators: Vec<R>,
+}
+
+impl<R: Rng> Zip<R> {
+    /// Create a zipped generator from a slice of generators, one per dimension
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyGenerators`] if `generators` is empty.
+    ///
+    /// [`Error::EmptyGenerators`]: ../enum.Error.html#variant.EmptyGenerators
+    pub fn new(generators: &[R]) -> Result<Zip<R>>
+        where R: Clone {
+
+        if generators.is_empty() {
+            return Err(Error::EmptyGenerators);
+        }
+        Ok(Zip { generators: generators.to_vec() })
+    }
+
+    /// Number of zipped generators, the dimension of every point
+    pub fn len(&self) -> usize {
+        self.generators.len()
+    }
+
+    /// Always `false`, since a zipped generator holds at least one generator
+    pub fn is_empty(&self) -> bool {
+        self.generators.is_empty()
+    }
+
+    /// Next point as an array, without allocating
+    ///
+    /// # Panics
+    ///
+    /// If `N` is not the number of generators.
+    pub fn next_array<const N: usize>(&mut self) -> [f64; N] {
+        assert_eq!(N, self.generators.len(), "{}-dimensional array for {} generators",
+            N, self.generators.len());
+        let mut point = [0.; N];
+        self.next_into(&mut point);
+        point
+    }
+
+    /// Write the next point into `out`, which has one element per generator
+    pub(crate) fn next_into(&mut self, out: &mut [f64]) {
+        for (x, gen) in out.iter_mut().zip(self.generators.iter_mut()) {
+            *x = gen.next_f64();
+        }
+    }
+
+    /// Unwrap the zipped generators
+    pub fn into_inner(self) -> Vec<R> {
+        self.generators
+    }
+}
+
+impl<R: Rng> Iterator for Zip<R> {
+    type Item = Vec<f64>;
+
+    fn next(&mut self) -> Option<Vec<f64>> {
+        Some(self.generators.iter_mut().map(|gen| gen.next_f64()).collect())
+    }
+}
+
+impl<R: Rng + JumpAhead> JumpAhead for Zip<R> {
+    /// Skip `n` whole points
+    fn jump(&mut self, n: u64) {
+        for gen in self.generators.iter_mut() {
+            gen.jump(n);
+        }
+    }
+}
+
+/// Generators which can report their next value without advancing
+///
+/// Lookahead-based algorithms can inspect upcoming values without cloning the generator.
+/// Implementations compute the value from the current index, leaving the state untouched.
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate tapas;
+/// # use tapas::quasi::{Halton, Peek};
+/// use rand::Rng;
+///
+/// # fn main() {
+/// let mut gen = Halton::new(1, 2).unwrap();
+/// assert_eq!(gen.peek(), 0.5);
+/// assert_eq!(gen.next_f64(), 0.5);
+/// assert_eq!(gen.peek(), 0.25);
+/// # }
+/// ```
+pub trait Peek {
+    /// Next value in `[0, 1)` which `next_f64` will return
+    fn peek(&self) -> f64;
+}
+
+/// Generators which can skip ahead in their output
+///
+/// Quasi-random sequences such as [`Halton`] can usually compute the state at any index
+/// directly, which makes discarding the start of a sequence or splitting it between workers
+/// cheap.  Generators without such structure, like the pseudo-random generators from `rand`,
+/// implement jumps by drawing and discarding values.
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate tapas;
+/// # use tapas::quasi::{Halton, JumpAhead};
+/// use rand::Rng;
+///
+/// # fn main() {
+/// let mut a = Halton::new(1, 3).unwrap();
+/// let mut b = a.clone();
+///
+/// a.jump(1_000_000);
+/// for _ in 0..1_000_000 {
+///     b.next_f64();
+/// }
+///
+/// assert!((a.next_f64() - b.next_f64()).abs() < 1e-12);
+/// # }
+/// ```
+///
+/// [`Halton`]: halton/struct.Halton.html
+pub trait JumpAhead {
+    /// Skip the next `n` draws
+    fn jump(&mut self, n: u64);
+
+    /// Independent copy of the generator positioned `offset` draws ahead
+    ///
+    /// Forks with offsets at least as large as the number of draws each consumer needs never
+    /// overlap, which makes splitting one sequence between parallel tasks straightforward:
+    ///
+    /// ```
+    /// # extern crate rand;
+    /// # extern crate tapas;
+    /// # use tapas::quasi::{Halton, JumpAhead};
+    /// use rand::Rng;
+    ///
+    /// # fn main() {
+    /// let gen = Halton::new(1, 2).unwrap();
+    ///
+    /// // Each task gets its own block of 1000 draws
+    /// let mut tasks: Vec<Halton> = (0..4).map(|t| gen.fork(t * 1000)).collect();
+    ///
+    /// let mut reference = gen.clone();
+    /// reference.jump(3000);
+    /// assert_eq!(tasks[3].next_f64(), reference.next_f64());
+    /// # }
+    /// ```
+    fn fork(&self, offset: u64) -> Self
+        where Self: Clone + Sized {
+
+        let mut fork = self.clone();
+        fork.jump(offset);
+        fork
+    }
+}
+
+impl<J: JumpAhead + ?Sized> JumpAhead for &mut J {
+    fn jump(&mut self, n: u64) {
+        (**self).jump(n)
+    }
+}
+
+macro_rules! discard_jump {
+    ($($rng:ty),*) => {
+        $(
+            impl JumpAhead for $rng {
+                fn jump(&mut self, n: u64) {
+                    for _ in 0..n {
+                        self.next_u32();
+                    }
+                }
+            }
+        )*
+    };
+}
+
+discard_jump!(
+    rand::XorShiftRng, rand::StdRng, rand::IsaacRng, rand::Isaac64Rng, rand::ChaChaRng,
+    rand::ThreadRng
+);
+
 #[cfg(test)]
 mod test {
     use super::*;
 
     #[test]
+    fn empty_interleave() {
+        let empty: &[Halton] = &[];
+        assert!(matches!(Interleave::new(empty), Err(Error::EmptyGenerators)));
+    }
+
+    #[test]
+    fn interleave_peek_point() {
+        let mut gen = Interleave::new(&[Halton::new(1, 2).unwrap(), Halton::new(1, 3).unwrap()])
+            .unwrap();
+        gen.next_f64();
+
+        let point = gen.peek_point();
+        assert_eq!(gen.peek(), point[0]);
+        assert_eq!(point, vec![gen.next_f64(), gen.next_f64()]);
+    }
+
+    #[test]
+    fn fork_leaves_parent_untouched() {
+        let mut parent = Interleave::new(&[Halton::new(1, 2).unwrap(), Halton::new(1, 3).unwrap()])
+            .unwrap();
+        let mut fork = parent.fork(5);
+
+        let mut skipped = parent.clone();
+        assert_eq!(parent.next_f64(), 1. / 2.);
+        for _ in 0..5 {
+            skipped.next_f64();
+        }
+        abs_err_eq!((fork.next_f64()) == (skipped.next_f64()) ~ 1e-15);
+    }
+
+    #[test]
+    fn interleave_jump() {
+        let mut jumped = Interleave::new(&[
+            Halton::new(1, 2).unwrap(),
+            Halton::new(1, 3).unwrap(),
+            Halton::new(1, 5).unwrap(),
+        ]).unwrap();
+        let mut stepped = jumped.clone();
+
+        jumped.next_f64();
+        stepped.next_f64();
+        jumped.jump(7);
+        for _ in 0..7 {
+            stepped.next_f64();
+        }
+
+        for _ in 0..6 {
+            abs_err_eq!((jumped.next_f64()) == (stepped.next_f64()) ~ 1e-15);
+        }
+    }
+
+    #[test]
+    fn zip_yields_whole_points() {
+        let empty: &[Halton] = &[];
+        assert!(matches!(Zip::new(empty), Err(Error::EmptyGenerators)));
+
+        let halton = [Halton::new(1, 2).unwrap(), Halton::new(1, 3).unwrap()];
+        let mut zipped = Zip::new(&halton).unwrap();
+        let mut interleaved = Interleave::new(&halton).unwrap();
+        for _ in 0..20 {
+            let [x, y] = zipped.next_array();
+            assert_eq!((x, y), (interleaved.next_f64(), interleaved.next_f64()));
+            assert_eq!(zipped.next(), Some(vec![interleaved.next_f64(), interleaved.next_f64()]));
+        }
+
+        // Jumps skip whole points
+        let jumped = zipped.fork(9).next().unwrap();
+        let drawn = zipped.nth(9).unwrap();
+        for (a, b) in jumped.into_iter().zip(drawn) {
+            abs_err_eq!(a == b ~ 1e-15);
+        }
+        assert_eq!(zipped.into_inner().len(), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn zip_array_length() {
+        let mut zipped = Zip::new(&[Halton::new(1, 2).unwrap()]).unwrap();
+        let _: [f64; 2] = zipped.next_array();
+    }
+
     test_known! {
         // Ensure that interleaved tests wrap by interleaving known halton 2 and 3 sequences
-        fn interleave_wrap(Interleave::new(&[Halton::new(1,2), Halton::new(1,3)])) {
+        fn interleave_wrap(
+            Interleave::new(&[Halton::new(1,2).unwrap(), Halton::new(1,3).unwrap()]).unwrap()
+        ) {
             [// base 2 | base 3
                 1./2.,   1./3.,
                 1./4.,   2./3.,
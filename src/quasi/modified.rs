@@ -0,0 +1,279 @@
+use rand::Rng;
+
+use error::{Error, Result};
+use primes::{gcd, pow_mod, Primes};
+use super::JumpAhead;
+use super::radical::reverse;
+
+/// Atanassov's modified Halton sequence
+///
+/// Every dimension `j` has a prime base `p_j` and an integer modifier `k_j`.  Coordinate `j` of
+/// point `i` is the radical inverse of `i` in base `p_j`, except that digit `a_r` of weight
+/// `p_j^r` is replaced by `a_r k_j^(r+1) mod p_j`.  Since the multiplier changes with the
+/// position of the digit, neighbouring dimensions in large bases no longer move in lockstep.
+///
+/// Modifiers must be _admissible_: `k_j` is not divisible by `p_j`, and `k_j` together with the
+/// other bases generates the multiplicative group modulo `p_j`.  With admissible modifiers the
+/// star discrepancy of the first `N` points is bounded by a constant times `log(N)^s`, with a
+/// constant that decreases superexponentially in the number of dimensions `s`, a better bound
+/// than the one known for the plain Halton sequence.  A primitive root modulo `p_j` is always
+/// admissible, which is what [`new`] chooses.
+///
+/// The sequence starts at index `0`.  Implements [`Rng`] by returning the coordinates of
+/// consecutive points one after another, and [`Iterator`] by returning whole points.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::ModifiedHalton;
+/// let mut gen = ModifiedHalton::new(2).unwrap();
+/// assert_eq!(gen.modifiers(), &[1, 2]);
+///
+/// gen.next();
+/// // Digit 1 in base 3 is multiplied by 2
+/// assert_eq!(gen.next(), Some(vec![0.5, 2. / 3.]));
+///
+/// // Modifiers may also be chosen by hand
+/// let gen = ModifiedHalton::with_modifiers(&[1, 2, 3]).unwrap();
+/// assert_eq!(gen.bases(), &[2, 3, 5]);
+/// ```
+///
+/// # References
+/// - Atanassov, E. I., On the discrepancy of the Halton sequences
+/// - Atanassov, E. I., Durchova, M., Generating and testing the modified Halton sequences
+///
+/// [`new`]: #method.new
+/// [`Rng`]: ../../../rand/trait.Rng.html
+/// [`Iterator`]: https://doc.rust-lang.org/std/iter/trait.Iterator.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModifiedHalton {
+    bases: Vec<u32>,
+    modifiers: Vec<u32>,
+    /// Index of the point whose coordinates are being returned
+    index: u64,
+    /// Next coordinate of the current point
+    current: usize,
+}
+
+impl ModifiedHalton {
+    /// Sequence over the first `dimensions` primes with the smallest primitive root of each
+    /// base as its modifier
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyGenerators`] if `dimensions` is zero.
+    ///
+    /// [`Error::EmptyGenerators`]: ../../enum.Error.html#variant.EmptyGenerators
+    pub fn new(dimensions: usize) -> Result<ModifiedHalton> {
+        if dimensions == 0 {
+            return Err(Error::EmptyGenerators);
+        }
+        let bases: Vec<u32> = Primes::new().take(dimensions).collect();
+        let modifiers = bases.iter().map(|&p| primitive_root(p)).collect();
+        Ok(ModifiedHalton { bases, modifiers, index: 0, current: 0 })
+    }
+
+    /// Sequence over the first primes, with one dimension per modifier
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyGenerators`] if there are no modifiers and
+    /// [`Error::InvalidModifier`] for the first modifier which is not admissible.
+    ///
+    /// [`Error::EmptyGenerators`]: ../../enum.Error.html#variant.EmptyGenerators
+    /// [`Error::InvalidModifier`]: ../../enum.Error.html#variant.InvalidModifier
+    pub fn with_modifiers(modifiers: &[u32]) -> Result<ModifiedHalton> {
+        if modifiers.is_empty() {
+            return Err(Error::EmptyGenerators);
+        }
+        let bases: Vec<u32> = Primes::new().take(modifiers.len()).collect();
+        for (j, (&base, &modifier)) in bases.iter().zip(modifiers.iter()).enumerate() {
+            if !admissible(&bases, j, modifier) {
+                return Err(Error::InvalidModifier { base, modifier });
+            }
+        }
+        Ok(ModifiedHalton { bases, modifiers: modifiers.to_vec(), index: 0, current: 0 })
+    }
+
+    /// Number of dimensions of each point
+    pub fn dimensions(&self) -> usize {
+        self.bases.len()
+    }
+
+    /// Base of every dimension
+    pub fn bases(&self) -> &[u32] {
+        &self.bases
+    }
+
+    /// Modifier of every dimension
+    pub fn modifiers(&self) -> &[u32] {
+        &self.modifiers
+    }
+}
+
+/// Radical inverse of `index` in `base` with digit `r` multiplied by `modifier^(r+1)`
+fn modified_radical_inverse(base: u32, modifier: u32, index: u64) -> f64 {
+    let b = base as u64;
+    let k = modifier as u64 % b;
+    // Digits are mapped from the least significant one up
+    let mut multiplier = k;
+    reverse(base, index, |d| {
+        let modified = d as u64 * multiplier % b;
+        multiplier = multiplier * k % b;
+        modified as u32
+    })
+}
+
+/// Whether `modifier` is admissible for dimension `j` of the sequence over `bases`
+fn admissible(bases: &[u32], j: usize, modifier: u32) -> bool {
+    let p = bases[j] as u64;
+    if (modifier as u64).is_multiple_of(p) {
+        return false;
+    }
+
+    // The multiplicative group is cyclic, so the generated subgroup has the lcm of the orders
+    let factors = prime_factors(p - 1);
+    let others = bases.iter().enumerate().filter(|&(i, _)| i != j).map(|(_, &b)| b);
+    let mut generated = order(modifier as u64 % p, p, &factors);
+    for b in others {
+        let o = order(b as u64 % p, p, &factors);
+        generated = generated / gcd(generated, o) * o;
+        if generated == p - 1 {
+            break;
+        }
+    }
+    generated == p - 1
+}
+
+/// Smallest primitive root modulo the prime `p`
+fn primitive_root(p: u32) -> u32 {
+    let p = p as u64;
+    let factors = prime_factors(p - 1);
+    (1..p).find(|&g| order(g, p, &factors) == p - 1).unwrap() as u32
+}
+
+/// Multiplicative order of `g` modulo the prime `p`, given the prime factors of `p - 1`
+fn order(g: u64, p: u64, factors: &[u64]) -> u64 {
+    let mut order = p - 1;
+    for &q in factors.iter() {
+        while order.is_multiple_of(q) && pow_mod(g, order / q, p) == 1 {
+            order /= q;
+        }
+    }
+    order
+}
+
+/// Distinct prime factors of `n`
+fn prime_factors(mut n: u64) -> Vec<u64> {
+    let mut factors = Vec::new();
+    let mut q = 2;
+    while q * q <= n {
+        if n.is_multiple_of(q) {
+            factors.push(q);
+            while n.is_multiple_of(q) {
+                n /= q;
+            }
+        }
+        q += 1;
+    }
+    if n > 1 {
+        factors.push(n);
+    }
+    factors
+}
+
+impl Rng for ModifiedHalton {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_f64() * u32::MAX as f64).floor() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        (self.next_f64() * u64::MAX as f64).floor() as u64
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        let j = self.current;
+        let x = modified_radical_inverse(self.bases[j], self.modifiers[j], self.index);
+        self.current += 1;
+        if self.current == self.bases.len() {
+            self.current = 0;
+            self.index = self.index.wrapping_add(1);
+        }
+        x
+    }
+}
+
+impl JumpAhead for ModifiedHalton {
+    /// Skip `n` coordinates, which need not be whole points
+    fn jump(&mut self, n: u64) {
+        let d = self.bases.len() as u64;
+        let target = self.current as u64 + n;
+        self.index = self.index.wrapping_add(target / d);
+        self.current = (target % d) as usize;
+    }
+}
+
+impl Iterator for ModifiedHalton {
+    type Item = Vec<f64>;
+
+    /// Remaining coordinates of the current point, or the next whole point
+    fn next(&mut self) -> Option<Vec<f64>> {
+        let n = self.bases.len() - self.current;
+        Some((0..n).map(|_| self.next_f64()).collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::radical::radical_inverse;
+
+    #[test]
+    fn primitive_roots() {
+        let roots: Vec<u32> = Primes::new().take(9).map(primitive_root).collect();
+        assert_eq!(roots, vec![1, 2, 2, 3, 2, 2, 3, 2, 5]);
+    }
+
+    #[test]
+    fn digits_are_modified_by_position() {
+        // 3 = 10 in base 3: the second digit is multiplied by 2^2 = 1 mod 3
+        assert_eq!(modified_radical_inverse(3, 2, 3), 1. / 9.);
+        // 5 = 12 in base 3: 2 * 2 = 1 and 1 * 4 = 1 mod 3
+        abs_err_eq!((modified_radical_inverse(3, 2, 5)) == (1. / 3. + 1. / 9.) ~ 1e-15);
+        for i in 0..100 {
+            abs_err_eq!((modified_radical_inverse(7, 1, i)) == (radical_inverse(7, i)) ~ 1e-15);
+        }
+    }
+
+    #[test]
+    fn modifiers_keep_strata() {
+        let gen = ModifiedHalton::new(20).unwrap();
+        let b = gen.bases()[19] as usize;
+        let mut strata = vec![false; b * b];
+        for p in gen.take(b * b) {
+            // Every point sits on the left end of its stratum, up to rounding
+            strata[(p[19] * (b * b) as f64).round() as usize] = true;
+        }
+        assert!(strata.iter().all(|&s| s));
+    }
+
+    #[test]
+    fn invalid_modifiers() {
+        assert!(matches!(ModifiedHalton::new(0), Err(Error::EmptyGenerators)));
+        assert!(matches!(ModifiedHalton::with_modifiers(&[]), Err(Error::EmptyGenerators)));
+        assert!(matches!(ModifiedHalton::with_modifiers(&[1, 2, 10]),
+            Err(Error::InvalidModifier { base: 5, modifier: 10 })));
+        assert!(ModifiedHalton::with_modifiers(&[1, 4, 4]).is_ok());
+    }
+
+    #[test]
+    fn jump_matches_drawing() {
+        let mut jumped = ModifiedHalton::new(5).unwrap();
+        let mut drawn = jumped.clone();
+        jumped.jump(37);
+        for _ in 0..37 {
+            drawn.next_f64();
+        }
+        assert_eq!(jumped, drawn);
+    }
+}
@@ -0,0 +1,263 @@
+#[cfg(feature = "io")]
+use std::io::BufRead;
+
+use error::{Error, Result};
+use super::digital::bits_to_f64;
+use super::gf2::BitMatrix;
+
+/// Binary digital net given by its generating matrices
+///
+/// Coordinate `j` of point `i` multiplies the binary digits of `i` by the generating matrix
+/// `C_j` over GF(2): column `c` of `C_j` is added for every set bit `c` of the index, and row
+/// `r` of the product is binary digit `r + 1` of the coordinate.  Matrices of `m` columns give
+/// `2^m` points.  Every binary digital net, such as those of [`Sobol`] or [`Niederreiter2`],
+/// or nets found by search and published in tables such as MinT, can be described this way.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::DigitalNet;
+/// # use tapas::quasi::gf2::BitMatrix;
+/// // The two dimensional Hammersley net of 4 points: reversed identity and identity
+/// let reversed = BitMatrix::from_columns(2, vec![0b10, 0b01]);
+/// let net = DigitalNet::new(vec![reversed, BitMatrix::identity(2)]).unwrap();
+///
+/// let points: Vec<Vec<f64>> = net.collect();
+/// assert_eq!(points, vec![
+///     vec![0., 0.],
+///     vec![0.25, 0.5],
+///     vec![0.5, 0.25],
+///     vec![0.75, 0.75],
+/// ]);
+/// ```
+///
+/// [`Sobol`]: ../sobol/struct.Sobol.html
+/// [`Niederreiter2`]: ../niederreiter/struct.Niederreiter2.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigitalNet {
+    matrices: Vec<BitMatrix>,
+    /// Columns of every matrix as 64 bit binary fractions, with row `r` in bit `63 - r`
+    columns: Vec<Vec<u64>>,
+    index: u64,
+}
+
+impl DigitalNet {
+    /// Net with one generating matrix per dimension
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyGenerators`] if there are no matrices, and
+    /// [`Error::InvalidParameter`] if they do not all have the same number of columns, or have
+    /// more than 63.
+    ///
+    /// [`Error::EmptyGenerators`]: ../../enum.Error.html#variant.EmptyGenerators
+    /// [`Error::InvalidParameter`]: ../../enum.Error.html#variant.InvalidParameter
+    pub fn new(matrices: Vec<BitMatrix>) -> Result<DigitalNet> {
+        let m = match matrices.first() {
+            Some(first) => first.cols(),
+            None => return Err(Error::EmptyGenerators),
+        };
+        let invalid = |reason: String| Err(Error::InvalidParameter { name: "matrices", reason });
+        if m >= 64 {
+            return invalid(format!("{} columns give more than 2^63 points", m));
+        }
+        if let Some(c) = matrices.iter().find(|c| c.cols() != m) {
+            return invalid(format!("matrices of {} and {} columns", m, c.cols()));
+        }
+
+        let columns = matrices.iter()
+            .map(|c| (0..m).map(|k| c.column(k).reverse_bits()).collect())
+            .collect();
+        Ok(DigitalNet { matrices, columns, index: 0 })
+    }
+
+    /// Read a net from a table of generating matrices
+    ///
+    /// Every matrix is written as its rows, one per line, each row a string of `0` and `1`
+    /// digits which may be separated by whitespace; matrices are separated by blank lines.
+    /// This is the plain layout in which MinT and similar tools list generating matrices.
+    /// Everything after a `#` is ignored.
+    ///
+    /// Only available with the `io` feature.
+    ///
+    /// ```
+    /// # use tapas::quasi::DigitalNet;
+    /// let table = "# Hammersley, m = 2\n01\n10\n\n1 0\n0 1\n";
+    /// let net = DigitalNet::read_from(table.as_bytes()).unwrap();
+    /// assert_eq!((net.dimensions(), net.points()), (2, 4));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if reading fails, [`Error::Parse`] for rows which are not binary,
+    /// matrices with more than 64 rows or 63 columns and rows of different lengths, and
+    /// [`Error::EmptyGenerators`] if there are no matrices.
+    ///
+    /// [`Error::Io`]: ../../enum.Error.html#variant.Io
+    /// [`Error::Parse`]: ../../enum.Error.html#variant.Parse
+    /// [`Error::EmptyGenerators`]: ../../enum.Error.html#variant.EmptyGenerators
+    #[cfg(feature = "io")]
+    pub fn read_from<B: BufRead>(input: B) -> Result<DigitalNet> {
+        let mut matrices = Vec::new();
+        let mut rows: Vec<Vec<bool>> = Vec::new();
+        let mut width = None;
+        let mut finish = |rows: &mut Vec<Vec<bool>>| {
+            if !rows.is_empty() {
+                let mut matrix = BitMatrix::zeros(rows.len(), rows[0].len());
+                for (r, row) in rows.iter().enumerate() {
+                    for (c, &bit) in row.iter().enumerate() {
+                        matrix.set(r, c, bit);
+                    }
+                }
+                matrices.push(matrix);
+                rows.clear();
+            }
+        };
+
+        for (n, line) in input.lines().enumerate() {
+            let line = line?;
+            let content = line.split('#').next().unwrap_or("").trim();
+            if content.is_empty() {
+                // Comment lines do not end a matrix, blank lines do
+                if !line.contains('#') {
+                    finish(&mut rows);
+                }
+                continue;
+            }
+
+            let parse = |reason: String| Error::Parse { line: n + 1, reason };
+            let row = content.chars()
+                .filter(|c| !c.is_whitespace())
+                .map(|c| match c {
+                    '0' => Ok(false),
+                    '1' => Ok(true),
+                    _ => Err(parse(format!("expected binary digits, found `{}`", content))),
+                })
+                .collect::<Result<Vec<bool>>>()?;
+            if row.len() > 63 {
+                return Err(parse(format!("row of {} digits, at most 63 columns", row.len())));
+            }
+            let columns = *width.get_or_insert(row.len());
+            if row.len() != columns {
+                return Err(parse(format!("row of {} digits in a table of {} columns",
+                    row.len(), columns)));
+            }
+            if rows.len() == 64 {
+                return Err(parse("matrix with more than 64 rows".to_string()));
+            }
+            rows.push(row);
+        }
+        finish(&mut rows);
+        DigitalNet::new(matrices)
+    }
+
+    /// Number of dimensions of each point
+    pub fn dimensions(&self) -> usize {
+        self.matrices.len()
+    }
+
+    /// Total number of points, `2^m` for matrices of `m` columns
+    pub fn points(&self) -> u64 {
+        1 << self.columns[0].len()
+    }
+
+    /// Generating matrix of every dimension
+    pub fn matrices(&self) -> &[BitMatrix] {
+        &self.matrices
+    }
+
+    /// Point `i` of the net, which need not be the next one
+    ///
+    /// # Panics
+    ///
+    /// If `i` is not below the number of points.
+    pub fn point(&self, i: u64) -> Vec<f64> {
+        assert!(i < self.points(), "point {} of a {} point net", i, self.points());
+        self.columns.iter()
+            .map(|columns| {
+                let x = columns.iter().enumerate()
+                    .filter(|&(c, _)| i >> c & 1 == 1)
+                    .fold(0, |acc, (_, &col)| acc ^ col);
+                bits_to_f64(x)
+            })
+            .collect()
+    }
+}
+
+impl Iterator for DigitalNet {
+    type Item = Vec<f64>;
+
+    fn next(&mut self) -> Option<Vec<f64>> {
+        if self.index == self.points() {
+            return None;
+        }
+        let point = self.point(self.index);
+        self.index += 1;
+        Some(point)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = (self.points() - self.index) as usize;
+        (left, Some(left))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Vec<f64>> {
+        self.index = self.index.saturating_add(n as u64).min(self.points());
+        self.next()
+    }
+}
+
+impl ExactSizeIterator for DigitalNet {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::Sobol;
+
+    #[test]
+    fn matches_sobol() {
+        // Upper triangular Pascal matrix mod 2 generates the second Sobol dimension
+        let mut pascal = BitMatrix::zeros(8, 8);
+        for c in 0..8 {
+            for r in 0..=c {
+                let binomial = (c & r) == r;
+                pascal.set(r, c, binomial);
+            }
+        }
+        let net = DigitalNet::new(vec![BitMatrix::identity(8), pascal]).unwrap();
+        assert_eq!(net.len(), 256);
+        let sobol: Vec<Vec<f64>> = Sobol::new(2).unwrap().take(256).collect();
+        assert_eq!(net.collect::<Vec<Vec<f64>>>(), sobol);
+    }
+
+    #[test]
+    fn shapes() {
+        assert!(matches!(DigitalNet::new(vec![]), Err(Error::EmptyGenerators)));
+        let net = DigitalNet::new(vec![BitMatrix::zeros(3, 0)]).unwrap();
+        assert_eq!(net.collect::<Vec<Vec<f64>>>(), vec![vec![0.]]);
+    }
+
+    #[test]
+    fn mismatched_columns() {
+        let net = DigitalNet::new(vec![BitMatrix::identity(2), BitMatrix::identity(3)]);
+        assert!(matches!(net, Err(Error::InvalidParameter { name: "matrices", .. })));
+        let wide = DigitalNet::new(vec![BitMatrix::zeros(2, 64)]);
+        assert!(matches!(wide, Err(Error::InvalidParameter { name: "matrices", .. })));
+    }
+
+    #[cfg(feature = "io")]
+    #[test]
+    fn reads_tables() {
+        let table = "# two matrices\n1 0 0\n0 1 0\n# middle comment\n0 0 1\n\n\n111\n011\n";
+        let net = DigitalNet::read_from(table.as_bytes()).unwrap();
+        assert_eq!(net.matrices()[0], BitMatrix::identity(3));
+        assert_eq!(net.matrices()[1], BitMatrix::from_columns(2, vec![0b01, 0b11, 0b11]));
+
+        let bad = DigitalNet::read_from("10\n01\n\n1x\n".as_bytes());
+        assert!(matches!(bad, Err(Error::Parse { line: 4, .. })));
+        let ragged = DigitalNet::read_from("10\n011\n".as_bytes());
+        assert!(matches!(ragged, Err(Error::Parse { line: 2, .. })));
+        assert!(matches!(DigitalNet::read_from("# nothing".as_bytes()),
+            Err(Error::EmptyGenerators)));
+    }
+}
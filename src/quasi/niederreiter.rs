@@ -0,0 +1,294 @@
+use rand::Rng;
+
+use error::{Error, Result};
+use super::{Digital, JumpAhead};
+use super::gf2::degree;
+
+/// Number of bits of every coordinate
+const BITS: usize = 32;
+
+/// Largest number of dimensions of [`Niederreiter2`], one per irreducible polynomial over GF(2)
+/// of degree at most 16
+///
+/// [`Niederreiter2`]: struct.Niederreiter2.html
+pub const MAX_DIMENSIONS: usize = 8800;
+
+/// Niederreiter sequence in base 2
+///
+/// Dimension `i` is generated by the `i`-th irreducible polynomial `p` over GF(2) in increasing
+/// order, starting from `x`, `x + 1` and `x² + x + 1`.  For a polynomial of degree `e`, row
+/// `j = Q e + u` of the generating matrix holds the Laurent coefficients of `1 / p(x)^(Q + 1)`
+/// from the `u`-th on, the classical construction of Niederreiter as implemented by Bratley,
+/// Fox and Niederreiter.  The first dimension is the van der Corput sequence.
+///
+/// Points are generated in Gray code order, so advancing to the next point changes a single
+/// column per coordinate and costs one XOR per dimension.  Every prefix of `2^m` points is
+/// still a digital net, and the first point is the origin.
+///
+/// Implements [`Rng`] by returning the coordinates of consecutive points one after another, and
+/// [`Iterator`] by returning whole points.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::Niederreiter2;
+/// let mut gen = Niederreiter2::new(2).unwrap();
+/// let points: Vec<Vec<f64>> = gen.by_ref().take(4).collect();
+///
+/// // One point in each quarter of both axes
+/// for j in 0..2 {
+///     let mut quarters: Vec<usize> = points.iter().map(|p| (p[j] * 4.) as usize).collect();
+///     quarters.sort();
+///     assert_eq!(quarters, vec![0, 1, 2, 3]);
+/// }
+/// ```
+///
+/// # References
+/// - Niederreiter, H., Low-discrepancy and low-dispersion sequences
+/// - Bratley, P., Fox, B. L., Niederreiter, H., Implementation and tests of low-discrepancy
+///   sequences
+///
+/// [`Rng`]: ../../../rand/trait.Rng.html
+/// [`Iterator`]: https://doc.rust-lang.org/std/iter/trait.Iterator.html
+#[derive(Debug, Clone)]
+pub struct Niederreiter2 {
+    /// Generating matrix columns of every dimension, as 32 bit binary fractions
+    columns: Vec<[u32; BITS]>,
+    /// Coordinates of the current point
+    point: Vec<u32>,
+    /// Index of the current point in Gray code order
+    index: u32,
+    /// Next coordinate of the current point
+    current: usize,
+}
+
+impl Niederreiter2 {
+    /// Niederreiter sequence in `dimensions` dimensions
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyGenerators`] for zero dimensions and [`Error::DimensionOverflow`]
+    /// for more than [`MAX_DIMENSIONS`].
+    ///
+    /// [`Error::EmptyGenerators`]: ../../enum.Error.html#variant.EmptyGenerators
+    /// [`Error::DimensionOverflow`]: ../../enum.Error.html#variant.DimensionOverflow
+    /// [`MAX_DIMENSIONS`]: constant.MAX_DIMENSIONS.html
+    pub fn new(dimensions: usize) -> Result<Niederreiter2> {
+        if dimensions > MAX_DIMENSIONS {
+            return Err(Error::DimensionOverflow {
+                requested: dimensions,
+                supported: MAX_DIMENSIONS,
+            });
+        }
+        if dimensions == 0 {
+            return Err(Error::EmptyGenerators);
+        }
+
+        let columns = irreducible_polynomials().take(dimensions).map(columns).collect();
+        Ok(Niederreiter2 { columns, point: vec![0; dimensions], index: 0, current: 0 })
+    }
+
+    /// Number of dimensions of each point
+    pub fn dimensions(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// Move to the next point in Gray code order
+    #[inline]
+    fn advance(&mut self) {
+        self.index = self.index.wrapping_add(1);
+        let bit = self.index.trailing_zeros() as usize % BITS;
+        for (x, c) in self.point.iter_mut().zip(self.columns.iter()) {
+            *x ^= c[bit];
+        }
+    }
+
+    /// Next coordinate as a 32 bit binary fraction
+    fn next_coordinate(&mut self) -> u32 {
+        let x = self.point[self.current];
+        self.current += 1;
+        if self.current == self.point.len() {
+            self.current = 0;
+            self.advance();
+        }
+        x
+    }
+}
+
+/// Irreducible polynomials over GF(2) of degree at most 16, in increasing order
+fn irreducible_polynomials() -> impl Iterator<Item = u64> {
+    let mut found: Vec<u64> = Vec::new();
+    (2u64..1 << 17).filter(move |&p| {
+        let d = degree(p);
+        let irreducible = found.iter()
+            .take_while(|&&q| 2 * degree(q) <= d)
+            .all(|&q| rem(p, q) != 0);
+        if irreducible && 2 * d <= 16 {
+            found.push(p);
+        }
+        irreducible
+    })
+}
+
+/// Remainder of `a` divided by `b` over GF(2)
+fn rem(mut a: u64, b: u64) -> u64 {
+    let db = degree(b);
+    while a != 0 && degree(a) >= db {
+        a ^= b << (degree(a) - db);
+    }
+    a
+}
+
+/// Product of `a` and `b` over GF(2), for products of degree below 64
+fn mul(a: u64, b: u64) -> u64 {
+    (0..64).filter(|&k| b >> k & 1 == 1).fold(0, |acc, k| acc ^ (a << k))
+}
+
+/// Generating matrix columns of the dimension with irreducible polynomial `p`
+fn columns(p: u64) -> [u32; BITS] {
+    let e = degree(p) as usize;
+    let mut columns = [0; BITS];
+    let mut q = 1;
+    let mut laurent = Vec::new();
+    for j in 0..BITS {
+        let u = j % e;
+        if u == 0 {
+            q = mul(q, p);
+            laurent = inverse_series(q, BITS + e);
+        }
+        for (r, column) in columns.iter_mut().enumerate() {
+            if laurent[u + r] {
+                *column |= 1 << (BITS - 1 - j);
+            }
+        }
+    }
+    columns
+}
+
+/// First `n` coefficients of `1 / q`, of `x^-1`, `x^-2`, ... in order
+fn inverse_series(q: u64, n: usize) -> Vec<bool> {
+    // The coefficient of x^-(k + 1) is the leading coefficient of x^k mod q
+    let d = degree(q);
+    let mut r = 1u64;
+    (0..n).map(|_| {
+        let a = r >> (d - 1) & 1 == 1;
+        r <<= 1;
+        if r >> d & 1 == 1 {
+            r ^= q;
+        }
+        a
+    }).collect()
+}
+
+impl Digital for Niederreiter2 {
+    fn next_bits(&mut self) -> u64 {
+        (self.next_coordinate() as u64) << 32
+    }
+}
+
+impl Rng for Niederreiter2 {
+    fn next_u32(&mut self) -> u32 {
+        self.next_coordinate()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.next_bits()
+    }
+
+    fn next_f32(&mut self) -> f32 {
+        (self.next_coordinate() >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        self.next_coordinate() as f64 / 4_294_967_296.
+    }
+}
+
+impl JumpAhead for Niederreiter2 {
+    /// Skip `n` coordinates, which need not be whole points
+    fn jump(&mut self, n: u64) {
+        let d = self.columns.len() as u64;
+        let target = self.current as u64 + n;
+        self.index = self.index.wrapping_add((target / d) as u32);
+        self.current = (target % d) as usize;
+
+        // Recompute the point from the Gray code of its index
+        let gray = self.index ^ (self.index >> 1);
+        for (x, c) in self.point.iter_mut().zip(self.columns.iter()) {
+            *x = (0..BITS).filter(|&k| gray >> k & 1 == 1).fold(0, |acc, k| acc ^ c[k]);
+        }
+    }
+}
+
+impl Iterator for Niederreiter2 {
+    type Item = Vec<f64>;
+
+    /// Remaining coordinates of the current point, or the next whole point
+    fn next(&mut self) -> Option<Vec<f64>> {
+        let n = self.columns.len() - self.current;
+        Some((0..n).map(|_| self.next_f64()).collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::gf2::BitMatrix;
+
+    #[test]
+    fn first_irreducible_polynomials() {
+        let polys: Vec<u64> = irreducible_polynomials().take(8).collect();
+        assert_eq!(polys, vec![0b10, 0b11, 0b111, 0b1011, 0b1101, 0b10011, 0b11001, 0b11111]);
+        assert_eq!(irreducible_polynomials().count(), MAX_DIMENSIONS);
+    }
+
+    #[test]
+    fn van_der_corput_first() {
+        let gen = Niederreiter2::new(1).unwrap();
+        let mut values: Vec<f64> = gen.take(8).map(|p| p[0]).collect();
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let expected: Vec<f64> = (0..8).map(|i| i as f64 / 8.).collect();
+        assert_eq!(values, expected);
+    }
+
+    #[test]
+    fn nonsingular_matrices() {
+        // Leading blocks of whole multiples of the degree are nonsingular
+        let gen = Niederreiter2::new(50).unwrap();
+        for (c, p) in gen.columns.iter().zip(irreducible_polynomials()) {
+            let e = degree(p) as usize;
+            let n = BITS / e * e;
+            let m = BitMatrix::from_columns(n, c[..n].iter().map(|&x| (x >> (BITS - n)) as u64)
+                .collect());
+            assert_eq!(m.rank(), n);
+        }
+    }
+
+    #[test]
+    fn two_dimensional_net() {
+        // The first two dimensions have t = 0: 2^m points fill every elementary box of
+        // volume 2^-m exactly once
+        let points: Vec<Vec<f64>> = Niederreiter2::new(2).unwrap().take(64).collect();
+        for k in 0..=6 {
+            let (a, b) = (1 << k, 1 << (6 - k));
+            let mut boxes = [false; 64];
+            for p in points.iter() {
+                boxes[(p[0] * a as f64) as usize * b + (p[1] * b as f64) as usize] = true;
+            }
+            assert!(boxes.iter().all(|&x| x), "boxes of {}×{} are not filled", a, b);
+        }
+    }
+
+    #[test]
+    fn jump_matches_drawing() {
+        let mut jumped = Niederreiter2::new(5).unwrap();
+        let mut drawn = jumped.clone();
+        jumped.jump(1003);
+        for _ in 0..1003 {
+            drawn.next_u32();
+        }
+        for _ in 0..20 {
+            assert_eq!(jumped.next_u32(), drawn.next_u32());
+        }
+    }
+}
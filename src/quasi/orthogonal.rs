@@ -0,0 +1,218 @@
+use rand::Rng;
+
+use error::{Error, Result};
+use primes::is_prime;
+use super::lhs::shuffle;
+
+/// Orthogonal array of strength 2 from the construction of Bose
+///
+/// An orthogonal array `OA(q², k, q, 2)` has `q²` runs of `k` factors with `q` levels each,
+/// such that any two factors take every pair of levels in exactly one run.  For a prime `q`,
+/// run `(a, b)` sets the first factor to `b` and factor `j + 1` to `a + j b mod q`, giving up
+/// to `q + 1` factors.
+///
+/// Such designs screen the main effects of many discrete factors in few runs, and as point
+/// sets in the unit cube they stratify every two dimensional projection into a `q × q` grid.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::OrthogonalArray;
+/// let oa = OrthogonalArray::bose(3, 4).unwrap();
+/// assert_eq!(oa.runs(), 9);
+///
+/// // Factors 1 and 3 take every pair of levels once
+/// let mut pairs: Vec<(u32, u32)> = (0..9).map(|i| (oa.run(i)[1], oa.run(i)[3])).collect();
+/// pairs.sort();
+/// pairs.dedup();
+/// assert_eq!(pairs.len(), 9);
+/// ```
+///
+/// # References
+/// - Bose, R. C., Bush, K. A., Orthogonal arrays of strength two and three
+/// - Owen, A. B., Orthogonal arrays for computer experiments, integration and visualization
+/// - Tang, B., Orthogonal array-based Latin hypercubes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrthogonalArray {
+    levels: u32,
+    factors: usize,
+    /// Levels of every run, one run after another
+    array: Vec<u32>,
+}
+
+impl OrthogonalArray {
+    /// Array of `q²` runs of `factors` factors with `q` levels, for a prime `q`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidBase`] if `q` is not prime, [`Error::EmptyGenerators`] if there
+    /// are no factors, [`Error::DimensionOverflow`] for more than `q + 1` factors and
+    /// [`Error::InvalidParameter`] if the levels of all runs would not fit in memory.
+    ///
+    /// [`Error::InvalidBase`]: ../../enum.Error.html#variant.InvalidBase
+    /// [`Error::EmptyGenerators`]: ../../enum.Error.html#variant.EmptyGenerators
+    /// [`Error::DimensionOverflow`]: ../../enum.Error.html#variant.DimensionOverflow
+    /// [`Error::InvalidParameter`]: ../../enum.Error.html#variant.InvalidParameter
+    pub fn bose(q: u32, factors: usize) -> Result<OrthogonalArray> {
+        if !is_prime(q as u64) {
+            return Err(Error::InvalidBase(q));
+        }
+        if factors == 0 {
+            return Err(Error::EmptyGenerators);
+        }
+        if factors > q as usize + 1 {
+            return Err(Error::DimensionOverflow { requested: factors, supported: q as usize + 1 });
+        }
+
+        let len = (q as usize).checked_mul(q as usize).and_then(|runs| runs.checked_mul(factors));
+        let len = match len {
+            Some(len) => len,
+            None => return Err(Error::InvalidParameter {
+                name: "levels",
+                reason: format!("{}² runs of {} factors overflow the address space", q, factors),
+            }),
+        };
+
+        let q64 = q as u64;
+        let mut array = Vec::with_capacity(len);
+        for a in 0..q64 {
+            for b in 0..q64 {
+                array.push(b as u32);
+                array.extend((0..factors as u64 - 1).map(|j| ((a + j * b) % q64) as u32));
+            }
+        }
+        Ok(OrthogonalArray { levels: q, factors, array })
+    }
+
+    /// Number of levels of every factor
+    pub fn levels(&self) -> u32 {
+        self.levels
+    }
+
+    /// Number of factors of every run
+    pub fn factors(&self) -> usize {
+        self.factors
+    }
+
+    /// Number of runs, the square of the number of levels
+    pub fn runs(&self) -> usize {
+        self.array.len() / self.factors
+    }
+
+    /// Levels of the factors of run `i`
+    ///
+    /// # Panics
+    ///
+    /// If `i` is not below the number of runs.
+    pub fn run(&self, i: usize) -> &[u32] {
+        &self.array[i * self.factors..(i + 1) * self.factors]
+    }
+
+    /// Randomized points in the unit cube, one per run
+    ///
+    /// The levels of every factor are relabelled by an independent random permutation and
+    /// every point is placed uniformly at random in the cell of its levels, which makes each
+    /// point uniformly distributed while every two dimensional projection keeps one point per
+    /// cell of the `q × q` grid.
+    pub fn points<R: Rng>(&self, rng: &mut R) -> Vec<Vec<f64>> {
+        let q = self.levels as f64;
+        let relabel: Vec<Vec<u32>> = (0..self.factors)
+            .map(|_| {
+                let mut perm: Vec<u32> = (0..self.levels).collect();
+                shuffle(&mut perm, rng);
+                perm
+            })
+            .collect();
+
+        (0..self.runs())
+            .map(|i| {
+                self.run(i).iter().zip(relabel.iter())
+                    .map(|(&level, perm)| (perm[level as usize] as f64 + rng.next_f64()) / q)
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Points forming a Latin hypercube which keeps the strata of the array
+    ///
+    /// Within every factor, the `q` runs sharing a level are spread over the `q` sub-bins of
+    /// that level in random order, so every axis is cut into `q²` bins holding one point each,
+    /// while every two dimensional projection still has one point per cell of the `q × q`
+    /// grid.
+    pub fn latin_hypercube<R: Rng>(&self, rng: &mut R) -> Vec<Vec<f64>> {
+        let n = self.runs();
+        let q = self.levels as usize;
+        let mut points = vec![vec![0.; self.factors]; n];
+        for j in 0..self.factors {
+            let mut runs: Vec<Vec<usize>> = vec![Vec::with_capacity(q); q];
+            for i in 0..n {
+                runs[self.run(i)[j] as usize].push(i);
+            }
+            for (level, same) in runs.iter_mut().enumerate() {
+                shuffle(same, rng);
+                for (k, &i) in same.iter().enumerate() {
+                    points[i][j] = ((level * q + k) as f64 + rng.next_f64()) / n as f64;
+                }
+            }
+        }
+        points
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{SeedableRng, StdRng};
+
+    fn pairs_covered(points: &[Vec<f64>], q: usize) -> bool {
+        let k = points[0].len();
+        (0..k).all(|a| (0..a).all(|b| {
+            let mut cells = vec![false; q * q];
+            for p in points.iter() {
+                cells[(p[a] * q as f64) as usize * q + (p[b] * q as f64) as usize] = true;
+            }
+            cells.iter().all(|&c| c)
+        }))
+    }
+
+    #[test]
+    fn invalid_arrays() {
+        assert!(matches!(OrthogonalArray::bose(4, 2), Err(Error::InvalidBase(4))));
+        assert!(matches!(OrthogonalArray::bose(5, 0), Err(Error::EmptyGenerators)));
+        assert!(matches!(OrthogonalArray::bose(5, 7),
+            Err(Error::DimensionOverflow { requested: 7, supported: 6 })));
+        // Two factors of the largest prime below 2^32 hold more than 2^64 levels
+        assert!(matches!(OrthogonalArray::bose(4_294_967_291, 2),
+            Err(Error::InvalidParameter { name: "levels", .. })));
+    }
+
+    #[test]
+    fn strength_two() {
+        let oa = OrthogonalArray::bose(7, 8).unwrap();
+        assert_eq!((oa.runs(), oa.factors(), oa.levels()), (49, 8, 7));
+        for a in 0..8 {
+            for b in 0..a {
+                let mut seen = [false; 49];
+                for i in 0..49 {
+                    seen[(oa.run(i)[a] * 7 + oa.run(i)[b]) as usize] = true;
+                }
+                assert!(seen.iter().all(|&s| s), "factors {} and {}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn point_sets_keep_strata() {
+        let mut rng = StdRng::from_seed(&[6][..]);
+        let oa = OrthogonalArray::bose(5, 6).unwrap();
+        assert!(pairs_covered(&oa.points(&mut rng), 5));
+
+        let lhs = oa.latin_hypercube(&mut rng);
+        assert!(pairs_covered(&lhs, 5));
+        for j in 0..6 {
+            let mut bins: Vec<usize> = lhs.iter().map(|p| (p[j] * 25.) as usize).collect();
+            bins.sort();
+            assert_eq!(bins, (0..25).collect::<Vec<usize>>());
+        }
+    }
+}
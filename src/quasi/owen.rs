@@ -0,0 +1,253 @@
+use error::{Error, Result};
+use super::digital::mix64;
+
+/// Lazily evaluated Owen scrambling tree
+///
+/// Owen scrambling applies a random permutation to every digit of a point, where the
+/// permutation of digit `k` depends on all `k - 1` digits before it.  The full tree of
+/// permutations grows exponentially with the depth, so instead of storing it each node's
+/// permutation is derived on demand from a hash of the seed and the path of digits leading to
+/// the node.  Scrambling is deterministic for a given seed and needs constant memory at any
+/// depth.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::OwenTree;
+/// let tree = OwenTree::new(3, 42).unwrap();
+///
+/// // The scrambled radical inverse of the first 9 indices still hits every ninth once
+/// let mut strata = [false; 9];
+/// for i in 0..9 {
+///     strata[(tree.radical_inverse(i) * 9.) as usize] = true;
+/// }
+/// assert!(strata.iter().all(|&s| s));
+/// ```
+///
+/// # References
+/// - Owen, A. B., Randomly permuted (t,m,s)-nets and (t,s)-sequences
+/// - Burley, B., Practical Hash-based Owen Scrambling
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OwenTree {
+    base: u32,
+    seed: u64,
+}
+
+/// Multiplier separating the contributions of successive digits to a node hash
+const GOLDEN: u64 = 0x9e37_79b9_7f4a_7c15;
+
+impl OwenTree {
+    /// Scrambling tree for digits in `base` with the given `seed`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidBase`] if `base < 2`.
+    ///
+    /// [`Error::InvalidBase`]: ../enum.Error.html#variant.InvalidBase
+    pub fn new(base: u32, seed: u64) -> Result<OwenTree> {
+        if base < 2 {
+            return Err(Error::InvalidBase(base));
+        }
+        Ok(OwenTree { base, seed })
+    }
+
+    /// Base of the scrambled digits
+    pub fn base(&self) -> u32 {
+        self.base
+    }
+
+    /// Number of digits needed to resolve every bit of an `f64` in `[0, 1)`
+    pub fn depth(&self) -> usize {
+        (53. / (self.base as f64).log2()).ceil() as usize
+    }
+
+    /// Hash of the root node
+    #[inline]
+    fn root(&self) -> u64 {
+        mix64(self.seed ^ (self.base as u64).wrapping_mul(GOLDEN))
+    }
+
+    /// Hash of the child of `node` reached through `digit`
+    #[inline]
+    fn child(node: u64, digit: u32) -> u64 {
+        mix64(node ^ (digit as u64 + 1).wrapping_mul(GOLDEN))
+    }
+
+    /// Apply the permutation of the node with hash `node` to `digit`
+    fn permute(&self, node: u64, digit: u32) -> u32 {
+        if self.base == 2 {
+            return digit ^ (node >> 63) as u32;
+        }
+
+        // Fisher-Yates shuffle driven by successive hashes of the node
+        let b = self.base as usize;
+        let mut perm: Vec<u32> = (0..self.base).collect();
+        let mut h = node;
+        for i in (1..b).rev() {
+            h = mix64(h.wrapping_add(GOLDEN));
+            let j = (((h >> 32) * (i as u64 + 1)) >> 32) as usize;
+            perm.swap(i, j);
+        }
+        perm[digit as usize]
+    }
+
+    /// Scramble `digits`, most significant first, padding with zeros to `depth` digits
+    ///
+    /// Every digit is permuted by the node reached through the original digits before it.
+    ///
+    /// # Panics
+    ///
+    /// If a digit is not below the base.
+    pub fn scramble_digits(&self, digits: &[u32], depth: usize) -> Vec<u32> {
+        let mut node = self.root();
+        (0..depth.max(digits.len()))
+            .map(|k| {
+                let d = digits.get(k).cloned().unwrap_or(0);
+                assert!(d < self.base, "digit {} out of range for base {}", d, self.base);
+                let scrambled = self.permute(node, d);
+                node = OwenTree::child(node, d);
+                scrambled
+            })
+            .collect()
+    }
+
+    /// Owen scrambled 64 bit binary fraction, for base 2 trees
+    ///
+    /// # Panics
+    ///
+    /// If the tree is not base 2.
+    pub fn scramble_bits(&self, bits: u64) -> u64 {
+        assert_eq!(self.base, 2, "bit scrambling needs a base 2 tree");
+
+        let mut node = self.root();
+        let mut out = 0;
+        for k in (0..64).rev() {
+            let d = ((bits >> k) & 1) as u32;
+            out |= (self.permute(node, d) as u64) << k;
+            node = OwenTree::child(node, d);
+        }
+        out
+    }
+
+    /// Owen scrambled radical inverse of `index`, the scrambled van der Corput sequence
+    pub fn radical_inverse(&self, mut index: u64) -> f64 {
+        let b = self.base as u64;
+        let mut digits = Vec::with_capacity(self.depth());
+        while index > 0 {
+            digits.push((index % b) as u32);
+            index /= b;
+        }
+
+        let scrambled = self.scramble_digits(&digits, self.depth());
+        let inv = 1. / self.base as f64;
+        // Sum from the least significant digit to keep rounding error small
+        let value = scrambled.iter().rev().fold(0., |acc, &d| (acc + d as f64) * inv);
+        value.min(1. - f64::EPSILON / 2.)
+    }
+}
+
+/// Owen scrambling of a 32 bit binary fraction by the Laine–Karras hash, seeded by `seed`
+///
+/// The hash of the reversed bits only lets each bit depend on the bits below it, so on the
+/// original fraction every digit is flipped depending on the digits before it: a nested
+/// uniform scramble evaluated in constant time, without building the permutation tree.
+pub(crate) fn nested_uniform_scramble(x: u32, seed: u32) -> u32 {
+    laine_karras(x.reverse_bits(), seed).reverse_bits()
+}
+
+/// Owen scrambling of a 64 bit binary fraction, seeded by `seed`
+///
+/// The leading 32 bits are scrambled as by [`nested_uniform_scramble`], and the trailing 32
+/// bits with a seed hashed from the leading bits, so the scramble stays nested across halves.
+///
+/// [`nested_uniform_scramble`]: fn.nested_uniform_scramble.html
+pub(crate) fn nested_uniform_scramble_64(x: u64, seed: u64) -> u64 {
+    let high = (x >> 32) as u32;
+    let low = nested_uniform_scramble(x as u32, (mix64(seed ^ high as u64) >> 32) as u32);
+    (nested_uniform_scramble(high, seed as u32) as u64) << 32 | low as u64
+}
+
+/// Burley's constants for the Laine–Karras permutation
+fn laine_karras(mut x: u32, seed: u32) -> u32 {
+    x = x.wrapping_add(seed);
+    x ^= x.wrapping_mul(0x6c50_b47c);
+    x ^= x.wrapping_mul(0xb82f_1e52);
+    x ^= x.wrapping_mul(0xc7af_e638);
+    x ^= x.wrapping_mul(0x8d22_f6e6);
+    x
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn invalid_base() {
+        assert!(matches!(OwenTree::new(1, 0), Err(Error::InvalidBase(1))));
+    }
+
+    #[test]
+    fn node_permutations_are_bijections() {
+        let tree = OwenTree::new(7, 3).unwrap();
+        let mut node = tree.root();
+        for d in 0..5 {
+            let mut image: Vec<u32> = (0..7).map(|x| tree.permute(node, x)).collect();
+            image.sort();
+            assert_eq!(image, (0..7).collect::<Vec<u32>>());
+            node = OwenTree::child(node, d);
+        }
+    }
+
+    #[test]
+    fn seeds_differ() {
+        let a = OwenTree::new(2, 1).unwrap();
+        let b = OwenTree::new(2, 2).unwrap();
+        assert!((1..10).any(|i| a.radical_inverse(i) != b.radical_inverse(i)));
+        assert_eq!(a.radical_inverse(5), a.radical_inverse(5));
+    }
+
+    #[test]
+    fn bits_keep_binary_strata() {
+        let tree = OwenTree::new(2, 99).unwrap();
+        let m = 8;
+        let mut strata = vec![false; 1 << m];
+        for i in 0..1u64 << m {
+            strata[(tree.scramble_bits(i.reverse_bits()) >> (64 - m)) as usize] = true;
+        }
+        assert!(strata.iter().all(|&s| s));
+    }
+
+    #[test]
+    fn digits_share_prefix_permutations() {
+        // Points agreeing in their leading digits are permuted by the same nodes
+        let tree = OwenTree::new(5, 11).unwrap();
+        let a = tree.scramble_digits(&[1, 2, 3], 3);
+        let b = tree.scramble_digits(&[1, 2, 4], 3);
+        assert_eq!(a[..2], b[..2]);
+        assert!(a[2] != b[2]);
+    }
+
+    #[test]
+    fn hashed_scramble_is_nested() {
+        // Fractions agreeing in their leading k bits still agree after scrambling, and the
+        // scramble is a bijection on every prefix
+        for &seed in [0, 7, 0xdead_beef].iter() {
+            let mut seen = [false; 256];
+            for x in 0..256u32 {
+                let high = nested_uniform_scramble(x << 24, seed);
+                let low = nested_uniform_scramble(x << 24 | 0x00ab_cdef, seed);
+                assert_eq!(high >> 24, low >> 24);
+                seen[(high >> 24) as usize] = true;
+            }
+            assert!(seen.iter().all(|&s| s));
+        }
+
+        let x = 0x1234_5678_9abc_def0;
+        let a = nested_uniform_scramble_64(x, 5);
+        let b = nested_uniform_scramble_64(x ^ 1 << 20, 5);
+        let c = nested_uniform_scramble_64(x ^ 1 << 40, 5);
+        // Flipping a digit leaves the digits before it alone and rescrambles those after it
+        assert_eq!((a >> 21, a >> 41), (b >> 21, c >> 41));
+        assert!(a != b && a as u32 != c as u32);
+    }
+}
@@ -0,0 +1,215 @@
+use rand::Rng;
+
+use error::{Error, Result};
+use primes::Primes;
+use super::{Digital, HashStream, JumpAhead, OwenTree};
+
+/// Halton sequence with Owen's nested scrambling of the digits of every dimension
+///
+/// Coordinate `j` of point `i` is the radical inverse of `i` in base `b_j`, with every digit
+/// permuted by a random permutation which depends on all the digits before it.  Each
+/// dimension has its own permutation tree, an [`OwenTree`] seeded from the seed of the
+/// sequence, so the same seed always gives the same points.
+///
+/// Unlike the fixed permutations of a [`GeneralizedHalton`], nested scrambling makes every
+/// point uniformly distributed over the unit cube while keeping every prefix of `b^k` points
+/// stratified in dimension `j`.  Estimates from independently seeded replicates are then
+/// unbiased and independent, so their spread gives a proper error estimate for randomized
+/// quasi-Monte Carlo.  The sequence starts at index `0`, which is scrambled like any other.
+///
+/// Implements [`Rng`] by returning the coordinates of consecutive points one after another,
+/// and [`Iterator`] by returning whole points.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::OwenHalton;
+/// // Independent replicates of the mean of x y over 27 points
+/// let estimates: Vec<f64> = (0..10)
+///     .map(|seed| {
+///         let gen = OwenHalton::new(2, seed).unwrap();
+///         gen.take(27).map(|p| p[0] * p[1]).sum::<f64>() / 27.
+///     })
+///     .collect();
+/// let mean = estimates.iter().sum::<f64>() / 10.;
+/// assert!((mean - 0.25).abs() < 0.01);
+///
+/// // Seeds reproduce their sequence
+/// assert_eq!(OwenHalton::new(2, 3).unwrap().next(), OwenHalton::new(2, 3).unwrap().next());
+/// ```
+///
+/// # References
+/// - Owen, A. B., A randomized Halton algorithm in R
+/// - Owen, A. B., Randomly permuted (t,m,s)-nets and (t,s)-sequences
+///
+/// [`OwenTree`]: ../owen/struct.OwenTree.html
+/// [`GeneralizedHalton`]: ../generalized/struct.GeneralizedHalton.html
+/// [`Rng`]: ../../../rand/trait.Rng.html
+/// [`Iterator`]: https://doc.rust-lang.org/std/iter/trait.Iterator.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwenHalton {
+    trees: Vec<OwenTree>,
+    /// Index of the point whose coordinates are being returned
+    index: u64,
+    /// Next coordinate of the current point
+    current: usize,
+}
+
+impl OwenHalton {
+    /// Sequence over the first `dimensions` primes, scrambled by trees drawn from `seed`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyGenerators`] if `dimensions` is zero.
+    ///
+    /// [`Error::EmptyGenerators`]: ../../enum.Error.html#variant.EmptyGenerators
+    pub fn new(dimensions: usize, seed: u64) -> Result<OwenHalton> {
+        let bases: Vec<u32> = Primes::new().take(dimensions).collect();
+        OwenHalton::with_bases(&bases, seed)
+    }
+
+    /// Sequence with one dimension per base, scrambled by trees drawn from `seed`
+    ///
+    /// Bases should be pairwise coprime for the points to fill the unit cube; repeated bases
+    /// are still scrambled by distinct trees.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyGenerators`] if there are no bases and [`Error::InvalidBase`] if
+    /// one of them is less than `2`.
+    ///
+    /// [`Error::EmptyGenerators`]: ../../enum.Error.html#variant.EmptyGenerators
+    /// [`Error::InvalidBase`]: ../../enum.Error.html#variant.InvalidBase
+    pub fn with_bases(bases: &[u32], seed: u64) -> Result<OwenHalton> {
+        if bases.is_empty() {
+            return Err(Error::EmptyGenerators);
+        }
+        let mut hash = HashStream::new(seed);
+        let trees = bases.iter()
+            .map(|&b| OwenTree::new(b, hash.next_bits()))
+            .collect::<Result<Vec<OwenTree>>>()?;
+        Ok(OwenHalton { trees, index: 0, current: 0 })
+    }
+
+    /// Number of dimensions of each point
+    pub fn dimensions(&self) -> usize {
+        self.trees.len()
+    }
+
+    /// Scrambling tree of every dimension, which also holds its base
+    pub fn trees(&self) -> &[OwenTree] {
+        &self.trees
+    }
+}
+
+impl Rng for OwenHalton {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_f64() * u32::MAX as f64).floor() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        (self.next_f64() * u64::MAX as f64).floor() as u64
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        let x = self.trees[self.current].radical_inverse(self.index);
+        self.current += 1;
+        if self.current == self.trees.len() {
+            self.current = 0;
+            self.index = self.index.wrapping_add(1);
+        }
+        x
+    }
+}
+
+impl JumpAhead for OwenHalton {
+    /// Skip `n` coordinates, which need not be whole points
+    fn jump(&mut self, n: u64) {
+        let d = self.trees.len() as u64;
+        let target = self.current as u64 + n;
+        self.index = self.index.wrapping_add(target / d);
+        self.current = (target % d) as usize;
+    }
+}
+
+impl Iterator for OwenHalton {
+    type Item = Vec<f64>;
+
+    /// Remaining coordinates of the current point, or the next whole point
+    fn next(&mut self) -> Option<Vec<f64>> {
+        let n = self.trees.len() - self.current;
+        Some((0..n).map(|_| self.next_f64()).collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn invalid_bases() {
+        assert!(matches!(OwenHalton::new(0, 1), Err(Error::EmptyGenerators)));
+        assert!(matches!(OwenHalton::with_bases(&[2, 1], 1), Err(Error::InvalidBase(1))));
+    }
+
+    #[test]
+    fn prefixes_stay_stratified() {
+        let gen = OwenHalton::new(6, 5).unwrap();
+        assert_eq!(gen.trees()[5].base(), 13);
+        let points: Vec<Vec<f64>> = gen.take(169).collect();
+        for (j, &b) in [2usize, 3, 5, 7, 11, 13].iter().enumerate() {
+            // Every prefix of b^k points hits each interval of length b^-k once
+            let mut n = b;
+            while n <= points.len() {
+                let mut strata = vec![false; n];
+                for p in points[..n].iter() {
+                    strata[(p[j] * n as f64) as usize] = true;
+                }
+                assert!(strata.iter().all(|&s| s), "base {} prefix {}", b, n);
+                n *= b;
+            }
+        }
+    }
+
+    #[test]
+    fn seeds_give_distinct_trees() {
+        let a = OwenHalton::new(3, 1).unwrap();
+        assert_eq!(a, OwenHalton::new(3, 1).unwrap());
+        assert!(a != OwenHalton::new(3, 2).unwrap());
+
+        // Repeated bases are scrambled independently
+        let mut twins = OwenHalton::with_bases(&[3, 3], 4).unwrap();
+        let p = twins.nth(7).unwrap();
+        assert!(p[0] != p[1]);
+    }
+
+    #[test]
+    fn replicates_are_unbiased() {
+        // The origin of the plain sequence is uniformly scrambled over replicates
+        let reps = 2000;
+        let mean = (0..reps)
+            .map(|seed| OwenHalton::new(2, seed).unwrap().next_f64())
+            .sum::<f64>() / reps as f64;
+        abs_err_eq!(mean == 0.5 ~ 0.02);
+
+        let mean = (0..reps)
+            .map(|seed| {
+                let gen = OwenHalton::new(2, seed).unwrap();
+                gen.take(12).map(|p| p[0] * p[1]).sum::<f64>() / 12.
+            })
+            .sum::<f64>() / reps as f64;
+        abs_err_eq!(mean == 0.25 ~ 0.003);
+    }
+
+    #[test]
+    fn jump_matches_drawing() {
+        let mut jumped = OwenHalton::new(4, 9).unwrap();
+        let mut drawn = jumped.clone();
+        jumped.jump(41);
+        for _ in 0..41 {
+            drawn.next_f64();
+        }
+        assert_eq!(jumped, drawn);
+        assert_eq!(jumped.next(), drawn.next());
+    }
+}
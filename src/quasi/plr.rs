@@ -0,0 +1,266 @@
+//! Polynomial lattice rules over GF(2)
+//!
+//! A polynomial lattice rule is the analogue of a rank-1 [`Lattice`] rule in which integers
+//! are replaced by polynomials over GF(2).  For a modulus `p` of degree `m` and generating
+//! polynomials `q_1, ..., q_s`, point `i` has coordinates `v_m(i(x) q_j(x) / p(x))`, where
+//! `i(x)` has the binary digits of `i` as coefficients and `v_m` maps the first `m` Laurent
+//! coefficients of a series in `x^-1` to a binary fraction.  The `2^m` points form a digital
+//! net whose generating matrices are Hankel matrices of the series of `q_j / p`.
+//!
+//! Good generating polynomials are found by computer search and published as tables, which can
+//! be loaded with [`PolynomialLattice::read_from`].
+//!
+//! # References
+//! - Niederreiter, H., Low-discrepancy point sets obtained by digital constructions over finite
+//!   fields
+//! - Dick, J., Pillichshammer, F., Digital Nets and Sequences, chapter 10
+//!
+//! [`Lattice`]: ../lattice/struct.Lattice.html
+//! [`PolynomialLattice::read_from`]: struct.PolynomialLattice.html#method.read_from
+
+use std::io::BufRead;
+
+use error::{Error, Result};
+use super::gf2::degree;
+
+/// Largest degree of the modulus, so that every coordinate fits in 32 bits
+pub const MAX_DEGREE: u32 = 32;
+
+/// Polynomial lattice rule of `2^m` points
+///
+/// Polynomials are given as integers whose bit `k` is the coefficient of `x^k`, so
+/// `x³ + x + 1` is `0b1011`.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::plr::PolynomialLattice;
+/// // Modulus x³ + x + 1 and generating polynomials 1 and x² + x
+/// let rule = PolynomialLattice::new(0b1011, &[0b1, 0b110]).unwrap();
+/// assert_eq!(rule.len(), 8);
+///
+/// // Every coordinate takes each of the values k / 8 exactly once
+/// for j in 0..2 {
+///     let mut values: Vec<f64> = rule.clone().map(|p| p[j] * 8.).collect();
+///     values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+///     assert_eq!(values, vec![0., 1., 2., 3., 4., 5., 6., 7.]);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolynomialLattice {
+    modulus: u64,
+    generator: Vec<u64>,
+    /// Columns of the generating matrix of every dimension, as 32 bit binary fractions
+    columns: Vec<Vec<u32>>,
+    index: u64,
+}
+
+impl PolynomialLattice {
+    /// Rule with the given `modulus` and `generator` polynomials
+    ///
+    /// Generating polynomials are reduced modulo the modulus.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyGenerators`] if `generator` is empty, and
+    /// [`Error::InvalidParameter`] if the modulus has degree zero or above [`MAX_DEGREE`].
+    ///
+    /// [`Error::EmptyGenerators`]: ../../enum.Error.html#variant.EmptyGenerators
+    /// [`Error::InvalidParameter`]: ../../enum.Error.html#variant.InvalidParameter
+    /// [`MAX_DEGREE`]: constant.MAX_DEGREE.html
+    pub fn new(modulus: u64, generator: &[u64]) -> Result<PolynomialLattice> {
+        if generator.is_empty() {
+            return Err(Error::EmptyGenerators);
+        }
+        let m = degree(modulus);
+        if m == 0 || m > MAX_DEGREE {
+            return Err(Error::InvalidParameter {
+                name: "modulus",
+                reason: format!("{:#b} must have degree between 1 and {}", modulus, MAX_DEGREE),
+            });
+        }
+
+        let generator: Vec<u64> = generator.iter().map(|&q| rem(q, modulus)).collect();
+        let columns = generator.iter().map(|&q| hankel_columns(q, modulus)).collect();
+        Ok(PolynomialLattice { modulus, generator, columns, index: 0 })
+    }
+
+    /// Read a rule from a table of polynomials
+    ///
+    /// The first entry is the modulus, followed by one generating polynomial per dimension.
+    /// Entries are decimal integers, one per line, and may also be separated by whitespace on
+    /// a line.  Blank lines and everything after a `#` are ignored.
+    ///
+    /// ```
+    /// # use tapas::quasi::plr::PolynomialLattice;
+    /// let table = "# m = 3\n11\n1\n6\n";
+    /// let rule = PolynomialLattice::read_from(table.as_bytes()).unwrap();
+    /// assert_eq!(rule, PolynomialLattice::new(0b1011, &[0b1, 0b110]).unwrap());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if reading fails, [`Error::Parse`] for entries which are not
+    /// integers, and the errors of [`new`] for the polynomials read.
+    ///
+    /// [`Error::Io`]: ../../enum.Error.html#variant.Io
+    /// [`Error::Parse`]: ../../enum.Error.html#variant.Parse
+    /// [`new`]: #method.new
+    pub fn read_from<B: BufRead>(input: B) -> Result<PolynomialLattice> {
+        let mut polynomials = Vec::new();
+        for (n, line) in input.lines().enumerate() {
+            let line = line?;
+            let content = line.split('#').next().unwrap_or("");
+            for entry in content.split_whitespace() {
+                let poly = entry.parse().map_err(|_| Error::Parse {
+                    line: n + 1,
+                    reason: format!("expected a polynomial as an integer, found `{}`", entry),
+                })?;
+                polynomials.push(poly);
+            }
+        }
+        match polynomials.split_first() {
+            Some((&modulus, generator)) => PolynomialLattice::new(modulus, generator),
+            None => Err(Error::EmptyGenerators),
+        }
+    }
+
+    /// Number of dimensions of each point
+    pub fn dimensions(&self) -> usize {
+        self.generator.len()
+    }
+
+    /// Total number of points, `2^m` for a modulus of degree `m`
+    pub fn points(&self) -> u64 {
+        1 << degree(self.modulus)
+    }
+
+    /// Modulus polynomial
+    pub fn modulus(&self) -> u64 {
+        self.modulus
+    }
+
+    /// Generating polynomials, reduced modulo the modulus
+    pub fn generator(&self) -> &[u64] {
+        &self.generator
+    }
+
+    /// Point `i` of the rule, which need not be the next one
+    ///
+    /// # Panics
+    ///
+    /// If `i` is not below the number of points.
+    pub fn point(&self, i: u64) -> Vec<f64> {
+        assert!(i < self.points(), "point {} of a {} point rule", i, self.points());
+        self.columns.iter()
+            .map(|columns| {
+                let x = columns.iter().enumerate()
+                    .filter(|&(c, _)| i >> c & 1 == 1)
+                    .fold(0, |acc, (_, &col)| acc ^ col);
+                x as f64 / 4_294_967_296.
+            })
+            .collect()
+    }
+}
+
+/// Remainder of `a` divided by `b` over GF(2)
+fn rem(mut a: u64, b: u64) -> u64 {
+    let db = degree(b);
+    while a != 0 && degree(a) >= db {
+        a ^= b << (degree(a) - db);
+    }
+    a
+}
+
+/// Columns of the generating matrix of `q / p`, whose entry at row `r` and column `c` is the
+/// Laurent coefficient of `x^-(r + c + 1)`
+fn hankel_columns(q: u64, p: u64) -> Vec<u32> {
+    let m = degree(p) as usize;
+
+    // The coefficient of x^-(k + 1) is the x^(m - 1) coefficient of x^k q mod p
+    let mut r = q;
+    let laurent: Vec<u32> = (0..2 * m).map(|_| {
+        let u = (r >> (m - 1) & 1) as u32;
+        r <<= 1;
+        if r >> m & 1 == 1 {
+            r ^= p;
+        }
+        u
+    }).collect();
+
+    (0..m).map(|c| (0..m).fold(0, |col, row| col | laurent[row + c] << (31 - row))).collect()
+}
+
+impl Iterator for PolynomialLattice {
+    type Item = Vec<f64>;
+
+    fn next(&mut self) -> Option<Vec<f64>> {
+        if self.index == self.points() {
+            return None;
+        }
+        let point = self.point(self.index);
+        self.index += 1;
+        Some(point)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = (self.points() - self.index) as usize;
+        (left, Some(left))
+    }
+}
+
+impl ExactSizeIterator for PolynomialLattice {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn invalid_modulus() {
+        for &modulus in [1, 1 << 33].iter() {
+            assert!(matches!(PolynomialLattice::new(modulus, &[1]),
+                Err(Error::InvalidParameter { name: "modulus", .. })));
+        }
+        assert!(matches!(PolynomialLattice::new(0b111, &[]), Err(Error::EmptyGenerators)));
+    }
+
+    #[test]
+    fn monomial_modulus_is_hammersley() {
+        // 1 / x^m has a single nonzero coefficient, so the first coordinate is i / 2^m
+        let rule = PolynomialLattice::new(1 << 6, &[1]).unwrap();
+        for (i, p) in rule.enumerate() {
+            assert_eq!(p[0], i as f64 / 64.);
+        }
+    }
+
+    #[test]
+    fn two_dimensional_net() {
+        // Search a generating polynomial which makes the 2D rule a (0, 6, 2)-net
+        let p = 0b100_0011; // x⁶ + x + 1
+        let fills = |q: u64| {
+            let points: Vec<Vec<f64>> = PolynomialLattice::new(p, &[1, q]).unwrap().collect();
+            (0..=6).all(|k| {
+                let (a, b) = (1 << k, 1 << (6 - k));
+                let mut boxes = [false; 64];
+                for x in points.iter() {
+                    boxes[(x[0] * a as f64) as usize * b + (x[1] * b as f64) as usize] = true;
+                }
+                boxes.iter().all(|&x| x)
+            })
+        };
+        assert!((1..64).any(fills));
+        assert!(!fills(1));
+    }
+
+    #[test]
+    fn table_errors() {
+        let bad = "11\n1\nsix\n";
+        assert!(matches!(PolynomialLattice::read_from(bad.as_bytes()),
+            Err(Error::Parse { line: 3, .. })));
+        assert!(matches!(PolynomialLattice::read_from("# empty\n".as_bytes()),
+            Err(Error::EmptyGenerators)));
+        let rule = PolynomialLattice::read_from("67 1 # modulus and first\n\n 21 \n".as_bytes())
+            .unwrap();
+        assert_eq!((rule.modulus(), rule.generator()), (67, &[1, 21][..]));
+    }
+}
@@ -0,0 +1,164 @@
+use rand::Rng;
+use std::collections::HashMap;
+
+use super::zero_two::pascal;
+
+/// Progressive multi-jittered (0,2) samples
+///
+/// A table of `n` random points in the unit square whose every prefix of `2^m` points is a
+/// (0,m,2)-net: one point in each elementary interval of area `2^-m`, from `2^m` thin columns
+/// through the `2^(m/2)` square grid of a jittered sampler to `2^m` thin rows.  Prefixes of any
+/// other length stay evenly spread, so a renderer can stop at any sample count.
+///
+/// Rather than the rejection sampling of Christensen, Kensler and Kilpatrick, the samples follow
+/// the stochastic construction of Helmer, Christensen and Kensler: the strata of every new
+/// sample are those of the first two Sobol dimensions, with each choice between the two halves
+/// of an interval made at random, and the sample is then jittered uniformly inside its finest
+/// strata.  Generation takes linear time and can never run out of valid positions.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate tapas;
+/// # use tapas::quasi::Pmj02;
+/// use rand::{SeedableRng, StdRng};
+///
+/// # fn main() {
+/// let samples = Pmj02::new(16, &mut StdRng::from_seed(&[5][..]));
+///
+/// // The first four samples are one per quadrant
+/// let mut quadrants = [false; 4];
+/// for &[x, y] in samples.samples()[..4].iter() {
+///     quadrants[(2. * x) as usize * 2 + (2. * y) as usize] = true;
+/// }
+/// assert!(quadrants.iter().all(|&q| q));
+/// # }
+/// ```
+///
+/// # References
+/// - Christensen, P., Kensler, A., Kilpatrick, C., Progressive multi-jittered sample sequences
+/// - Helmer, A., Christensen, P., Kensler, A., Stochastic generation of (t, s) sample
+///   sequences
+#[derive(Debug, Clone)]
+pub struct Pmj02 {
+    samples: Vec<[f64; 2]>,
+    index: usize,
+}
+
+impl Pmj02 {
+    /// Table of `n` samples with random choices drawn from `rng`
+    pub fn new<R: Rng>(n: usize, rng: &mut R) -> Pmj02 {
+        // Strata are resolved down to the finest intervals of the smallest enclosing power of 2
+        let m = (n.max(1) as u64).next_power_of_two().trailing_zeros();
+        let mut x_flips = HashMap::new();
+        let mut y_flips = HashMap::new();
+
+        let samples = (0..n as u64)
+            .map(|i| {
+                let x = strata(i.reverse_bits(), m, &mut x_flips, rng);
+                let y = strata(pascal(i), m, &mut y_flips, rng);
+                let scale = 0.5f64.powi(m as i32);
+                [(x as f64 + rng.next_f64()) * scale, (y as f64 + rng.next_f64()) * scale]
+            })
+            .collect();
+        Pmj02 { samples, index: 0 }
+    }
+
+    /// Every sample of the table, in progressive order
+    pub fn samples(&self) -> &[[f64; 2]] {
+        &self.samples
+    }
+
+    /// Sample `i` of the table, which need not be the next one
+    ///
+    /// # Panics
+    ///
+    /// If `i` is not below the number of samples.
+    pub fn point(&self, i: usize) -> [f64; 2] {
+        self.samples[i]
+    }
+}
+
+/// Leading `m` digits of the binary fraction `bits` with the digit below every node of the
+/// binary tree of intervals flipped at random, drawing each node's flip once
+fn strata<R: Rng>(bits: u64, m: u32, flips: &mut HashMap<(u32, u64), bool>, rng: &mut R) -> u64 {
+    let mut out = 0;
+    for d in 0..m {
+        let prefix = bits.checked_shr(64 - d).unwrap_or(0);
+        let flip = *flips.entry((d, prefix)).or_insert_with(|| rng.gen());
+        out = out << 1 | (bits >> (63 - d) & 1) ^ flip as u64;
+    }
+    out
+}
+
+impl Iterator for Pmj02 {
+    type Item = [f64; 2];
+
+    fn next(&mut self) -> Option<[f64; 2]> {
+        let sample = self.samples.get(self.index).cloned();
+        self.index = (self.index + 1).min(self.samples.len());
+        sample
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.samples.len() - self.index;
+        (left, Some(left))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<[f64; 2]> {
+        self.index = self.index.saturating_add(n).min(self.samples.len());
+        self.next()
+    }
+}
+
+impl ExactSizeIterator for Pmj02 {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{SeedableRng, StdRng};
+
+    fn is_net(points: &[[f64; 2]]) -> bool {
+        let n = points.len();
+        let m = n.trailing_zeros();
+        (0..=m).all(|k| {
+            let (a, b) = (1 << k, 1 << (m - k));
+            let mut boxes = vec![false; n];
+            for p in points.iter() {
+                boxes[(p[0] * a as f64) as usize * b + (p[1] * b as f64) as usize] = true;
+            }
+            boxes.iter().all(|&x| x)
+        })
+    }
+
+    #[test]
+    fn prefixes_are_nets() {
+        let samples = Pmj02::new(1000, &mut StdRng::from_seed(&[1][..]));
+        for m in 0..10 {
+            assert!(is_net(&samples.samples()[..1 << m]), "prefix of {} samples", 1 << m);
+        }
+    }
+
+    #[test]
+    fn random_choices_differ() {
+        let a = Pmj02::new(64, &mut StdRng::from_seed(&[1][..]));
+        let b = Pmj02::new(64, &mut StdRng::from_seed(&[2][..]));
+        let c = Pmj02::new(64, &mut StdRng::from_seed(&[1][..]));
+        assert_eq!(a.samples(), c.samples());
+
+        // Different seeds give different strata, not only different jitter
+        let cell = |p: &[f64; 2]| ((p[0] * 8.) as usize, (p[1] * 8.) as usize);
+        assert!(a.samples().iter().zip(b.samples()).any(|(p, q)| cell(p) != cell(q)));
+    }
+
+    #[test]
+    fn finite_and_exact() {
+        let mut samples = Pmj02::new(10, &mut StdRng::from_seed(&[3][..]));
+        assert_eq!(samples.len(), 10);
+        assert_eq!(samples.nth(8), Some(samples.point(8)));
+        assert_eq!(samples.by_ref().count(), 1);
+        assert_eq!(samples.next(), None);
+        assert!(Pmj02::new(0, &mut StdRng::from_seed(&[3][..])).next().is_none());
+    }
+}
@@ -0,0 +1,313 @@
+//! Radical inverse functions, the digit reversal at the heart of Halton-type sequences
+//!
+//! The radical inverse of an index in base `b` mirrors its base-`b` digits around the radix
+//! point: `i = d_0 + d_1 b + d_2 b² + ...` maps to `d_0 / b + d_1 / b² + ...`.  Applying a
+//! permutation of `0..b` to every digit first gives the scrambled radical inverse used by
+//! generalized and Faure-scrambled Halton sequences, and shifting every digit by its position
+//! gives the folded radical inverse.
+//!
+//! These functions evaluate a single index directly; [`Halton`] updates its value
+//! incrementally and is faster for consecutive indices.
+//!
+//! [`Halton`]: ../halton/struct.Halton.html
+
+/// Radical inverse of `index` in `base`
+///
+/// # Panics
+///
+/// If `base < 2`.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::radical::radical_inverse;
+/// assert_eq!(radical_inverse(2, 6), 3. / 8.);   // 110 → 0.011
+/// assert_eq!(radical_inverse(3, 5), 7. / 9.);   // 12 → 0.21
+/// ```
+pub fn radical_inverse(base: u32, index: u64) -> f64 {
+    assert!(base >= 2, "radical inverse needs a base of at least 2");
+    reverse(base, index, |d| d)
+}
+
+/// Radical inverse of `index` in `base` with every digit mapped through `permutation`
+///
+/// Digit `d` becomes `permutation[d]`, including the infinitely many leading zero digits of
+/// the index: if `permutation[0]` is not zero they add the tail `permutation[0] / (b - 1)`
+/// scaled to just past the last significant digit.  The result is clamped below one.
+///
+/// # Panics
+///
+/// If `base < 2` or `permutation` does not have `base` entries.  In debug builds also if
+/// `permutation` is not a permutation of `0..base`.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::radical::{radical_inverse, scrambled_radical_inverse};
+/// // The identity permutation gives the plain radical inverse
+/// assert_eq!(scrambled_radical_inverse(3, 5, &[0, 1, 2]), radical_inverse(3, 5));
+///
+/// // Swapping 1 and 2 maps 5 = 12₃ to 0.12₃
+/// assert_eq!(scrambled_radical_inverse(3, 5, &[0, 2, 1]), 5. / 9.);
+/// ```
+pub fn scrambled_radical_inverse(base: u32, index: u64, permutation: &[u32]) -> f64 {
+    check(base, permutation);
+    permuted(base, index, permutation)
+}
+
+/// Scrambled radical inverse for a `permutation` already checked against `base`
+pub(crate) fn permuted(base: u32, index: u64, permutation: &[u32]) -> f64 {
+    let value = reverse(base, index, |d| permutation[d as usize]);
+
+    let p0 = permutation[0];
+    if p0 == 0 {
+        return value;
+    }
+    // Leading zeros of the index, each mapped to p0, form a geometric series
+    let tail = p0 as f64 / (base as f64 - 1.) / (base as f64).powi(digit_count(base, index));
+    (value + tail).min(1. - f64::EPSILON / 2.)
+}
+
+/// Folded radical inverse of `index` in `base`
+///
+/// Digit `d_k` of the index, counting from the least significant, becomes `(d_k + k) mod b`,
+/// and so do the infinitely many leading zero digits, giving `Σ ((d_k + k) mod b) / b^(k+1)`.
+/// Shifting every digit by its position breaks up the regular lines which plain radical
+/// inverses in small bases form against each other, so the folded Halton sequence has better
+/// two-dimensional projections; in base 2 this is Zaremba's sequence.  Every `b^m` consecutive
+/// indices aligned to a multiple of `b^m` still hit every interval of width `b^-m` once.
+///
+/// # Panics
+///
+/// If `base < 2`.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::radical::folded_radical_inverse;
+/// // Index 0 folds to 0.010101...₂ = 1/3, and index 1 to 0.110101...₂ = 5/6
+/// assert!((folded_radical_inverse(2, 0) - 1. / 3.).abs() < 1e-15);
+/// assert!((folded_radical_inverse(2, 1) - 5. / 6.).abs() < 1e-15);
+/// ```
+///
+/// # References
+/// - Tuffin, B., A new permutation choice in Halton sequences
+/// - Zaremba, S. K., La discrépance isotrope et l'intégration numérique
+pub fn folded_radical_inverse(base: u32, mut index: u64) -> f64 {
+    assert!(base >= 2, "radical inverse needs a base of at least 2");
+    let b = base as u64;
+    let inv = 1. / base as f64;
+    let mut value = 0.;
+    let mut weight = inv;
+    let mut k = 0;
+    // Folded leading zeros keep contributing until they drop below the precision of the value
+    while index > 0 || weight > f64::EPSILON / 4. {
+        value += ((index % b + k % b) % b) as f64 * weight;
+        index /= b;
+        weight *= inv;
+        k += 1;
+    }
+    value.min(1. - f64::EPSILON / 2.)
+}
+
+/// Index below `base^digits` whose radical inverse is `value`
+///
+/// Inverts [`radical_inverse`] for indices with at most `digits` digits, the largest usable
+/// number being limited by `base^digits ≤ 2^53` so every value is exactly representable.
+///
+/// # Panics
+///
+/// If `base < 2` or `base^digits` exceeds `2^53`.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::radical::{inverse_radical_inverse, radical_inverse};
+/// assert_eq!(inverse_radical_inverse(3, radical_inverse(3, 500), 6), 500);
+/// ```
+///
+/// [`radical_inverse`]: fn.radical_inverse.html
+pub fn inverse_radical_inverse(base: u32, value: f64, digits: u32) -> u64 {
+    assert!(base >= 2, "radical inverse needs a base of at least 2");
+    unreverse(base, value, digits, 0, |d| d)
+}
+
+/// Index below `base^digits` whose scrambled radical inverse is `value`
+///
+/// Inverts [`scrambled_radical_inverse`] with the same `permutation` for indices with at most
+/// `digits` digits.
+///
+/// # Panics
+///
+/// As [`inverse_radical_inverse`] and [`scrambled_radical_inverse`].
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::radical::*;
+/// let perm = [3, 0, 4, 1, 2];
+/// let value = scrambled_radical_inverse(5, 1234, &perm);
+/// assert_eq!(inverse_scrambled_radical_inverse(5, value, 5, &perm), 1234);
+/// ```
+///
+/// [`scrambled_radical_inverse`]: fn.scrambled_radical_inverse.html
+/// [`inverse_radical_inverse`]: fn.inverse_radical_inverse.html
+pub fn inverse_scrambled_radical_inverse(base: u32, value: f64, digits: u32, permutation: &[u32])
+    -> u64
+{
+    check(base, permutation);
+    let mut inverse = vec![0; base as usize];
+    for (d, &p) in permutation.iter().enumerate() {
+        inverse[p as usize] = d as u32;
+    }
+    unreverse(base, value, digits, permutation[0], |d| inverse[d as usize])
+}
+
+fn check(base: u32, permutation: &[u32]) {
+    assert!(base >= 2, "radical inverse needs a base of at least 2");
+    assert_eq!(permutation.len(), base as usize, "permutation of {} digits for base {}",
+        permutation.len(), base);
+    debug_assert!({
+        let mut sorted = permutation.to_vec();
+        sorted.sort_unstable();
+        sorted.iter().enumerate().all(|(i, &d)| i as u32 == d)
+    }, "{:?} is not a permutation of 0..{}", permutation, base);
+}
+
+/// Number of base-`b` digits of `index`, zero for zero
+fn digit_count(base: u32, mut index: u64) -> i32 {
+    let mut n = 0;
+    while index > 0 {
+        index /= base as u64;
+        n += 1;
+    }
+    n
+}
+
+/// Radical inverse with every digit passed through `map`, least significant digit first
+pub(crate) fn reverse<P: FnMut(u32) -> u32>(base: u32, mut index: u64, mut map: P) -> f64 {
+    let b = base as u64;
+    let mut digits = 0u64;
+    let mut scale = 1u64;
+    let inv = 1. / base as f64;
+    let mut value = 0.;
+    let mut weight = 1.;
+
+    // Accumulate reversed digits as an integer while it stays exact, then continue in floats
+    while index > 0 {
+        let d = map((index % b) as u32) as u64;
+        index /= b;
+        match scale.checked_mul(b).filter(|&s| s <= 1 << 53) {
+            Some(s) => {
+                digits = digits * b + d;
+                scale = s;
+            },
+            None => {
+                weight *= inv;
+                value += d as f64 * weight;
+            },
+        }
+    }
+    // Indices with more than 53 significant bits could otherwise round up to one
+    ((digits as f64 + value) / scale as f64).min(1. - f64::EPSILON / 2.)
+}
+
+fn unreverse<P: Fn(u32) -> u32>(base: u32, value: f64, digits: u32, p0: u32, unmap: P) -> u64 {
+    let b = base as u64;
+    let scale = b.checked_pow(digits).filter(|&s| s <= 1 << 53)
+        .unwrap_or_else(|| panic!("{} digits in base {} exceed double precision", digits, base));
+
+    // Remove the tail contributed by the permuted leading zeros before rounding
+    let tail = p0 as f64 / (base as f64 - 1.);
+    let mut reversed = ((value * scale as f64 - tail).round().max(0.) as u64).min(scale - 1);
+
+    let mut index = 0;
+    for _ in 0..digits {
+        index = index * b + unmap((reversed % b) as u32) as u64;
+        reversed /= b;
+    }
+    index
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::Halton;
+
+    #[test]
+    fn matches_halton() {
+        for &base in [2, 3, 7, 29].iter() {
+            let halton = Halton::new(1, base).unwrap();
+            for (i, x) in halton.take(500).enumerate() {
+                abs_err_eq!((radical_inverse(base, i as u64 + 1)) == x ~ 1e-15);
+            }
+        }
+    }
+
+    #[test]
+    fn large_indices() {
+        assert_eq!(radical_inverse(2, u64::MAX), 1. - f64::EPSILON / 2.);
+        assert_eq!(radical_inverse(2, 1 << 62), 0.5f64.powi(63));
+        assert!(radical_inverse(3, u64::MAX) < 1.);
+    }
+
+    #[test]
+    fn scrambled_permutes_strata() {
+        // Any permutation keeps one point in every interval of width b^-m among b^m indices
+        let perm = [2, 4, 0, 3, 1];
+        let mut strata = [false; 125];
+        for i in 0..125 {
+            let x = scrambled_radical_inverse(5, i, &perm);
+            strata[(x * 125.) as usize] = true;
+        }
+        assert!(strata.iter().all(|&s| s));
+    }
+
+    #[test]
+    fn permuted_leading_zeros() {
+        // With every digit mapped 0 → 1 in base 2, index 0 is 0.111... = 1
+        assert_eq!(scrambled_radical_inverse(2, 0, &[1, 0]), 1. - f64::EPSILON / 2.);
+        // 2 = 10₂ → 0.01 → 0.10111... = 3/4
+        abs_err_eq!((scrambled_radical_inverse(2, 2, &[1, 0])) == 0.75 ~ 1e-15);
+    }
+
+    #[test]
+    fn folded_keeps_strata() {
+        for &(base, m) in [(2, 10), (3, 6), (7, 3)].iter() {
+            let n = (base as u64).pow(m);
+            for start in [0, n, 5 * n].iter() {
+                let mut strata = vec![false; n as usize];
+                for i in *start..start + n {
+                    strata[(folded_radical_inverse(base, i) * n as f64) as usize] = true;
+                }
+                assert!(strata.iter().all(|&s| s), "base {}", base);
+            }
+        }
+    }
+
+    #[test]
+    fn folded_digits() {
+        // 5 = 12₃ folds to digits 2, (1 + 1) mod 3 = 2, then 2, 0, 1, 2, 0, ...
+        let tail = (2. / 27. + 0. / 81. + 1. / 243.) / (1. - 1. / 27.);
+        abs_err_eq!((folded_radical_inverse(3, 5)) == (2. / 3. + 2. / 9. + tail) ~ 1e-15);
+        assert!(folded_radical_inverse(2, u64::MAX) < 1.);
+    }
+
+    #[test]
+    fn inverse_round_trips() {
+        let perm = [2, 0, 6, 1, 5, 3, 4];
+        for i in (0..7u64.pow(6)).step_by(13) {
+            assert_eq!(inverse_radical_inverse(7, radical_inverse(7, i), 6), i);
+            let x = scrambled_radical_inverse(7, i, &perm);
+            assert_eq!(inverse_scrambled_radical_inverse(7, x, 6, &perm), i);
+        }
+        let i = (1 << 52) + 12345;
+        assert_eq!(inverse_radical_inverse(2, radical_inverse(2, i), 53), i);
+    }
+
+    #[test]
+    #[should_panic]
+    fn wrong_permutation_length() {
+        scrambled_radical_inverse(3, 1, &[0, 1]);
+    }
+}
@@ -0,0 +1,158 @@
+use rand::Rng;
+
+use error::{Error, Result};
+use super::{JumpAhead, Kronecker};
+
+/// Generalized golden ratio `φ_d`, the unique positive root of `x^(d + 1) = x + 1`
+///
+/// `φ_1` is the golden ratio and `φ_2` the plastic number.
+///
+/// ```
+/// # use tapas::quasi::roberts::phi;
+/// assert!((phi(1) - (1. + 5f64.sqrt()) / 2.).abs() < 1e-15);
+/// assert!((phi(2) - 1.324_717_957_244_746).abs() < 1e-15);
+/// ```
+pub fn phi(d: usize) -> f64 {
+    // Fixed point iteration of x = (1 + x)^(1 / (d + 1)) converges from any positive start
+    let power = 1. / (d as f64 + 1.);
+    let mut x = 2f64;
+    for _ in 0..100 {
+        let next = (1. + x).powf(power);
+        if next == x {
+            break;
+        }
+        x = next;
+    }
+    x
+}
+
+/// Roberts' `R_d` sequence of generalized golden ratio steps
+///
+/// A [`Kronecker`] sequence whose step in dimension `i` is `φ_d^-i` for the generalized golden
+/// ratio [`phi`]`(d)`, starting from the centre of the cube.  These steps are as irrational
+/// as possible in a precise sense, so the points have some of the best projections of any
+/// additive recurrence, particularly in two and three dimensions, and the generator needs
+/// only constant state.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::Rd;
+/// let mut r2 = Rd::r2();
+/// assert_eq!(r2.next(), Some(vec![0.5, 0.5]));
+/// ```
+///
+/// # References
+/// - Roberts, M., The Unreasonable Effectiveness of Quasirandom Sequences
+///
+/// [`Kronecker`]: ../kronecker/struct.Kronecker.html
+/// [`phi`]: fn.phi.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rd {
+    inner: Kronecker,
+}
+
+impl Rd {
+    /// Sequence in `dimensions` dimensions
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyGenerators`] if `dimensions` is zero.
+    ///
+    /// [`Error::EmptyGenerators`]: ../../enum.Error.html#variant.EmptyGenerators
+    pub fn new(dimensions: usize) -> Result<Rd> {
+        if dimensions == 0 {
+            return Err(Error::EmptyGenerators);
+        }
+        let g = phi(dimensions);
+        let alpha: Vec<f64> = (1..=dimensions as i32).map(|i| g.powi(-i)).collect();
+        let start = vec![0.5; dimensions];
+        Ok(Rd { inner: Kronecker::new(&alpha)?.with_start(&start) })
+    }
+
+    /// The two-dimensional `R_2` sequence of plastic number steps
+    pub fn r2() -> Rd {
+        Rd::new(2).unwrap()
+    }
+
+    /// Number of dimensions of each point
+    pub fn dimensions(&self) -> usize {
+        self.inner.dimensions()
+    }
+
+    /// Unwrap the underlying additive recurrence
+    pub fn into_inner(self) -> Kronecker {
+        self.inner
+    }
+}
+
+impl Rng for Rd {
+    fn next_u32(&mut self) -> u32 {
+        self.inner.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.inner.next_u64()
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        self.inner.next_f64()
+    }
+}
+
+impl JumpAhead for Rd {
+    fn jump(&mut self, n: u64) {
+        self.inner.jump(n)
+    }
+}
+
+impl Iterator for Rd {
+    type Item = Vec<f64>;
+
+    fn next(&mut self) -> Option<Vec<f64>> {
+        self.inner.next()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn phi_roots() {
+        for d in 1..12 {
+            let g = phi(d);
+            abs_err_eq!((g.powi(d as i32 + 1)) == (g + 1.) ~ 1e-13, "φ_{} = {}", d, g);
+        }
+    }
+
+    #[test]
+    fn r2_steps() {
+        let g = phi(2);
+        let mut r2 = Rd::r2();
+        r2.next();
+        let p = r2.next().unwrap();
+        abs_err_eq!((p[0]) == ((0.5 + 1. / g).fract()) ~ 1e-15);
+        abs_err_eq!((p[1]) == ((0.5 + 1. / (g * g)).fract()) ~ 1e-15);
+    }
+
+    #[test]
+    fn r2_fills_grid() {
+        // Roberts' R2 points keep a minimum distance of order 1/√n
+        let points: Vec<Vec<f64>> = Rd::r2().take(1000).collect();
+        let mut min = 1f64;
+        for (i, p) in points.iter().enumerate() {
+            for q in points[..i].iter() {
+                let dx = (p[0] - q[0]).abs().min(1. - (p[0] - q[0]).abs());
+                let dy = (p[1] - q[1]).abs().min(1. - (p[1] - q[1]).abs());
+                min = min.min((dx * dx + dy * dy).sqrt());
+            }
+        }
+        assert!(min * 1000f64.sqrt() > 0.5, "minimum distance {}", min);
+    }
+
+    #[test]
+    fn no_dimensions() {
+        assert!(matches!(Rd::new(0), Err(Error::EmptyGenerators)));
+    }
+}
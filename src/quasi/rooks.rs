@@ -0,0 +1,121 @@
+use rand::Rng;
+
+use super::lhs::shuffle;
+
+/// N-rooks samples in the unit square
+///
+/// The square is cut into `n` columns and `n` rows, and the samples are placed like `n` rooks
+/// on an `n × n` chessboard that cannot capture each other: every column and every row holds
+/// exactly one sample.  Columns and rows are matched to the samples by two independent
+/// random permutations and every sample is jittered uniformly inside its cell, so both one
+/// dimensional projections are stratified while the order of the samples carries no pattern.
+/// This is the two dimensional [`LatinHypercube`].
+///
+/// # Examples
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate tapas;
+/// # use tapas::quasi::NRooks;
+/// use rand::{SeedableRng, StdRng};
+///
+/// # fn main() {
+/// let rooks = NRooks::new(8, &mut StdRng::from_seed(&[42][..]));
+///
+/// let mut rows: Vec<usize> = rooks.map(|[_, y]| (y * 8.) as usize).collect();
+/// rows.sort();
+/// assert_eq!(rows, (0..8).collect::<Vec<usize>>());
+/// # }
+/// ```
+///
+/// [`LatinHypercube`]: ../lhs/struct.LatinHypercube.html
+#[derive(Debug, Clone)]
+pub struct NRooks {
+    samples: Vec<[f64; 2]>,
+    index: usize,
+}
+
+impl NRooks {
+    /// `n` samples with strata shuffled and jittered by `rng`
+    pub fn new<R: Rng>(n: usize, rng: &mut R) -> NRooks {
+        let mut columns: Vec<usize> = (0..n).collect();
+        let mut rows = columns.clone();
+        shuffle(&mut columns, rng);
+        shuffle(&mut rows, rng);
+        let samples = columns.iter().zip(rows.iter())
+            .map(|(&column, &row)| {
+                let x = (column as f64 + rng.next_f64()) / n as f64;
+                [x, (row as f64 + rng.next_f64()) / n as f64]
+            })
+            .collect();
+        NRooks { samples, index: 0 }
+    }
+
+    /// Every sample, in the order of iteration
+    pub fn samples(&self) -> &[[f64; 2]] {
+        &self.samples
+    }
+
+    /// Sample `i`, which need not be the next one
+    ///
+    /// # Panics
+    ///
+    /// If `i` is not below the number of samples.
+    pub fn point(&self, i: usize) -> [f64; 2] {
+        self.samples[i]
+    }
+}
+
+impl Iterator for NRooks {
+    type Item = [f64; 2];
+
+    fn next(&mut self) -> Option<[f64; 2]> {
+        let sample = self.samples.get(self.index).cloned();
+        self.index = (self.index + 1).min(self.samples.len());
+        sample
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.samples.len() - self.index;
+        (left, Some(left))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<[f64; 2]> {
+        self.index = self.index.saturating_add(n).min(self.samples.len());
+        self.next()
+    }
+}
+
+impl ExactSizeIterator for NRooks {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{SeedableRng, StdRng};
+
+    #[test]
+    fn rows_and_columns_once() {
+        let rooks = NRooks::new(100, &mut StdRng::from_seed(&[11][..]));
+        let mut columns = [false; 100];
+        let mut rows = [false; 100];
+        for &[x, y] in rooks.samples().iter() {
+            columns[(x * 100.) as usize] = true;
+            rows[(y * 100.) as usize] = true;
+        }
+        assert!(columns.iter().all(|&c| c) && rows.iter().all(|&r| r));
+    }
+
+    #[test]
+    fn seeded_shuffles() {
+        let a = NRooks::new(16, &mut StdRng::from_seed(&[1][..]));
+        let b = NRooks::new(16, &mut StdRng::from_seed(&[1][..]));
+        let c = NRooks::new(16, &mut StdRng::from_seed(&[2][..]));
+        assert_eq!(a.samples(), b.samples());
+        assert!(a.samples() != c.samples());
+
+        let mut a = a;
+        assert_eq!(a.len(), 16);
+        assert_eq!(a.nth(15), Some(b.point(15)));
+        assert_eq!(a.next(), None);
+    }
+}
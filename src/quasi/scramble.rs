@@ -0,0 +1,152 @@
+//! Deterministic digit permutations for scrambling the Halton sequence
+//!
+//! Every scheme permutes the base-`b` digits of a radical inverse while keeping `0` fixed, so
+//! the scrambled sequence keeps the stratification of the plain one and can still be updated
+//! incrementally.  Select a scheme with [`Halton::scrambled`].
+//!
+//! [`Halton::scrambled`]: ../halton/struct.Halton.html#method.scrambled
+
+use error::{Error, Result};
+
+/// Digit scrambling scheme of [`Halton::scrambled`]
+///
+/// [`Halton::scrambled`]: ../halton/struct.Halton.html#method.scrambled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scramble {
+    /// Permutations of Braaten and Weller, chosen digit by digit to minimize the discrepancy
+    /// of the one-dimensional projections, for the first 16 primes
+    ///
+    /// Only the bases `2` to `53` have a permutation, which covers Halton points of up to 16
+    /// dimensions; every other base is an [`Error::InvalidBase`].  Points of more dimensions
+    /// can use [`Faure`] permutations, defined for every base, or the pseudo-random
+    /// permutations of [`GeneralizedHalton::random`].
+    ///
+    /// [`Error::InvalidBase`]: ../../enum.Error.html#variant.InvalidBase
+    /// [`Faure`]: #variant.Faure
+    /// [`GeneralizedHalton::random`]: ../generalized/struct.GeneralizedHalton.html#method.random
+    ///
+    /// # References
+    /// - Braaten, E., Weller, G., An improved low-discrepancy sequence for multidimensional
+    ///   quasi-Monte Carlo integration
+    BraatenWeller,
+    /// Recursive permutations of Faure, defined for every base
+    ///
+    /// `σ_2 = (0, 1)`.  For an even base `2c`, `σ_2c` lists `2 σ_c` followed by `2 σ_c + 1`.
+    /// For an odd base `2c + 1`, `σ_2c+1` increments the values of `σ_2c` from `c` on and
+    /// inserts `c` in the middle.
+    ///
+    /// # References
+    /// - Faure, H., Good permutations for extreme discrepancy
+    Faure,
+}
+
+impl Scramble {
+    /// Digit permutation of the scheme in `base`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidBase`] if the scheme has no permutation for `base`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tapas::quasi::Scramble;
+    /// assert_eq!(Scramble::BraatenWeller.permutation(5).unwrap(), vec![0, 3, 1, 4, 2]);
+    /// assert!(Scramble::BraatenWeller.permutation(59).is_err());
+    /// ```
+    ///
+    /// [`Error::InvalidBase`]: ../../enum.Error.html#variant.InvalidBase
+    pub fn permutation(self, base: u32) -> Result<Vec<u32>> {
+        match self {
+            Scramble::BraatenWeller => BRAATEN_WELLER.iter()
+                .find(|perm| perm.len() == base as usize)
+                .map(|perm| perm.to_vec())
+                .ok_or(Error::InvalidBase(base)),
+            Scramble::Faure if base >= 2 => Ok(faure(base)),
+            Scramble::Faure => Err(Error::InvalidBase(base)),
+        }
+    }
+}
+
+/// Faure permutation in `base`, which is at least 2
+fn faure(base: u32) -> Vec<u32> {
+    if base == 2 {
+        return vec![0, 1];
+    }
+    let c = base / 2;
+    if base.is_multiple_of(2) {
+        let half = faure(c);
+        half.iter().map(|&d| 2 * d).chain(half.iter().map(|&d| 2 * d + 1)).collect()
+    } else {
+        let mut perm: Vec<u32> = faure(2 * c).into_iter()
+            .map(|d| if d >= c { d + 1 } else { d })
+            .collect();
+        perm.insert(c as usize, c);
+        perm
+    }
+}
+
+/// Braaten–Weller permutations of the digits in the first 16 prime bases
+const BRAATEN_WELLER: [&[u32]; 16] = [
+    &[0, 1],
+    &[0, 2, 1],
+    &[0, 3, 1, 4, 2],
+    &[0, 4, 2, 6, 1, 5, 3],
+    &[0, 5, 8, 2, 10, 3, 6, 1, 9, 7, 4],
+    &[0, 6, 10, 2, 8, 4, 12, 1, 9, 5, 11, 3, 7],
+    &[0, 8, 13, 3, 11, 5, 16, 1, 10, 7, 14, 4, 12, 2, 15, 6, 9],
+    &[0, 9, 14, 3, 17, 6, 11, 1, 15, 7, 12, 4, 18, 8, 2, 16, 10, 5, 13],
+    &[0, 11, 5, 18, 2, 14, 8, 21, 4, 16, 9, 20, 1, 13, 7, 17, 3, 12, 22, 6, 15, 10, 19],
+    &[0, 14, 22, 5, 18, 9, 27, 2, 20, 11, 25, 7, 16, 3, 24, 13, 19, 6, 28, 10, 1, 23, 15, 12,
+        26, 4, 17, 8, 21],
+    &[0, 16, 8, 26, 4, 22, 13, 29, 2, 19, 11, 24, 6, 20, 14, 28, 1, 17, 9, 30, 10, 23, 5, 21,
+        15, 3, 27, 12, 25, 7, 18],
+    &[0, 18, 28, 6, 23, 11, 34, 3, 25, 14, 31, 8, 20, 36, 1, 16, 27, 10, 22, 13, 32, 4, 29, 17, 7,
+        35, 19, 2, 26, 12, 30, 9, 24, 15, 33, 5, 21],
+    &[0, 20, 31, 7, 26, 12, 38, 3, 23, 34, 14, 17, 5, 29, 9, 36, 1, 21, 40, 10, 32, 25, 15, 2, 27,
+        19, 35, 4, 18, 8, 30, 13, 39, 6, 24, 33, 16, 37, 22, 11, 28],
+    &[0, 21, 32, 7, 38, 13, 25, 3, 35, 17, 28, 10, 41, 5, 23, 30, 15, 37, 1, 19, 33, 11, 26, 42, 8,
+        18, 29, 4, 39, 14, 22, 34, 6, 24, 12, 40, 2, 31, 20, 16, 36, 9, 27],
+    &[0, 23, 35, 8, 41, 14, 27, 3, 37, 18, 31, 10, 44, 5, 25, 39, 15, 33, 1, 21, 42, 12, 29, 6, 46,
+        19, 36, 9, 24, 2, 40, 16, 30, 13, 45, 4, 26, 34, 11, 38, 20, 7, 32, 17, 43, 22, 28],
+    &[0, 26, 40, 9, 33, 16, 49, 4, 36, 21, 12, 45, 7, 30, 18, 51, 2, 28, 42, 14, 38, 23, 6, 47, 10,
+        32, 19, 35, 1, 25, 44, 11, 41, 15, 50, 5, 29, 20, 37, 8, 46, 24, 13, 34, 3, 52, 17, 39, 27,
+        48, 22, 31, 43],
+];
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use primes::Primes;
+
+    #[test]
+    fn faure_permutations() {
+        assert_eq!(faure(3), vec![0, 1, 2]);
+        assert_eq!(faure(4), vec![0, 2, 1, 3]);
+        assert_eq!(faure(5), vec![0, 3, 2, 1, 4]);
+        assert_eq!(faure(7), vec![0, 2, 5, 3, 1, 4, 6]);
+        for b in 2..200 {
+            let mut sorted = faure(b);
+            sorted.sort_unstable();
+            assert_eq!(sorted, (0..b).collect::<Vec<u32>>());
+        }
+        assert!(matches!(Scramble::Faure.permutation(1), Err(Error::InvalidBase(1))));
+    }
+
+    #[test]
+    fn braaten_weller_permutations() {
+        assert_eq!(BRAATEN_WELLER.len(), 16);
+        for (perm, p) in BRAATEN_WELLER.iter().zip(Primes::new()) {
+            assert_eq!(perm.len(), p as usize);
+            let mut sorted = perm.to_vec();
+            sorted.sort_unstable();
+            assert!(sorted.iter().enumerate().all(|(i, &d)| i as u32 == d), "base {}", p);
+        }
+        assert!(matches!(Scramble::BraatenWeller.permutation(4), Err(Error::InvalidBase(4))));
+
+        // The table stops at the sixteenth prime
+        assert_eq!(Scramble::BraatenWeller.permutation(37).unwrap()[..4], [0, 18, 28, 6]);
+        assert_eq!(Scramble::BraatenWeller.permutation(53).unwrap().len(), 53);
+        assert!(matches!(Scramble::BraatenWeller.permutation(59), Err(Error::InvalidBase(59))));
+    }
+}
@@ -0,0 +1,1045 @@
+use rand::Rng;
+#[cfg(feature = "io")]
+use std::io::BufRead;
+use std::sync::{Arc, OnceLock};
+
+use error::{Error, Result};
+use super::{Digital, JumpAhead};
+use super::digital::{bits_to_f32, bits_to_f64, mix64};
+use super::gf2::degree;
+use super::owen::nested_uniform_scramble_64;
+
+/// Number of bits of every Sobol coordinate
+const BITS: usize = 64;
+
+/// Whether the polynomial over GF(2) with coefficient bits `poly` is primitive
+///
+/// Bit `k` of `poly` holds the coefficient of `x^k`.  A primitive polynomial of degree `s` is
+/// one for which `x` generates the whole multiplicative group of `GF(2^s)`, that is `x` has
+/// order exactly `2^s - 1` modulo the polynomial.  Degrees up to 32 are supported.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::sobol::is_primitive;
+/// assert!(is_primitive(0b111));        // x² + x + 1
+/// assert!(is_primitive(0b1011));       // x³ + x + 1
+/// assert!(!is_primitive(0b101));       // x² + 1 = (x + 1)²
+/// assert!(!is_primitive(0b1_1111));    // x⁴ + x³ + x² + x + 1 is irreducible, of order 5
+/// ```
+pub fn is_primitive(poly: u64) -> bool {
+    let s = degree(poly);
+    if s == 0 || s > 32 || poly & 1 == 0 {
+        return false;
+    }
+
+    has_full_order(poly, &prime_factors((1u64 << s) - 1))
+}
+
+/// Whether `x` has order `2^s - 1` modulo `poly`, given the prime factors of `2^s - 1`
+fn has_full_order(poly: u64, factors: &[u64]) -> bool {
+    let order = (1u64 << degree(poly)) - 1;
+    pow_x(order, poly) == 1 && factors.iter().all(|&q| pow_x(order / q, poly) != 1)
+}
+
+/// Primitive polynomials in the order of the Joe–Kuo tables: by degree, then by the
+/// coefficients between the leading and constant terms read as a binary number
+fn joe_kuo_polynomials() -> impl Iterator<Item = u64> {
+    (1..33u32).flat_map(|s| {
+        let factors = prime_factors((1u64 << s) - 1);
+        (0..1u64 << (s - 1))
+            .map(move |a| (1 << s) | (a << 1) | 1)
+            .filter(move |&poly| has_full_order(poly, &factors))
+    })
+}
+
+/// Product of `a` and `b` modulo `poly`, for operands of lower degree than `poly`
+fn mul_mod(a: u64, b: u64, poly: u64) -> u64 {
+    let s = degree(poly);
+    let mut result = 0u64;
+    let mut a = a;
+    let mut b = b;
+    while b != 0 {
+        if b & 1 == 1 {
+            result ^= a;
+        }
+        b >>= 1;
+        a <<= 1;
+        if a >> s & 1 == 1 {
+            a ^= poly;
+        }
+    }
+    result
+}
+
+/// `x^e` modulo `poly`
+fn pow_x(mut e: u64, poly: u64) -> u64 {
+    let mut result = 1;
+    // x ≡ 1 modulo x + 1
+    let mut base = if degree(poly) == 1 { 1 } else { 0b10 };
+    while e > 0 {
+        if e & 1 == 1 {
+            result = mul_mod(result, base, poly);
+        }
+        base = mul_mod(base, base, poly);
+        e >>= 1;
+    }
+    result
+}
+
+/// Distinct prime factors of `n` by trial division
+fn prime_factors(mut n: u64) -> Vec<u64> {
+    let mut factors = Vec::new();
+    let mut p = 2;
+    while p * p <= n {
+        if n.is_multiple_of(p) {
+            factors.push(p);
+            while n.is_multiple_of(p) {
+                n /= p;
+            }
+        }
+        p += 1;
+    }
+    if n > 1 {
+        factors.push(n);
+    }
+    factors
+}
+
+/// Direction numbers of a single Sobol dimension
+///
+/// Built from a primitive polynomial and its initial direction numbers `m_1, ..., m_s` in the
+/// convention of Joe and Kuo: every `m_k` is odd and below `2^k`, and the remaining numbers
+/// follow from the recurrence defined by the polynomial.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::sobol::DirectionNumbers;
+/// // Second dimension of the Joe–Kuo table: x + 1 with m_1 = 1
+/// let dim = DirectionNumbers::new(0b11, &[1]).unwrap();
+///
+/// // Not primitive, so rejected
+/// assert!(DirectionNumbers::new(0b101, &[1, 1]).is_err());
+/// ```
+///
+/// # References
+/// - Joe, S., Kuo, F. Y., Constructing Sobol sequences with better two-dimensional projections
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectionNumbers {
+    /// Direction numbers `v_k = m_k · 2^(64 - k)`, most significant first
+    v: Vec<u64>,
+    /// Change of the coordinate from index `i` to `i + 1`, by the trailing ones of `i`
+    steps: Vec<u64>,
+}
+
+impl DirectionNumbers {
+    /// The first Sobol dimension, which is the van der Corput sequence in base 2
+    pub fn identity() -> DirectionNumbers {
+        DirectionNumbers::from_v((0..BITS).map(|k| 1u64 << (BITS - 1 - k)).collect())
+    }
+
+    /// Direction numbers `v` with their cumulative steps
+    fn from_v(v: Vec<u64>) -> DirectionNumbers {
+        // Incrementing an index with `c` trailing ones flips its lowest `c + 1` bits
+        let steps = v.iter()
+            .scan(0, |acc, &vk| {
+                *acc ^= vk;
+                Some(*acc)
+            })
+            .collect();
+        DirectionNumbers { v, steps }
+    }
+
+    /// Direction numbers from the primitive polynomial `poly` and initial numbers `m`
+    ///
+    /// Bit `k` of `poly` holds the coefficient of `x^k`, so `x³ + x + 1` is `0b1011`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidDirectionNumbers`] if the polynomial is not primitive or has
+    /// degree above 32, if the number of initial numbers differs from the degree, or if some
+    /// `m_k` is even or not below `2^k`.
+    ///
+    /// [`Error::InvalidDirectionNumbers`]: ../../enum.Error.html#variant.InvalidDirectionNumbers
+    pub fn new(poly: u64, m: &[u32]) -> Result<DirectionNumbers> {
+        let s = degree(poly) as usize;
+        if !is_primitive(poly) {
+            return Err(Error::InvalidDirectionNumbers(
+                format!("polynomial {:#b} is not primitive", poly)));
+        }
+        if m.len() != s {
+            return Err(Error::InvalidDirectionNumbers(
+                format!("degree {} polynomial needs {} initial numbers, found {}", s, s, m.len())));
+        }
+        for (k, &mk) in m.iter().enumerate() {
+            if mk % 2 == 0 || (k < 31 && mk >= 1 << (k + 1)) {
+                return Err(Error::InvalidDirectionNumbers(
+                    format!("m_{} = {} must be odd and below 2^{}", k + 1, mk, k + 1)));
+            }
+        }
+        Ok(DirectionNumbers::from_recurrence(poly, m))
+    }
+
+    /// Read every dimension of a table in the format of the Joe–Kuo `new-joe-kuo-6.21201` file
+    ///
+    /// Every line lists a dimension `d`, the degree `s` of its primitive polynomial, the
+    /// polynomial's interior coefficients `a` as an integer whose most significant of `s - 1`
+    /// bits is the coefficient of `x^(s-1)`, and the `s` initial numbers `m_1, ..., m_s`.  The
+    /// table starts at dimension 2, so the returned dimensions start with [`identity`].  A
+    /// header line of column names, blank lines and everything after a `#` are ignored.
+    ///
+    /// Only available with the `io` feature.
+    ///
+    /// ```
+    /// # use tapas::quasi::Sobol;
+    /// # use tapas::quasi::sobol::DirectionNumbers;
+    /// let table = "d s a m_i\n2 1 0 1\n3 2 1 1 3\n";
+    /// let dimensions = DirectionNumbers::read_joe_kuo(table.as_bytes()).unwrap();
+    ///
+    /// let custom = Sobol::from_direction_numbers(dimensions).unwrap();
+    /// assert!(custom.take(64).eq(Sobol::new(3).unwrap().take(64)));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if reading fails and [`Error::Parse`] for lines which are not
+    /// integers, skip or repeat a dimension, or do not give valid direction numbers.
+    ///
+    /// [`identity`]: #method.identity
+    /// [`Error::Io`]: ../../enum.Error.html#variant.Io
+    /// [`Error::Parse`]: ../../enum.Error.html#variant.Parse
+    #[cfg(feature = "io")]
+    pub fn read_joe_kuo<B: BufRead>(input: B) -> Result<Vec<DirectionNumbers>> {
+        let mut all = vec![DirectionNumbers::identity()];
+        for (n, line) in input.lines().enumerate() {
+            let line = line?;
+            let content = line.split('#').next().unwrap_or("").trim();
+            let parse = |reason: String| Error::Parse { line: n + 1, reason };
+            if content.is_empty() || (n == 0 && content.starts_with('d')) {
+                continue;
+            }
+
+            let entries = content.split_whitespace()
+                .map(|entry| entry.parse::<u32>()
+                    .map_err(|_| parse(format!("expected an integer, found `{}`", entry))))
+                .collect::<Result<Vec<u32>>>()?;
+            let (d, s, a) = match entries.get(0..3) {
+                Some(&[d, s, a]) if (1..=32).contains(&s) => (d as usize, s, a as u64),
+                _ => return Err(parse("expected `d s a m_1 ... m_s`, 1 ≤ s ≤ 32".to_string())),
+            };
+            if d != all.len() + 1 {
+                return Err(parse(format!("dimension {} where {} was expected", d, all.len() + 1)));
+            }
+            let poly = 1 << s | a << 1 | 1;
+            let dim = DirectionNumbers::new(poly, &entries[3..])
+                .map_err(|err| parse(err.to_string()))?;
+            all.push(dim);
+        }
+        Ok(all)
+    }
+
+    /// Direction numbers from already validated polynomial and initial numbers
+    fn from_recurrence(poly: u64, m: &[u32]) -> DirectionNumbers {
+        let s = degree(poly) as usize;
+
+        // m_k = 2 a_1 m_{k-1} ⊕ 4 a_2 m_{k-2} ⊕ ... ⊕ 2^s m_{k-s} ⊕ m_{k-s}
+        let mut ms: Vec<u64> = m.iter().map(|&x| x as u64).collect();
+        for k in s..BITS {
+            let mut mk = ms[k - s] ^ (ms[k - s] << s);
+            for j in 1..s {
+                if poly >> (s - j) & 1 == 1 {
+                    mk ^= ms[k - j] << j;
+                }
+            }
+            ms.push(mk);
+        }
+
+        let v = ms.iter().take(BITS).enumerate()
+            .map(|(k, &mk)| mk << (BITS - 1 - k))
+            .collect();
+        DirectionNumbers::from_v(v)
+    }
+
+    /// Coordinate of the point with the given `index`, as a 64 bit binary fraction
+    #[inline]
+    fn coordinate(&self, index: u64) -> u64 {
+        let mut x = 0;
+        let mut i = index;
+        let mut k = 0;
+        while i != 0 {
+            if i & 1 == 1 {
+                x ^= self.v[k];
+            }
+            i >>= 1;
+            k += 1;
+        }
+        x
+    }
+
+    /// Coordinate of the point after `index`, given the coordinate `x` of point `index`
+    #[inline]
+    fn step(&self, x: u64, index: u64) -> u64 {
+        // Past the last index the coordinate wraps back to the origin
+        x ^ self.steps[(index.trailing_ones() as usize).min(BITS - 1)]
+    }
+}
+
+/// Largest number of dimensions of [`Sobol::new`], those of the embedded Joe–Kuo numbers
+///
+/// Sequences with more dimensions need the full `new-joe-kuo-6.21201` table, read with
+/// [`DirectionNumbers::read_joe_kuo`] and passed to [`Sobol::from_table`].
+///
+/// [`Sobol::new`]: struct.Sobol.html#method.new
+/// [`DirectionNumbers::read_joe_kuo`]: struct.DirectionNumbers.html#method.read_joe_kuo
+/// [`Sobol::from_table`]: struct.Sobol.html#method.from_table
+pub const MAX_DIMENSIONS: usize = JOE_KUO.len() + 1;
+
+/// Initial direction numbers of dimensions 2 to 21 from the Joe–Kuo `new-joe-kuo-6.21201`
+/// table, which pair up with the polynomials of `joe_kuo_polynomials` in order
+const JOE_KUO: [&[u32]; 20] = [
+    &[1],
+    &[1, 3],
+    &[1, 3, 1],
+    &[1, 1, 1],
+    &[1, 1, 3, 3],
+    &[1, 3, 5, 13],
+    &[1, 1, 5, 5, 17],
+    &[1, 1, 5, 5, 5],
+    &[1, 1, 7, 11, 19],
+    &[1, 1, 5, 1, 1],
+    &[1, 1, 1, 3, 11],
+    &[1, 3, 5, 5, 31],
+    &[1, 3, 3, 9, 7, 49],
+    &[1, 1, 1, 15, 21, 21],
+    &[1, 3, 1, 13, 27, 49],
+    &[1, 1, 1, 15, 7, 5],
+    &[1, 3, 1, 15, 13, 25],
+    &[1, 1, 5, 5, 19, 61],
+    &[1, 3, 7, 11, 23, 15, 103],
+    &[1, 3, 7, 13, 13, 15, 69],
+];
+
+/// Direction numbers of the first `dimensions` dimensions of [`Sobol::new`], at most
+/// [`MAX_DIMENSIONS`]
+fn default_direction_numbers(dimensions: usize) -> Vec<DirectionNumbers> {
+    let table = joe_kuo_polynomials().zip(JOE_KUO.iter())
+        .map(|(poly, m)| DirectionNumbers::from_recurrence(poly, m));
+    Some(DirectionNumbers::identity()).into_iter().chain(table).take(dimensions).collect()
+}
+
+/// Direction numbers of every dimension of [`Sobol::new`], built once and shared
+///
+/// [`Sobol::new`]: struct.Sobol.html#method.new
+fn embedded_table() -> &'static Arc<Vec<DirectionNumbers>> {
+    static TABLE: OnceLock<Arc<Vec<DirectionNumbers>>> = OnceLock::new();
+    TABLE.get_or_init(|| Arc::new(default_direction_numbers(MAX_DIMENSIONS)))
+}
+
+/// Sobol sequence built from explicit direction numbers
+///
+/// Implements [`Rng`] by returning the coordinates of consecutive points one after another,
+/// like an [`Interleave`] of its dimensions, and [`Iterator`] by returning whole points.  The
+/// sequence starts at the origin, point `0`, so that every prefix of `2^m` points is a digital
+/// net.  [`owen_scrambled`] randomizes the sequence while keeping that property.
+///
+/// Direction numbers, coordinates and the index all have 64 bits, so the sequence runs for
+/// `2^64` points before repeating and keeps resolving new digits past `2^32` points.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::sobol::{DirectionNumbers, Sobol};
+/// let mut sobol = Sobol::from_direction_numbers(vec![
+///     DirectionNumbers::identity(),
+///     DirectionNumbers::new(0b11, &[1]).unwrap(),
+/// ]).unwrap();
+///
+/// let points: Vec<Vec<f64>> = sobol.by_ref().take(4).collect();
+/// assert_eq!(points, vec![
+///     vec![0., 0.],
+///     vec![0.5, 0.5],
+///     vec![0.25, 0.75],
+///     vec![0.75, 0.25],
+/// ]);
+/// ```
+///
+/// [`owen_scrambled`]: #method.owen_scrambled
+/// [`Rng`]: ../../../rand/trait.Rng.html
+/// [`Interleave`]: ../struct.Interleave.html
+/// [`Iterator`]: https://doc.rust-lang.org/std/iter/trait.Iterator.html
+#[derive(Debug, Clone)]
+pub struct Sobol {
+    dimensions: Vec<DirectionNumbers>,
+    /// Direction numbers of every dimension the sequence can grow to, starting with its own
+    table: Arc<Vec<DirectionNumbers>>,
+    /// Seed of the Owen scrambling, if scrambled
+    seed: Option<u64>,
+    /// Owen scrambling seed of every dimension, empty unless scrambled
+    seeds: Vec<u64>,
+    /// Index of the point whose coordinates are being returned
+    index: u64,
+    /// Coordinates of the point with the current index
+    point: Vec<u64>,
+    /// Next coordinate of the current point
+    current: usize,
+}
+
+impl Sobol {
+    /// Sobol sequence in `dimensions` dimensions with the Joe–Kuo primitive polynomials
+    ///
+    /// Uses the direction numbers of the first 21 dimensions of the Joe–Kuo
+    /// `new-joe-kuo-6.21201` table, optimized for the quality of their two-dimensional
+    /// projections.  Load the full table with [`DirectionNumbers::read_joe_kuo`] and
+    /// [`from_table`] for more dimensions.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyGenerators`] for zero dimensions and [`Error::DimensionOverflow`]
+    /// for more than [`MAX_DIMENSIONS`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tapas::quasi::Sobol;
+    /// let mut sobol = Sobol::new(3).unwrap();
+    /// sobol.next();
+    /// assert_eq!(sobol.next(), Some(vec![0.5, 0.5, 0.5]));
+    /// assert_eq!(sobol.next(), Some(vec![0.25, 0.75, 0.75]));
+    /// ```
+    ///
+    /// [`DirectionNumbers::read_joe_kuo`]: struct.DirectionNumbers.html#method.read_joe_kuo
+    /// [`from_table`]: #method.from_table
+    /// [`Error::EmptyGenerators`]: ../../enum.Error.html#variant.EmptyGenerators
+    /// [`Error::DimensionOverflow`]: ../../enum.Error.html#variant.DimensionOverflow
+    /// [`MAX_DIMENSIONS`]: constant.MAX_DIMENSIONS.html
+    pub fn new(dimensions: usize) -> Result<Sobol> {
+        Sobol::with_table(embedded_table().clone(), dimensions)
+    }
+
+    /// Sobol sequence with one dimension per set of direction numbers
+    ///
+    /// The sequence has no further dimensions to [`push`]; use [`from_table`] for a sequence
+    /// which can grow.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyGenerators`] if no dimensions are given.
+    ///
+    /// [`push`]: #method.push_dimension
+    /// [`from_table`]: #method.from_table
+    /// [`Error::EmptyGenerators`]: ../../enum.Error.html#variant.EmptyGenerators
+    pub fn from_direction_numbers(dimensions: Vec<DirectionNumbers>) -> Result<Sobol> {
+        let d = dimensions.len();
+        Sobol::with_table(Arc::new(dimensions), d)
+    }
+
+    /// Sobol sequence in the first `dimensions` dimensions of `table`
+    ///
+    /// The remaining dimensions of the table are those added by [`push_dimension`], so a
+    /// sequence over a full table read with [`DirectionNumbers::read_joe_kuo`] can start small
+    /// and grow up to the size of the table.
+    ///
+    /// ```
+    /// # use tapas::quasi::Sobol;
+    /// # use tapas::quasi::sobol::DirectionNumbers;
+    /// let table = vec![DirectionNumbers::identity(), DirectionNumbers::new(0b11, &[1]).unwrap()];
+    /// let mut sobol = Sobol::from_table(table, 1).unwrap();
+    /// sobol.push_dimension().unwrap();
+    /// assert!(sobol.push_dimension().is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyGenerators`] for zero dimensions and [`Error::DimensionOverflow`]
+    /// for more dimensions than the table has.
+    ///
+    /// [`push_dimension`]: #method.push_dimension
+    /// [`DirectionNumbers::read_joe_kuo`]: struct.DirectionNumbers.html#method.read_joe_kuo
+    /// [`Error::EmptyGenerators`]: ../../enum.Error.html#variant.EmptyGenerators
+    /// [`Error::DimensionOverflow`]: ../../enum.Error.html#variant.DimensionOverflow
+    pub fn from_table(table: Vec<DirectionNumbers>, dimensions: usize) -> Result<Sobol> {
+        Sobol::with_table(Arc::new(table), dimensions)
+    }
+
+    fn with_table(table: Arc<Vec<DirectionNumbers>>, dimensions: usize) -> Result<Sobol> {
+        if dimensions == 0 {
+            return Err(Error::EmptyGenerators);
+        }
+        if dimensions > table.len() {
+            return Err(Error::DimensionOverflow { requested: dimensions, supported: table.len() });
+        }
+        Ok(Sobol {
+            dimensions: table[..dimensions].to_vec(),
+            table,
+            seed: None,
+            seeds: Vec::new(),
+            index: 0,
+            point: vec![0; dimensions],
+            current: 0,
+        })
+    }
+
+    /// Number of dimensions of each point
+    pub fn dimensions(&self) -> usize {
+        self.dimensions.len()
+    }
+
+    /// Owen scramble every dimension, with independent scrambles derived from `seed`
+    ///
+    /// Nested uniform scrambling randomizes the sequence so that every point is uniformly
+    /// distributed, while every prefix of `2^m` points remains a digital net.  Averages over
+    /// independently seeded replicates are unbiased, and their spread estimates the error of
+    /// randomized quasi-Monte Carlo integration.  Each coordinate is scrambled in constant time
+    /// by the hash of Laine, Karras and Burley rather than an explicit permutation tree.
+    ///
+    /// ```
+    /// # use tapas::quasi::Sobol;
+    /// let points: Vec<Vec<f64>> = Sobol::new(2).unwrap().owen_scrambled(7).take(4).collect();
+    ///
+    /// // Still one point in each half of both axes for every pair of points
+    /// assert!((points[0][0] < 0.5) != (points[1][0] < 0.5));
+    /// assert!((points[2][1] < 0.5) != (points[3][1] < 0.5));
+    /// ```
+    ///
+    /// # References
+    /// - Owen, A. B., Randomly permuted (t,m,s)-nets and (t,s)-sequences
+    /// - Burley, B., Practical Hash-based Owen Scrambling
+    pub fn owen_scrambled(mut self, seed: u64) -> Sobol {
+        self.seeds = (0..self.dimensions.len()).map(|j| dimension_seed(seed, j)).collect();
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Add the next dimension of the sequence's table to every point, including the current one
+    ///
+    /// The table is the embedded one for [`new`], or the one given to [`from_table`].
+    /// Adaptive algorithms which only find out that they need another dimension partway
+    /// through can keep their points: the sequence continues as if it had been built with one
+    /// more dimension, and [`point`] reproduces the new coordinate of earlier points.  An
+    /// Owen scrambled sequence scrambles the new dimension as [`owen_scrambled`] would have.
+    ///
+    /// ```
+    /// # use tapas::quasi::Sobol;
+    /// let mut sobol = Sobol::new(2).unwrap();
+    /// let first: Vec<Vec<f64>> = sobol.by_ref().take(3).collect();
+    ///
+    /// sobol.push_dimension().unwrap();
+    /// let mut wider = Sobol::new(3).unwrap();
+    /// assert_eq!(sobol.point(2), wider.nth(2).unwrap());
+    /// assert_eq!(sobol.next(), wider.next());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::DimensionOverflow`] if the sequence already has every dimension of its
+    /// table, which for [`new`] has [`MAX_DIMENSIONS`].
+    ///
+    /// [`new`]: #method.new
+    /// [`from_table`]: #method.from_table
+    /// [`point`]: #method.point
+    /// [`owen_scrambled`]: #method.owen_scrambled
+    /// [`Error::DimensionOverflow`]: ../../enum.Error.html#variant.DimensionOverflow
+    /// [`MAX_DIMENSIONS`]: constant.MAX_DIMENSIONS.html
+    pub fn push_dimension(&mut self) -> Result<()> {
+        let j = self.dimensions.len();
+        if j >= self.table.len() {
+            return Err(Error::DimensionOverflow { requested: j + 1, supported: self.table.len() });
+        }
+        let dim = self.table[j].clone();
+        self.point.push(dim.coordinate(self.index));
+        self.dimensions.push(dim);
+        if let Some(seed) = self.seed {
+            self.seeds.push(dimension_seed(seed, j));
+        }
+        Ok(())
+    }
+
+    /// Point `i` of the sequence, which need not be the next one
+    pub fn point(&self, i: u64) -> Vec<f64> {
+        self.dimensions.iter().enumerate()
+            .map(|(j, dim)| bits_to_f64(self.scramble(j, dim.coordinate(i))))
+            .collect()
+    }
+
+    /// Owen scramble coordinate `x` of dimension `j`, if the sequence is scrambled
+    #[inline]
+    fn scramble(&self, j: usize, x: u64) -> u64 {
+        match self.seeds.get(j) {
+            Some(&seed) => nested_uniform_scramble_64(x, seed),
+            None => x,
+        }
+    }
+
+    /// Next coordinate as a 64 bit binary fraction
+    fn next_coordinate(&mut self) -> u64 {
+        let x = self.scramble(self.current, self.point[self.current]);
+        self.current += 1;
+        if self.current == self.dimensions.len() {
+            self.current = 0;
+            self.advance();
+        }
+        x
+    }
+
+    /// Move every coordinate to the next point with a single XOR per dimension
+    ///
+    /// This is the Gray-code update of Antonov and Saleev kept in the natural order: the bits
+    /// flipped by an increment are always the trailing ones and the zero above them, so the
+    /// combined direction numbers of every such run are precomputed.
+    #[inline]
+    fn advance(&mut self) {
+        let index = self.index;
+        for (x, dim) in self.point.iter_mut().zip(self.dimensions.iter()) {
+            *x = dim.step(*x, index);
+        }
+        self.index = index.wrapping_add(1);
+    }
+}
+
+impl Digital for Sobol {
+    fn next_bits(&mut self) -> u64 {
+        self.next_coordinate()
+    }
+}
+
+impl Rng for Sobol {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_coordinate() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.next_bits()
+    }
+
+    fn next_f32(&mut self) -> f32 {
+        bits_to_f32(self.next_coordinate())
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        bits_to_f64(self.next_coordinate())
+    }
+}
+
+impl JumpAhead for Sobol {
+    /// Skip `n` coordinates, which need not be whole points
+    fn jump(&mut self, n: u64) {
+        let d = self.dimensions.len() as u64;
+        let target = self.current as u64 + n;
+        self.current = (target % d) as usize;
+        if target >= d {
+            self.index = self.index.wrapping_add(target / d);
+            for (x, dim) in self.point.iter_mut().zip(self.dimensions.iter()) {
+                *x = dim.coordinate(self.index);
+            }
+        }
+    }
+}
+
+impl Iterator for Sobol {
+    type Item = Vec<f64>;
+
+    /// Remaining coordinates of the current point, or the next whole point
+    fn next(&mut self) -> Option<Vec<f64>> {
+        let n = self.dimensions.len() - self.current;
+        Some((0..n).map(|_| self.next_f64()).collect())
+    }
+}
+
+/// Number of dimensions of [`sobol_owen`], those of the embedded Joe–Kuo table
+///
+/// [`sobol_owen`]: fn.sobol_owen.html
+pub const SOBOL_OWEN_DIMENSIONS: usize = MAX_DIMENSIONS;
+
+/// Shuffled and Owen scrambled Sobol coordinate looked up without any generator state
+///
+/// Coordinate `dim` of point `index` of a Sobol sequence, following Burley: the index is first
+/// shuffled by a nested uniform scramble of its bits, and the coordinate of the shuffled index
+/// is Owen scrambled, with independent scrambles of every dimension derived from `seed`.  The
+/// shuffle only permutes aligned blocks of indices, so the first `2^m` indices are still a
+/// block of the sequence and form a digital net in every pair of the first dimensions.
+///
+/// Each call costs a few hashes and one XOR per bit of the index, and only reads a table of
+/// direction numbers shared by all threads, so it suits parallel and GPU-style renderers
+/// where every sample is computed independently from its pixel, sample number and dimension.
+/// Problems needing more than [`SOBOL_OWEN_DIMENSIONS`] dimensions, such as deep light paths,
+/// can look coordinates up in a larger table with [`sobol_owen_table`].
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::sobol_owen;
+/// // Four samples of a pixel put one in each quadrant
+/// let seed = 0x5eed;
+/// let mut quadrants = [false; 4];
+/// for i in 0..4 {
+///     let (x, y) = (sobol_owen(i, 0, seed), sobol_owen(i, 1, seed));
+///     quadrants[(2. * x) as usize * 2 + (2. * y) as usize] = true;
+/// }
+/// assert!(quadrants.iter().all(|&q| q));
+///
+/// // The same arguments always give the same value
+/// assert_eq!(sobol_owen(3, 2, seed), sobol_owen(3, 2, seed));
+/// ```
+///
+/// # Panics
+///
+/// If `dim` is not below [`SOBOL_OWEN_DIMENSIONS`].
+///
+/// # References
+/// - Burley, B., Practical Hash-based Owen Scrambling
+///
+/// [`SOBOL_OWEN_DIMENSIONS`]: constant.SOBOL_OWEN_DIMENSIONS.html
+/// [`sobol_owen_table`]: fn.sobol_owen_table.html
+pub fn sobol_owen(index: u64, dim: usize, seed: u64) -> f64 {
+    assert!(dim < SOBOL_OWEN_DIMENSIONS,
+        "dimension {} of {} without state", dim, SOBOL_OWEN_DIMENSIONS);
+    sobol_owen_table(embedded_table(), index, dim, seed)
+}
+
+/// [`sobol_owen`] over the dimensions of `table`, such as a full Joe–Kuo table
+///
+/// Gives the same values as [`sobol_owen`] for a table starting with the embedded dimensions,
+/// and extends it to every dimension of the table.  Read the table once, for example with
+/// [`DirectionNumbers::read_joe_kuo`], and share it between threads.
+///
+/// # Panics
+///
+/// If `dim` is not below the number of dimensions of `table`.
+///
+/// [`sobol_owen`]: fn.sobol_owen.html
+/// [`DirectionNumbers::read_joe_kuo`]: struct.DirectionNumbers.html#method.read_joe_kuo
+pub fn sobol_owen_table(table: &[DirectionNumbers], index: u64, dim: usize, seed: u64) -> f64 {
+    assert!(dim < table.len(), "dimension {} of a {}-dimensional table", dim, table.len());
+
+    let shuffled = nested_uniform_scramble_64(index, mix64(seed));
+    let x = table[dim].coordinate(shuffled);
+    bits_to_f64(nested_uniform_scramble_64(x, dimension_seed(seed, dim)))
+}
+
+/// Owen scrambling seed of dimension `j` derived from `seed`
+#[inline]
+fn dimension_seed(seed: u64, j: usize) -> u64 {
+    mix64(seed ^ (j as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Primitive polynomials of degree 1 to 4, from the tables of Joe and Kuo
+    const PRIMITIVE: [u64; 6] = [0b11, 0b111, 0b1011, 0b1101, 0b10011, 0b11001];
+
+    #[test]
+    fn primitive_polynomials() {
+        for &p in PRIMITIVE.iter() {
+            assert!(is_primitive(p), "{:#b} should be primitive", p);
+        }
+        // There are 16 primitive polynomials of degree 8
+        assert_eq!((256..512).filter(|&p| is_primitive(p)).count(), 16);
+        assert!(is_primitive((1 << 31) | (1 << 3) | 1));
+    }
+
+    #[test]
+    fn polynomials_in_table_order() {
+        // Degree and coefficient columns of the first rows of the Joe–Kuo table
+        let table = [(1, 0), (2, 1), (3, 1), (3, 2), (4, 1), (4, 4), (5, 2), (5, 4), (5, 7),
+            (5, 11), (5, 13), (5, 14), (6, 1), (6, 13), (6, 16), (6, 19), (6, 22), (6, 25),
+            (7, 1), (7, 4)];
+        let polys: Vec<u64> = joe_kuo_polynomials().take(JOE_KUO.len()).collect();
+        for (&poly, &(s, a)) in polys.iter().zip(table.iter()) {
+            assert_eq!(poly, 1 << s | a << 1 | 1);
+        }
+        for (&poly, m) in polys.iter().zip(JOE_KUO.iter()) {
+            assert!(DirectionNumbers::new(poly, m).is_ok());
+        }
+    }
+
+    #[test]
+    fn last_dimension_follows_table() {
+        // Point 2^(k-1) of a dimension is m_k / 2^k; dimension 21 starts with m = 1 3 7 13
+        let sobol = Sobol::new(MAX_DIMENSIONS).unwrap();
+        let last = |i| sobol.point(i)[MAX_DIMENSIONS - 1];
+        assert_eq!((last(1), last(2), last(4), last(8)), (0.5, 3. / 4., 7. / 8., 13. / 16.));
+    }
+
+    #[test]
+    fn dimension_limits() {
+        assert!(matches!(Sobol::new(0), Err(Error::EmptyGenerators)));
+        assert!(matches!(Sobol::new(MAX_DIMENSIONS + 1),
+            Err(Error::DimensionOverflow { supported: MAX_DIMENSIONS, .. })));
+    }
+
+    #[test]
+    fn two_dimensional_projections_are_nets() {
+        // Dimensions j and k with polynomials of degrees s_j and s_k form a (t,m,2)-net with
+        // t = (s_j - 1) + (s_k - 1): every elementary interval of area 2^(t-m) holds 2^t points
+        let m = 14;
+        let points: Vec<Vec<u64>> = Sobol::new(MAX_DIMENSIONS).unwrap().take(1 << m)
+            .map(|p| p.iter().map(|x| (x * (1 << m) as f64) as u64).collect())
+            .collect();
+        let defect: Vec<u32> = Some(0).into_iter()
+            .chain(joe_kuo_polynomials().take(MAX_DIMENSIONS - 1).map(|p| degree(p) - 1))
+            .collect();
+
+        for j in 0..MAX_DIMENSIONS {
+            for k in j + 1..MAX_DIMENSIONS {
+                let t = defect[j] + defect[k];
+                for a in 0..=m - t {
+                    let b = m - t - a;
+                    let mut counts = vec![0; 1 << (m - t)];
+                    for p in points.iter() {
+                        counts[(p[j] >> (m - a) << b | p[k] >> (m - b)) as usize] += 1;
+                    }
+                    assert!(counts.iter().all(|&c| c == 1 << t),
+                        "dimensions {} and {} with {} by {} intervals", j, k, 1 << a, 1 << b);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn reducible_or_imprimitive() {
+        for &p in [0b1, 0b10, 0b110, 0b101, 0b1111, 0b11111, 0b10001].iter() {
+            assert!(!is_primitive(p), "{:#b} should not be primitive", p);
+        }
+    }
+
+    #[test]
+    fn invalid_initial_numbers() {
+        let cases: [(u64, &[u32]); 4] = [
+            (0b111, &[1]),
+            (0b111, &[2, 1]),
+            (0b111, &[1, 5]),
+            (0b1011, &[1, 3, 8]),
+        ];
+        for &(p, m) in cases.iter() {
+            assert!(matches!(DirectionNumbers::new(p, m), Err(Error::InvalidDirectionNumbers(_))),
+                "{:#b} with {:?}", p, m);
+        }
+    }
+
+    #[test]
+    fn prefixes_are_nets() {
+        // Every prefix of 2^m points puts one point in each elementary interval of size 2^-m
+        // along each axis
+        let dims = vec![
+            DirectionNumbers::identity(),
+            DirectionNumbers::new(0b11, &[1]).unwrap(),
+            DirectionNumbers::new(0b111, &[1, 3]).unwrap(),
+            DirectionNumbers::new(0b1011, &[1, 3, 1]).unwrap(),
+        ];
+        let sobol = Sobol::from_direction_numbers(dims).unwrap();
+        let points: Vec<Vec<f64>> = sobol.take(256).collect();
+        for j in 0..4 {
+            let mut strata = vec![false; 256];
+            for p in points.iter() {
+                strata[(p[j] * 256.) as usize] = true;
+            }
+            assert!(strata.iter().all(|&s| s), "dimension {} is not stratified", j);
+        }
+    }
+
+    #[test]
+    fn owen_scrambling_keeps_nets() {
+        let scrambled = Sobol::new(3).unwrap().owen_scrambled(1234);
+        let other = Sobol::new(3).unwrap().owen_scrambled(1235);
+        let points: Vec<Vec<f64>> = scrambled.take(256).collect();
+        let others: Vec<Vec<f64>> = other.take(256).collect();
+        assert!(points != others);
+
+        // Two dimensional elementary intervals of area 2^-8 each hold one point
+        for k in 0..9 {
+            let mut strata = vec![false; 256];
+            for p in points.iter() {
+                let x = (p[0] * (1 << k) as f64) as usize;
+                let y = (p[1] * (1 << (8 - k)) as f64) as usize;
+                strata[x << (8 - k) | y] = true;
+            }
+            assert!(strata.iter().all(|&s| s), "{} by {} intervals", 1 << k, 1 << (8 - k));
+        }
+    }
+
+    #[test]
+    fn owen_scrambling_is_unbiased() {
+        // The first point is the scrambled origin, uniform over seeds
+        let n = 4000;
+        let mean = (0..n)
+            .map(|seed| Sobol::new(1).unwrap().owen_scrambled(seed).next_f64())
+            .sum::<f64>() / n as f64;
+        abs_err_eq!(mean == 0.5 ~ (4. / (12. * n as f64).sqrt()));
+    }
+
+    #[test]
+    fn incremental_matches_index() {
+        let mut sobol = Sobol::new(5).unwrap();
+        for i in 0..3000 {
+            for j in 0..5 {
+                assert_eq!(sobol.next_u64(), sobol.dimensions[j].coordinate(i));
+            }
+        }
+
+        // The last index wraps back to the origin
+        for _ in 0..5 {
+            sobol.jump(u64::MAX - 3000);
+        }
+        assert_eq!(sobol.index, u64::MAX);
+        let last: Vec<u64> = (0..5).map(|_| sobol.next_u64()).collect();
+        assert!(last.iter().all(|&x| x != 0));
+        assert!((0..5).all(|_| sobol.next_u64() == 0));
+    }
+
+    #[test]
+    fn past_two_to_the_32() {
+        // Points beyond 2^32 are new, and resolved below 2^-32
+        let mut sobol = Sobol::new(2).unwrap();
+        sobol.jump(2 << 32);
+        assert_eq!(sobol.next_f64(), 0.5f64.powi(33));
+        // Direction number v_33 = m_33 2^31 with m_33 odd
+        assert_eq!(sobol.next_u64().trailing_zeros(), 31);
+        assert_eq!(sobol.next_u64(), 1 << 63 | 1 << 31);
+    }
+
+    #[cfg(feature = "io")]
+    #[test]
+    fn reads_joe_kuo_tables() {
+        let table = "d       s       a       m_i\n\
+                     2       1       0       1\n\
+                     3       2       1       1 3\n\
+                     4       3       1       1 3 1\n\
+                     \n\
+                     5       3       2       1 1 1  # x³ + x² + 1\n";
+        let dims = DirectionNumbers::read_joe_kuo(table.as_bytes()).unwrap();
+        assert_eq!(dims, default_direction_numbers(5));
+
+        let reducible = DirectionNumbers::read_joe_kuo("2 2 0 1 1\n".as_bytes());
+        assert!(matches!(reducible, Err(Error::Parse { line: 1, .. })));
+        let short = DirectionNumbers::read_joe_kuo("2 1 0 1\n3 2 1 1\n".as_bytes());
+        assert!(matches!(short, Err(Error::Parse { line: 2, .. })));
+        let garbage = DirectionNumbers::read_joe_kuo("2 1 zero 1\n".as_bytes());
+        assert!(matches!(garbage, Err(Error::Parse { line: 1, .. })));
+        let skipped = DirectionNumbers::read_joe_kuo("2 1 0 1\n4 2 1 1 3\n".as_bytes());
+        assert!(matches!(skipped, Err(Error::Parse { line: 2, .. })));
+        let repeated = DirectionNumbers::read_joe_kuo("2 1 0 1\n2 2 1 1 3\n".as_bytes());
+        assert!(matches!(repeated, Err(Error::Parse { line: 2, .. })));
+    }
+
+    #[test]
+    fn jump_matches_drawing() {
+        let dims = vec![
+            DirectionNumbers::identity(),
+            DirectionNumbers::new(0b111, &[1, 1]).unwrap(),
+        ];
+        let mut jumped = Sobol::from_direction_numbers(dims).unwrap();
+        let mut drawn = jumped.clone();
+        jumped.jump(7);
+        for _ in 0..7 {
+            drawn.next_u32();
+        }
+        for _ in 0..10 {
+            assert_eq!(jumped.next_u32(), drawn.next_u32());
+        }
+    }
+
+    #[test]
+    fn stateless_lookup_stratifies() {
+        let m = 8;
+        for &seed in [0, 1, 0xdead_beef].iter() {
+            let bits = |i: u64, j: usize| (sobol_owen(i, j, seed) * (1 << m) as f64) as u64;
+            // Every elementary interval of area 2^-m holds one of the first 2^m points of the
+            // first two dimensions, and every dimension alone is stratified
+            for k in 0..=m {
+                let mut boxes = vec![false; 1 << m];
+                for i in 0..1 << m {
+                    boxes[(bits(i, 0) >> (m - k) << (m - k) | bits(i, 1) >> k) as usize] = true;
+                }
+                assert!(boxes.iter().all(|&b| b), "seed {} k {}", seed, k);
+            }
+            for j in 0..SOBOL_OWEN_DIMENSIONS {
+                let mut strata = vec![false; 1 << m];
+                for i in 0..1 << m {
+                    strata[bits(i, j) as usize] = true;
+                }
+                assert!(strata.iter().all(|&s| s));
+            }
+        }
+        assert!(sobol_owen(5, 3, 1) != sobol_owen(5, 3, 2));
+        assert!(sobol_owen(5, 3, 1) != sobol_owen(5, 4, 1));
+    }
+
+    #[test]
+    fn stateless_lookup_is_unbiased() {
+        let n = 4000;
+        let mean = (0..n).map(|seed| sobol_owen(0, 7, seed)).sum::<f64>() / n as f64;
+        abs_err_eq!(mean == 0.5 ~ (4. / (12. * n as f64).sqrt()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn stateless_lookup_dimensions() {
+        sobol_owen(0, SOBOL_OWEN_DIMENSIONS, 0);
+    }
+
+    #[test]
+    fn stateless_lookup_in_larger_tables() {
+        let mut table = default_direction_numbers(MAX_DIMENSIONS);
+        table.extend(joe_kuo_polynomials().skip(MAX_DIMENSIONS - 1).take(43).map(|poly| {
+            DirectionNumbers::new(poly, &vec![1; degree(poly) as usize]).unwrap()
+        }));
+        let m = 8;
+        let seed = 0x5eed;
+        for i in 0..1 << m {
+            let x = sobol_owen_table(&table, i, 3, seed);
+            assert_eq!(x, sobol_owen(i, 3, seed));
+        }
+        for j in 60..64 {
+            let mut strata = vec![false; 1 << m];
+            for i in 0..1 << m {
+                strata[(sobol_owen_table(&table, i, j, seed) * (1 << m) as f64) as usize] = true;
+            }
+            assert!(strata.iter().all(|&s| s), "dimension {}", j);
+        }
+    }
+
+    #[test]
+    fn pushed_dimensions_match_wider_sequences() {
+        for &scrambled in [false, true].iter() {
+            let build = |d| {
+                let sobol = Sobol::new(d).unwrap();
+                if scrambled { sobol.owen_scrambled(3) } else { sobol }
+            };
+            let mut narrow = build(17);
+            let mut wide = build(20);
+            narrow.jump(17 * 100 + 7);
+            wide.jump(20 * 100 + 7);
+            for _ in 0..3 {
+                narrow.push_dimension().unwrap();
+            }
+            assert_eq!(narrow.dimensions(), 20);
+            for _ in 0..100 {
+                assert_eq!(narrow.next_u64(), wide.next_u64());
+            }
+            assert_eq!(narrow.point(41), wide.point(41));
+        }
+
+        let mut full = Sobol::new(MAX_DIMENSIONS).unwrap();
+        assert!(matches!(full.push_dimension(), Err(Error::DimensionOverflow { .. })));
+
+        // Sequences grow up to the size of their own table
+        let mut table = default_direction_numbers(MAX_DIMENSIONS);
+        table.extend(joe_kuo_polynomials().skip(MAX_DIMENSIONS - 1).take(9).map(|poly| {
+            DirectionNumbers::new(poly, &vec![1; degree(poly) as usize]).unwrap()
+        }));
+        let mut grown = Sobol::from_table(table.clone(), 2).unwrap();
+        for _ in 2..30 {
+            grown.push_dimension().unwrap();
+        }
+        assert!(grown.take(64).eq(Sobol::from_direction_numbers(table).unwrap().take(64)));
+
+        let mut fixed = Sobol::from_direction_numbers(default_direction_numbers(3)).unwrap();
+        assert!(matches!(fixed.push_dimension(),
+            Err(Error::DimensionOverflow { requested: 4, supported: 3 })));
+        assert!(matches!(Sobol::from_table(default_direction_numbers(3), 4),
+            Err(Error::DimensionOverflow { requested: 4, supported: 3 })));
+    }
+}
@@ -0,0 +1,187 @@
+use rand::Rng;
+
+use error::{Error, Result};
+use super::{JumpAhead, Peek};
+use super::radical::{folded_radical_inverse, radical_inverse};
+
+/// Element `index` of the van der Corput sequence in `base`, its radical inverse
+///
+/// # Panics
+///
+/// If `base < 2`.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::van_der_corput;
+/// assert_eq!(van_der_corput(0, 2), 0.);
+/// assert_eq!(van_der_corput(3, 2), 0.75);
+/// assert_eq!(van_der_corput(4, 3), 4. / 9.);
+/// ```
+pub fn van_der_corput(index: u64, base: u32) -> f64 {
+    if base == 2 {
+        // Bit reversal is the radical inverse in base 2, exact to 53 significant bits
+        return (index.reverse_bits() >> 11) as f64 / (1u64 << 53) as f64;
+    }
+    radical_inverse(base, index)
+}
+
+/// Van der Corput sequence evaluated directly from its index
+///
+/// Where [`Halton`] keeps digits and remainders to update its value incrementally, `VdC` only
+/// stores the index and computes every value with [`van_der_corput`].  That costs a few more
+/// operations per value, but the generator is two words in size, jumps for free and is cheap
+/// to create by the thousand.  The sequence starts at index `0`, the value `0`.
+///
+/// [`VdC::folded`] evaluates the folded radical inverse instead; interleaving folded
+/// sequences in distinct prime bases gives the folded Halton sequence.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::VdC;
+/// let values: Vec<f64> = VdC::new(2).unwrap().take(4).collect();
+/// assert_eq!(values, vec![0., 0.5, 0.25, 0.75]);
+/// ```
+///
+/// [`Halton`]: ../halton/struct.Halton.html
+/// [`van_der_corput`]: fn.van_der_corput.html
+/// [`VdC::folded`]: #method.folded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VdC {
+    base: u32,
+    index: u64,
+    folded: bool,
+}
+
+impl VdC {
+    /// Van der Corput sequence in `base`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidBase`] if `base < 2`.
+    ///
+    /// [`Error::InvalidBase`]: ../../enum.Error.html#variant.InvalidBase
+    pub fn new(base: u32) -> Result<VdC> {
+        if base < 2 {
+            return Err(Error::InvalidBase(base));
+        }
+        Ok(VdC { base, index: 0, folded: false })
+    }
+
+    /// Folded van der Corput sequence in `base`, which starts away from `0`
+    ///
+    /// See [`folded_radical_inverse`] for details.
+    ///
+    /// ```
+    /// # use tapas::quasi::VdC;
+    /// let mut zaremba = VdC::folded(2).unwrap();
+    /// assert!((zaremba.next().unwrap() - 1. / 3.).abs() < 1e-15);
+    /// assert!(zaremba.is_folded());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidBase`] if `base < 2`.
+    ///
+    /// [`folded_radical_inverse`]: ../radical/fn.folded_radical_inverse.html
+    /// [`Error::InvalidBase`]: ../../enum.Error.html#variant.InvalidBase
+    pub fn folded(base: u32) -> Result<VdC> {
+        VdC::new(base).map(|vdc| VdC { folded: true, ..vdc })
+    }
+
+    /// Base of the radical inverse
+    pub fn base(&self) -> u32 {
+        self.base
+    }
+
+    /// Index of the next value
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+
+    /// Whether values are folded radical inverses
+    pub fn is_folded(&self) -> bool {
+        self.folded
+    }
+}
+
+impl Rng for VdC {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_f64() * u32::MAX as f64).floor() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        (self.next_f64() * u64::MAX as f64).floor() as u64
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        let value = self.peek();
+        self.index = self.index.wrapping_add(1);
+        value
+    }
+}
+
+impl Peek for VdC {
+    fn peek(&self) -> f64 {
+        if self.folded {
+            folded_radical_inverse(self.base, self.index)
+        } else {
+            van_der_corput(self.index, self.base)
+        }
+    }
+}
+
+impl JumpAhead for VdC {
+    fn jump(&mut self, n: u64) {
+        self.index = self.index.wrapping_add(n);
+    }
+}
+
+impl Iterator for VdC {
+    type Item = f64;
+
+    fn next(&mut self) -> Option<f64> {
+        Some(self.next_f64())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::Halton;
+
+    #[test]
+    fn invalid_base() {
+        assert!(matches!(VdC::new(1), Err(Error::InvalidBase(1))));
+    }
+
+    #[test]
+    fn matches_halton() {
+        for &base in [2, 3, 11].iter() {
+            let mut vdc = VdC::new(base).unwrap();
+            vdc.jump(1);
+            for (v, h) in vdc.zip(Halton::new(1, base).unwrap()).take(1000) {
+                abs_err_eq!(v == h ~ 1e-15);
+            }
+        }
+    }
+
+    #[test]
+    fn folded_sequence() {
+        assert!(matches!(VdC::folded(0), Err(Error::InvalidBase(0))));
+        let mut folded = VdC::folded(5).unwrap();
+        folded.jump(100);
+        assert_eq!(folded.index(), 100);
+        for i in 100..200 {
+            assert_eq!(folded.next_f64(), folded_radical_inverse(5, i));
+        }
+    }
+
+    #[test]
+    fn base_two_bit_reversal() {
+        for &i in [1u64, 12_345, 1 << 40, (1 << 53) - 1].iter() {
+            assert_eq!(van_der_corput(i, 2), radical_inverse(2, i));
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use rand::Rng;
+
+use super::{Digital, JumpAhead};
+use super::digital::{bits_to_f32, bits_to_f64, mix64};
+use super::owen::nested_uniform_scramble_64;
+
+/// Owen scrambled two dimensional (0,2)-sequence for per-pixel sampling
+///
+/// The first two dimensions of the Sobol sequence, each Owen scrambled with a seed derived
+/// from the seed of the instance.  Every prefix of `2^m` points is a (0,m,2)-net, so any
+/// power of two samples puts exactly one point in every elementary interval of area `2^-m`,
+/// while giving every pixel its own seed decorrelates the patterns of neighbouring pixels.
+///
+/// Points are computed directly from their index with [`point`], without any state.  The
+/// sequence equals `Sobol::new(2).unwrap().owen_scrambled(seed)`, only specialized for two
+/// dimensions.
+///
+/// Implements [`Rng`] by returning the coordinates of consecutive points one after another, and
+/// [`Iterator`] by returning whole points, skipping the second coordinate of a point whose
+/// first coordinate was already drawn.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::Zero2Sequence;
+/// let pixel = Zero2Sequence::new(17);
+///
+/// // Four samples, one in each quadrant of the pixel
+/// let mut quadrants = [false; 4];
+/// for i in 0..4 {
+///     let [x, y] = pixel.point(i);
+///     quadrants[(2. * x) as usize * 2 + (2. * y) as usize] = true;
+/// }
+/// assert!(quadrants.iter().all(|&q| q));
+///
+/// // Iterating gives the same points
+/// assert_eq!(pixel.clone().nth(3), Some(pixel.point(3)));
+/// ```
+///
+/// # References
+/// - Burley, B., Practical Hash-based Owen Scrambling
+///
+/// [`point`]: #method.point
+/// [`Rng`]: ../../../rand/trait.Rng.html
+/// [`Iterator`]: https://doc.rust-lang.org/std/iter/trait.Iterator.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Zero2Sequence {
+    seed: u64,
+    /// Owen scrambling seeds of both dimensions
+    seeds: [u64; 2],
+    /// Index of the point whose coordinates are being returned
+    index: u64,
+    /// Next coordinate of the current point
+    current: usize,
+}
+
+impl Zero2Sequence {
+    /// Scrambled sequence for the given `seed`, starting at the first point
+    pub fn new(seed: u64) -> Zero2Sequence {
+        let hash = |j: u64| mix64(seed ^ (j + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+        Zero2Sequence { seed, seeds: [hash(0), hash(1)], index: 0, current: 0 }
+    }
+
+    /// Seed of the instance
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Point with the given `index`
+    #[inline]
+    pub fn point(&self, index: u64) -> [f64; 2] {
+        [bits_to_f64(self.coordinate(index, 0)), bits_to_f64(self.coordinate(index, 1))]
+    }
+
+    /// Coordinate `j` of point `index` as a 64 bit binary fraction
+    #[inline]
+    fn coordinate(&self, index: u64, j: usize) -> u64 {
+        let x = if j == 0 { index.reverse_bits() } else { pascal(index) };
+        nested_uniform_scramble_64(x, self.seeds[j])
+    }
+
+    /// Next coordinate as a 64 bit binary fraction
+    fn next_coordinate(&mut self) -> u64 {
+        let x = self.coordinate(self.index, self.current);
+        self.current += 1;
+        if self.current == 2 {
+            self.current = 0;
+            self.index = self.index.wrapping_add(1);
+        }
+        x
+    }
+}
+
+/// Second Sobol dimension, whose generating matrix is Pascal's triangle modulo 2
+#[inline]
+pub(crate) fn pascal(mut index: u64) -> u64 {
+    let mut v = 1 << 63;
+    let mut x = 0;
+    while index != 0 {
+        if index & 1 == 1 {
+            x ^= v;
+        }
+        index >>= 1;
+        v ^= v >> 1;
+    }
+    x
+}
+
+impl Digital for Zero2Sequence {
+    fn next_bits(&mut self) -> u64 {
+        self.next_coordinate()
+    }
+}
+
+impl Rng for Zero2Sequence {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_coordinate() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.next_coordinate()
+    }
+
+    fn next_f32(&mut self) -> f32 {
+        bits_to_f32(self.next_coordinate())
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        bits_to_f64(self.next_coordinate())
+    }
+}
+
+impl JumpAhead for Zero2Sequence {
+    /// Skip `n` coordinates, which need not be whole points
+    fn jump(&mut self, n: u64) {
+        let target = self.current as u64 + n;
+        self.index = self.index.wrapping_add(target / 2);
+        self.current = (target % 2) as usize;
+    }
+}
+
+impl Iterator for Zero2Sequence {
+    type Item = [f64; 2];
+
+    fn next(&mut self) -> Option<[f64; 2]> {
+        if self.current != 0 {
+            self.current = 0;
+            self.index = self.index.wrapping_add(1);
+        }
+        let p = self.point(self.index);
+        self.index = self.index.wrapping_add(1);
+        Some(p)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<[f64; 2]> {
+        self.jump(2 * n as u64);
+        self.next()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::Sobol;
+
+    #[test]
+    fn matches_scrambled_sobol() {
+        let mut sobol = Sobol::new(2).unwrap().owen_scrambled(5);
+        let mut seq = Zero2Sequence::new(5);
+        for _ in 0..1000 {
+            assert_eq!(seq.next_u64(), sobol.next_u64());
+        }
+    }
+
+    #[test]
+    fn prefixes_are_nets() {
+        let seq = Zero2Sequence::new(123);
+        for k in 0..7 {
+            let mut strata = [false; 64];
+            for i in 0..64 {
+                let [x, y] = seq.point(i);
+                let sx = (x * (1 << k) as f64) as usize;
+                let sy = (y * (1 << (6 - k)) as f64) as usize;
+                strata[sx << (6 - k) | sy] = true;
+            }
+            assert!(strata.iter().all(|&s| s));
+        }
+    }
+
+    #[test]
+    fn seeds_decorrelate() {
+        let a = Zero2Sequence::new(1);
+        let b = Zero2Sequence::new(2);
+        assert!((0..16).all(|i| a.point(i) != b.point(i)));
+        assert_eq!(a.seed(), 1);
+    }
+
+    #[test]
+    fn drawing_mixes_with_iteration() {
+        let mut seq = Zero2Sequence::new(9);
+        let x = seq.next_f64();
+        assert_eq!(seq.next(), Some(seq.point(1)));
+        assert_eq!(x, seq.point(0)[0]);
+        seq.jump(3);
+        assert_eq!(seq.next_f64(), seq.point(3)[1]);
+    }
+}
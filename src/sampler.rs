@@ -0,0 +1,865 @@
+//! # Automatic Sampler Selection
+//!
+//! Pick a reasonable quasi-random construction from the number of dimensions and the expected
+//! number of points, without studying the literature first.
+//!
+//! [`Sampler::auto`] records why it chose a construction in a [`Decision`], so the choice can
+//! be logged, inspected in tests, or overridden once the defaults are outgrown.
+//!
+//! Every generator also implements the object-safe [`PointSampler`] trait, so the sampler of
+//! an application can be chosen at runtime and stored as a `Box<dyn PointSampler>`.
+//!
+//! [`Sampler::auto`]: struct.Sampler.html#method.auto
+//! [`Decision`]: struct.Decision.html
+//! [`PointSampler`]: trait.PointSampler.html
+
+use rand::Rng;
+
+use adapter::{BurnIn, IntoPoints, Padded, Points, Project, ScaledTo, Shuffle, Warp};
+use primes::Primes;
+use quasi::{BestCandidate, Digital, DigitalShift, ExtensibleLattice, GeneralizedHalton, Halton};
+use quasi::{HaltonSeq, HashStream, Interlaced, Interleave, Kronecker, Lattice, LinearScramble};
+use quasi::{ModifiedHalton, Niederreiter2, OwenHalton, Rd, Sobol, VdC, Xor, Zero2Sequence, Zip};
+use quasi::fixed::{HaltonPoints, RdPoints, SobolPoints};
+use quasi::sobol::MAX_DIMENSIONS;
+
+/// Object-safe interface to generators of points in the unit cube
+///
+/// Applications selecting a sampler from configuration can hold any generator as a
+/// `Box<dyn PointSampler>`, which every endless generator and point adapter of the crate
+/// converts into with `From`.  Finite point sets, such as a [`Lattice`], are iterated
+/// directly instead.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::{Halton, Interleave};
+/// # use tapas::sampler::{PointSampler, Sampler};
+/// fn configured(name: &str) -> Box<dyn PointSampler> {
+///     match name {
+///         "halton" => Interleave::new(&[Halton::new(1, 2).unwrap(), Halton::new(1, 3).unwrap()])
+///             .unwrap()
+///             .into(),
+///         _ => Sampler::auto(2, 1024).into(),
+///     }
+/// }
+///
+/// let mut sampler = configured("halton");
+/// let mut point = [0.; 2];
+/// sampler.next_point(&mut point);
+/// assert_eq!(point, [1. / 2., 1. / 3.]);
+/// ```
+///
+/// Integrators can also be generic over their source of points, which keeps track of the
+/// dimension of every point instead of drawing a stream of scalars:
+///
+/// ```
+/// # use tapas::quasi::{OwenHalton, Sobol};
+/// # use tapas::sampler::PointSampler;
+/// fn mean<P: PointSampler, F: Fn(&[f64]) -> f64>(points: &mut P, n: usize, f: F) -> f64 {
+///     let mut x = vec![0.; points.dimensions()];
+///     let mut sum = 0.;
+///     for _ in 0..n {
+///         points.next_point(&mut x);
+///         sum += f(&x);
+///     }
+///     sum / n as f64
+/// }
+///
+/// let f = |x: &[f64]| x[0] * x[1];
+/// assert!((mean(&mut Sobol::new(2).unwrap(), 1024, f) - 0.25).abs() < 1e-3);
+/// assert!((mean(&mut OwenHalton::new(2, 1).unwrap(), 1024, f) - 0.25).abs() < 1e-2);
+/// ```
+///
+/// [`Lattice`]: ../quasi/lattice/struct.Lattice.html
+pub trait PointSampler {
+    /// Number of coordinates of each point
+    fn dimensions(&self) -> usize;
+
+    /// Write the next point into `out`
+    ///
+    /// # Panics
+    ///
+    /// If `out` does not have exactly `dimensions()` elements.
+    fn next_point(&mut self, out: &mut [f64]);
+
+    /// Iterator over exactly the next `n` complete points
+    ///
+    /// See [`Points`] for details.
+    ///
+    /// [`Points`]: ../adapter/points/struct.Points.html
+    fn points(self, n: usize) -> Points<Self>
+        where Self: Sized {
+
+        Points::new(self, n)
+    }
+
+    /// Keep only the coordinates in `dimensions` of every point, in that order
+    ///
+    /// See [`Project`] for details.
+    ///
+    /// # Panics
+    ///
+    /// If `dimensions` is empty or holds a dimension which the points do not have.
+    ///
+    /// [`Project`]: ../adapter/project/struct.Project.html
+    fn project(self, dimensions: &[usize]) -> Project<Self>
+        where Self: Sized {
+
+        Project::new(self, dimensions)
+    }
+
+    /// Pad every point to `dimensions` dimensions with draws of `rng`
+    ///
+    /// See [`Padded`] for details.
+    ///
+    /// # Panics
+    ///
+    /// If `dimensions` is less than the number of dimensions of the points.
+    ///
+    /// [`Padded`]: ../adapter/padded/struct.Padded.html
+    fn padded<R: Rng>(self, rng: R, dimensions: usize) -> Padded<Self, R>
+        where Self: Sized {
+
+        Padded::new(self, rng, dimensions)
+    }
+
+    /// Map every point from the unit cube into the box with corners `lo` and `hi`
+    ///
+    /// See [`ScaledTo`] for details.
+    ///
+    /// # Panics
+    ///
+    /// If the corners do not have one coordinate per dimension, or `lo` exceeds `hi` along
+    /// any axis.
+    ///
+    /// [`ScaledTo`]: ../adapter/scaled/struct.ScaledTo.html
+    fn in_box(self, lo: &[f64], hi: &[f64]) -> ScaledTo<Self>
+        where Self: Sized {
+
+        ScaledTo::new(self, lo, hi)
+    }
+
+    /// Map every point through `transform`, such as those of the [`transform`] module
+    ///
+    /// See [`Warp`] for details.
+    ///
+    /// # Panics
+    ///
+    /// If the points do not have `I` dimensions.
+    ///
+    /// [`Warp`]: ../adapter/warp/struct.Warp.html
+    /// [`transform`]: ../transform/index.html
+    fn warp<const I: usize, const O: usize>(self, transform: fn([f64; I]) -> [f64; O])
+        -> Warp<Self, I, O>
+        where Self: Sized {
+
+        Warp::new(self, transform)
+    }
+}
+
+impl<P: PointSampler + ?Sized> PointSampler for &mut P {
+    fn dimensions(&self) -> usize {
+        (**self).dimensions()
+    }
+
+    fn next_point(&mut self, out: &mut [f64]) {
+        (**self).next_point(out)
+    }
+}
+
+impl<P: PointSampler + ?Sized> PointSampler for Box<P> {
+    fn dimensions(&self) -> usize {
+        (**self).dimensions()
+    }
+
+    fn next_point(&mut self, out: &mut [f64]) {
+        (**self).next_point(out)
+    }
+}
+
+/// Fill `out` with consecutive draws of a generator producing `dimensions` values per point
+#[inline]
+fn fill<R: Rng + ?Sized>(rng: &mut R, dimensions: usize, out: &mut [f64]) {
+    assert_eq!(out.len(), dimensions, "{}-dimensional buffer for {}-dimensional points",
+        out.len(), dimensions);
+    for x in out.iter_mut() {
+        *x = rng.next_f64();
+    }
+}
+
+macro_rules! scalar_point_sampler {
+    ($($gen:ty),*) => {
+        $(
+            impl PointSampler for $gen {
+                fn dimensions(&self) -> usize {
+                    1
+                }
+
+                fn next_point(&mut self, out: &mut [f64]) {
+                    fill(self, 1, out)
+                }
+            }
+
+            impl From<$gen> for Box<dyn PointSampler> {
+                fn from(gen: $gen) -> Box<dyn PointSampler> {
+                    Box::new(gen)
+                }
+            }
+        )*
+    };
+}
+
+scalar_point_sampler!(Halton, HashStream, VdC);
+
+impl<R: Rng> PointSampler for Interleave<R> {
+    fn dimensions(&self) -> usize {
+        self.len()
+    }
+
+    fn next_point(&mut self, out: &mut [f64]) {
+        let d = self.len();
+        fill(self, d, out)
+    }
+}
+
+impl<R: Rng> IntoIterator for Interleave<R> {
+    type Item = Vec<f64>;
+    type IntoIter = IntoPoints<Interleave<R>>;
+
+    /// Every following point, with one coordinate from each generator
+    fn into_iter(self) -> IntoPoints<Interleave<R>> {
+        IntoPoints::new(self)
+    }
+}
+
+impl<R: Rng + 'static> From<Interleave<R>> for Box<dyn PointSampler> {
+    fn from(gen: Interleave<R>) -> Box<dyn PointSampler> {
+        Box::new(gen)
+    }
+}
+
+impl<R: Rng> PointSampler for Zip<R> {
+    fn dimensions(&self) -> usize {
+        self.len()
+    }
+
+    fn next_point(&mut self, out: &mut [f64]) {
+        assert_eq!(out.len(), self.len(), "{}-dimensional buffer for {}-dimensional points",
+            out.len(), self.len());
+        self.next_into(out)
+    }
+}
+
+impl<R: Rng + 'static> From<Zip<R>> for Box<dyn PointSampler> {
+    fn from(gen: Zip<R>) -> Box<dyn PointSampler> {
+        Box::new(gen)
+    }
+}
+
+impl<A: Digital, B: Digital> PointSampler for Xor<A, B> {
+    fn dimensions(&self) -> usize {
+        1
+    }
+
+    fn next_point(&mut self, out: &mut [f64]) {
+        fill(self, 1, out)
+    }
+}
+
+impl<A: Digital + 'static, B: Digital + 'static> From<Xor<A, B>> for Box<dyn PointSampler> {
+    fn from(gen: Xor<A, B>) -> Box<dyn PointSampler> {
+        Box::new(gen)
+    }
+}
+
+impl<R: Rng> PointSampler for BestCandidate<R> {
+    fn dimensions(&self) -> usize {
+        BestCandidate::dimensions(self)
+    }
+
+    fn next_point(&mut self, out: &mut [f64]) {
+        let d = BestCandidate::dimensions(self);
+        fill(self, d, out)
+    }
+}
+
+impl<R: Rng + 'static> From<BestCandidate<R>> for Box<dyn PointSampler> {
+    fn from(gen: BestCandidate<R>) -> Box<dyn PointSampler> {
+        Box::new(gen)
+    }
+}
+
+impl<D: Digital> PointSampler for Interlaced<D> {
+    fn dimensions(&self) -> usize {
+        Interlaced::dimensions(self)
+    }
+
+    fn next_point(&mut self, out: &mut [f64]) {
+        let d = Interlaced::dimensions(self);
+        fill(self, d, out)
+    }
+}
+
+impl<D: Digital + 'static> From<Interlaced<D>> for Box<dyn PointSampler> {
+    fn from(gen: Interlaced<D>) -> Box<dyn PointSampler> {
+        Box::new(gen)
+    }
+}
+
+impl<D: Digital> PointSampler for LinearScramble<D> {
+    fn dimensions(&self) -> usize {
+        LinearScramble::dimensions(self)
+    }
+
+    fn next_point(&mut self, out: &mut [f64]) {
+        let d = LinearScramble::dimensions(self);
+        fill(self, d, out)
+    }
+}
+
+impl<D: Digital + 'static> From<LinearScramble<D>> for Box<dyn PointSampler> {
+    fn from(gen: LinearScramble<D>) -> Box<dyn PointSampler> {
+        Box::new(gen)
+    }
+}
+
+impl<D: Digital> PointSampler for DigitalShift<D> {
+    fn dimensions(&self) -> usize {
+        DigitalShift::dimensions(self)
+    }
+
+    fn next_point(&mut self, out: &mut [f64]) {
+        let d = DigitalShift::dimensions(self);
+        fill(self, d, out)
+    }
+}
+
+impl<D: Digital + 'static> From<DigitalShift<D>> for Box<dyn PointSampler> {
+    fn from(gen: DigitalShift<D>) -> Box<dyn PointSampler> {
+        Box::new(gen)
+    }
+}
+
+impl<R: Rng> PointSampler for Shuffle<R> {
+    fn dimensions(&self) -> usize {
+        Shuffle::dimensions(self)
+    }
+
+    fn next_point(&mut self, out: &mut [f64]) {
+        let d = Shuffle::dimensions(self);
+        fill(self, d, out)
+    }
+}
+
+impl<R: Rng + 'static> From<Shuffle<R>> for Box<dyn PointSampler> {
+    fn from(gen: Shuffle<R>) -> Box<dyn PointSampler> {
+        Box::new(gen)
+    }
+}
+
+impl PointSampler for Zero2Sequence {
+    fn dimensions(&self) -> usize {
+        2
+    }
+
+    fn next_point(&mut self, out: &mut [f64]) {
+        fill(self, 2, out)
+    }
+}
+
+impl From<Zero2Sequence> for Box<dyn PointSampler> {
+    fn from(gen: Zero2Sequence) -> Box<dyn PointSampler> {
+        Box::new(gen)
+    }
+}
+
+macro_rules! fixed_point_sampler {
+    ($($gen:ident),*) => {
+        $(
+            impl<const D: usize> PointSampler for $gen<D> {
+                fn dimensions(&self) -> usize {
+                    D
+                }
+
+                fn next_point(&mut self, out: &mut [f64]) {
+                    assert_eq!(out.len(), D, "{}-dimensional buffer for {}-dimensional points",
+                        out.len(), D);
+                    let p = self.next().expect("fixed point sequences are endless");
+                    out.copy_from_slice(&p)
+                }
+            }
+
+            impl<const D: usize> From<$gen<D>> for Box<dyn PointSampler> {
+                fn from(gen: $gen<D>) -> Box<dyn PointSampler> {
+                    Box::new(gen)
+                }
+            }
+        )*
+    };
+}
+
+fixed_point_sampler!(HaltonPoints, RdPoints, SobolPoints);
+
+macro_rules! vector_point_sampler {
+    ($($gen:ident),*) => {
+        $(
+            impl PointSampler for $gen {
+                fn dimensions(&self) -> usize {
+                    $gen::dimensions(self)
+                }
+
+                fn next_point(&mut self, out: &mut [f64]) {
+                    let d = $gen::dimensions(self);
+                    fill(self, d, out)
+                }
+            }
+
+            impl From<$gen> for Box<dyn PointSampler> {
+                fn from(gen: $gen) -> Box<dyn PointSampler> {
+                    Box::new(gen)
+                }
+            }
+        )*
+    };
+}
+
+vector_point_sampler!(
+    ExtensibleLattice, GeneralizedHalton, HaltonSeq, Kronecker, ModifiedHalton, Niederreiter2,
+    OwenHalton, Rd, Sobol
+);
+
+/// Quasi-random construction chosen by [`Sampler::auto`]
+///
+/// [`Sampler::auto`]: struct.Sampler.html#method.auto
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Construction {
+    /// Halton sequence using the first primes as bases, skipping its first `skip` points
+    Halton {
+        /// Number of leading points discarded
+        skip: u64,
+    },
+    /// Sobol sequence with the Joe–Kuo direction numbers, starting at the origin
+    Sobol {
+        /// Seed of the Owen scrambling, or `None` for the plain sequence
+        scramble: Option<u64>,
+    },
+    /// Halton sequence over the first primes with pseudo-random digit permutations
+    GeneralizedHalton {
+        /// Seed of the digit permutations
+        seed: u64,
+    },
+    /// Randomly shifted rank-1 lattice rule built component by component, which repeats
+    /// after its points
+    Lattice {
+        /// Number of points of the rule
+        points: u64,
+        /// Seed of the shift
+        seed: u64,
+    },
+}
+
+/// Construction chosen by [`Sampler::auto`] together with the reasoning behind it
+///
+/// [`Sampler::auto`]: struct.Sampler.html#method.auto
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decision {
+    /// Chosen construction
+    pub construction: Construction,
+    /// Number of dimensions requested
+    pub dimensions: usize,
+    /// Expected number of points
+    pub points: u64,
+    /// Human readable explanation of the choice
+    pub reason: String,
+}
+
+/// Largest number of points of the lattice rules chosen by [`Decision::choose`], whose
+/// component-by-component search takes time proportional to the square of the points
+///
+/// [`Decision::choose`]: struct.Decision.html#method.choose
+const LATTICE_MAX_POINTS: u64 = 1 << 12;
+
+impl Decision {
+    /// Choose a construction for `dimensions` dimensions and about `points` points
+    ///
+    /// - Up to the [`MAX_DIMENSIONS`] of the embedded Joe–Kuo numbers, an Owen scrambled Sobol
+    ///   sequence, whose prefixes of `2^m` points are digital nets with an unbiased randomized
+    ///   error
+    /// - In more dimensions, for a known count of up to 4096 points, a randomly shifted lattice
+    ///   rule of exactly that many points, built for the count by a component-by-component
+    ///   search which favours the leading dimensions
+    /// - Otherwise, including when `points` is zero because the count is unknown, a
+    ///   generalized Halton sequence, which extends to any number of points in any dimension
+    ///   while its digit permutations break up the correlations of large bases
+    ///
+    /// Every choice is randomized with the seed `0`; change it in the [`Construction`] for
+    /// independent replicates.
+    ///
+    /// [`MAX_DIMENSIONS`]: ../quasi/sobol/constant.MAX_DIMENSIONS.html
+    /// [`Construction`]: enum.Construction.html
+    pub fn choose(dimensions: usize, points: u64) -> Decision {
+        let (construction, reason) = if dimensions <= MAX_DIMENSIONS {
+            let advice = if points.is_power_of_two() {
+                format!("{} points are a power of two, so they form a digital net", points)
+            } else {
+                format!("{} points are not a power of two; {} points would form a digital net",
+                    points, points.max(1).next_power_of_two())
+            };
+            (Construction::Sobol { scramble: Some(0) },
+                format!("scrambled Sobol points are well distributed in {} dimensions, and {}",
+                    dimensions, advice))
+        } else if points > 0 && points <= LATTICE_MAX_POINTS {
+            (Construction::Lattice { points, seed: 0 },
+                format!("Sobol supports {} dimensions, and a lattice rule can be built for \
+                    exactly {} points in {} dimensions", MAX_DIMENSIONS, points, dimensions))
+        } else {
+            (Construction::GeneralizedHalton { seed: 0 },
+                format!("Sobol supports {} dimensions and a lattice rule at most {} points, \
+                    while permuted Halton extends to any number of points in {} dimensions",
+                    MAX_DIMENSIONS, LATTICE_MAX_POINTS, dimensions))
+        };
+
+        Decision { construction, dimensions, points, reason }
+    }
+}
+
+/// Generator of points in the unit cube with an automatically chosen construction
+///
+/// Implements [`Rng`] by returning the coordinates of consecutive points one after another.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::sampler::{Construction, Sampler};
+/// let mut sampler = Sampler::auto(3, 1024);
+/// assert_eq!(sampler.decision().construction, Construction::Sobol { scramble: Some(0) });
+///
+/// let p = sampler.point();
+/// assert!(p.iter().all(|&x| x > 0. && x < 1.));
+///
+/// // The decision can be overridden
+/// let mut halton = Sampler::from_decision(tapas::sampler::Decision {
+///     construction: Construction::Halton { skip: 0 },
+///     ..sampler.decision().clone()
+/// });
+/// assert_eq!(halton.point(), vec![1. / 2., 1. / 3., 1. / 5.]);
+/// ```
+///
+/// [`Rng`]: ../../rand/trait.Rng.html
+#[derive(Debug, Clone)]
+pub struct Sampler {
+    inner: Inner,
+    decision: Decision,
+}
+
+#[derive(Debug, Clone)]
+enum Inner {
+    Halton(BurnIn<Interleave<Halton>>),
+    Sobol(Sobol),
+    GeneralizedHalton(GeneralizedHalton),
+    Lattice(LatticeStream),
+}
+
+/// Coordinates of the points of a lattice rule one after another, starting over after the
+/// last point
+#[derive(Debug, Clone)]
+struct LatticeStream {
+    rule: Lattice,
+    point: Vec<f64>,
+    index: u64,
+    current: usize,
+}
+
+impl LatticeStream {
+    fn new(rule: Lattice) -> LatticeStream {
+        LatticeStream { point: rule.point(0), rule, index: 0, current: 0 }
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        let x = self.point[self.current];
+        self.current += 1;
+        if self.current == self.point.len() {
+            self.current = 0;
+            self.index = (self.index + 1) % self.rule.points();
+            self.point = self.rule.point(self.index);
+        }
+        x
+    }
+}
+
+impl Sampler {
+    /// Choose and build a sampler for `dimensions` dimensions and about `points` points
+    ///
+    /// # Panics
+    ///
+    /// If `dimensions` is zero.
+    pub fn auto(dimensions: usize, points: u64) -> Sampler {
+        Sampler::from_decision(Decision::choose(dimensions, points))
+    }
+
+    /// Build the sampler described by `decision`
+    ///
+    /// # Panics
+    ///
+    /// If the decision has zero dimensions, more than the construction supports, skips more
+    /// than `u64::MAX` coordinates, or is a lattice rule of no points.
+    pub fn from_decision(decision: Decision) -> Sampler {
+        assert!(decision.dimensions > 0, "sampler needs at least one dimension");
+
+        assert!(!matches!(decision.construction, Construction::Lattice { points: 0, .. }),
+            "lattice rule needs at least one point");
+
+        let d = decision.dimensions as u64;
+        let inner = match decision.construction {
+            Construction::Halton { skip } => {
+                let halton: Vec<Halton> = Primes::new().take(decision.dimensions)
+                    .map(|b| Halton::new(1, b).unwrap())
+                    .collect();
+                let skip = skip.checked_mul(d).expect("skipped coordinates overflow");
+                Inner::Halton(BurnIn::new(Interleave::new(&halton).unwrap(), skip))
+            },
+            Construction::Sobol { scramble } => {
+                let sobol = Sobol::new(decision.dimensions).unwrap_or_else(|e| panic!("{}", e));
+                Inner::Sobol(match scramble {
+                    Some(seed) => sobol.owen_scrambled(seed),
+                    None => sobol,
+                })
+            },
+            Construction::GeneralizedHalton { seed } => {
+                Inner::GeneralizedHalton(GeneralizedHalton::random(decision.dimensions, seed))
+            },
+            Construction::Lattice { points, seed } => {
+                let rule = Lattice::cbc(decision.dimensions, points)
+                    .unwrap()
+                    .random_shift(&mut HashStream::new(seed));
+                Inner::Lattice(LatticeStream::new(rule))
+            },
+        };
+
+        Sampler { inner, decision }
+    }
+
+    /// Construction chosen for this sampler and the reasoning behind it
+    pub fn decision(&self) -> &Decision {
+        &self.decision
+    }
+
+    /// Number of dimensions of each point
+    pub fn dimensions(&self) -> usize {
+        self.decision.dimensions
+    }
+
+    /// Next point of the sequence as a new vector
+    pub fn point(&mut self) -> Vec<f64> {
+        (0..self.dimensions()).map(|_| self.next_f64()).collect()
+    }
+}
+
+impl PointSampler for Sampler {
+    fn dimensions(&self) -> usize {
+        self.decision.dimensions
+    }
+
+    fn next_point(&mut self, out: &mut [f64]) {
+        let d = self.decision.dimensions;
+        fill(self, d, out)
+    }
+}
+
+impl IntoIterator for Sampler {
+    type Item = Vec<f64>;
+    type IntoIter = IntoPoints<Sampler>;
+
+    /// Every following point of the sampler
+    fn into_iter(self) -> IntoPoints<Sampler> {
+        IntoPoints::new(self)
+    }
+}
+
+impl From<Sampler> for Box<dyn PointSampler> {
+    fn from(sampler: Sampler) -> Box<dyn PointSampler> {
+        Box::new(sampler)
+    }
+}
+
+impl Rng for Sampler {
+    fn next_u32(&mut self) -> u32 {
+        match self.inner {
+            Inner::Halton(ref mut g) => g.next_u32(),
+            Inner::Sobol(ref mut g) => g.next_u32(),
+            Inner::GeneralizedHalton(ref mut g) => g.next_u32(),
+            Inner::Lattice(ref mut g) => (g.next_f64() * u32::MAX as f64).floor() as u32,
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match self.inner {
+            Inner::Halton(ref mut g) => g.next_u64(),
+            Inner::Sobol(ref mut g) => g.next_u64(),
+            Inner::GeneralizedHalton(ref mut g) => g.next_u64(),
+            Inner::Lattice(ref mut g) => (g.next_f64() * u64::MAX as f64).floor() as u64,
+        }
+    }
+
+    fn next_f32(&mut self) -> f32 {
+        match self.inner {
+            Inner::Halton(ref mut g) => g.next_f32(),
+            Inner::Sobol(ref mut g) => g.next_f32(),
+            Inner::GeneralizedHalton(ref mut g) => g.next_f32(),
+            Inner::Lattice(ref mut g) => (g.next_f64() as f32).min(1. - f32::EPSILON / 2.),
+        }
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        match self.inner {
+            Inner::Halton(ref mut g) => g.next_f64(),
+            Inner::Sobol(ref mut g) => g.next_f64(),
+            Inner::GeneralizedHalton(ref mut g) => g.next_f64(),
+            Inner::Lattice(ref mut g) => g.next_f64(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn boxed_samplers_agree() {
+        let mut direct = Sampler::auto(4, 100);
+        let mut boxed: Box<dyn PointSampler> = Sampler::auto(4, 100).into();
+        assert_eq!(boxed.dimensions(), 4);
+
+        let mut point = [0.; 4];
+        for _ in 0..10 {
+            boxed.next_point(&mut point);
+            assert_eq!(point.to_vec(), direct.point());
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn wrong_buffer_length() {
+        let mut gen: Box<dyn PointSampler> = Halton::new(1, 2).unwrap().into();
+        gen.next_point(&mut [0.; 2]);
+    }
+
+    #[test]
+    fn wrapped_generators_keep_dimensions() {
+        use rand::{SeedableRng, StdRng};
+
+        let mut rng = StdRng::from_seed(&[1][..]);
+        let samplers: Vec<Box<dyn PointSampler>> = vec![
+            BestCandidate::new(Sobol::new(3).unwrap(), 3, 4).unwrap().into(),
+            Interlaced::new(Sobol::new(4).unwrap(), 2, 4).into(),
+            Sobol::new(5).unwrap().linear_scrambled(&mut rng).into(),
+        ];
+        for (mut sampler, d) in samplers.into_iter().zip(vec![3, 2, 5]) {
+            assert_eq!(sampler.dimensions(), d);
+            let mut point = vec![0.; d];
+            sampler.next_point(&mut point);
+            sampler.next_point(&mut point);
+            assert!(point.iter().all(|&x| (0. ..1.).contains(&x) && x > 0.));
+        }
+    }
+
+    #[test]
+    fn every_generator_boxes() {
+        use rand::{SeedableRng, StdRng};
+        use transform::unit_disk;
+
+        let mut rng = StdRng::from_seed(&[2][..]);
+        let samplers: Vec<Box<dyn PointSampler>> = vec![
+            DigitalShift::new(Sobol::new(3).unwrap(), 3, &mut rng).into(),
+            Shuffle::new(Sobol::new(4).unwrap(), 4, 5).into(),
+            Zero2Sequence::new(3).into(),
+            HaltonPoints::<3>::new().into(),
+            RdPoints::<2>::new().into(),
+            SobolPoints::<5>::new().into(),
+            Sobol::new(2).unwrap().in_box(&[1., 1.], &[2., 3.]).into(),
+            Sobol::new(4).unwrap().project(&[0, 3]).into(),
+            Sobol::new(2).unwrap().padded(rng, 6).into(),
+            Sobol::new(2).unwrap().warp(unit_disk).into(),
+        ];
+        let dimensions = samplers.iter().map(|s| s.dimensions()).collect::<Vec<_>>();
+        assert_eq!(dimensions, vec![3, 4, 2, 3, 2, 5, 2, 2, 6, 2]);
+        for mut sampler in samplers {
+            let mut point = vec![0.; sampler.dimensions()];
+            sampler.next_point(&mut point);
+            assert!(point.iter().all(|x| x.is_finite()));
+        }
+    }
+
+    #[test]
+    fn points_stay_whole() {
+        let mut sampler = Sampler::auto(3, 100);
+        let expected: Vec<Vec<f64>> = (0..8).map(|_| sampler.point()).collect();
+        assert_eq!(Sampler::auto(3, 100).into_iter().take(8).collect::<Vec<_>>(), expected);
+
+        let mut boxed: Box<dyn PointSampler> = Sampler::auto(3, 100).into();
+        let points: Vec<Vec<f64>> = (&mut boxed).points(8).collect();
+        assert_eq!(points, expected);
+        assert_eq!(boxed.points(2).len(), 2);
+    }
+
+    #[test]
+    fn sobol_in_few_dimensions() {
+        let mut sampler = Sampler::auto(MAX_DIMENSIONS, 1 << 12);
+        assert_eq!(sampler.decision().construction, Construction::Sobol { scramble: Some(0) });
+        assert!(sampler.decision().reason.contains("power of two"));
+        assert!(Sampler::auto(2, 1000).decision().reason.contains("1024"));
+
+        let mut sobol = Sobol::new(MAX_DIMENSIONS).unwrap().owen_scrambled(0);
+        for _ in 0..4 {
+            assert_eq!(sampler.point(), sobol.next().unwrap());
+        }
+    }
+
+    #[test]
+    fn lattice_for_known_counts() {
+        // Just past the Sobol dimensions
+        let d = MAX_DIMENSIONS + 1;
+        let mut sampler = Sampler::auto(d, 250);
+        assert_eq!(sampler.decision().construction, Construction::Lattice { points: 250, seed: 0 });
+
+        let rule = Lattice::cbc(d, 250).unwrap().random_shift(&mut HashStream::new(0));
+        let first = sampler.point();
+        assert_eq!(first, rule.point(0));
+        for i in 1..250 {
+            assert_eq!(sampler.point(), rule.point(i));
+        }
+        // The rule starts over after its points
+        assert_eq!(sampler.point(), first);
+    }
+
+    #[test]
+    fn generalized_halton_otherwise() {
+        let d = MAX_DIMENSIONS + 1;
+        for &points in [0, LATTICE_MAX_POINTS + 1].iter() {
+            let mut sampler = Sampler::auto(d, points);
+            let construction = Construction::GeneralizedHalton { seed: 0 };
+            assert_eq!(sampler.decision().construction, construction);
+
+            let mut halton = GeneralizedHalton::random(d, 0);
+            for _ in 0..4 {
+                assert_eq!(sampler.point(), halton.next().unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn skipped_points_match_sequence() {
+        let mut sampler = Sampler::from_decision(Decision {
+            construction: Construction::Halton { skip: 19 * 19 },
+            dimensions: 8,
+            points: 100,
+            reason: String::new(),
+        });
+        let mut last = Halton::new(1, 19).unwrap();
+        for _ in 0..19 * 19 {
+            last.next_f64();
+        }
+        abs_err_eq!((sampler.point()[7]) == (last.next_f64()) ~ 1e-15);
+    }
+}
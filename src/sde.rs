@@ -0,0 +1,320 @@
+//! # SDE Path Generation
+//!
+//! Drive Euler-type discretizations of stochastic differential equations with quasi-random
+//! points, one point per path.
+//!
+//! A path of `n` time steps consumes one `n`-dimensional point, so every step is tied to fixed
+//! coordinates of the sequence.  Low-discrepancy sequences are most uniform in their leading
+//! coordinates, and the [`PathOrdering`] decides which features of the Brownian path those
+//! coordinates control:
+//!
+//! - [`Sequential`] gives coordinate `i` to increment `i`,
+//! - [`BrownianBridge`] gives the first coordinate to the terminal value and the following ones
+//!   to successive midpoints, so the coarse shape of the path uses the best coordinates,
+//! - [`Pca`] gives coordinate `k` to the `k`-th principal component of the path, which
+//!   concentrates the most variance in the leading coordinates.
+//!
+//! All orderings produce paths with exactly the Brownian covariance; they differ only in how
+//! well the integrand's effective dimension lines up with the sequence.
+//!
+//! [`PathOrdering`]: enum.PathOrdering.html
+//! [`Sequential`]: enum.PathOrdering.html#variant.Sequential
+//! [`BrownianBridge`]: enum.PathOrdering.html#variant.BrownianBridge
+//! [`Pca`]: enum.PathOrdering.html#variant.Pca
+//!
+//! # References
+//! - Glasserman, P., Monte Carlo Methods in Financial Engineering, section 3.1
+
+use rand::Rng;
+use std::collections::VecDeque;
+use std::f64::consts::PI;
+
+use distributions::special::inverse_normal;
+
+/// Assignment of sequence coordinates to features of a Brownian path
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathOrdering {
+    /// Coordinate `i` drives increment `i`
+    Sequential,
+    /// Coordinates fill in the terminal value first, then midpoints breadth first
+    BrownianBridge,
+    /// Coordinate `k` drives the `k`-th principal component of the path
+    Pca,
+}
+
+/// One step of the Brownian bridge construction
+#[derive(Debug, Clone, Copy)]
+struct BridgeStep {
+    target: usize,
+    left: usize,
+    right: usize,
+    /// Weights of the left and right values in the conditional mean
+    weights: (f64, f64),
+    sd: f64,
+}
+
+#[derive(Debug, Clone)]
+enum Plan {
+    Sequential,
+    Bridge(Vec<BridgeStep>),
+    /// Rows of the matrix mapping normals to path values
+    Pca(Vec<Vec<f64>>),
+}
+
+/// Driver producing Brownian increments for equally spaced time steps
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::Halton;
+/// # use tapas::sampler::Sampler;
+/// # use tapas::sde::{PathOrdering, SdeDriver};
+/// // Geometric Brownian motion with 16 steps over one year
+/// let (mu, sigma, steps) = (0.05, 0.2, 16);
+/// let mut driver = SdeDriver::new(Sampler::auto(steps, 1024), steps, 1., PathOrdering::Pca);
+///
+/// let mut mean = 0.;
+/// for _ in 0..1024 {
+///     let mut s = 100f64;
+///     for _ in 0..steps {
+///         s += mu * s * driver.dt() + sigma * s * driver.next_increment();
+///     }
+///     mean += s / 1024.;
+/// }
+/// assert!((mean - 100. * 0.05f64.exp()).abs() < 0.5);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SdeDriver<R> {
+    rng: R,
+    steps: usize,
+    dt: f64,
+    ordering: PathOrdering,
+    plan: Plan,
+    increments: Vec<f64>,
+    next: usize,
+}
+
+impl<R: Rng> SdeDriver<R> {
+    /// Drive paths of `steps` equal time steps over `[0, horizon]`
+    ///
+    /// Every path consumes `steps` consecutive draws from `rng`, which should therefore produce
+    /// points of `steps` dimensions.
+    ///
+    /// # Panics
+    ///
+    /// If `steps` is zero or `horizon` is not positive.
+    pub fn new(rng: R, steps: usize, horizon: f64, ordering: PathOrdering) -> SdeDriver<R> {
+        assert!(steps > 0, "paths need at least one time step");
+        assert!(horizon > 0., "horizon {} must be positive", horizon);
+
+        let dt = horizon / steps as f64;
+        let plan = match ordering {
+            PathOrdering::Sequential => Plan::Sequential,
+            PathOrdering::BrownianBridge => Plan::Bridge(bridge_plan(steps, dt)),
+            PathOrdering::Pca => Plan::Pca(pca_matrix(steps, dt)),
+        };
+
+        SdeDriver {
+            rng,
+            steps,
+            dt,
+            ordering,
+            plan,
+            increments: Vec::with_capacity(steps),
+            next: steps,
+        }
+    }
+
+    /// Length of each time step
+    pub fn dt(&self) -> f64 {
+        self.dt
+    }
+
+    /// Number of time steps per path
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Ordering of the sequence coordinates
+    pub fn ordering(&self) -> PathOrdering {
+        self.ordering
+    }
+
+    /// Index of the next time step within the current path
+    ///
+    /// Equals [`steps`](#method.steps) once the current path is used up.
+    pub fn step(&self) -> usize {
+        self.next
+    }
+
+    /// Brownian increment over the next time step
+    ///
+    /// A new path is drawn once every increment of the current path has been returned.
+    pub fn next_increment(&mut self) -> f64 {
+        if self.next == self.steps {
+            self.new_path();
+        }
+        self.next += 1;
+        self.increments[self.next - 1]
+    }
+
+    /// Discard the rest of the current path and draw the next one
+    pub fn new_path(&mut self) {
+        let z: Vec<f64> = (0..self.steps).map(|_| inverse_normal(self.rng.next_f64())).collect();
+        let w = self.path(&z);
+
+        self.increments.clear();
+        let mut last = 0.;
+        for x in w {
+            self.increments.push(x - last);
+            last = x;
+        }
+        self.next = 0;
+    }
+
+    /// All increments of a new path
+    pub fn path_increments(&mut self) -> Vec<f64> {
+        self.new_path();
+        self.next = self.steps;
+        self.increments.clone()
+    }
+
+    /// Unwrap the underlying generator
+    pub fn into_inner(self) -> R {
+        self.rng
+    }
+
+    /// Values `W(t_1), ..., W(t_n)` of the path driven by the standard normals `z`
+    fn path(&self, z: &[f64]) -> Vec<f64> {
+        match self.plan {
+            Plan::Sequential => {
+                let sd = self.dt.sqrt();
+                let mut w = 0.;
+                z.iter().map(|z| { w += sd * z; w }).collect()
+            },
+            Plan::Bridge(ref plan) => {
+                // Index 0 holds W(0) = 0
+                let mut w = vec![0.; self.steps + 1];
+                for (s, z) in plan.iter().zip(z) {
+                    w[s.target] = s.weights.0 * w[s.left] + s.weights.1 * w[s.right] + s.sd * z;
+                }
+                w.remove(0);
+                w
+            },
+            Plan::Pca(ref rows) => {
+                rows.iter().map(|row| row.iter().zip(z).map(|(a, z)| a * z).sum()).collect()
+            },
+        }
+    }
+}
+
+/// Brownian bridge steps filling the terminal value, then midpoints breadth first
+fn bridge_plan(n: usize, dt: f64) -> Vec<BridgeStep> {
+    let t = |i: usize| i as f64 * dt;
+    let mut plan = vec![BridgeStep {
+        target: n,
+        left: 0,
+        right: 0,
+        weights: (0., 0.),
+        sd: t(n).sqrt(),
+    }];
+
+    let mut queue = VecDeque::new();
+    queue.push_back((0, n));
+    while let Some((l, r)) = queue.pop_front() {
+        if r - l < 2 {
+            continue;
+        }
+        let m = (l + r) / 2;
+        let (a, b) = (t(m) - t(l), t(r) - t(m));
+        plan.push(BridgeStep {
+            target: m,
+            left: l,
+            right: r,
+            weights: (b / (a + b), a / (a + b)),
+            sd: (a * b / (a + b)).sqrt(),
+        });
+        queue.push_back((l, m));
+        queue.push_back((m, r));
+    }
+    plan
+}
+
+/// Matrix of scaled eigenvectors of the covariance `dt · min(i, j)` of the path
+///
+/// Uses the closed form eigen decomposition for equally spaced time steps.
+fn pca_matrix(n: usize, dt: f64) -> Vec<Vec<f64>> {
+    let m = (2 * n + 1) as f64;
+    let norm = 2. / m.sqrt();
+    let columns: Vec<(f64, f64)> = (1..=n)
+        .map(|k| {
+            let angle = (2 * k - 1) as f64 * PI / m;
+            let sd = (dt / (4. * (angle / 2.).sin().powi(2))).sqrt();
+            (angle, sd)
+        })
+        .collect();
+
+    (1..=n)
+        .map(|i| columns.iter().map(|&(angle, sd)| sd * norm * (angle * i as f64).sin()).collect())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::Halton;
+
+    // Each ordering is linear in the normals, so its covariance is A Aᵀ
+    fn check_covariance(ordering: PathOrdering) {
+        let (n, dt) = (7, 0.25);
+        let driver = SdeDriver::new(Halton::new(1, 2).unwrap(), n, n as f64 * dt, ordering);
+        let columns: Vec<Vec<f64>> = (0..n)
+            .map(|k| {
+                let mut z = vec![0.; n];
+                z[k] = 1.;
+                driver.path(&z)
+            })
+            .collect();
+
+        for i in 0..n {
+            for j in 0..n {
+                let cov: f64 = columns.iter().map(|c| c[i] * c[j]).sum();
+                let expected = dt * (i.min(j) + 1) as f64;
+                abs_err_eq!(cov == expected ~ 1e-12, "{:?} covariance ({}, {})", ordering, i, j);
+            }
+        }
+    }
+
+    #[test]
+    fn orderings_have_brownian_covariance() {
+        check_covariance(PathOrdering::Sequential);
+        check_covariance(PathOrdering::BrownianBridge);
+        check_covariance(PathOrdering::Pca);
+    }
+
+    #[test]
+    fn bridge_leads_with_terminal_value() {
+        let gen = Halton::new(1, 3).unwrap();
+        let mut driver = SdeDriver::new(gen, 5, 2., PathOrdering::BrownianBridge);
+        let terminal: f64 = driver.path_increments().iter().sum();
+        abs_err_eq!(terminal == (2f64.sqrt() * inverse_normal(1. / 3.)) ~ 1e-12);
+    }
+
+    #[test]
+    fn one_point_per_path() {
+        let gen = Halton::new(1, 2).unwrap();
+        let mut driver = SdeDriver::new(gen, 4, 1., PathOrdering::Sequential);
+        for _ in 0..6 {
+            driver.next_increment();
+        }
+        assert_eq!(driver.step(), 2);
+
+        // The second path starts from the fifth draw
+        let mut gen = driver.into_inner();
+        let mut plain = Halton::new(1, 2).unwrap();
+        for _ in 0..8 {
+            plain.next_f64();
+        }
+        assert_eq!(gen.next_f64(), plain.next_f64());
+    }
+}
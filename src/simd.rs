@@ -0,0 +1,73 @@
+//! Lanes of consecutive draws for SIMD consumers
+//!
+//! [`SimdRng`] fills `f64x4` and `f32x8` vectors from the next values of any generator, so
+//! integrands written against the [`wide`] types can take samples directly rather than
+//! gathering scalars into vectors themselves.  Lane `i` holds the `i`-th value drawn.
+//!
+//! Only available with the `simd` feature.
+//!
+//! [`SimdRng`]: trait.SimdRng.html
+//! [`wide`]: https://docs.rs/wide
+
+use rand::Rng;
+
+pub use wide::{f32x8, f64x4};
+
+/// Draw SIMD vectors of consecutive values from a generator
+///
+/// Implemented for every [`Rng`].
+///
+/// # Examples
+///
+/// ```
+/// # extern crate tapas;
+/// # use tapas::quasi::Halton;
+/// use tapas::simd::SimdRng;
+///
+/// # fn main() {
+/// let mut gen = Halton::new(1, 2).unwrap();
+/// let lanes = gen.next_f64x4();
+/// assert_eq!(lanes.to_array(), [1. / 2., 1. / 4., 3. / 4., 1. / 8.]);
+/// # }
+/// ```
+///
+/// [`Rng`]: ../../rand/trait.Rng.html
+pub trait SimdRng: Rng {
+    /// Next four values as `f64` lanes
+    fn next_f64x4(&mut self) -> f64x4 {
+        let mut lanes = [0.; 4];
+        for lane in lanes.iter_mut() {
+            *lane = self.next_f64();
+        }
+        f64x4::new(lanes)
+    }
+
+    /// Next eight values as `f32` lanes
+    fn next_f32x8(&mut self) -> f32x8 {
+        let mut lanes = [0.; 8];
+        for lane in lanes.iter_mut() {
+            *lane = self.next_f32();
+        }
+        f32x8::new(lanes)
+    }
+}
+
+impl<R: Rng + ?Sized> SimdRng for R {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::Halton;
+
+    #[test]
+    fn lanes_follow_sequence() {
+        let mut scalar = Halton::new(1, 3).unwrap();
+        let mut vector = Halton::new(1, 3).unwrap();
+
+        let expected: Vec<f32> = (0..8).map(|_| scalar.next_f32()).collect();
+        assert_eq!(vector.next_f32x8().to_array().to_vec(), expected);
+
+        let expected: Vec<f64> = (0..4).map(|_| scalar.next_f64()).collect();
+        assert_eq!(vector.next_f64x4().to_array().to_vec(), expected);
+    }
+}
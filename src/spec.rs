@@ -0,0 +1,349 @@
+//! # Sampler Specifications
+//!
+//! Build a [`Sampler`] from a compact textual description, for command line tools and
+//! configuration files.
+//!
+//! A specification names a construction, optionally followed by a colon and comma separated
+//! `key=value` settings:
+//!
+//! ```text
+//! auto: dims=8, points=4096
+//! halton: dims=16, skip=1000
+//! sobol: dims=16, scramble=owen, seed=7
+//! ```
+//!
+//! | Construction | Settings                                                            |
+//! |--------------|---------------------------------------------------------------------|
+//! | `auto`       | `dims` (required), `points` (default `1024`), see [`Sampler::auto`] |
+//! | `halton`     | `dims` (required), `skip` (default `0`)                             |
+//! | `sobol`      | `dims` (required, at most [`MAX_DIMENSIONS`]), `scramble` (`none`   |
+//! |              | or `owen`, default `none`), `seed` (default `0`, with `owen` only)  |
+//!
+//! Configuration files can give the same settings as a table of TOML, read by [`parse_toml`],
+//! with the construction under the `construction` key:
+//!
+//! ```toml
+//! [sampler]
+//! construction = "sobol"
+//! dims = 16
+//! scramble = "owen"
+//! seed = 7
+//! ```
+//!
+//! Names and keys are case-insensitive and whitespace around every token is ignored.
+//!
+//! [`Sampler`]: ../sampler/struct.Sampler.html
+//! [`Sampler::auto`]: ../sampler/struct.Sampler.html#method.auto
+//! [`MAX_DIMENSIONS`]: ../quasi/sobol/constant.MAX_DIMENSIONS.html
+//! [`parse_toml`]: fn.parse_toml.html
+
+use std::str::FromStr;
+
+use error::{Error, Result};
+use quasi::sobol::MAX_DIMENSIONS;
+use sampler::{Construction, Decision, Sampler};
+
+/// Settings of a specification, in the order they were given, with their line numbers
+struct Settings<'a> {
+    pairs: Vec<(String, &'a str, usize)>,
+}
+
+impl<'a> Settings<'a> {
+    /// Comma separated settings of a one line specification
+    fn parse(text: &'a str) -> Result<Settings<'a>> {
+        let mut settings = Settings { pairs: Vec::new() };
+        for setting in text.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            settings.push(setting, 1)?;
+        }
+        Ok(settings)
+    }
+
+    /// Settings of a flat TOML table, optionally under a `[sampler]` header
+    fn parse_toml(text: &'a str) -> Result<Settings<'a>> {
+        let mut settings = Settings { pairs: Vec::new() };
+        let mut header = false;
+        for (n, line) in text.lines().enumerate() {
+            let content = line.split('#').next().unwrap_or("").trim();
+            if content.is_empty() {
+                continue;
+            }
+            if content.starts_with('[') {
+                if header || content != "[sampler]" {
+                    return Err(fail(n + 1, format!("unexpected table `{}`", content)));
+                }
+                header = true;
+                continue;
+            }
+            settings.push(content, n + 1)?;
+        }
+        for (_, value, line) in settings.pairs.iter_mut() {
+            if value.starts_with('"') {
+                if value.len() < 2 || !value.ends_with('"') {
+                    return Err(fail(*line, format!("unterminated string {}", value)));
+                }
+                *value = &value[1..value.len() - 1];
+            }
+        }
+        Ok(settings)
+    }
+
+    fn push(&mut self, setting: &'a str, line: usize) -> Result<()> {
+        let mut kv = setting.splitn(2, '=');
+        let key = kv.next().unwrap().trim().to_lowercase();
+        let value = kv.next().map(str::trim)
+            .ok_or_else(|| fail(line, format!("expected `key=value`, found `{}`", setting)))?;
+        if self.pairs.iter().any(|(k, _, _)| *k == key) {
+            return Err(fail(line, format!("`{}` is set more than once", key)));
+        }
+        self.pairs.push((key, value, line));
+        Ok(())
+    }
+
+    /// Remove and parse the setting `key`, if present, along with its line
+    fn take_at<T: FromStr>(&mut self, key: &str) -> Result<Option<(T, usize)>> {
+        match self.pairs.iter().position(|(k, _, _)| k == key) {
+            Some(i) => {
+                let (_, value, line) = self.pairs.remove(i);
+                value.parse().map(|v| Some((v, line)))
+                    .map_err(|_| fail(line, format!("invalid value `{}` for `{}`", value, key)))
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Remove and parse the setting `key`, if present
+    fn take<T: FromStr>(&mut self, key: &str) -> Result<Option<T>> {
+        Ok(self.take_at(key)?.map(|(v, _)| v))
+    }
+
+    fn require<T: FromStr>(&mut self, key: &str) -> Result<T> {
+        self.take(key)?
+            .ok_or_else(|| fail(1, format!("the `{}` setting is required", key)))
+    }
+
+    /// Fail on any setting which was not taken
+    fn finish(self) -> Result<()> {
+        match self.pairs.first() {
+            Some((key, _, line)) => Err(fail(*line, format!("unknown setting `{}`", key))),
+            None => Ok(()),
+        }
+    }
+}
+
+fn fail(line: usize, reason: String) -> Error {
+    Error::Parse { line, reason }
+}
+
+/// Build the sampler described by `spec`
+///
+/// # Errors
+///
+/// Returns [`Error::Parse`] naming the problem if the construction is unknown, a setting is
+/// unknown, repeated, malformed, or out of range, or a required setting is missing.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::spec::parse;
+/// # use tapas::sampler::Construction;
+/// let sampler = parse("sobol: dims=16, scramble=owen, seed=7").unwrap();
+/// assert_eq!(sampler.dimensions(), 16);
+/// assert_eq!(sampler.decision().construction, Construction::Sobol { scramble: Some(7) });
+///
+/// let sampler = parse("halton: dims=3, skip=10").unwrap();
+/// assert_eq!(sampler.decision().construction, Construction::Halton { skip: 10 });
+///
+/// assert!(parse("halton: dims=3, scramble=owen").is_err());
+/// ```
+///
+/// [`Error::Parse`]: ../enum.Error.html#variant.Parse
+pub fn parse(spec: &str) -> Result<Sampler> {
+    let spec = spec.trim();
+    let (name, rest) = match spec.find(':') {
+        Some(i) => (spec[..i].trim(), &spec[i + 1..]),
+        None => (spec, ""),
+    };
+    let settings = Settings::parse(rest)?;
+    build(name, settings, format!("requested by specification `{}`", spec))
+}
+
+/// Build the sampler described by a TOML table, with the construction under `construction`
+///
+/// Reads the flat subset of TOML needed for the settings of [`parse`]: one `key = value` per
+/// line, with quoted strings and integers as values, `#` comments, and an optional
+/// `[sampler]` header.  Other tables, arrays and multi-line values are rejected.
+///
+/// # Errors
+///
+/// Returns [`Error::Parse`] with the offending line for the problems listed under [`parse`],
+/// and for input outside the supported subset of TOML.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::spec::{parse, parse_toml};
+/// let config = r#"
+///     [sampler]
+///     construction = "sobol"  # scrambled for error estimates
+///     dims = 16
+///     scramble = "owen"
+///     seed = 7
+/// "#;
+/// let sampler = parse_toml(config).unwrap();
+/// let same = parse("sobol: dims=16, scramble=owen, seed=7").unwrap();
+/// assert_eq!(sampler.decision().construction, same.decision().construction);
+/// ```
+///
+/// [`parse`]: fn.parse.html
+/// [`Error::Parse`]: ../enum.Error.html#variant.Parse
+pub fn parse_toml(config: &str) -> Result<Sampler> {
+    let mut settings = Settings::parse_toml(config)?;
+    let name: String = settings.require("construction")?;
+    build(&name, settings, "requested by configuration".to_owned())
+}
+
+/// Build the sampler of construction `name` from its `settings`
+fn build(name: &str, mut settings: Settings, reason: String) -> Result<Sampler> {
+    let dimensions: usize = settings.require("dims")?;
+    if dimensions == 0 {
+        return Err(fail(1, "`dims` must be positive".to_owned()));
+    }
+
+    let decision = match name.to_lowercase().as_str() {
+        "auto" => {
+            let points = settings.take("points")?.unwrap_or(1024);
+            Decision::choose(dimensions, points)
+        },
+        "halton" => {
+            let skip = match settings.take_at::<u64>("skip")? {
+                Some((skip, line)) => {
+                    if skip.checked_mul(dimensions as u64).is_none() {
+                        return Err(fail(line, format!("skipping {} points of {} dimensions \
+                            overflows", skip, dimensions)));
+                    }
+                    skip
+                },
+                None => 0,
+            };
+            Decision {
+                construction: Construction::Halton { skip },
+                dimensions,
+                points: 0,
+                reason,
+            }
+        },
+        "sobol" => {
+            if dimensions > MAX_DIMENSIONS {
+                return Err(fail(1, format!("`sobol` supports at most {} dimensions",
+                    MAX_DIMENSIONS)));
+            }
+            let scramble = settings.take_at::<String>("scramble")?;
+            let seed = settings.take_at::<u64>("seed")?;
+            let scramble = match (scramble, seed) {
+                (Some((ref s, _)), seed) if s.eq_ignore_ascii_case("owen") => {
+                    Some(seed.map_or(0, |(seed, _)| seed))
+                },
+                (Some((ref s, line)), _) if !s.eq_ignore_ascii_case("none") => {
+                    return Err(fail(line, format!("unknown scramble `{}`, expected `none` or \
+                        `owen`", s)));
+                },
+                (_, Some((_, line))) => {
+                    return Err(fail(line, "`seed` requires `scramble=owen`".to_owned()));
+                },
+                _ => None,
+            };
+            Decision {
+                construction: Construction::Sobol { scramble },
+                dimensions,
+                points: 0,
+                reason,
+            }
+        },
+        _ => return Err(fail(1, format!("unknown construction `{}`", name))),
+    };
+    settings.finish()?;
+
+    Ok(Sampler::from_decision(decision))
+}
+
+impl FromStr for Sampler {
+    type Err = Error;
+
+    fn from_str(spec: &str) -> Result<Sampler> {
+        parse(spec)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn reason(spec: &str) -> String {
+        match parse(spec) {
+            Err(Error::Parse { reason, .. }) => reason,
+            other => panic!("expected parse error for `{}`, found {:?}", spec, other),
+        }
+    }
+
+    #[test]
+    fn auto_defaults() {
+        let sampler: Sampler = " Auto : DIMS = 12 ".parse().unwrap();
+        assert_eq!(sampler.decision(), &Decision::choose(12, 1024));
+    }
+
+    #[test]
+    fn sobol_points() {
+        let mut sampler = parse("sobol: dims=2").unwrap();
+        assert_eq!(sampler.decision().construction, Construction::Sobol { scramble: None });
+        assert_eq!(sampler.point(), vec![0., 0.]);
+        assert_eq!(sampler.point(), vec![0.5, 0.5]);
+    }
+
+    #[test]
+    fn errors_name_the_problem() {
+        assert!(reason("lattice: dims=2").contains("lattice"));
+        assert!(reason("halton").contains("dims"));
+        assert!(reason("halton: dims=two").contains("two"));
+        assert!(reason("halton: dims=2, dims=3").contains("more than once"));
+        assert!(reason("halton: dims=2, seed=7").contains("seed"));
+        assert!(reason("halton: dims").contains("key=value"));
+        assert!(reason("auto: dims=0").contains("positive"));
+        let wide = format!("sobol: dims={}", MAX_DIMENSIONS + 1);
+        assert!(reason(&wide).contains(&format!("at most {}", MAX_DIMENSIONS)));
+        assert!(parse(&format!("sobol: dims={}", MAX_DIMENSIONS)).is_ok());
+        assert!(reason("sobol: dims=2, scramble=faure").contains("faure"));
+        assert!(reason("sobol: dims=2, seed=7").contains("owen"));
+        assert!(reason("halton: dims=2, skip=18446744073709551615").contains("overflows"));
+    }
+
+    #[test]
+    fn scrambled_sobol() {
+        let mut sampler = parse("sobol: dims=16, scramble=owen, seed=7").unwrap();
+        let mut sobol = ::quasi::Sobol::new(16).unwrap().owen_scrambled(7);
+        assert_eq!(sampler.point(), sobol.next().unwrap());
+        let plain = parse("sobol: dims=2, scramble=none").unwrap();
+        assert_eq!(plain.decision().construction, Construction::Sobol { scramble: None });
+    }
+
+    #[test]
+    fn toml_tables() {
+        let config = "# sampler of the simulation\n\
+                      [sampler]\n\
+                      construction = \"halton\"\n\
+                      dims = 3   # x, y and time\n\
+                      \n\
+                      skip = 10\n";
+        let sampler = parse_toml(config).unwrap();
+        assert_eq!(sampler.decision().construction, Construction::Halton { skip: 10 });
+        assert_eq!(sampler.dimensions(), 3);
+
+        let line = |config: &str| match parse_toml(config) {
+            Err(Error::Parse { line, .. }) => line,
+            other => panic!("expected parse error for `{}`, found {:?}", config, other),
+        };
+        assert_eq!(line("construction = \"sobol\"\ndims = 2\nscramble = \"owen\n"), 3);
+        assert_eq!(line("construction = \"sobol\"\n[grid]\ndims = 2\n"), 2);
+        assert_eq!(line("construction = \"sobol\"\ndims = 2\n\ncolour = 1\n"), 4);
+        assert_eq!(line("dims = 2\n"), 1);
+    }
+}
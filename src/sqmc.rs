@@ -0,0 +1,159 @@
+//! # Sequential Quasi-Monte Carlo
+//!
+//! Building blocks for SQMC particle filters (Gerber & Chopin, 2015).
+//!
+//! A standard particle filter resamples ancestors with independent uniforms.  SQMC replaces them
+//! with a low-discrepancy point set of dimension `d + 1`: the first coordinate of every point
+//! selects an ancestor and the remaining `d` coordinates drive the transition of the new
+//! particle.  For the first coordinate to carry its low discrepancy into the multivariate state
+//! space, particles are ordered along a Hilbert curve before the weights are inverted.
+//!
+//! A single step therefore looks like this:
+//!
+//! 1. sort the current particles with [`hilbert_sort`],
+//! 2. draw `N` points of dimension `d + 1` from a quasi-random generator,
+//! 3. find the ancestor of every point with [`resample`] on the first coordinates,
+//! 4. propagate each ancestor with the remaining coordinates of its point.
+//!
+//! [`hilbert_sort`]: fn.hilbert_sort.html
+//! [`resample`]: fn.resample.html
+//!
+//! # References
+//! - Gerber, M., Chopin, N., Sequential quasi Monte Carlo
+
+use curve::hilbert_order;
+
+/// Indices sorting particle `states` along the Hilbert curve
+///
+/// States may take any real values: each coordinate is first rescaled to `[0, 1]` by the
+/// bounding box of all states, then points are ordered by their Hilbert index.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::sqmc::hilbert_sort;
+/// let states = [[10., -3.], [0., -5.], [10., 5.], [0., 5.]];
+/// assert_eq!(hilbert_sort(&states), vec![1, 3, 2, 0]);
+/// ```
+pub fn hilbert_sort<T: AsRef<[f64]>>(states: &[T]) -> Vec<usize> {
+    let d = match states.first() {
+        Some(s) => s.as_ref().len(),
+        None => return Vec::new(),
+    };
+
+    let mut lo = vec![f64::INFINITY; d];
+    let mut hi = vec![f64::NEG_INFINITY; d];
+    for s in states {
+        for (j, &x) in s.as_ref().iter().enumerate() {
+            lo[j] = lo[j].min(x);
+            hi[j] = hi[j].max(x);
+        }
+    }
+
+    let unit: Vec<Vec<f64>> = states.iter()
+        .map(|s| {
+            s.as_ref().iter().enumerate()
+                .map(|(j, &x)| if hi[j] > lo[j] { (x - lo[j]) / (hi[j] - lo[j]) } else { 0.5 })
+                .collect()
+        })
+        .collect();
+
+    hilbert_order(&unit)
+}
+
+/// Select ancestors by inverting the cumulative `weights` of particles visited in `order`
+///
+/// `uniforms` are typically the first coordinates of the quasi-random point set of the current
+/// step.  They need not be sorted: the ancestor returned at position `n` belongs to
+/// `uniforms[n]`, so the remaining coordinates of the same point can be used to propagate it.
+/// Weights do not need to be normalized.
+///
+/// # Panics
+///
+/// If `order` and `weights` have different lengths, or the weights do not have a positive sum.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::sqmc::resample;
+/// let weights = [0.1, 0.6, 0.3];
+/// let order = [2, 0, 1];
+///
+/// // Cumulative weights in order are 0.3, 0.4, 1.0
+/// assert_eq!(resample(&weights, &order, &[0.9, 0.1, 0.35]), vec![1, 2, 0]);
+/// ```
+pub fn resample(weights: &[f64], order: &[usize], uniforms: &[f64]) -> Vec<usize> {
+    assert_eq!(weights.len(), order.len(), "{} weights but {} particles in order",
+        weights.len(), order.len());
+
+    let total: f64 = weights.iter().sum();
+    assert!(total > 0., "particle weights sum to {}", total);
+
+    let mut by_value: Vec<usize> = (0..uniforms.len()).collect();
+    by_value.sort_by(|&a, &b| uniforms[a].partial_cmp(&uniforms[b]).unwrap());
+
+    // Sweep sorted uniforms and cumulative weights together, never past the last particle with
+    // positive weight: rounding can leave the cumulative sum just below one
+    let last = order.iter().rposition(|&i| weights[i] > 0.).unwrap();
+    let mut ancestors = vec![0; uniforms.len()];
+    let mut j = 0;
+    let mut acc = weights[order[0]] / total;
+    for n in by_value {
+        while uniforms[n] >= acc && j < last {
+            j += 1;
+            acc += weights[order[j]] / total;
+        }
+        ancestors[n] = order[j];
+    }
+
+    ancestors
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn uniform_weights_pick_every_particle() {
+        let n = 8;
+        let weights = vec![1.; n];
+        let order: Vec<usize> = (0..n).rev().collect();
+        let uniforms: Vec<f64> = (0..n).map(|i| (i as f64 + 0.5) / n as f64).collect();
+
+        assert_eq!(resample(&weights, &order, &uniforms), order);
+    }
+
+    #[test]
+    fn degenerate_weights() {
+        let weights = [0., 0., 2., 0.];
+        let ancestors = resample(&weights, &[3, 1, 0, 2], &[0.01, 0.5, 0.99]);
+        assert_eq!(ancestors, vec![2, 2, 2]);
+    }
+
+    #[test]
+    fn trailing_zero_weight_is_never_picked() {
+        // Cumulative weights round to just below one
+        let mut weights = vec![0.3; 7];
+        weights.push(0.);
+        let order: Vec<usize> = (0..weights.len()).collect();
+        let ancestors = resample(&weights, &order, &[1. - f64::EPSILON / 2.]);
+        assert_eq!(ancestors, vec![6]);
+    }
+
+    #[test]
+    fn constant_dimension_is_ignored() {
+        let states = [[1., 7.], [0., 7.], [0.5, 7.]];
+        assert_eq!(hilbert_sort(&states), vec![1, 2, 0]);
+    }
+
+    #[test]
+    fn many_dimensional_states() {
+        // Two hundred state variables, more than fit in a 128 bit index
+        let states: Vec<Vec<f64>> = (0..3)
+            .map(|i| (0..200).map(|j| ((i * 7 + j) % 5) as f64).collect())
+            .collect();
+        let mut order = hilbert_sort(&states);
+        order.sort();
+        assert_eq!(order, vec![0, 1, 2]);
+    }
+}
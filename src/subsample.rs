@@ -0,0 +1,226 @@
+//! # Subsampling
+//!
+//! Select well spread representatives from large point sets.
+
+use rand::Rng;
+
+/// Select `k` well spread points from `points` with farthest-point sampling
+///
+/// The first point is chosen from a single draw of `rng`; every following point is the one
+/// farthest from all points selected so far.  Points are bucketed into a uniform grid which
+/// tracks the largest nearest-selection distance inside each cell, so adding a selection only
+/// revisits cells close enough to it to change that distance.
+///
+/// Returns the indices of the selected points in selection order.  If `points` contains fewer
+/// than `k` distinct positions, duplicates are selected in index order once no point is left at
+/// a positive distance.
+///
+/// # Panics
+///
+/// If `k` is larger than the number of points.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::Halton;
+/// # use tapas::subsample::farthest_points;
+/// let points = [[0., 0.], [0.1, 0.], [1., 1.], [0.9, 1.], [0., 1.]];
+///
+/// let picked = farthest_points(&points, 3, &mut Halton::new(1, 2).unwrap());
+/// assert_eq!(picked.len(), 3);
+/// ```
+pub fn farthest_points<R: Rng, const D: usize>(
+    points: &[[f64; D]],
+    k: usize,
+    rng: &mut R,
+) -> Vec<usize> {
+
+    let n = points.len();
+    assert!(k <= n, "cannot select {} points from {}", k, n);
+
+    let mut selected = Vec::with_capacity(k);
+    if k == 0 {
+        return selected;
+    }
+
+    let mut grid = Grid::new(points);
+    let mut next = ((rng.next_f64() * n as f64) as usize).min(n - 1);
+
+    loop {
+        selected.push(next);
+        if selected.len() == k {
+            return selected;
+        }
+
+        grid.insert(points, &points[next]);
+        grid.clear(next);
+
+        next = match grid.farthest() {
+            Some(i) => i,
+            None => (0..n).find(|i| !selected.contains(i)).unwrap(),
+        };
+    }
+}
+
+/// Uniform bucket grid over the bounding box of a point set
+struct Grid<const D: usize> {
+    lo: [f64; D],
+    size: [f64; D],
+    cells: usize,
+    buckets: Vec<Bucket>,
+    /// Squared distance from every point to its nearest selected point
+    dist: Vec<f64>,
+}
+
+struct Bucket {
+    members: Vec<usize>,
+    /// Largest squared distance in `dist` over all members and the member it belongs to
+    far: (f64, usize),
+}
+
+impl<const D: usize> Grid<D> {
+    fn new(points: &[[f64; D]]) -> Grid<D> {
+        let mut lo = [f64::INFINITY; D];
+        let mut hi = [f64::NEG_INFINITY; D];
+        for p in points {
+            for j in 0..D {
+                lo[j] = lo[j].min(p[j]);
+                hi[j] = hi[j].max(p[j]);
+            }
+        }
+
+        // Aim for a handful of points per cell without letting the grid explode in high dimension
+        let per_axis = (points.len() as f64 / 4.).powf(1. / D.max(1) as f64).floor() as usize;
+        let cells = per_axis.clamp(1, 64);
+
+        let mut size = [0.; D];
+        for j in 0..D {
+            size[j] = (hi[j] - lo[j]) / cells as f64;
+        }
+
+        let mut buckets: Vec<Bucket> = (0..cells.pow(D as u32))
+            .map(|_| Bucket { members: Vec::new(), far: (0., 0) })
+            .collect();
+
+        let mut grid = Grid { lo, size, cells, buckets: Vec::new(), dist: Vec::new() };
+        for (i, p) in points.iter().enumerate() {
+            let b = &mut buckets[grid.bucket(p)];
+            b.members.push(i);
+            b.far = (f64::INFINITY, b.members[0]);
+        }
+        grid.buckets = buckets;
+        grid.dist = vec![f64::INFINITY; points.len()];
+        grid
+    }
+
+    fn bucket(&self, p: &[f64; D]) -> usize {
+        let mut index = 0;
+        for ((x, lo), size) in p.iter().zip(self.lo.iter()).zip(self.size.iter()) {
+            let c = if *size > 0. {
+                (((x - lo) / size) as usize).min(self.cells - 1)
+            } else {
+                0
+            };
+            index = index * self.cells + c;
+        }
+        index
+    }
+
+    /// Lower bound on the squared distance from `p` to any point in bucket `b`
+    fn lower_bound(&self, b: usize, p: &[f64; D]) -> f64 {
+        let mut rest = b;
+        let mut d2 = 0.;
+        for j in (0..D).rev() {
+            let c = (rest % self.cells) as f64;
+            rest /= self.cells;
+
+            let lo = self.lo[j] + c * self.size[j];
+            let hi = lo + self.size[j];
+            let d = if p[j] < lo { lo - p[j] } else if p[j] > hi { p[j] - hi } else { 0. };
+            d2 += d * d;
+        }
+        d2
+    }
+
+    /// Update nearest-selection distances after `center` was selected
+    fn insert(&mut self, points: &[[f64; D]], center: &[f64; D]) {
+        for b in 0..self.buckets.len() {
+            if self.buckets[b].members.is_empty()
+                || self.lower_bound(b, center) >= self.buckets[b].far.0 {
+                continue;
+            }
+
+            let bucket = &mut self.buckets[b];
+            bucket.far = (0., bucket.members[0]);
+            for &i in bucket.members.iter() {
+                let d2 = points[i].iter().zip(center.iter()).map(|(a, c)| (a - c) * (a - c)).sum();
+                if d2 < self.dist[i] {
+                    self.dist[i] = d2;
+                }
+                if self.dist[i] > bucket.far.0 {
+                    bucket.far = (self.dist[i], i);
+                }
+            }
+        }
+    }
+
+    /// Mark a point as selected so it can never be chosen again
+    fn clear(&mut self, i: usize) {
+        self.dist[i] = 0.;
+    }
+
+    /// Point with the largest positive distance to the selection, if any
+    fn farthest(&self) -> Option<usize> {
+        self.buckets.iter()
+            .filter(|b| b.far.0 > 0.)
+            .max_by(|a, b| a.far.0.partial_cmp(&b.far.0).unwrap())
+            .map(|b| b.far.1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::Halton;
+
+    // Quadratic reference implementation without the grid
+    fn brute_force<const D: usize>(points: &[[f64; D]], k: usize, first: usize) -> Vec<usize> {
+        let mut selected = vec![first];
+        let mut dist = vec![f64::INFINITY; points.len()];
+        while selected.len() < k {
+            let last = points[*selected.last().unwrap()];
+            for (i, p) in points.iter().enumerate() {
+                let d2 = p.iter().zip(last.iter()).map(|(a, c)| (a - c) * (a - c)).sum();
+                if d2 < dist[i] {
+                    dist[i] = d2;
+                }
+            }
+            let (next, _) = dist.iter().enumerate()
+                .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
+                .unwrap();
+            selected.push(next);
+        }
+        selected
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let points: Vec<[f64; 3]> = Halton::new(1, 2).unwrap()
+            .zip(Halton::new(1, 3).unwrap())
+            .zip(Halton::new(1, 5).unwrap())
+            .take(500)
+            .map(|((x, y), z)| [x * x, y, 4. * z])
+            .collect();
+
+        let picked = farthest_points(&points, 40, &mut Halton::new(1, 7).unwrap());
+        assert_eq!(picked, brute_force(&points, 40, picked[0]));
+    }
+
+    #[test]
+    fn duplicates_fill_in_index_order() {
+        let points = [[0.], [1.], [0.], [1.]];
+        let mut picked = farthest_points(&points, 4, &mut Halton::new(1, 2).unwrap());
+        picked.sort();
+        assert_eq!(picked, vec![0, 1, 2, 3]);
+    }
+}
@@ -0,0 +1,119 @@
+//! # Survey Sampling
+//!
+//! Systematic selection of units from a population list.
+//!
+//! Systematic sampling walks through the list with a fixed interval from a single fractional
+//! start, so one uniform draw determines the whole sample.  With size measures attached to the
+//! units this becomes probability-proportional-to-size (PPS) systematic sampling: unit `i` is
+//! selected with probability `n * w_i / Σw`.  Driving the start from a quasi-random stream
+//! spreads the starts of repeated samples evenly, stratifying the replicates.
+
+use rand::Rng;
+
+/// Select `n` units with probability proportional to their size `weights`
+///
+/// The cumulative weights are laid out on a line of length `Σw` and cut at the `n` points
+/// `(u + k) * Σw / n` for `k = 0, 1, ..., n - 1`, where `u` is a single draw from `rng`.  The
+/// unit owning each cut is selected, so units larger than the interval `Σw / n` can be selected
+/// more than once.  Indices are returned in list order.
+///
+/// # Panics
+///
+/// If a weight is negative or the weights do not have a positive sum.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate tapas;
+/// # use tapas::survey::pps_systematic;
+/// # fn main() {
+/// let sizes = [10., 40., 5., 25., 20.];
+/// let sample = pps_systematic(&sizes, 4, &mut rand::thread_rng());
+///
+/// // The interval is 25, so the unit of size 40 is always selected at least once
+/// assert_eq!(sample.len(), 4);
+/// assert!(sample.contains(&1));
+/// # }
+/// ```
+pub fn pps_systematic<R: Rng>(weights: &[f64], n: usize, rng: &mut R) -> Vec<usize> {
+    assert!(weights.iter().all(|&w| w >= 0.), "negative size measure in {:?}", weights);
+    let total: f64 = weights.iter().sum();
+    assert!(total > 0., "size measures {:?} sum to zero", weights);
+
+    let start = rng.next_f64();
+    let interval = total / n as f64;
+
+    let mut sample = Vec::with_capacity(n);
+    let mut unit = 0;
+    let mut acc = weights[0];
+    for k in 0..n {
+        let cut = (start + k as f64) * interval;
+        // Skip zero-sized units and guard the last cut against rounding past the end
+        while (cut >= acc || weights[unit] == 0.) && unit + 1 < weights.len() {
+            unit += 1;
+            acc += weights[unit];
+        }
+        sample.push(unit);
+    }
+
+    sample
+}
+
+/// Select `n` of `population` units with equal probability by systematic sampling
+///
+/// Equivalent to [`pps_systematic`] with unit weights: every unit is included with probability
+/// `n / population`.
+///
+/// # Panics
+///
+/// If `n` exceeds `population` or the population is empty.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::Halton;
+/// # use tapas::survey::systematic;
+/// // The first start of a base-2 stream is 1/2, so the cuts fall at 1.5, 4.0 and 6.5
+/// let sample = systematic(8, 3, &mut Halton::new(1, 2).unwrap());
+/// assert_eq!(sample, vec![1, 4, 6]);
+/// ```
+///
+/// [`pps_systematic`]: fn.pps_systematic.html
+pub fn systematic<R: Rng>(population: usize, n: usize, rng: &mut R) -> Vec<usize> {
+    assert!(population > 0, "empty population");
+    assert!(n <= population, "cannot select {} of {} units", n, population);
+
+    pps_systematic(&vec![1.; population], n, rng)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::Halton;
+
+    #[test]
+    fn inclusion_proportional_to_size() {
+        let sizes = [1., 3., 0., 2., 2.];
+        let mut counts = [0; 5];
+        let mut starts = Halton::new(1, 2).unwrap();
+        let reps = 1024;
+        for _ in 0..reps {
+            for i in pps_systematic(&sizes, 2, &mut starts) {
+                counts[i] += 1;
+            }
+        }
+
+        // Expected inclusions are 2 * w / 8 per sample
+        for (&w, &c) in sizes.iter().zip(counts.iter()) {
+            let expected = reps as f64 * 2. * w / 8.;
+            abs_err_eq!((c as f64) == expected ~ 1.5);
+        }
+    }
+
+    #[test]
+    fn large_units_repeat() {
+        let mut starts = Halton::new(1, 3).unwrap();
+        assert_eq!(pps_systematic(&[1., 10., 1.], 3, &mut starts), vec![1, 1, 1]);
+    }
+}
@@ -0,0 +1,345 @@
+//! # Transforms
+//!
+//! Map points of the unit square or cube onto other shapes while preserving their uniformity.
+//!
+//! Quasi-random points are only as good as the mapping that brings them into the domain of the
+//! integrand: a mapping which stretches some cells of the square much more than others undoes
+//! the stratification the sequence was built for.  The transforms here are continuous and
+//! keep the distortion of areas low, so evenly spread points stay evenly spread.  Apply them
+//! to whole generators with a [`Warp`].
+//!
+//! [`Warp`]: ../adapter/warp/struct.Warp.html
+
+use std::f64::consts::{FRAC_1_PI, FRAC_PI_4, PI};
+
+/// Map a point of the unit square onto the unit disk with Shirley and Chiu's concentric mapping
+///
+/// Concentric squares around the centre of `[0, 1)^2` are mapped onto concentric circles, so
+/// the square of half-width `r` around the centre lands exactly on the disk of radius `r`.
+/// Unlike the polar mapping `(sqrt(u), 2 pi v)`, which squeezes the cells along one edge into
+/// the centre and splits the disk along a seam, adjacent cells stay adjacent and keep similar
+/// shapes.  Uses Cline's single-branch formulation of the mapping.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::transform::unit_disk;
+/// assert_eq!(unit_disk([0.5, 0.5]), [0., 0.]);
+///
+/// // Corners of the square go to the diagonals of the disk
+/// let [x, y] = unit_disk([1., 1.]);
+/// assert!((x - 0.5f64.sqrt()).abs() < 1e-15 && (y - 0.5f64.sqrt()).abs() < 1e-15);
+/// ```
+///
+/// # References
+/// - Shirley, P., Chiu, K., A low distortion map between disk and square
+pub fn unit_disk([u, v]: [f64; 2]) -> [f64; 2] {
+    let (a, b) = (2. * u - 1., 2. * v - 1.);
+    if a == 0. && b == 0. {
+        return [0., 0.];
+    }
+
+    let (r, phi) = if a * a > b * b {
+        (a, FRAC_PI_4 * (b / a))
+    } else {
+        (b, 2. * FRAC_PI_4 - FRAC_PI_4 * (a / b))
+    };
+    [r * phi.cos(), r * phi.sin()]
+}
+
+/// Map a point of the unit square onto a uniformly distributed direction of the unit sphere
+///
+/// The first coordinate sets the height `z = 1 - 2u` and the second the azimuth `2 pi v`.  By
+/// Archimedes' hat-box theorem, bands of equal height have equal area, so the mapping is
+/// area-preserving: every cell of the square covers the same share of the sphere.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::Sobol;
+/// # use tapas::sampler::PointSampler;
+/// # use tapas::transform::unit_sphere;
+/// assert_eq!(unit_sphere([0., 0.]), [0., 0., 1.]);
+///
+/// // Directions from a generator of two dimensional points average out to the origin
+/// let mean_z = Sobol::new(2).unwrap().warp(unit_sphere).take(1024).map(|[_, _, z]| z)
+///     .sum::<f64>() / 1024.;
+/// assert!(mean_z.abs() < 1e-2);
+/// ```
+pub fn unit_sphere([u, v]: [f64; 2]) -> [f64; 3] {
+    let z = 1. - 2. * u;
+    let s = (1. - z * z).max(0.).sqrt();
+    let phi = 2. * PI * v;
+    [s * phi.cos(), s * phi.sin(), z]
+}
+
+/// Map a point of the unit square onto a cosine-distributed direction of the upper hemisphere
+///
+/// Returns the direction around the `z` axis and its density `cos(theta) / pi` with respect to
+/// solid angle, the density of the ideal diffuse (Lambertian) reflection, so the weight of a
+/// sample is the reflectance itself.  Uses Malley's method: the point is mapped onto the
+/// disk with [`unit_disk`] and projected up onto the hemisphere, so the low distortion of the
+/// concentric mapping carries over.  Use `|u| cosine_hemisphere(u).0` to [`Warp`] a generator
+/// into directions alone.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::transform::cosine_hemisphere;
+/// let ([x, y, z], pdf) = cosine_hemisphere([0.5, 0.5]);
+/// assert_eq!(([x, y, z], pdf), ([0., 0., 1.], 1. / std::f64::consts::PI));
+/// ```
+///
+/// [`unit_disk`]: fn.unit_disk.html
+/// [`Warp`]: ../adapter/warp/struct.Warp.html
+pub fn cosine_hemisphere(u: [f64; 2]) -> ([f64; 3], f64) {
+    let [x, y] = unit_disk(u);
+    let z = (1. - x * x - y * y).max(0.).sqrt();
+    ([x, y, z], z * FRAC_1_PI)
+}
+
+/// Map a point of the unit cube onto a uniformly distributed point of the unit ball
+///
+/// The first coordinate sets the radius `cbrt(u)`, so shells of equal volume get equal shares
+/// of the points, and the other two the direction through [`unit_sphere`].  Scale and shift
+/// the result for other balls, or sample a [`Ball`] domain, which uses the same mapping.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::HaltonSeq;
+/// # use tapas::sampler::PointSampler;
+/// # use tapas::transform::unit_ball;
+/// // Particles in a ball of radius 2 around (1, 1, 1)
+/// for [x, y, z] in HaltonSeq::new(3).unwrap().warp(unit_ball).take(100) {
+///     let p = [1. + 2. * x, 1. + 2. * y, 1. + 2. * z];
+///     assert!((p[0] - 1.).powi(2) + (p[1] - 1.).powi(2) + (p[2] - 1.).powi(2) <= 4. + 1e-12);
+/// }
+/// ```
+///
+/// [`unit_sphere`]: fn.unit_sphere.html
+/// [`Ball`]: ../domain/struct.Ball.html
+pub fn unit_ball([u, v, w]: [f64; 3]) -> [f64; 3] {
+    let r = u.cbrt();
+    let [x, y, z] = unit_sphere([v, w]);
+    [r * x, r * y, r * z]
+}
+
+/// Map a point of the unit cube `[0, 1)^d` onto uniformly distributed weights of the simplex
+///
+/// Returns the `d + 1` barycentric weights of a point of the `d`-simplex, which are
+/// non-negative and sum to one, as a draw of the flat Dirichlet distribution.  The weights are
+/// the spacings of the sorted coordinates of `u`, with `0` and `1` added at the ends: the order
+/// statistics of `d` uniform values split the unit interval into `d + 1` exchangeable gaps,
+/// uniformly distributed over the simplex.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::transform::unit_simplex;
+/// assert_eq!(unit_simplex(&[0.75, 0.25]), vec![0.25, 0.5, 0.25]);
+///
+/// // Random weights of three components from two dimensional points
+/// # use tapas::quasi::Sobol;
+/// for p in Sobol::new(2).unwrap().take(16) {
+///     let w = unit_simplex(&p);
+///     assert!(w.iter().all(|&w| w >= 0.) && (w.iter().sum::<f64>() - 1.).abs() < 1e-15);
+/// }
+/// ```
+pub fn unit_simplex(u: &[f64]) -> Vec<f64> {
+    let mut sorted = u.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    sorted.push(1.);
+
+    let mut last = 0.;
+    sorted.into_iter().map(|s| { let gap = s - last; last = s; gap }).collect()
+}
+
+/// Map a point of the unit square onto uniformly distributed barycentric weights of a triangle
+///
+/// Uses Heitz's low-distortion mapping: the square is split along its diagonal and each half
+/// is sheared onto the triangle, so the mapping is area-preserving and cells of the square
+/// keep similar shapes.  The common square-root parameterization `(1 - sqrt(u), v sqrt(u))`
+/// is also uniform, but squeezes a whole edge of the square into a vertex of the triangle.
+/// Returns the weights of the three vertices, which are non-negative and sum to one.
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::transform::unit_triangle;
+/// assert_eq!(unit_triangle([0.5, 0.5]), [0.25, 0.25, 0.5]);
+/// assert_eq!(unit_triangle([0., 0.]), [0., 0., 1.]);
+/// ```
+///
+/// # References
+/// - Heitz, E., A low-distortion map between triangle and square
+pub fn unit_triangle([u, v]: [f64; 2]) -> [f64; 3] {
+    let (b0, b1) = if v > u {
+        let b0 = 0.5 * u;
+        (b0, v - b0)
+    } else {
+        let b1 = 0.5 * v;
+        (u - b1, b1)
+    };
+    [b0, b1, 1. - b0 - b1]
+}
+
+/// Map a point of the unit square onto a uniformly distributed point of the triangle `abc`
+///
+/// Weighs the vertices with the barycentric coordinates of [`unit_triangle`].
+///
+/// # Examples
+///
+/// ```
+/// # use tapas::quasi::HaltonSeq;
+/// # use tapas::transform::triangle;
+/// let (a, b, c) = ([0., 0., 1.], [2., 0., 1.], [0., 2., 1.]);
+/// for p in HaltonSeq::new(2).unwrap().take(100) {
+///     let [x, y, z] = triangle(&a, &b, &c, [p[0], p[1]]);
+///     assert!(x >= 0. && y >= 0. && x + y <= 2. + 1e-12 && z == 1.);
+/// }
+/// ```
+///
+/// [`unit_triangle`]: fn.unit_triangle.html
+pub fn triangle(a: &[f64; 3], b: &[f64; 3], c: &[f64; 3], u: [f64; 2]) -> [f64; 3] {
+    let [b0, b1, b2] = unit_triangle(u);
+    [
+        b0 * a[0] + b1 * b[0] + b2 * c[0],
+        b0 * a[1] + b1 * b[1] + b2 * c[1],
+        b0 * a[2] + b1 * b[2] + b2 * c[2],
+    ]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use quasi::Sobol;
+
+    #[test]
+    fn squares_map_to_circles() {
+        let n = 64;
+        for i in 0..=n {
+            for j in 0..=n {
+                let (u, v) = (i as f64 / n as f64, j as f64 / n as f64);
+                let [x, y] = unit_disk([u, v]);
+                let half_width = (2. * u - 1.).abs().max((2. * v - 1.).abs());
+                abs_err_eq!((x.hypot(y)) == half_width ~ 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn preserves_area() {
+        // Each quarter of the disk collects the points of one triangle of the square
+        let mut quarters = [0; 4];
+        let n = 4096;
+        for p in Sobol::new(2).unwrap().take(n) {
+            let [x, y] = unit_disk([p[0], p[1]]);
+            let k = if x.abs() >= y.abs() { if x >= 0. { 0 } else { 2 } }
+                else if y >= 0. { 1 } else { 3 };
+            quarters[k] += 1;
+        }
+        for &q in quarters.iter() {
+            abs_err_eq!((q as f64 / n as f64) == 0.25 ~ 0.01);
+        }
+    }
+
+    #[test]
+    fn directions_have_unit_length() {
+        for p in Sobol::new(2).unwrap().take(500) {
+            let [x, y, z] = unit_sphere([p[0], p[1]]);
+            abs_err_eq!((x * x + y * y + z * z) == 1. ~ 1e-12);
+        }
+
+        // Octants of the sphere have equal area
+        let mut octants = [0; 8];
+        let n = 4096;
+        for p in Sobol::new(2).unwrap().take(n) {
+            let [x, y, z] = unit_sphere([p[0], p[1]]);
+            octants[(x < 0.) as usize + 2 * (y < 0.) as usize + 4 * (z < 0.) as usize] += 1;
+        }
+        for &o in octants.iter() {
+            abs_err_eq!((o as f64 / n as f64) == 0.125 ~ 0.005);
+        }
+    }
+
+    #[test]
+    fn cosine_weighted_directions() {
+        let n = 4096;
+        let (mut mean_z, mut integral) = (0., 0.);
+        for p in Sobol::new(2).unwrap().skip(1).take(n) {
+            let ([x, y, z], pdf) = cosine_hemisphere([p[0], p[1]]);
+            abs_err_eq!((x * x + y * y + z * z) == 1. ~ 1e-12);
+            assert!(z >= 0.);
+            abs_err_eq!(pdf == (z / PI) ~ 1e-15);
+            mean_z += z / n as f64;
+            integral += z * z * z / pdf / n as f64;
+        }
+        // The mean of cos(theta) is 2/3, and cos^3(theta) integrates to pi/2 over solid angle
+        abs_err_eq!(mean_z == (2. / 3.) ~ 1e-3);
+        abs_err_eq!(integral == (PI / 2.) ~ 5e-3);
+    }
+
+    #[test]
+    fn ball_shells_by_volume() {
+        // The ball of half the radius holds an eighth of the volume
+        let n = 4096;
+        let inner = Sobol::new(3).unwrap().take(n)
+            .map(|p| unit_ball([p[0], p[1], p[2]]))
+            .filter(|&[x, y, z]| x * x + y * y + z * z < 0.25)
+            .count();
+        assert_eq!(inner, n / 8);
+    }
+
+    #[test]
+    fn simplex_weights_are_exchangeable() {
+        // Every weight has the mean 1 / (d + 1) of the flat Dirichlet distribution
+        let n = 4096;
+        let mut means = [0.; 4];
+        for p in Sobol::new(3).unwrap().take(n) {
+            let w = unit_simplex(&p);
+            assert_eq!(w.len(), 4);
+            abs_err_eq!((w.iter().sum::<f64>()) == 1. ~ 1e-15);
+            for (m, w) in means.iter_mut().zip(w) {
+                *m += w / n as f64;
+            }
+        }
+        for &m in means.iter() {
+            abs_err_eq!(m == 0.25 ~ 1e-3);
+        }
+        assert_eq!(unit_simplex(&[]), vec![1.]);
+    }
+
+    #[test]
+    fn triangle_weights_are_uniform() {
+        // Each corner triangle cut by the midpoints holds a quarter of the area
+        let n = 4096;
+        let mut corners = [0; 3];
+        for p in Sobol::new(2).unwrap().take(n) {
+            let w = unit_triangle([p[0], p[1]]);
+            assert!(w.iter().all(|&w| w >= 0.));
+            abs_err_eq!((w.iter().sum::<f64>()) == 1. ~ 1e-15);
+            for (c, &w) in corners.iter_mut().zip(w.iter()) {
+                *c += (w > 0.5) as usize;
+            }
+        }
+        for &c in corners.iter() {
+            abs_err_eq!((c as f64 / n as f64) == 0.25 ~ 0.01);
+        }
+    }
+
+    #[test]
+    fn triangle_is_continuous() {
+        // Both halves of the square agree on the diagonal
+        for i in 0..=16 {
+            let t = i as f64 / 16.;
+            let below = unit_triangle([t, t]);
+            let above = unit_triangle([t, t + 1e-12]);
+            for (x, y) in below.iter().zip(above.iter()) {
+                abs_err_eq!(x == y ~ 1e-11);
+            }
+        }
+        let (a, b, c) = ([1., 2., 3.], [4., 5., 6.], [-1., 0., 2.]);
+        assert_eq!(triangle(&a, &b, &c, [0., 0.]), c);
+    }
+}